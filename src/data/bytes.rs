@@ -0,0 +1,36 @@
+//! `BYTEA` is written as a hex string literal, e.g. `X'DEADBEEF'`, which has two hex digits for
+//! each byte.
+
+/// Parses the hex digits of `X'...'`, `None` if they are not valid.
+pub fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Uppercase hex digits of `bytes`, which `parse_hex` parses back.
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(parse_hex(""), Some(vec![]));
+        assert_eq!(parse_hex("00ff7F"), Some(vec![0, 255, 127]));
+        assert_eq!(parse_hex("0"), None);
+        assert_eq!(parse_hex("0g"), None);
+        assert_eq!(parse_hex("+1"), None);
+
+        assert_eq!(format_hex(&[0, 255, 127]), "00FF7F");
+        assert_eq!(format_hex(&[]), "");
+    }
+}
@@ -0,0 +1,192 @@
+//! `DATE` is stored as the number of days since `1970-01-01`, and `TIMESTAMP` is stored as the
+//! number of microseconds since `1970-01-01 00:00:00`. Both are parsed from and formatted to
+//! ISO-8601, e.g. `2020-10-03` and `2020-10-03 12:30:00.125`.
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SECOND;
+
+/// Parses `YYYY-MM-DD`.
+pub fn parse_date(s: &str) -> Option<i32> {
+    let mut parts = s.trim().splitn(3, '-');
+
+    let year = parse_digits(parts.next()?, 4)?;
+    let month = parse_digits(parts.next()?, 2)?;
+    let day = parse_digits(parts.next()?, 2)?;
+
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) as i32)
+}
+
+/// Parses `YYYY-MM-DD HH:MM:SS` with optional fractional seconds up to microseconds. `T` can be
+/// used instead of a space, and the time can be omitted for midnight.
+pub fn parse_timestamp(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let (date, time) = match s.find([' ', 'T']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+
+    let days = i64::from(parse_date(date)?);
+    let micros = match time {
+        Some(time) => parse_time(time)?,
+        None => 0,
+    };
+
+    Some(days * MICROS_PER_DAY + micros)
+}
+
+pub fn format_date(days: i32) -> String {
+    let (year, month, day) = civil_from_days(i64::from(days));
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn format_timestamp(micros: i64) -> String {
+    let days = micros.div_euclid(MICROS_PER_DAY);
+    let micros = micros.rem_euclid(MICROS_PER_DAY);
+    let seconds = micros / MICROS_PER_SECOND;
+    let fraction = micros % MICROS_PER_SECOND;
+
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    let time = match fraction {
+        0 => time,
+        _ => format!(
+            "{}.{}",
+            time,
+            format!("{:06}", fraction).trim_end_matches('0')
+        ),
+    };
+
+    format!("{} {}", format_date(days as i32), time)
+}
+
+/// Timestamp of the midnight of the date.
+pub fn date_to_timestamp(days: i32) -> i64 {
+    i64::from(days) * MICROS_PER_DAY
+}
+
+/// Date of the timestamp, the time is discarded.
+pub fn timestamp_to_date(micros: i64) -> i32 {
+    micros.div_euclid(MICROS_PER_DAY) as i32
+}
+
+fn parse_time(s: &str) -> Option<i64> {
+    let (time, fraction) = match s.find('.') {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+
+    let mut parts = time.splitn(3, ':');
+    let hour = parse_digits(parts.next()?, 2)?;
+    let minute = parse_digits(parts.next()?, 2)?;
+    let second = parse_digits(parts.next()?, 2)?;
+
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let fraction = match fraction {
+        Some(fraction) if fraction.is_empty() || fraction.len() > 6 => {
+            return None;
+        }
+        Some(fraction) => {
+            parse_digits(fraction, fraction.len())? * 10_i64.pow(6 - fraction.len() as u32)
+        }
+        None => 0,
+    };
+
+    Some((hour * 3600 + minute * 60 + second) * MICROS_PER_SECOND + fraction)
+}
+
+fn parse_digits(s: &str, len: usize) -> Option<i64> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("1969-12-31"), Some(-1));
+        assert_eq!(parse_date("2000-03-01"), Some(11_017));
+        assert_eq!(parse_date("2020-02-29"), Some(18_321));
+        assert_eq!(parse_date("2021-02-29"), None);
+        assert_eq!(parse_date("2021-13-01"), None);
+        assert_eq!(parse_date("2021-1-01"), None);
+        assert_eq!(parse_date("hello"), None);
+
+        for days in [-719_162, -1, 0, 1, 11_017, 18_321, 2_932_896].iter() {
+            assert_eq!(parse_date(&format_date(*days)), Some(*days));
+        }
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01"), Some(0));
+        assert_eq!(parse_timestamp("1970-01-01 00:00:01"), Some(1_000_000));
+        assert_eq!(parse_timestamp("1970-01-01T00:01:00.5"), Some(60_500_000));
+        assert_eq!(parse_timestamp("1969-12-31 23:59:59"), Some(-1_000_000));
+        assert_eq!(parse_timestamp("1970-01-01 24:00:00"), None);
+        assert_eq!(parse_timestamp("1970-01-01 00:00:00."), None);
+        assert_eq!(parse_timestamp("1970-01-01 00:00"), None);
+
+        assert_eq!(format_timestamp(-1_000_000), "1969-12-31 23:59:59");
+        assert_eq!(format_timestamp(60_500_000), "1970-01-01 00:01:00.5");
+        assert_eq!(timestamp_to_date(-1), -1);
+        assert_eq!(date_to_timestamp(1), 86_400_000_000);
+    }
+}
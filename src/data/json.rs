@@ -0,0 +1,337 @@
+//! `JSON` is stored as its text, which is parsed and formatted again when it is stored, so that
+//! equal documents have the same text regardless of whitespace, e.g. `{"a": [1, 2]}`. Fields are
+//! extracted by parsing the text whenever they are needed.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// Numbers keep their text, they are not computed with.
+    Number(String),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Field of an object, or an element of an array if `key` is an integer, which counts from
+    /// the end when it is negative. `None` if there is no such field or element.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            Json::Array(values) => {
+                let index = key.parse::<i64>().ok()?;
+                let index = if index < 0 {
+                    values.len() as i64 + index
+                } else {
+                    index
+                };
+
+                usize::try_from(index).ok().and_then(|i| values.get(i))
+            }
+            _ => None,
+        }
+    }
+
+    /// Text which `->>` extracts, a string is unquoted and `null` is `None`.
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            Json::Null => None,
+            Json::Str(s) => Some(s.clone()),
+            json => Some(json.to_string()),
+        }
+    }
+}
+
+/// Parses a JSON document, `None` if it is not valid.
+pub fn parse_json(s: &str) -> Option<Json> {
+    let mut chars = s.chars().peekable();
+    let json = parse_value(&mut chars)?;
+
+    skip_whitespace(&mut chars);
+
+    match chars.next() {
+        Some(_) => None,
+        None => Some(json),
+    }
+}
+
+/// Text of `s` as it is stored, `None` if it is not valid.
+pub fn format_json(s: &str) -> Option<String> {
+    parse_json(s).map(|json| json.to_string())
+}
+
+type Input<'a> = Peekable<Chars<'a>>;
+
+fn skip_whitespace(chars: &mut Input<'_>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_value(chars: &mut Input<'_>) -> Option<Json> {
+    skip_whitespace(chars);
+
+    match chars.peek()? {
+        '{' => parse_object(chars),
+        '[' => parse_array(chars),
+        '"' => parse_string(chars).map(Json::Str),
+        '-' | '0'..='9' => parse_number(chars).map(Json::Number),
+        _ => {
+            let word =
+                std::iter::from_fn(|| chars.next_if(char::is_ascii_alphabetic)).collect::<String>();
+
+            match word.as_str() {
+                "null" => Some(Json::Null),
+                "true" => Some(Json::Bool(true)),
+                "false" => Some(Json::Bool(false)),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn parse_object(chars: &mut Input<'_>) -> Option<Json> {
+    chars.next();
+
+    let fields = parse_items(chars, '}', |chars| {
+        skip_whitespace(chars);
+
+        let name = parse_string(chars)?;
+
+        skip_whitespace(chars);
+        chars.next_if_eq(&':')?;
+
+        Some((name, parse_value(chars)?))
+    })?;
+
+    Some(Json::Object(fields))
+}
+
+fn parse_array(chars: &mut Input<'_>) -> Option<Json> {
+    chars.next();
+
+    parse_items(chars, ']', parse_value).map(Json::Array)
+}
+
+/// Items separated by commas until `close`, the opening bracket is already consumed.
+fn parse_items<T>(
+    chars: &mut Input<'_>,
+    close: char,
+    parse_item: impl Fn(&mut Input<'_>) -> Option<T>,
+) -> Option<Vec<T>> {
+    skip_whitespace(chars);
+
+    if chars.next_if_eq(&close).is_some() {
+        return Some(vec![]);
+    }
+
+    let mut items = vec![];
+
+    loop {
+        items.push(parse_item(chars)?);
+        skip_whitespace(chars);
+
+        match chars.next()? {
+            ',' => {}
+            c if c == close => {
+                return Some(items);
+            }
+            _ => {
+                return None;
+            }
+        }
+    }
+}
+
+fn parse_string(chars: &mut Input<'_>) -> Option<String> {
+    chars.next_if_eq(&'"')?;
+
+    let mut s = String::new();
+
+    loop {
+        match chars.next()? {
+            '"' => {
+                return Some(s);
+            }
+            '\\' => {
+                let c = match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => parse_unicode(chars)?,
+                    c @ ('"' | '\\' | '/') => c,
+                    _ => {
+                        return None;
+                    }
+                };
+
+                s.push(c);
+            }
+            c if c.is_control() => {
+                return None;
+            }
+            c => s.push(c),
+        }
+    }
+}
+
+/// `\uXXXX` after `\u`, a surrogate pair is written as two of them.
+fn parse_unicode(chars: &mut Input<'_>) -> Option<char> {
+    let hex = |chars: &mut Input<'_>| {
+        let digits = (0..4).map(|_| chars.next()).collect::<Option<String>>()?;
+
+        u32::from_str_radix(&digits, 16).ok()
+    };
+    let high = hex(chars)?;
+
+    if !(0xD800..0xDC00).contains(&high) {
+        return char::from_u32(high);
+    }
+
+    chars.next_if_eq(&'\\')?;
+    chars.next_if_eq(&'u')?;
+
+    let low = hex(chars)?;
+
+    if !(0xDC00..0xE000).contains(&low) {
+        return None;
+    }
+
+    char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+}
+
+fn parse_number(chars: &mut Input<'_>) -> Option<String> {
+    let number = std::iter::from_fn(|| {
+        chars.next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+    })
+    .collect::<String>();
+
+    let digits = number.strip_prefix('-').unwrap_or(&number);
+    let leading_zero =
+        digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit());
+    let valid = digits.parse::<f64>().is_ok()
+        && digits.starts_with(|c: char| c.is_ascii_digit())
+        && !leading_zero
+        && !digits.ends_with('.')
+        && !digits.contains(".e")
+        && !digits.contains(".E");
+
+    if valid {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+
+    s.chars().try_for_each(|c| match c {
+        '"' => f.write_str("\\\""),
+        '\\' => f.write_str("\\\\"),
+        '\n' => f.write_str("\\n"),
+        '\t' => f.write_str("\\t"),
+        '\r' => f.write_str("\\r"),
+        c if c.is_control() => write!(f, "\\u{:04x}", c as u32),
+        c => write!(f, "{}", c),
+    })?;
+
+    f.write_str("\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(v) => write!(f, "{}", v),
+            Json::Number(v) => f.write_str(v),
+            Json::Str(v) => write_string(f, v),
+            Json::Array(values) => {
+                f.write_str("[")?;
+
+                values.iter().enumerate().try_for_each(|(i, value)| {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    write!(f, "{}", value)
+                })?;
+
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+
+                fields
+                    .iter()
+                    .enumerate()
+                    .try_for_each(|(i, (name, value))| {
+                        if i > 0 {
+                            f.write_str(", ")?;
+                        }
+
+                        write_string(f, name)?;
+                        write!(f, ": {}", value)
+                    })?;
+
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            format_json(" {\"a\":[1, -2.5e3 ,true,null],\"b\" : {\"c\":\"x\\\"y\"}} "),
+            Some(r#"{"a": [1, -2.5e3, true, null], "b": {"c": "x\"y"}}"#.to_owned())
+        );
+        assert_eq!(
+            format_json("\"\\u00e9\\ud83d\\ude00\""),
+            Some("\"é😀\"".to_owned())
+        );
+        assert_eq!(format_json("[]"), Some("[]".to_owned()));
+        assert_eq!(format_json("{}"), Some("{}".to_owned()));
+
+        for invalid in [
+            "",
+            "{",
+            "[1,]",
+            "{\"a\" 1}",
+            "01",
+            "1.",
+            "tru",
+            "\"a",
+            "1 2",
+        ]
+        .iter()
+        {
+            assert_eq!(format_json(invalid), None, "{}", invalid);
+        }
+
+        let json =
+            parse_json(r#"{"a": {"b": [10, 20, "x"]}, "a": {"b": [30]}, "n": null}"#).unwrap();
+        let b = json.get("a").and_then(|a| a.get("b")).unwrap();
+
+        assert_eq!(b.to_string(), "[30]");
+        assert_eq!(b.get("0").and_then(Json::to_text), Some("30".to_owned()));
+        assert_eq!(b.get("-1").and_then(Json::to_text), Some("30".to_owned()));
+        assert_eq!(b.get("1"), None);
+        assert_eq!(json.get("n").map(Json::to_text), Some(None));
+        assert_eq!(json.get("missing"), None);
+    }
+}
@@ -1,9 +1,18 @@
+mod bytes;
+mod date;
+mod json;
 mod row;
 mod schema;
 mod table;
 mod value;
 
+pub use bytes::{format_hex, parse_hex};
+pub use date::{
+    date_to_timestamp, format_date, format_timestamp, parse_date, parse_timestamp,
+    timestamp_to_date,
+};
+pub use json::{format_json, parse_json, Json};
 pub use row::{Row, RowError};
-pub use schema::Schema;
+pub use schema::{ColumnSchema, Schema, TableSchema};
 pub use table::{get_name, Table, TableError};
 pub use value::{Value, ValueError};
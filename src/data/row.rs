@@ -3,7 +3,8 @@ use std::fmt::Debug;
 use thiserror::Error;
 
 use sqlparser::ast::{
-    ColumnDef, ColumnOption, ColumnOptionDef, Expr, Ident, Query, SetExpr, Values,
+    ColumnDef, ColumnOption, ColumnOptionDef, Expr, Ident, Query, SetExpr, Value as AstValue,
+    Values,
 };
 
 use crate::data::Value;
@@ -61,7 +62,7 @@ impl Row {
         values
             .iter()
             .map(|values| {
-                (&column_defs)
+                column_defs
                     .iter()
                     .enumerate()
                     .map(|(i, column_def)| {
@@ -90,9 +91,14 @@ impl Row {
 
                         match literal {
                             Expr::Value(literal) => {
-                                Value::from_data_type(&data_type, nullable, literal)
+                                Value::from_data_type(data_type, nullable, literal)
                             }
                             Expr::Identifier(Ident { value, .. }) => Ok(Value::Str(value.clone())),
+                            Expr::TypedString { value, .. } => Value::from_data_type(
+                                data_type,
+                                nullable,
+                                &AstValue::SingleQuotedString(value.clone()),
+                            ),
                             _ => Err(RowError::UnsupportedAstValueType.into()),
                         }
                     })
@@ -101,4 +107,48 @@ impl Row {
             })
             .collect()
     }
+
+    /// Rows of `INSERT ... SELECT`, each of `rows` has the values of `columns` in order, or
+    /// of all the columns if `columns` is empty. Values are converted by `Value::to_column`.
+    pub fn from_rows(
+        column_defs: Vec<ColumnDef>,
+        columns: &[Ident],
+        rows: Vec<Row>,
+    ) -> Result<Vec<Self>> {
+        rows.into_iter()
+            .map(|row| {
+                column_defs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, column_def)| {
+                        let ColumnDef {
+                            name,
+                            data_type,
+                            options,
+                            ..
+                        } = column_def;
+                        let name = name.to_string();
+
+                        let i = match columns.len() {
+                            0 => Ok(i),
+                            _ => columns
+                                .iter()
+                                .position(|target| target.value == name)
+                                .ok_or_else(|| RowError::LackOfRequiredColumn(name.clone())),
+                        }?;
+
+                        let value = row
+                            .get_value(i)
+                            .ok_or_else(|| RowError::LackOfRequiredValue(name.clone()))?;
+                        let nullable = options
+                            .iter()
+                            .any(|ColumnOptionDef { option, .. }| option == &ColumnOption::Null);
+
+                        value.to_column(data_type, nullable)
+                    })
+                    .collect::<Result<_>>()
+                    .map(Self)
+            })
+            .collect()
+    }
 }
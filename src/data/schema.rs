@@ -1,8 +1,67 @@
 use serde::{Deserialize, Serialize};
-use sqlparser::ast::ColumnDef;
+use sqlparser::ast::{ColumnDef, ColumnOption, ColumnOptionDef, DataType};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Schema {
     pub table_name: String,
     pub column_defs: Vec<ColumnDef>,
 }
+
+/// Columns of a table with their declared types, see `Store::get_schema`. `primary_key` is the
+/// names of the columns declared as `PRIMARY KEY`, in the order of columns.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TableSchema {
+    pub table_name: String,
+    pub columns: Vec<ColumnSchema>,
+    pub primary_key: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub data_type: DataType,
+    pub nullable: bool,
+}
+
+impl From<&Schema> for TableSchema {
+    fn from(schema: &Schema) -> Self {
+        let has_option = |options: &[ColumnOptionDef], found: fn(&ColumnOption) -> bool| {
+            options
+                .iter()
+                .any(|ColumnOptionDef { option, .. }| found(option))
+        };
+
+        let columns = schema
+            .column_defs
+            .iter()
+            .map(
+                |ColumnDef {
+                     name,
+                     data_type,
+                     options,
+                     ..
+                 }| ColumnSchema {
+                    name: name.value.clone(),
+                    data_type: data_type.clone(),
+                    nullable: has_option(options, |option| option == &ColumnOption::Null),
+                },
+            )
+            .collect();
+        let primary_key = schema
+            .column_defs
+            .iter()
+            .filter(|ColumnDef { options, .. }| {
+                has_option(options, |option| {
+                    matches!(option, ColumnOption::Unique { is_primary: true })
+                })
+            })
+            .map(|ColumnDef { name, .. }| name.value.clone())
+            .collect();
+
+        Self {
+            table_name: schema.table_name.clone(),
+            columns,
+            primary_key,
+        }
+    }
+}
@@ -2,7 +2,7 @@ use serde::Serialize;
 use std::fmt::Debug;
 use thiserror::Error;
 
-use sqlparser::ast::{ObjectName, TableAlias, TableFactor};
+use sqlparser::ast::{Ident, ObjectName, Query, TableAlias, TableFactor, TableWithJoins};
 
 use crate::result::Result;
 
@@ -11,13 +11,30 @@ pub enum TableError {
     #[error("unreachable")]
     Unreachable,
 
-    #[error("TableFactorNotSupported")]
-    TableFactorNotSupported,
+    #[error("table factor not supported: {0}")]
+    TableFactorNotSupported(String),
+
+    #[error("derived table should have an alias")]
+    DerivedTableAliasRequired,
+
+    #[error("table {table} has {expected} columns available but {found} column aliases specified")]
+    TooManyColumnAliases {
+        table: String,
+        expected: usize,
+        found: usize,
+    },
 }
 
+/// Name of the table which `SELECT` without `FROM` reads from, and of nested joins.
+static EMPTY_NAME: String = String::new();
+
 pub struct Table<'a> {
     name: &'a String,
     alias: Option<&'a String>,
+    column_aliases: &'a [Ident],
+    subquery: Option<&'a Query>,
+    lateral: bool,
+    nested: Option<&'a TableWithJoins>,
 }
 
 impl<'a> Table<'a> {
@@ -25,11 +42,58 @@ impl<'a> Table<'a> {
         match table_factor {
             TableFactor::Table { name, alias, .. } => {
                 let name = get_name(name)?;
-                let alias = alias.as_ref().map(|TableAlias { name, .. }| &name.value);
+                let (alias, column_aliases) = match alias {
+                    Some(TableAlias { name, columns }) => (Some(&name.value), columns.as_slice()),
+                    None => (None, [].as_ref()),
+                };
+
+                Ok(Self {
+                    name,
+                    alias,
+                    column_aliases,
+                    subquery: None,
+                    lateral: false,
+                    nested: None,
+                })
+            }
+            TableFactor::Derived {
+                lateral,
+                subquery,
+                alias,
+            } => {
+                let TableAlias { name, columns } = alias
+                    .as_ref()
+                    .ok_or(TableError::DerivedTableAliasRequired)?;
 
-                Ok(Self { name, alias })
+                Ok(Self {
+                    name: &name.value,
+                    alias: None,
+                    column_aliases: columns,
+                    subquery: Some(subquery),
+                    lateral: *lateral,
+                    nested: None,
+                })
             }
-            _ => Err(TableError::TableFactorNotSupported.into()),
+            TableFactor::NestedJoin(nested) => Ok(Self {
+                name: &EMPTY_NAME,
+                alias: None,
+                column_aliases: &[],
+                subquery: None,
+                lateral: false,
+                nested: Some(nested),
+            }),
+        }
+    }
+
+    /// Table of `SELECT` without `FROM`, which has no name and no columns.
+    pub fn empty() -> Self {
+        Self {
+            name: &EMPTY_NAME,
+            alias: None,
+            column_aliases: &[],
+            subquery: None,
+            lateral: false,
+            nested: None,
         }
     }
 
@@ -43,9 +107,30 @@ impl<'a> Table<'a> {
             None => self.name,
         }
     }
+
+    /// Column names given with the alias, e.g. `FROM (SELECT ..) AS Sub (a, b)`.
+    pub fn get_column_aliases(&self) -> &'a [Ident] {
+        self.column_aliases
+    }
+
+    /// Derived tables are named by their alias, and their rows come from `subquery`.
+    pub fn get_subquery(&self) -> Option<&'a Query> {
+        self.subquery
+    }
+
+    /// `LATERAL` derived table, of which the subquery can refer to the tables before it.
+    pub fn is_lateral(&self) -> bool {
+        self.lateral
+    }
+
+    /// Tables joined in parentheses, e.g. `a JOIN (b JOIN c ON ..) ON ..`, which have no name of
+    /// their own and are referred to by the aliases of the joined tables.
+    pub fn get_nested(&self) -> Option<&'a TableWithJoins> {
+        self.nested
+    }
 }
 
-pub fn get_name<'a>(table_name: &'a ObjectName) -> Result<&'a String> {
+pub fn get_name(table_name: &ObjectName) -> Result<&String> {
     let ObjectName(idents) = table_name;
 
     idents
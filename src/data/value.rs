@@ -7,6 +7,10 @@ use thiserror::Error as ThisError;
 
 use sqlparser::ast::{DataType, Value as AstValue};
 
+use super::{
+    date_to_timestamp, format_date, format_json, format_timestamp, parse_date, parse_hex,
+    parse_timestamp, timestamp_to_date,
+};
 use crate::executor::GroupKey;
 use crate::result::{Error, Result};
 
@@ -33,11 +37,35 @@ pub enum ValueError {
     #[error("divide on non numeric value")]
     DivideOnNonNumeric,
 
+    #[error("modulo on non numeric value")]
+    ModuloOnNonNumeric,
+
+    #[error("divisor should not be zero")]
+    DivisorShouldNotBeZero,
+
+    #[error("impossible cast")]
+    ImpossibleCast,
+
     #[error("null value on not null field")]
     NullValueOnNotNullField,
 
     #[error("floating numbers cannot be grouped by")]
     FloatCannotBeGroupedBy,
+
+    #[error("failed to parse date: {0}")]
+    FailedToParseDate(String),
+
+    #[error("failed to parse timestamp: {0}")]
+    FailedToParseTimestamp(String),
+
+    #[error("failed to parse json: {0}")]
+    FailedToParseJson(String),
+
+    #[error("failed to parse hex string: {0}")]
+    FailedToParseHex(String),
+
+    #[error("value {value} cannot be stored in a column of {data_type}")]
+    IncompatibleDataType { data_type: String, value: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,10 +74,21 @@ pub enum Value {
     I64(i64),
     F64(f64),
     Str(String),
+    /// Days since `1970-01-01`
+    Date(i32),
+    /// Microseconds since `1970-01-01 00:00:00`
+    Timestamp(i64),
+    /// Text of the document as `format_json` writes it
+    Json(String),
+    Bytes(Vec<u8>),
     OptBool(Option<bool>),
     OptI64(Option<i64>),
     OptF64(Option<f64>),
     OptStr(Option<String>),
+    OptDate(Option<i32>),
+    OptTimestamp(Option<i64>),
+    OptJson(Option<String>),
+    OptBytes(Option<Vec<u8>>),
     Empty,
 }
 
@@ -72,10 +111,30 @@ impl PartialEq<Value> for Value {
             | (Value::OptStr(Some(l)), Value::Str(r))
             | (Value::Str(l), Value::OptStr(Some(r)))
             | (Value::OptStr(Some(l)), Value::OptStr(Some(r))) => l == r,
+            (Value::Date(l), Value::Date(r))
+            | (Value::OptDate(Some(l)), Value::Date(r))
+            | (Value::Date(l), Value::OptDate(Some(r)))
+            | (Value::OptDate(Some(l)), Value::OptDate(Some(r))) => l == r,
+            (Value::Timestamp(l), Value::Timestamp(r))
+            | (Value::OptTimestamp(Some(l)), Value::Timestamp(r))
+            | (Value::Timestamp(l), Value::OptTimestamp(Some(r)))
+            | (Value::OptTimestamp(Some(l)), Value::OptTimestamp(Some(r))) => l == r,
+            (Value::Json(l), Value::Json(r))
+            | (Value::OptJson(Some(l)), Value::Json(r))
+            | (Value::Json(l), Value::OptJson(Some(r)))
+            | (Value::OptJson(Some(l)), Value::OptJson(Some(r))) => l == r,
+            (Value::Bytes(l), Value::Bytes(r))
+            | (Value::OptBytes(Some(l)), Value::Bytes(r))
+            | (Value::Bytes(l), Value::OptBytes(Some(r)))
+            | (Value::OptBytes(Some(l)), Value::OptBytes(Some(r))) => l == r,
             (Value::OptBool(None), Value::OptBool(None))
             | (Value::OptI64(None), Value::OptI64(None))
             | (Value::OptF64(None), Value::OptF64(None))
             | (Value::OptStr(None), Value::OptStr(None))
+            | (Value::OptDate(None), Value::OptDate(None))
+            | (Value::OptTimestamp(None), Value::OptTimestamp(None))
+            | (Value::OptJson(None), Value::OptJson(None))
+            | (Value::OptBytes(None), Value::OptBytes(None))
             | (Value::Empty, Value::Empty) => true,
             _ => false,
         }
@@ -90,7 +149,7 @@ impl PartialEq<AstValue> for Value {
             (Value::I64(l), AstValue::Number(r))
             | (Value::OptI64(Some(l)), AstValue::Number(r)) => match r.parse::<i64>() {
                 Ok(r) => l == &r,
-                Err(_) => false,
+                Err(_) => r.parse::<f64>().is_ok_and(|r| *l as f64 == r),
             },
             (Value::F64(l), AstValue::Number(r))
             | (Value::OptF64(Some(l)), AstValue::Number(r)) => match r.parse::<f64>() {
@@ -99,10 +158,28 @@ impl PartialEq<AstValue> for Value {
             },
             (Value::Str(l), AstValue::SingleQuotedString(r))
             | (Value::OptStr(Some(l)), AstValue::SingleQuotedString(r)) => l == r,
+            (Value::Date(_), AstValue::SingleQuotedString(_))
+            | (Value::OptDate(Some(_)), AstValue::SingleQuotedString(_))
+            | (Value::Timestamp(_), AstValue::SingleQuotedString(_))
+            | (Value::OptTimestamp(Some(_)), AstValue::SingleQuotedString(_)) => {
+                self.partial_cmp(other) == Some(Ordering::Equal)
+            }
+            (Value::Json(l), AstValue::SingleQuotedString(r))
+            | (Value::OptJson(Some(l)), AstValue::SingleQuotedString(r)) => {
+                format_json(r).as_ref() == Some(l)
+            }
+            (Value::Bytes(l), AstValue::HexStringLiteral(r))
+            | (Value::OptBytes(Some(l)), AstValue::HexStringLiteral(r)) => {
+                parse_hex(r).as_ref() == Some(l)
+            }
             (Value::OptBool(None), AstValue::Null)
             | (Value::OptI64(None), AstValue::Null)
             | (Value::OptF64(None), AstValue::Null)
-            | (Value::OptStr(None), AstValue::Null) => true,
+            | (Value::OptStr(None), AstValue::Null)
+            | (Value::OptDate(None), AstValue::Null)
+            | (Value::OptTimestamp(None), AstValue::Null)
+            | (Value::OptJson(None), AstValue::Null)
+            | (Value::OptBytes(None), AstValue::Null) => true,
             _ => false,
         }
     }
@@ -110,6 +187,10 @@ impl PartialEq<AstValue> for Value {
 
 impl PartialOrd<Value> for Value {
     fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        if let Some((l, r)) = self.coerce(other) {
+            return l.partial_cmp(&r);
+        }
+
         match (self, other) {
             (Value::I64(l), Value::I64(r))
             | (Value::OptI64(Some(l)), Value::I64(r))
@@ -123,6 +204,18 @@ impl PartialOrd<Value> for Value {
             | (Value::OptStr(Some(l)), Value::Str(r))
             | (Value::Str(l), Value::OptStr(Some(r)))
             | (Value::OptStr(Some(l)), Value::OptStr(Some(r))) => Some(l.cmp(r)),
+            (Value::Date(l), Value::Date(r))
+            | (Value::OptDate(Some(l)), Value::Date(r))
+            | (Value::Date(l), Value::OptDate(Some(r)))
+            | (Value::OptDate(Some(l)), Value::OptDate(Some(r))) => Some(l.cmp(r)),
+            (Value::Timestamp(l), Value::Timestamp(r))
+            | (Value::OptTimestamp(Some(l)), Value::Timestamp(r))
+            | (Value::Timestamp(l), Value::OptTimestamp(Some(r)))
+            | (Value::OptTimestamp(Some(l)), Value::OptTimestamp(Some(r))) => Some(l.cmp(r)),
+            (Value::Bytes(l), Value::Bytes(r))
+            | (Value::OptBytes(Some(l)), Value::Bytes(r))
+            | (Value::Bytes(l), Value::OptBytes(Some(r)))
+            | (Value::OptBytes(Some(l)), Value::OptBytes(Some(r))) => Some(l.cmp(r)),
             _ => None,
         }
     }
@@ -134,7 +227,10 @@ impl PartialOrd<AstValue> for Value {
             (Value::I64(l), AstValue::Number(r))
             | (Value::OptI64(Some(l)), AstValue::Number(r)) => match r.parse::<i64>() {
                 Ok(r) => Some(l.cmp(&r)),
-                Err(_) => None,
+                Err(_) => r
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|r| (*l as f64).partial_cmp(&r)),
             },
             (Value::F64(l), AstValue::Number(r))
             | (Value::OptF64(Some(l)), AstValue::Number(r)) => match r.parse::<f64>() {
@@ -143,6 +239,19 @@ impl PartialOrd<AstValue> for Value {
             },
             (Value::Str(l), AstValue::SingleQuotedString(r))
             | (Value::OptStr(Some(l)), AstValue::SingleQuotedString(r)) => Some(l.cmp(r)),
+            (Value::Date(l), AstValue::SingleQuotedString(r))
+            | (Value::OptDate(Some(l)), AstValue::SingleQuotedString(r)) => match parse_date(r) {
+                Some(r) => Some(l.cmp(&r)),
+                None => parse_timestamp(r).map(|r| date_to_timestamp(*l).cmp(&r)),
+            },
+            (Value::Timestamp(l), AstValue::SingleQuotedString(r))
+            | (Value::OptTimestamp(Some(l)), AstValue::SingleQuotedString(r)) => {
+                parse_timestamp(r).map(|r| l.cmp(&r))
+            }
+            (Value::Bytes(l), AstValue::HexStringLiteral(r))
+            | (Value::OptBytes(Some(l)), AstValue::HexStringLiteral(r)) => {
+                parse_hex(r).map(|r| l.cmp(&r))
+            }
             _ => None,
         }
     }
@@ -158,6 +267,11 @@ impl TryFrom<&AstValue> for Value {
                 .map_or_else(|_| v.parse::<f64>().map(Value::F64), |v| Ok(Value::I64(v)))
                 .map_err(|_| ValueError::FailedToParseNumber.into()),
             AstValue::Boolean(v) => Ok(Value::Bool(*v)),
+            AstValue::SingleQuotedString(v) => Ok(Value::Str(v.clone())),
+            AstValue::HexStringLiteral(v) => parse_hex(v)
+                .map(Value::Bytes)
+                .ok_or_else(|| ValueError::FailedToParseHex(v.clone()).into()),
+            AstValue::Null => Ok(Value::Empty),
             _ => Err(ValueError::SqlTypeNotSupported.into()),
         }
     }
@@ -173,7 +287,12 @@ impl TryInto<GroupKey> for &Value {
             Bool(v) | OptBool(Some(v)) => Ok(GroupKey::Bool(*v)),
             I64(v) | OptI64(Some(v)) => Ok(GroupKey::I64(*v)),
             Str(v) | OptStr(Some(v)) => Ok(GroupKey::Str(v.clone())),
-            Empty | OptBool(None) | OptI64(None) | OptStr(None) => Ok(GroupKey::Null),
+            Date(v) | OptDate(Some(v)) => Ok(GroupKey::Date(*v)),
+            Timestamp(v) | OptTimestamp(Some(v)) => Ok(GroupKey::Timestamp(*v)),
+            Json(v) | OptJson(Some(v)) => Ok(GroupKey::Json(v.clone())),
+            Bytes(v) | OptBytes(Some(v)) => Ok(GroupKey::Bytes(v.clone())),
+            Empty | OptBool(None) | OptI64(None) | OptStr(None) | OptDate(None)
+            | OptTimestamp(None) | OptJson(None) | OptBytes(None) => Ok(GroupKey::Null),
             F64(_) | OptF64(_) => Err(ValueError::FloatCannotBeGroupedBy.into()),
         }
     }
@@ -189,13 +308,27 @@ impl TryInto<GroupKey> for Value {
             Bool(v) | OptBool(Some(v)) => Ok(GroupKey::Bool(v)),
             I64(v) | OptI64(Some(v)) => Ok(GroupKey::I64(v)),
             Str(v) | OptStr(Some(v)) => Ok(GroupKey::Str(v)),
-            Empty | OptBool(None) | OptI64(None) | OptStr(None) => Ok(GroupKey::Null),
+            Date(v) | OptDate(Some(v)) => Ok(GroupKey::Date(v)),
+            Timestamp(v) | OptTimestamp(Some(v)) => Ok(GroupKey::Timestamp(v)),
+            Json(v) | OptJson(Some(v)) => Ok(GroupKey::Json(v)),
+            Bytes(v) | OptBytes(Some(v)) => Ok(GroupKey::Bytes(v)),
+            Empty | OptBool(None) | OptI64(None) | OptStr(None) | OptDate(None)
+            | OptTimestamp(None) | OptJson(None) | OptBytes(None) => Ok(GroupKey::Null),
             F64(_) | OptF64(_) => Err(ValueError::FloatCannotBeGroupedBy.into()),
         }
     }
 }
 
+/// `JSON` is not a type of the parser, it is a custom type of that name.
+fn is_json(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Custom(name) => name.to_string().eq_ignore_ascii_case("JSON"),
+        _ => false,
+    }
+}
+
 trait BoolToValue: Sized {
+    #[allow(clippy::wrong_self_convention)]
     fn as_value(self, v1: Value, v2: Value) -> Value;
 }
 
@@ -228,6 +361,20 @@ impl Value {
             (DataType::Boolean, AstValue::Boolean(v)) => {
                 Ok(nullable.as_value(Value::OptBool(Some(*v)), Value::Bool(*v)))
             }
+            (DataType::Date, AstValue::SingleQuotedString(v)) => parse_date(v)
+                .map(|v| nullable.as_value(Value::OptDate(Some(v)), Value::Date(v)))
+                .ok_or_else(|| ValueError::FailedToParseDate(v.clone()).into()),
+            (DataType::Timestamp, AstValue::SingleQuotedString(v)) => parse_timestamp(v)
+                .map(|v| nullable.as_value(Value::OptTimestamp(Some(v)), Value::Timestamp(v)))
+                .ok_or_else(|| ValueError::FailedToParseTimestamp(v.clone()).into()),
+            (DataType::Custom(_), AstValue::SingleQuotedString(v)) if is_json(data_type) => {
+                format_json(v)
+                    .map(|v| nullable.as_value(Value::OptJson(Some(v.clone())), Value::Json(v)))
+                    .ok_or_else(|| ValueError::FailedToParseJson(v.clone()).into())
+            }
+            (DataType::Bytea, AstValue::HexStringLiteral(v)) => parse_hex(v)
+                .map(|v| nullable.as_value(Value::OptBytes(Some(v.clone())), Value::Bytes(v)))
+                .ok_or_else(|| ValueError::FailedToParseHex(v.clone()).into()),
             (DataType::Int, AstValue::Null) => nullable.as_result(
                 Value::OptI64(None),
                 ValueError::NullValueOnNotNullField.into(),
@@ -244,6 +391,22 @@ impl Value {
                 Value::OptStr(None),
                 ValueError::NullValueOnNotNullField.into(),
             ),
+            (DataType::Date, AstValue::Null) => nullable.as_result(
+                Value::OptDate(None),
+                ValueError::NullValueOnNotNullField.into(),
+            ),
+            (DataType::Timestamp, AstValue::Null) => nullable.as_result(
+                Value::OptTimestamp(None),
+                ValueError::NullValueOnNotNullField.into(),
+            ),
+            (DataType::Custom(_), AstValue::Null) if is_json(data_type) => nullable.as_result(
+                Value::OptJson(None),
+                ValueError::NullValueOnNotNullField.into(),
+            ),
+            (DataType::Bytea, AstValue::Null) => nullable.as_result(
+                Value::OptBytes(None),
+                ValueError::NullValueOnNotNullField.into(),
+            ),
             _ => Err(ValueError::SqlTypeNotSupported.into()),
         }
     }
@@ -276,6 +439,34 @@ impl Value {
             (Value::Bool(_), AstValue::Boolean(v)) => Ok(Value::Bool(*v)),
             (Value::OptBool(_), AstValue::Boolean(v)) => Ok(Value::OptBool(Some(*v))),
             (Value::OptBool(_), AstValue::Null) => Ok(Value::OptBool(None)),
+            (Value::Date(_), AstValue::SingleQuotedString(v)) => parse_date(v)
+                .map(Value::Date)
+                .ok_or_else(|| ValueError::FailedToParseDate(v.clone()).into()),
+            (Value::OptDate(_), AstValue::SingleQuotedString(v)) => parse_date(v)
+                .map(|v| Value::OptDate(Some(v)))
+                .ok_or_else(|| ValueError::FailedToParseDate(v.clone()).into()),
+            (Value::OptDate(_), AstValue::Null) => Ok(Value::OptDate(None)),
+            (Value::Timestamp(_), AstValue::SingleQuotedString(v)) => parse_timestamp(v)
+                .map(Value::Timestamp)
+                .ok_or_else(|| ValueError::FailedToParseTimestamp(v.clone()).into()),
+            (Value::OptTimestamp(_), AstValue::SingleQuotedString(v)) => parse_timestamp(v)
+                .map(|v| Value::OptTimestamp(Some(v)))
+                .ok_or_else(|| ValueError::FailedToParseTimestamp(v.clone()).into()),
+            (Value::OptTimestamp(_), AstValue::Null) => Ok(Value::OptTimestamp(None)),
+            (Value::Json(_), AstValue::SingleQuotedString(v)) => format_json(v)
+                .map(Value::Json)
+                .ok_or_else(|| ValueError::FailedToParseJson(v.clone()).into()),
+            (Value::OptJson(_), AstValue::SingleQuotedString(v)) => format_json(v)
+                .map(|v| Value::OptJson(Some(v)))
+                .ok_or_else(|| ValueError::FailedToParseJson(v.clone()).into()),
+            (Value::OptJson(_), AstValue::Null) => Ok(Value::OptJson(None)),
+            (Value::Bytes(_), AstValue::HexStringLiteral(v)) => parse_hex(v)
+                .map(Value::Bytes)
+                .ok_or_else(|| ValueError::FailedToParseHex(v.clone()).into()),
+            (Value::OptBytes(_), AstValue::HexStringLiteral(v)) => parse_hex(v)
+                .map(|v| Value::OptBytes(Some(v)))
+                .ok_or_else(|| ValueError::FailedToParseHex(v.clone()).into()),
+            (Value::OptBytes(_), AstValue::Null) => Ok(Value::OptBytes(None)),
             _ => Err(ValueError::LiteralNotSupported.into()),
         }
     }
@@ -283,12 +474,17 @@ impl Value {
     pub fn add(&self, other: &Value) -> Result<Value> {
         use Value::*;
 
+        if let Some((l, r)) = self.coerce(other) {
+            return l.add(&r);
+        }
+
         match (self, other) {
             (I64(a), I64(b)) => Ok(I64(a + b)),
             (I64(a), OptI64(Some(b))) | (OptI64(Some(a)), I64(b)) => Ok(OptI64(Some(a + b))),
             (OptI64(Some(a)), OptI64(Some(b))) => Ok(OptI64(Some(a + b))),
             (F64(a), F64(b)) => Ok(F64(a + b)),
             (F64(a), OptF64(Some(b))) | (OptF64(Some(a)), F64(b)) => Ok(OptF64(Some(a + b))),
+            (OptF64(Some(a)), OptF64(Some(b))) => Ok(OptF64(Some(a + b))),
             (OptI64(None), OptI64(_))
             | (OptI64(_), OptI64(None))
             | (OptI64(None), I64(_))
@@ -304,11 +500,17 @@ impl Value {
     pub fn subtract(&self, other: &Value) -> Result<Value> {
         use Value::*;
 
+        if let Some((l, r)) = self.coerce(other) {
+            return l.subtract(&r);
+        }
+
         match (self, other) {
             (I64(a), I64(b)) => Ok(I64(a - b)),
             (I64(a), OptI64(Some(b))) | (OptI64(Some(a)), I64(b)) => Ok(OptI64(Some(a - b))),
+            (OptI64(Some(a)), OptI64(Some(b))) => Ok(OptI64(Some(a - b))),
             (F64(a), F64(b)) => Ok(F64(a - b)),
             (F64(a), OptF64(Some(b))) | (OptF64(Some(a)), F64(b)) => Ok(OptF64(Some(a - b))),
+            (OptF64(Some(a)), OptF64(Some(b))) => Ok(OptF64(Some(a - b))),
             (OptI64(None), OptI64(_))
             | (OptI64(_), OptI64(None))
             | (OptI64(None), I64(_))
@@ -324,11 +526,17 @@ impl Value {
     pub fn multiply(&self, other: &Value) -> Result<Value> {
         use Value::*;
 
+        if let Some((l, r)) = self.coerce(other) {
+            return l.multiply(&r);
+        }
+
         match (self, other) {
             (I64(a), I64(b)) => Ok(I64(a * b)),
             (I64(a), OptI64(Some(b))) | (OptI64(Some(a)), I64(b)) => Ok(OptI64(Some(a * b))),
+            (OptI64(Some(a)), OptI64(Some(b))) => Ok(OptI64(Some(a * b))),
             (F64(a), F64(b)) => Ok(F64(a * b)),
             (F64(a), OptF64(Some(b))) | (OptF64(Some(a)), F64(b)) => Ok(OptF64(Some(a * b))),
+            (OptF64(Some(a)), OptF64(Some(b))) => Ok(OptF64(Some(a * b))),
             (OptI64(None), OptI64(_))
             | (OptI64(_), OptI64(None))
             | (OptI64(None), I64(_))
@@ -341,14 +549,27 @@ impl Value {
         }
     }
 
+    /// Integer division when both operands are integers, the quotient is truncated toward zero,
+    /// and float division when either one is a float, e.g. `7 / 2` is `3` but `7 / 2.0` is `3.5`.
+    /// Dividing by zero is an error even for floats, unless the dividend is `NULL`.
     pub fn divide(&self, other: &Value) -> Result<Value> {
         use Value::*;
 
+        if let Some((l, r)) = self.coerce(other) {
+            return l.divide(&r);
+        }
+
+        if other.is_zero() && self.is_some() {
+            return Err(ValueError::DivisorShouldNotBeZero.into());
+        }
+
         match (self, other) {
             (I64(a), I64(b)) => Ok(I64(a / b)),
             (I64(a), OptI64(Some(b))) | (OptI64(Some(a)), I64(b)) => Ok(OptI64(Some(a / b))),
+            (OptI64(Some(a)), OptI64(Some(b))) => Ok(OptI64(Some(a / b))),
             (F64(a), F64(b)) => Ok(F64(a / b)),
             (F64(a), OptF64(Some(b))) | (OptF64(Some(a)), F64(b)) => Ok(OptF64(Some(a / b))),
+            (OptF64(Some(a)), OptF64(Some(b))) => Ok(OptF64(Some(a / b))),
             (OptI64(None), OptI64(_))
             | (OptI64(_), OptI64(None))
             | (OptI64(None), I64(_))
@@ -361,14 +582,284 @@ impl Value {
         }
     }
 
-    pub fn is_some(&self) -> bool {
+    /// Remainder of `divide`, which has the sign of the dividend, e.g. `-7 % 3` is `-1`.
+    pub fn modulo(&self, other: &Value) -> Result<Value> {
+        use Value::*;
+
+        if let Some((l, r)) = self.coerce(other) {
+            return l.modulo(&r);
+        }
+
+        if other.is_zero() && self.is_some() {
+            return Err(ValueError::DivisorShouldNotBeZero.into());
+        }
+
+        match (self, other) {
+            (I64(a), I64(b)) => Ok(I64(a % b)),
+            (I64(a), OptI64(Some(b))) | (OptI64(Some(a)), I64(b)) => Ok(OptI64(Some(a % b))),
+            (OptI64(Some(a)), OptI64(Some(b))) => Ok(OptI64(Some(a % b))),
+            (F64(a), F64(b)) => Ok(F64(a % b)),
+            (F64(a), OptF64(Some(b))) | (OptF64(Some(a)), F64(b)) => Ok(OptF64(Some(a % b))),
+            (OptF64(Some(a)), OptF64(Some(b))) => Ok(OptF64(Some(a % b))),
+            (OptI64(None), OptI64(_))
+            | (OptI64(_), OptI64(None))
+            | (OptI64(None), I64(_))
+            | (I64(_), OptI64(None)) => Ok(OptI64(None)),
+            (OptF64(_), OptF64(None))
+            | (OptF64(None), OptF64(_))
+            | (F64(_), OptF64(None))
+            | (OptF64(None), F64(_)) => Ok(OptF64(None)),
+            _ => Err(ValueError::ModuloOnNonNumeric.into()),
+        }
+    }
+
+    pub fn cast(&self, data_type: &DataType) -> Result<Value> {
+        use Value::*;
+
+        let impossible_cast = || ValueError::ImpossibleCast.into();
+
+        match (data_type, self) {
+            (DataType::Int, value) if !value.is_some() => Ok(OptI64(None)),
+            (DataType::Float(_), value) if !value.is_some() => Ok(OptF64(None)),
+            (DataType::Text, value) if !value.is_some() => Ok(OptStr(None)),
+            (DataType::Boolean, value) if !value.is_some() => Ok(OptBool(None)),
+            (DataType::Date, value) if !value.is_some() => Ok(OptDate(None)),
+            (DataType::Timestamp, value) if !value.is_some() => Ok(OptTimestamp(None)),
+            (DataType::Custom(_), value) if is_json(data_type) && !value.is_some() => {
+                Ok(OptJson(None))
+            }
+            (DataType::Bytea, value) if !value.is_some() => Ok(OptBytes(None)),
+
+            (DataType::Int, I64(v)) | (DataType::Int, OptI64(Some(v))) => Ok(I64(*v)),
+            (DataType::Int, F64(v)) | (DataType::Int, OptF64(Some(v))) => Ok(I64(v.round() as i64)),
+            (DataType::Int, Bool(v)) | (DataType::Int, OptBool(Some(v))) => {
+                Ok(I64(if *v { 1 } else { 0 }))
+            }
+            (DataType::Int, Str(v)) | (DataType::Int, OptStr(Some(v))) => {
+                v.trim().parse().map(I64).map_err(|_| impossible_cast())
+            }
+
+            (DataType::Float(_), I64(v)) | (DataType::Float(_), OptI64(Some(v))) => {
+                Ok(F64(*v as f64))
+            }
+            (DataType::Float(_), F64(v)) | (DataType::Float(_), OptF64(Some(v))) => Ok(F64(*v)),
+            (DataType::Float(_), Str(v)) | (DataType::Float(_), OptStr(Some(v))) => {
+                v.trim().parse().map(F64).map_err(|_| impossible_cast())
+            }
+
+            (DataType::Text, I64(v)) | (DataType::Text, OptI64(Some(v))) => Ok(Str(v.to_string())),
+            (DataType::Text, F64(v)) | (DataType::Text, OptF64(Some(v))) => Ok(Str(v.to_string())),
+            (DataType::Text, Bool(v)) | (DataType::Text, OptBool(Some(v))) => {
+                Ok(Str(if *v { "TRUE" } else { "FALSE" }.to_owned()))
+            }
+            (DataType::Text, Str(v)) | (DataType::Text, OptStr(Some(v))) => Ok(Str(v.clone())),
+            (DataType::Text, Date(v)) | (DataType::Text, OptDate(Some(v))) => {
+                Ok(Str(format_date(*v)))
+            }
+            (DataType::Text, Timestamp(v)) | (DataType::Text, OptTimestamp(Some(v))) => {
+                Ok(Str(format_timestamp(*v)))
+            }
+            (DataType::Text, Json(v)) | (DataType::Text, OptJson(Some(v))) => Ok(Str(v.clone())),
+            (DataType::Text, Bytes(v)) | (DataType::Text, OptBytes(Some(v))) => {
+                String::from_utf8(v.clone())
+                    .map(Str)
+                    .map_err(|_| impossible_cast())
+            }
+
+            (DataType::Boolean, Bool(v)) | (DataType::Boolean, OptBool(Some(v))) => Ok(Bool(*v)),
+            (DataType::Boolean, I64(v)) | (DataType::Boolean, OptI64(Some(v))) => Ok(Bool(*v != 0)),
+            (DataType::Boolean, Str(v)) | (DataType::Boolean, OptStr(Some(v))) => {
+                match v.trim().to_uppercase().as_str() {
+                    "TRUE" => Ok(Bool(true)),
+                    "FALSE" => Ok(Bool(false)),
+                    _ => Err(impossible_cast()),
+                }
+            }
+
+            (DataType::Date, Date(v)) | (DataType::Date, OptDate(Some(v))) => Ok(Date(*v)),
+            (DataType::Date, Timestamp(v)) | (DataType::Date, OptTimestamp(Some(v))) => {
+                Ok(Date(timestamp_to_date(*v)))
+            }
+            (DataType::Date, Str(v)) | (DataType::Date, OptStr(Some(v))) => {
+                parse_date(v).map(Date).ok_or_else(impossible_cast)
+            }
+
+            (DataType::Timestamp, Timestamp(v)) | (DataType::Timestamp, OptTimestamp(Some(v))) => {
+                Ok(Timestamp(*v))
+            }
+            (DataType::Timestamp, Date(v)) | (DataType::Timestamp, OptDate(Some(v))) => {
+                Ok(Timestamp(date_to_timestamp(*v)))
+            }
+            (DataType::Timestamp, Str(v)) | (DataType::Timestamp, OptStr(Some(v))) => {
+                parse_timestamp(v)
+                    .map(Timestamp)
+                    .ok_or_else(impossible_cast)
+            }
+
+            (DataType::Custom(_), Json(v)) | (DataType::Custom(_), OptJson(Some(v)))
+                if is_json(data_type) =>
+            {
+                Ok(Json(v.clone()))
+            }
+            (DataType::Custom(_), Str(v)) | (DataType::Custom(_), OptStr(Some(v)))
+                if is_json(data_type) =>
+            {
+                format_json(v).map(Json).ok_or_else(impossible_cast)
+            }
+
+            (DataType::Bytea, Bytes(v)) | (DataType::Bytea, OptBytes(Some(v))) => {
+                Ok(Bytes(v.clone()))
+            }
+            (DataType::Bytea, Str(v)) | (DataType::Bytea, OptStr(Some(v))) => {
+                Ok(Bytes(v.as_bytes().to_vec()))
+            }
+
+            (DataType::Int, _)
+            | (DataType::Float(_), _)
+            | (DataType::Text, _)
+            | (DataType::Boolean, _)
+            | (DataType::Date, _)
+            | (DataType::Timestamp, _)
+            | (DataType::Bytea, _) => Err(impossible_cast()),
+            (DataType::Custom(_), _) if is_json(data_type) => Err(impossible_cast()),
+            _ => Err(ValueError::SqlTypeNotSupported.into()),
+        }
+    }
+
+    /// Value to be stored in a column of `data_type`, which should have the type of the column,
+    /// except that an integer can be stored as a float and a date as a timestamp.
+    pub fn to_column(&self, data_type: &DataType, nullable: bool) -> Result<Value> {
+        use Value::*;
+
+        if !self.is_some() {
+            return if nullable {
+                self.cast(data_type)
+            } else {
+                Err(ValueError::NullValueOnNotNullField.into())
+            };
+        }
+
+        let compatible = match (data_type, self) {
+            (DataType::Int, I64(_))
+            | (DataType::Int, OptI64(_))
+            | (DataType::Float(_), F64(_))
+            | (DataType::Float(_), OptF64(_))
+            | (DataType::Float(_), I64(_))
+            | (DataType::Float(_), OptI64(_))
+            | (DataType::Text, Str(_))
+            | (DataType::Text, OptStr(_))
+            | (DataType::Boolean, Bool(_))
+            | (DataType::Boolean, OptBool(_))
+            | (DataType::Date, Date(_))
+            | (DataType::Date, OptDate(_))
+            | (DataType::Timestamp, Timestamp(_))
+            | (DataType::Timestamp, OptTimestamp(_))
+            | (DataType::Timestamp, Date(_))
+            | (DataType::Timestamp, OptDate(_))
+            | (DataType::Bytea, Bytes(_))
+            | (DataType::Bytea, OptBytes(_)) => true,
+            (DataType::Custom(_), Json(_)) | (DataType::Custom(_), OptJson(_)) => {
+                is_json(data_type)
+            }
+            _ => false,
+        };
+
+        if !compatible {
+            return Err(ValueError::IncompatibleDataType {
+                data_type: data_type.to_string(),
+                value: format!("{:?}", self),
+            }
+            .into());
+        }
+
+        let value = self.cast(data_type)?;
+
+        Ok(if nullable {
+            value.into_nullable()
+        } else {
+            value
+        })
+    }
+
+    fn into_nullable(self) -> Value {
+        use Value::*;
+
+        match self {
+            Bool(v) => OptBool(Some(v)),
+            I64(v) => OptI64(Some(v)),
+            F64(v) => OptF64(Some(v)),
+            Str(v) => OptStr(Some(v)),
+            Date(v) => OptDate(Some(v)),
+            Timestamp(v) => OptTimestamp(Some(v)),
+            Json(v) => OptJson(Some(v)),
+            Bytes(v) => OptBytes(Some(v)),
+            value => value,
+        }
+    }
+
+    /// Same as `==` except that an integer and a float are compared as floating numbers, and a
+    /// date and a timestamp are compared as timestamps.
+    pub fn eq_coerced(&self, other: &Value) -> bool {
+        match self.coerce(other) {
+            Some((l, r)) => l == r,
+            None => self == other,
+        }
+    }
+
+    /// When one of operands is a float and the other is an integer, the integer is converted
+    /// to a float. Likewise, a date is converted to the timestamp of its midnight when the
+    /// other one is a timestamp. `NULL` of the narrower type becomes `NULL` of the wider type.
+    fn coerce(&self, other: &Value) -> Option<(Value, Value)> {
+        use Value::*;
+
+        let widen = |value: &Value| match value {
+            I64(v) => Some(F64(*v as f64)),
+            OptI64(v) => Some(OptF64(v.map(|v| v as f64))),
+            Date(v) => Some(Timestamp(date_to_timestamp(*v))),
+            OptDate(v) => Some(OptTimestamp(v.map(date_to_timestamp))),
+            _ => None,
+        };
+        let is_wider = |value: &Value, narrower: &Value| match value {
+            F64(_) | OptF64(_) => matches!(narrower, I64(_) | OptI64(_)),
+            Timestamp(_) | OptTimestamp(_) => matches!(narrower, Date(_) | OptDate(_)),
+            _ => false,
+        };
+
+        if is_wider(self, other) {
+            widen(other).map(|other| (self.clone(), other))
+        } else if is_wider(other, self) {
+            widen(self).map(|value| (value, other.clone()))
+        } else {
+            None
+        }
+    }
+
+    fn is_zero(&self) -> bool {
         use Value::*;
 
         match self {
-            Empty | OptBool(None) | OptI64(None) | OptF64(None) | OptStr(None) => false,
-            _ => true,
+            I64(v) | OptI64(Some(v)) => *v == 0,
+            F64(v) | OptF64(Some(v)) => *v == 0.0,
+            _ => false,
         }
     }
+
+    pub fn is_some(&self) -> bool {
+        use Value::*;
+
+        !matches!(
+            self,
+            Empty
+                | OptBool(None)
+                | OptI64(None)
+                | OptF64(None)
+                | OptStr(None)
+                | OptDate(None)
+                | OptTimestamp(None)
+                | OptJson(None)
+                | OptBytes(None)
+        )
+    }
 }
 
 #[cfg(test)]
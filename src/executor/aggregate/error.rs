@@ -13,6 +13,15 @@ pub enum AggregateError {
     #[error("only identifier is allowed in aggregation")]
     OnlyIdentifierAllowed,
 
+    #[error("only columns are allowed in ROLLUP and CUBE: {0}")]
+    GroupingSetNotColumn(String),
+
+    #[error("GROUPING should take a column of GROUP BY: {0}")]
+    GroupingNotGrouped(String),
+
+    #[error("separator of GROUP_CONCAT should be a string literal")]
+    SeparatorNotString,
+
     #[error("unreachable")]
     Unreachable,
 }
@@ -0,0 +1,175 @@
+use std::rc::Rc;
+
+use sqlparser::ast::{Expr, Function};
+
+use super::error::AggregateError;
+use crate::data::{get_name, Row, Value};
+use crate::executor::context::BlendContext;
+use crate::executor::evaluate::is_function;
+use crate::result::Result;
+
+/// Grouping columns of `GROUP BY` and its grouping sets, each of which is whether it keeps each
+/// of the columns. `ROLLUP(a, b)` is `(a, b), (a), ()` and `CUBE(a, b)` is every subset of
+/// `a, b`, other items of `GROUP BY` are kept in every set.
+pub struct GroupingSets<'a> {
+    pub columns: Vec<&'a Expr>,
+    pub sets: Vec<Vec<bool>>,
+}
+
+impl<'a> GroupingSets<'a> {
+    pub fn new(group_by: &'a [Expr]) -> Result<Self> {
+        group_by.iter().try_fold(
+            Self {
+                columns: vec![],
+                sets: vec![vec![]],
+            },
+            |Self { mut columns, sets }, expr| {
+                let (exprs, item_sets) = get_item_sets(expr)?;
+                let sets = sets
+                    .iter()
+                    .flat_map(|set| {
+                        item_sets
+                            .iter()
+                            .map(move |item_set| [set.as_slice(), item_set].concat())
+                    })
+                    .collect();
+
+                columns.extend(exprs);
+
+                Ok(Self { columns, sets })
+            },
+        )
+    }
+}
+
+fn get_item_sets(expr: &Expr) -> Result<(Vec<&Expr>, Vec<Vec<bool>>)> {
+    let (name, args) = match expr {
+        Expr::Function(Function {
+            name,
+            args,
+            over: None,
+            distinct: false,
+        }) => (get_name(name)?.to_uppercase(), args),
+        _ => {
+            return Ok((vec![expr], vec![vec![true]]));
+        }
+    };
+
+    let n = args.len();
+    let sets = match name.as_str() {
+        "ROLLUP" => (0..=n)
+            .rev()
+            .map(|k| (0..n).map(|i| i < k).collect())
+            .collect(),
+        "CUBE" => (0..1 << n)
+            .rev()
+            .map(|mask: usize| (0..n).map(|i| mask & (1 << (n - 1 - i)) != 0).collect())
+            .collect(),
+        _ => {
+            return Ok((vec![expr], vec![vec![true]]));
+        }
+    };
+
+    args.iter()
+        .map(|arg| match arg {
+            Expr::Identifier(_) | Expr::CompoundIdentifier(_) => Ok(arg),
+            _ => Err(AggregateError::GroupingSetNotColumn(arg.to_string()).into()),
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|columns| (columns, sets))
+}
+
+/// Context of which the `columns` are `NULL`, each of them is found as `BlendContext::get_value`
+/// or `BlendContext::get_alias_value` finds it.
+pub fn collapse<'a>(context: &BlendContext<'a>, columns: Vec<&Expr>) -> Rc<BlendContext<'a>> {
+    let BlendContext {
+        table_alias,
+        columns: names,
+        row,
+        next,
+    } = context;
+
+    let position = |expr: &Expr| match expr {
+        Expr::Identifier(ident) => names.iter().position(|name| name.value == ident.value),
+        Expr::CompoundIdentifier(idents)
+            if idents.len() == 2 && idents[0].value == *table_alias =>
+        {
+            names.iter().position(|name| name.value == idents[1].value)
+        }
+        _ => None,
+    };
+    let positions = columns
+        .iter()
+        .filter_map(|expr| position(expr))
+        .collect::<Vec<_>>();
+    let rest = columns
+        .into_iter()
+        .filter(|expr| position(expr).is_none())
+        .collect::<Vec<_>>();
+
+    let row = row.as_ref().map(|Row(values)| {
+        let values = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                if positions.contains(&i) {
+                    Value::Empty
+                } else {
+                    value.clone()
+                }
+            })
+            .collect();
+
+        Row(values)
+    });
+    let next = match next {
+        Some(next) if !rest.is_empty() => Some(collapse(next, rest)),
+        next => next.clone(),
+    };
+
+    Rc::new(BlendContext {
+        table_alias,
+        columns: Rc::clone(names),
+        row,
+        next,
+    })
+}
+
+pub fn find_groupings(expr: &Expr) -> Vec<&Function> {
+    match expr {
+        Expr::Function(func) => match get_name(&func.name) {
+            Ok(name) if func.over.is_none() && name.eq_ignore_ascii_case("GROUPING") => {
+                vec![func]
+            }
+            Ok(name) if is_function(name) => func.args.iter().flat_map(find_groupings).collect(),
+            _ => vec![],
+        },
+        Expr::Between {
+            expr, low, high, ..
+        } => [expr, low, high]
+            .iter()
+            .flat_map(|expr| find_groupings(expr))
+            .collect(),
+        Expr::BinaryOp { left, right, .. } => [left, right]
+            .iter()
+            .flat_map(|expr| find_groupings(expr))
+            .collect(),
+        Expr::UnaryOp { expr, .. } | Expr::Nested(expr) | Expr::Cast { expr, .. } => {
+            find_groupings(expr)
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .iter()
+            .chain(else_result)
+            .map(AsRef::as_ref)
+            .chain(conditions)
+            .chain(results)
+            .flat_map(find_groupings)
+            .collect(),
+        _ => vec![],
+    }
+}
@@ -9,6 +9,10 @@ pub enum GroupKey {
     I64(i64),
     Bool(bool),
     Str(String),
+    Date(i32),
+    Timestamp(i64),
+    Json(String),
+    Bytes(Vec<u8>),
     Null,
 }
 
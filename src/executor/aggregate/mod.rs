@@ -1,41 +1,54 @@
 mod error;
+mod grouping;
 mod hash;
 mod state;
+mod stream;
 
 use boolinator::Boolinator;
+use im_rc::HashMap;
 use iter_enum::Iterator;
+use itertools::Itertools;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::rc::Rc;
 
-use sqlparser::ast::{Expr, Function, SelectItem};
+use sqlparser::ast::{Expr, Function, OrderByExpr, SelectItem, Value as AstValue, WindowSpec};
 
 use super::context::{AggregateContext, BlendContext, FilterContext, UnionContext};
-use super::evaluate::{evaluate_union, Evaluated};
+use super::evaluate::{evaluate_union, is_function, Evaluated};
 use super::filter::check_blended_expr;
 use crate::data::{get_name, Value};
+use crate::parse::AGGREGATE_FILTER;
 use crate::result::Result;
 use crate::store::Store;
 
 pub use error::AggregateError;
+use grouping::{collapse, find_groupings, GroupingSets};
 pub use hash::GroupKey;
-use state::State;
+use state::{ExportedRow, State};
+pub use stream::{get_ascending_column, is_sorted_by_group};
 
 #[derive(Iterator)]
-enum Aggregated<I1, I2> {
+enum Aggregated<I1, I2, I3> {
     Applied(I1),
-    Skipped(I2),
+    Streamed(I2),
+    Skipped(I3),
 }
 
-pub struct Aggregate<'a, T: 'static + Debug> {
+/// `sorted_input` is whether rows of each group arrive one after another, see
+/// `is_sorted_by_group`. Groups of sorted rows are aggregated one at a time, otherwise every
+/// group is kept until the last row.
+pub struct Aggregate<'a, T: 'static + Debug + PartialEq> {
     storage: &'a dyn Store<T>,
     fields: &'a [SelectItem],
     group_by: &'a [Expr],
     having: Option<&'a Expr>,
+    order_by: &'a [OrderByExpr],
     filter_context: Option<&'a FilterContext<'a>>,
+    sorted_input: bool,
 }
 
-impl<'a, T: 'static + Debug> Aggregate<'a, T> {
+impl<'a, T: 'static + Debug + PartialEq> Aggregate<'a, T> {
     pub fn new(
         storage: &'a dyn Store<T>,
         fields: &'a [SelectItem],
@@ -48,14 +61,34 @@ impl<'a, T: 'static + Debug> Aggregate<'a, T> {
             fields,
             group_by,
             having,
+            order_by: &[],
             filter_context,
+            sorted_input: false,
         }
     }
 
+    /// Aggregates in `ORDER BY`, e.g. `ORDER BY SUM(a) - SUM(b)`, are aggregated as well, so
+    /// they can be sorted by even if they are not selected.
+    pub fn with_order_by(self, order_by: &'a [OrderByExpr]) -> Self {
+        Self { order_by, ..self }
+    }
+
+    pub fn with_sorted_input(self, sorted_input: bool) -> Self {
+        Self {
+            sorted_input,
+            ..self
+        }
+    }
+
+    /// Whether groups are aggregated one at a time, which needs `GROUP BY` and sorted rows.
+    pub fn is_streaming(&self) -> bool {
+        self.sorted_input && !self.group_by.is_empty()
+    }
+
     pub fn apply(
-        &self,
-        rows: impl Iterator<Item = Result<Rc<BlendContext<'a>>>>,
-    ) -> Result<impl Iterator<Item = Result<AggregateContext<'a>>>> {
+        self,
+        rows: impl Iterator<Item = Result<Rc<BlendContext<'a>>>> + 'a,
+    ) -> Result<impl Iterator<Item = Result<AggregateContext<'a>>> + 'a> {
         if !self.check_aggregate() {
             let rows = rows.map(|row| {
                 row.map(|blend_context| AggregateContext {
@@ -65,71 +98,255 @@ impl<'a, T: 'static + Debug> Aggregate<'a, T> {
             });
 
             return Ok(Aggregated::Skipped(rows));
+        } else if self.is_streaming() {
+            return Ok(Aggregated::Streamed(Rc::new(self).stream(rows)));
         }
 
+        let GroupingSets { columns, sets } = GroupingSets::new(self.group_by)?;
+        let rows = match sets.as_slice() {
+            [kept] => self.aggregate_set(rows, &columns, kept)?,
+            sets => {
+                let rows = rows.collect::<Result<Vec<_>>>()?;
+
+                sets.iter()
+                    .map(|kept| {
+                        let rows = rows.iter().map(|row| Ok(Rc::clone(row)));
+
+                        self.aggregate_set(rows, &columns, kept)
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .concat()
+            }
+        };
+
+        let rows = rows
+            .into_iter()
+            .filter_map(move |row| self.check_having(row).transpose());
+
+        Ok(Aggregated::Applied(rows))
+    }
+
+    /// Rows of a grouping set, which groups rows by the `columns` it keeps.
+    fn aggregate_set(
+        &self,
+        rows: impl Iterator<Item = Result<Rc<BlendContext<'a>>>>,
+        columns: &[&'a Expr],
+        kept: &[bool],
+    ) -> Result<Vec<ExportedRow<'a>>> {
+        let (grouped, collapsed): (Vec<_>, Vec<_>) =
+            columns.iter().zip(kept).partition(|(_, kept)| **kept);
+        let grouped = grouped
+            .into_iter()
+            .map(|(expr, _)| *expr)
+            .collect::<Vec<_>>();
+        let collapsed = collapsed
+            .into_iter()
+            .map(|(expr, _)| *expr)
+            .collect::<Vec<_>>();
+
         let state =
             rows.enumerate()
                 .try_fold::<_, _, Result<_>>(State::new(), |state, (index, row)| {
                     let context = row?;
-                    let evaluated: Vec<Evaluated<'_>> = self
-                        .group_by
-                        .iter()
-                        .map(|expr| {
-                            let union_context =
-                                UnionContext::new(self.filter_context, Some(&context));
-
-                            evaluate_union(self.storage, union_context, None, expr)
-                        })
-                        .collect::<Result<_>>()?;
-                    let group = evaluated
-                        .iter()
-                        .map(GroupKey::try_from)
-                        .collect::<Result<Vec<GroupKey>>>()?;
-
-                    let state = state.apply(index, group, Rc::clone(&context));
-                    let state = self
-                        .fields
-                        .iter()
-                        .try_fold(state, |state, field| match field {
-                            SelectItem::UnnamedExpr(expr)
-                            | SelectItem::ExprWithAlias { expr, .. } => {
-                                aggregate(state, &context, &expr)
-                            }
-                            _ => Ok(state),
-                        })?;
+                    let group = self.get_group(grouped.iter().copied(), &context)?;
 
-                    Ok(state)
+                    self.accumulate(state, index, group, context)
                 })?;
 
-        let storage = self.storage;
-        let filter_context = self.filter_context;
-        let having = self.having;
-        let rows = state
-            .export()
-            .into_iter()
-            .filter_map(|(aggregated, next)| next.map(|next| (aggregated, next)))
-            .filter_map(move |(aggregated, next)| match having {
-                Some(having) => {
-                    check_blended_expr(storage, filter_context, &next, aggregated.as_ref(), having)
-                        .map(|pass| pass.as_some(AggregateContext { aggregated, next }))
-                        .transpose()
+        let rows = match (state.export()?, grouped.is_empty()) {
+            (rows, true) if rows.is_empty() => vec![self.export_empty()?],
+            (rows, _) => rows,
+        };
+
+        rows.into_iter()
+            .map(|row| self.mark_grouping(row, columns, &collapsed))
+            .collect()
+    }
+
+    /// `GROUPING(column)` is 1 in rows of which the grouping set collapses the column, which is
+    /// `NULL` in them then, and 0 otherwise.
+    fn mark_grouping(
+        &self,
+        (aggregated, next): ExportedRow<'a>,
+        columns: &[&Expr],
+        collapsed: &[&Expr],
+    ) -> Result<ExportedRow<'a>> {
+        let groupings = self.exprs().flat_map(find_groupings).collect::<Vec<_>>();
+
+        if groupings.is_empty() && collapsed.is_empty() {
+            return Ok((aggregated, next));
+        }
+
+        let aggregated = groupings.into_iter().try_fold::<_, _, Result<_>>(
+            aggregated.unwrap_or_default(),
+            |aggregated, func| match func.args.as_slice() {
+                [column] if columns.contains(&column) => {
+                    let value = Value::I64(collapsed.contains(&column) as i64);
+
+                    Ok(aggregated.update(func, value))
                 }
-                None => Some(Ok(AggregateContext { aggregated, next })),
+                _ => Err(AggregateError::GroupingNotGrouped(func.to_string()).into()),
+            },
+        )?;
+        let next = match next {
+            Some(next) if !collapsed.is_empty() => Some(collapse(&next, collapsed.to_vec())),
+            next => next,
+        };
+
+        Ok((Some(aggregated), next))
+    }
+
+    /// Each group is exported as soon as a row of the next group comes, so only the current
+    /// group is kept in memory.
+    fn stream(
+        self: Rc<Self>,
+        rows: impl Iterator<Item = Result<Rc<BlendContext<'a>>>> + 'a,
+    ) -> impl Iterator<Item = Result<AggregateContext<'a>>> + 'a {
+        let aggregate = Rc::clone(&self);
+        let rows = rows
+            .map(move |row| {
+                let context = row?;
+                let group = aggregate.get_group(aggregate.group_by, &context)?;
+
+                Ok((group, context))
+            })
+            .enumerate()
+            .peekable();
+
+        let aggregate = Rc::clone(&self);
+        let rows = rows.batching(move |rows| {
+            let (index, row) = rows.next()?;
+            let exported = row.and_then(|(group, context)| {
+                let state = aggregate.accumulate(State::new(), index, group.clone(), context)?;
+                let state = rows
+                    .peeking_take_while(|(_, row)| match row {
+                        Ok((next, _)) => next == &group,
+                        Err(_) => false,
+                    })
+                    .try_fold(state, |state, (index, row)| {
+                        let (group, context) = row?;
+
+                        aggregate.accumulate(state, index, group, context)
+                    })?;
+
+                state.export()
             });
 
-        Ok(Aggregated::Applied(rows))
+            Some(exported)
+        });
+
+        let columns = self.group_by.iter().collect::<Vec<_>>();
+
+        rows.flat_map(|exported| match exported {
+            Ok(rows) => rows.into_iter().map(Ok).collect(),
+            Err(error) => vec![Err(error)],
+        })
+        .filter_map(move |row| {
+            row.and_then(|row| self.mark_grouping(row, &columns, &[]))
+                .and_then(|row| self.check_having(row))
+                .transpose()
+        })
+    }
+
+    fn get_group<'b>(
+        &self,
+        exprs: impl IntoIterator<Item = &'b Expr>,
+        context: &BlendContext<'_>,
+    ) -> Result<Vec<GroupKey>> {
+        let evaluated: Vec<Evaluated<'_>> = exprs
+            .into_iter()
+            .map(|expr| {
+                let union_context = UnionContext::new(self.filter_context, Some(context));
+
+                evaluate_union(self.storage, union_context, None, expr)
+            })
+            .collect::<Result<_>>()?;
+
+        evaluated.iter().map(GroupKey::try_from).collect()
     }
 
-    fn check_aggregate(&self) -> bool {
+    fn accumulate(
+        &self,
+        state: State<'a>,
+        index: usize,
+        group: Vec<GroupKey>,
+        context: Rc<BlendContext<'a>>,
+    ) -> Result<State<'a>> {
+        let state = state.apply(index, group, Rc::clone(&context));
+
+        let check_filter = |filter: &Expr| {
+            check_blended_expr(
+                self.storage,
+                self.filter_context,
+                &context,
+                None,
+                None,
+                filter,
+            )
+        };
+
+        self.exprs().try_fold(state, |state, expr| {
+            aggregate(state, &context, expr, &check_filter)
+        })
+    }
+
+    fn check_having(
+        &self,
+        (aggregated, next): ExportedRow<'a>,
+    ) -> Result<Option<AggregateContext<'a>>> {
+        let next = match next {
+            Some(next) => next,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        match self.having {
+            Some(having) => check_blended_expr(
+                self.storage,
+                self.filter_context,
+                &next,
+                aggregated.as_ref(),
+                None,
+                having,
+            )
+            .map(|pass| pass.as_some(AggregateContext { aggregated, next })),
+            None => Ok(Some(AggregateContext { aggregated, next })),
+        }
+    }
+
+    fn export_empty(&self) -> Result<ExportedRow<'a>> {
+        let aggregated = self.exprs().try_fold(HashMap::new(), aggregate_empty)?;
+
+        let context = Rc::new(BlendContext {
+            table_alias: "",
+            columns: Rc::new(vec![]),
+            row: None,
+            next: None,
+        });
+
+        Ok((Some(aggregated), Some(context)))
+    }
+
+    pub fn check_aggregate(&self) -> bool {
         if !self.group_by.is_empty() {
             return true;
         }
 
-        self.fields.iter().any(|field| match field {
-            SelectItem::UnnamedExpr(expr) => check(expr),
-            SelectItem::ExprWithAlias { expr, .. } => check(expr),
-            _ => false,
-        })
+        self.exprs().any(check)
+    }
+
+    fn exprs(&self) -> impl Iterator<Item = &'a Expr> {
+        self.fields
+            .iter()
+            .filter_map(|field| match field {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    Some(expr)
+                }
+                _ => None,
+            })
+            .chain(self.having)
+            .chain(self.order_by.iter().map(|OrderByExpr { expr, .. }| expr))
     }
 }
 
@@ -137,8 +354,9 @@ fn aggregate<'a>(
     state: State<'a>,
     context: &BlendContext<'_>,
     expr: &'a Expr,
+    check_filter: &dyn Fn(&Expr) -> Result<bool>,
 ) -> Result<State<'a>> {
-    let aggr = |state, expr| aggregate(state, context, expr);
+    let aggr = |state, expr| aggregate(state, context, expr, check_filter);
     let get_value = |expr: &Expr| match expr {
         Expr::Identifier(ident) => context.get_value(&ident.value),
         Expr::CompoundIdentifier(idents) => {
@@ -154,7 +372,7 @@ fn aggregate<'a>(
         _ => Err(AggregateError::OnlyIdentifierAllowed.into()),
     };
     let get_first_value = |args: &[Expr]| {
-        let expr = args.get(0).ok_or(AggregateError::Unreachable)?;
+        let expr = args.first().ok_or(AggregateError::Unreachable)?;
 
         get_value(expr)
     };
@@ -169,13 +387,60 @@ fn aggregate<'a>(
             .iter()
             .try_fold(state, |state, expr| aggr(state, expr)),
         Expr::UnaryOp { expr, .. } => aggr(state, expr),
-        Expr::Nested(expr) => aggr(state, expr),
+        Expr::Nested(expr) | Expr::Cast { expr, .. } => aggr(state, expr),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .iter()
+            .chain(else_result)
+            .map(AsRef::as_ref)
+            .chain(conditions)
+            .chain(results)
+            .try_fold(state, aggr),
+        Expr::Function(Function { name, args, .. }) if is_function(get_name(name)?) => {
+            args.iter().try_fold(state, aggr)
+        }
+        Expr::Function(Function {
+            over:
+                Some(WindowSpec {
+                    partition_by,
+                    order_by,
+                    ..
+                }),
+            ..
+        }) => partition_by
+            .iter()
+            .chain(order_by.iter().map(|OrderByExpr { expr, .. }| expr))
+            .try_fold(state, aggr),
         Expr::Function(func) => {
             let Function { name, args, .. } = func;
+            let (args, filter) = split_filter(args);
+            let name = get_name(name)?.to_uppercase();
+
+            if let Some(filter) = filter {
+                if !check_filter(filter)? {
+                    return Ok(state.skip(func, get_empty_value(&name)?));
+                }
+            }
+
+            let state = if func.distinct {
+                let value = get_first_value(args)?;
+
+                if state.is_distinct_found(func, value) {
+                    return Ok(state);
+                }
 
-            match get_name(name)?.to_uppercase().as_str() {
+                state.add_distinct(func, value)
+            } else {
+                state
+            };
+
+            match name.as_str() {
                 "COUNT" => {
-                    let expr = args.get(0).ok_or(AggregateError::Unreachable)?;
+                    let expr = args.first().ok_or(AggregateError::Unreachable)?;
                     let value = Value::I64(match expr {
                         Expr::Wildcard => 1,
                         _ => {
@@ -189,9 +454,22 @@ fn aggregate<'a>(
 
                     state.add(func, &value)
                 }
+                "GROUPING" => Ok(state),
                 "SUM" => state.add(func, get_first_value(args)?),
+                "AVG" => state.add_avg(func, get_first_value(args)?),
                 "MAX" => Ok(state.set_max(func, get_first_value(args)?)),
                 "MIN" => Ok(state.set_min(func, get_first_value(args)?)),
+                "GROUP_CONCAT" => {
+                    let separator = match args.get(1) {
+                        Some(Expr::Value(AstValue::SingleQuotedString(separator))) => separator,
+                        Some(_) => {
+                            return Err(AggregateError::SeparatorNotString.into());
+                        }
+                        None => ",",
+                    };
+
+                    state.concat(func, get_first_value(args)?, separator)
+                }
                 name => Err(AggregateError::UnsupportedAggregation(name.to_string()).into()),
             }
         }
@@ -199,6 +477,89 @@ fn aggregate<'a>(
     }
 }
 
+fn aggregate_empty<'a>(
+    aggregated: HashMap<&'a Function, Value>,
+    expr: &'a Expr,
+) -> Result<HashMap<&'a Function, Value>> {
+    let aggr = |aggregated, expr| aggregate_empty(aggregated, expr);
+
+    match expr {
+        Expr::Between {
+            expr, low, high, ..
+        } => [expr, low, high]
+            .iter()
+            .try_fold(aggregated, |aggregated, expr| aggr(aggregated, expr)),
+        Expr::BinaryOp { left, right, .. } => [left, right]
+            .iter()
+            .try_fold(aggregated, |aggregated, expr| aggr(aggregated, expr)),
+        Expr::UnaryOp { expr, .. } => aggr(aggregated, expr),
+        Expr::Nested(expr) | Expr::Cast { expr, .. } => aggr(aggregated, expr),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .iter()
+            .chain(else_result)
+            .map(AsRef::as_ref)
+            .chain(conditions)
+            .chain(results)
+            .try_fold(aggregated, aggr),
+        Expr::Function(Function { name, args, .. }) if is_function(get_name(name)?) => {
+            args.iter().try_fold(aggregated, aggr)
+        }
+        Expr::Function(Function {
+            over:
+                Some(WindowSpec {
+                    partition_by,
+                    order_by,
+                    ..
+                }),
+            ..
+        }) => partition_by
+            .iter()
+            .chain(order_by.iter().map(|OrderByExpr { expr, .. }| expr))
+            .try_fold(aggregated, aggr),
+        Expr::Function(func) => {
+            let value = get_empty_value(&get_name(&func.name)?.to_uppercase())?;
+
+            Ok(aggregated.update(func, value))
+        }
+        _ => Ok(aggregated),
+    }
+}
+
+/// Value of an aggregate over no rows.
+fn get_empty_value(name: &str) -> Result<Value> {
+    match name {
+        "COUNT" | "GROUPING" => Ok(Value::I64(0)),
+        "SUM" | "AVG" | "MAX" | "MIN" | "GROUP_CONCAT" => Ok(Value::Empty),
+        name => Err(AggregateError::UnsupportedAggregation(name.to_string()).into()),
+    }
+}
+
+/// `FILTER (WHERE ...)` of an aggregate is parsed as the last argument of it, a function
+/// `AGGREGATE_FILTER` of the condition. Returns the other arguments and the condition.
+pub fn split_filter(args: &[Expr]) -> (&[Expr], Option<&Expr>) {
+    match args.split_last() {
+        Some((
+            Expr::Function(Function {
+                name,
+                args: filter,
+                over: None,
+                distinct: false,
+            }),
+            args,
+        )) if matches!(get_name(name), Ok(name) if name == AGGREGATE_FILTER)
+            && filter.len() == 1 =>
+        {
+            (args, filter.first())
+        }
+        _ => (args, None),
+    }
+}
+
 fn check(expr: &Expr) -> bool {
     match expr {
         Expr::Between {
@@ -206,8 +567,35 @@ fn check(expr: &Expr) -> bool {
         } => check(expr) || check(low) || check(high),
         Expr::BinaryOp { left, right, .. } => check(left) || check(right),
         Expr::UnaryOp { expr, .. } => check(expr),
-        Expr::Nested(expr) => check(expr),
-        Expr::Function(_) => true,
+        Expr::Nested(expr) | Expr::Cast { expr, .. } => check(expr),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .iter()
+            .chain(else_result)
+            .map(AsRef::as_ref)
+            .chain(conditions)
+            .chain(results)
+            .any(check),
+        Expr::Function(Function {
+            over:
+                Some(WindowSpec {
+                    partition_by,
+                    order_by,
+                    ..
+                }),
+            ..
+        }) => partition_by
+            .iter()
+            .chain(order_by.iter().map(|OrderByExpr { expr, .. }| expr))
+            .any(check),
+        Expr::Function(Function { name, args, .. }) => match get_name(name) {
+            Ok(name) if is_function(name) => args.iter().any(check),
+            _ => true,
+        },
         _ => false,
     }
 }
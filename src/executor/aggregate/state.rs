@@ -3,24 +3,55 @@ use itertools::Itertools;
 use std::cmp::Ordering;
 use std::rc::Rc;
 
-use sqlparser::ast::Function;
+use sqlparser::ast::{DataType, Function};
 
 use crate::utils::{IndexMap, Vector};
 
 use super::hash::GroupKey;
-use crate::data::Value;
+use crate::data::{Value, ValueError};
 use crate::executor::context::BlendContext;
+use crate::executor::distinct::DistinctKey;
 use crate::result::Result;
 
 type Group = Rc<Vec<GroupKey>>;
 type ValuesMap<'a> = HashMap<&'a Function, Value>;
+pub type ExportedRow<'a> = (Option<ValuesMap<'a>>, Option<Rc<BlendContext<'a>>>);
+
+enum AggrValue {
+    Value(Value),
+    Avg { sum: Option<Value>, count: i64 },
+    Empty(Value),
+}
+
+impl AggrValue {
+    fn export(self) -> Result<Value> {
+        match self {
+            AggrValue::Value(value) | AggrValue::Empty(value) => Ok(value),
+            AggrValue::Avg { sum, count } => average(sum, count),
+        }
+    }
+}
+
+fn average(sum: Option<Value>, count: i64) -> Result<Value> {
+    use Value::*;
+
+    match sum {
+        None => Ok(OptF64(None)),
+        Some(I64(v)) => Ok(F64(v as f64 / count as f64)),
+        Some(OptI64(Some(v))) => Ok(OptF64(Some(v as f64 / count as f64))),
+        Some(F64(v)) => Ok(F64(v / count as f64)),
+        Some(OptF64(Some(v))) => Ok(OptF64(Some(v / count as f64))),
+        Some(_) => Err(ValueError::AddOnNonNumeric.into()),
+    }
+}
 
 pub struct State<'a> {
     index: usize,
     group: Group,
-    values: IndexMap<(Group, &'a Function), (usize, Value)>,
+    values: IndexMap<(Group, &'a Function), (usize, AggrValue)>,
     groups: HashSet<Group>,
     contexts: Vector<Rc<BlendContext<'a>>>,
+    distinct_values: HashSet<(Group, &'a Function, DistinctKey)>,
 }
 
 impl<'a> State<'a> {
@@ -31,6 +62,7 @@ impl<'a> State<'a> {
             values: IndexMap::new(),
             groups: HashSet::new(),
             contexts: Vector::new(),
+            distinct_values: HashSet::new(),
         }
     }
 
@@ -51,10 +83,11 @@ impl<'a> State<'a> {
             values: self.values,
             groups,
             contexts,
+            distinct_values: self.distinct_values,
         }
     }
 
-    fn update(self, func: &'a Function, value: Value) -> Self {
+    fn update(self, func: &'a Function, value: AggrValue) -> Self {
         let key = (Rc::clone(&self.group), func);
         let (values, _) = self.values.insert(key, (self.index, value));
 
@@ -64,23 +97,33 @@ impl<'a> State<'a> {
             values,
             groups: self.groups,
             contexts: self.contexts,
+            distinct_values: self.distinct_values,
         }
     }
 
-    fn get(&self, func: &'a Function) -> Option<&(usize, Value)> {
+    fn get(&self, func: &'a Function) -> Option<(usize, &AggrValue)> {
         let group = Rc::clone(&self.group);
 
-        self.values.get(&(group, func))
+        self.values
+            .get(&(group, func))
+            .map(|(index, value)| (*index, value))
+    }
+
+    fn get_value(&self, func: &'a Function) -> Option<(usize, &Value)> {
+        match self.get(func) {
+            Some((index, AggrValue::Value(value))) => Some((index, value)),
+            _ => None,
+        }
     }
 
-    pub fn export(self) -> Vec<(Option<ValuesMap<'a>>, Option<Rc<BlendContext<'a>>>)> {
+    pub fn export(self) -> Result<Vec<ExportedRow<'a>>> {
         let size = match self.values.keys().next() {
             Some((target, _)) => match self.values.keys().position(|(group, _)| group != target) {
                 Some(size) => size,
                 None => self.values.len(),
             },
             None => {
-                return self.contexts.into_iter().map(|c| (None, Some(c))).collect();
+                return Ok(self.contexts.into_iter().map(|c| (None, Some(c))).collect());
             }
         };
 
@@ -96,19 +139,55 @@ impl<'a> State<'a> {
             .enumerate()
             .map(|(i, entries)| {
                 let aggregated = entries
-                    .map(|((_, func), value)| (func, value))
-                    .collect::<HashMap<&'a Function, Value>>();
+                    .map(|((_, func), value)| value.export().map(|value| (func, value)))
+                    .collect::<Result<HashMap<&'a Function, Value>>>()?;
                 let next = contexts.get(i).map(Rc::clone);
 
-                (Some(aggregated), next)
+                Ok((Some(aggregated), next))
             })
-            .collect::<Vec<(Option<ValuesMap<'a>>, Option<Rc<BlendContext<'a>>>)>>()
+            .collect::<Result<Vec<ExportedRow<'a>>>>()
+    }
+
+    /// Row which `FILTER` of the aggregate skips, the aggregate still has `empty` as its value
+    /// in the group until a row passes, so that every group has the same aggregates.
+    pub fn skip(self, func: &'a Function, empty: Value) -> Self {
+        match self.get(func) {
+            Some(_) => self,
+            None => self.update(func, AggrValue::Empty(empty)),
+        }
+    }
+
+    /// Aggregates with `DISTINCT` skip values which are already found in the current group.
+    /// `NULL` is never kept in the set, aggregates handle it as they do without `DISTINCT`.
+    pub fn is_distinct_found(&self, func: &'a Function, target: &Value) -> bool {
+        let key = (Rc::clone(&self.group), func, DistinctKey::from(target));
+
+        target.is_some() && self.distinct_values.contains(&key)
     }
 
+    pub fn add_distinct(self, func: &'a Function, target: &Value) -> Self {
+        if !target.is_some() {
+            return self;
+        }
+
+        let key = (Rc::clone(&self.group), func, DistinctKey::from(target));
+
+        Self {
+            distinct_values: self.distinct_values.update(key),
+            ..self
+        }
+    }
+
+    /// `NULL` is skipped as `SUM`, `MAX` and `MIN` of SQL do, the aggregate stays `NULL` only if
+    /// every value of the group is `NULL`.
     pub fn add(self, func: &'a Function, target: &Value) -> Result<Self> {
-        let value = match self.get(func) {
+        if !target.is_some() {
+            return Ok(self.skip(func, target.clone()));
+        }
+
+        let value = match self.get_value(func) {
             Some((index, value)) => {
-                if &self.index <= index {
+                if self.index <= index {
                     return Ok(self);
                 }
 
@@ -117,12 +196,34 @@ impl<'a> State<'a> {
             None => target.clone(),
         };
 
-        Ok(self.update(func, value))
+        Ok(self.update(func, AggrValue::Value(value)))
+    }
+
+    pub fn add_avg(self, func: &'a Function, target: &Value) -> Result<Self> {
+        let (sum, count) = match self.get(func) {
+            Some((index, _)) if self.index <= index => {
+                return Ok(self);
+            }
+            Some((_, AggrValue::Avg { sum, count })) => (sum.clone(), *count),
+            _ => (None, 0),
+        };
+
+        let (sum, count) = match sum {
+            _ if !target.is_some() => (sum, count),
+            Some(sum) => (Some(sum.add(target)?), count + 1),
+            None => (Some(target.clone()), 1),
+        };
+
+        Ok(self.update(func, AggrValue::Avg { sum, count }))
     }
 
     pub fn set_max(self, func: &'a Function, target: &Value) -> Self {
-        if let Some((index, value)) = self.get(func) {
-            if &self.index <= index {
+        if !target.is_some() {
+            return self.skip(func, target.clone());
+        }
+
+        if let Some((index, value)) = self.get_value(func) {
+            if self.index <= index {
                 return self;
             }
 
@@ -134,12 +235,16 @@ impl<'a> State<'a> {
             }
         };
 
-        self.update(func, target.clone())
+        self.update(func, AggrValue::Value(target.clone()))
     }
 
     pub fn set_min(self, func: &'a Function, target: &Value) -> Self {
-        if let Some((index, value)) = self.get(func) {
-            if &self.index <= index {
+        if !target.is_some() {
+            return self.skip(func, target.clone());
+        }
+
+        if let Some((index, value)) = self.get_value(func) {
+            if self.index <= index {
                 return self;
             }
 
@@ -151,6 +256,28 @@ impl<'a> State<'a> {
             }
         }
 
-        self.update(func, target.clone())
+        self.update(func, AggrValue::Value(target.clone()))
+    }
+
+    /// `NULL` is skipped, other values are concatenated as `TEXT`.
+    pub fn concat(self, func: &'a Function, target: &Value, separator: &str) -> Result<Self> {
+        let value = match self.get_value(func) {
+            Some((index, _)) if self.index <= index => {
+                return Ok(self);
+            }
+            Some((_, value)) => value.clone(),
+            None => Value::Empty,
+        };
+
+        let value = match (value, target.is_some()) {
+            (value, false) => value,
+            (Value::Str(concatenated), true) => match target.cast(&DataType::Text)? {
+                Value::Str(target) => Value::Str(concatenated + separator + &target),
+                _ => Value::Str(concatenated),
+            },
+            (_, true) => target.cast(&DataType::Text)?,
+        };
+
+        Ok(self.update(func, AggrValue::Value(value)))
     }
 }
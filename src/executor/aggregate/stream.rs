@@ -0,0 +1,133 @@
+use im_rc::HashSet;
+
+use sqlparser::ast::{Expr, Ident, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr};
+
+use crate::data::Table;
+use crate::executor::join::JoinClause;
+
+/// Whether rows of the same group come one after another, so that groups can be aggregated one
+/// at a time as the rows stream in. Rows are known to be sorted only when they come from a
+/// derived table with `ORDER BY`, and the leading keys of it are the `GROUP BY` columns in any
+/// order. Joins keep the order of the joined rows, except `RIGHT` and `FULL` joins.
+pub fn is_sorted_by_group(group_by: &[Expr], table: &Table<'_>, joins: &[JoinClause<'_>]) -> bool {
+    let keeps_order = joins.iter().all(|(_, join_operator)| {
+        matches!(
+            join_operator,
+            JoinOperator::Inner(_) | JoinOperator::LeftOuter(_) | JoinOperator::CrossJoin
+        )
+    });
+
+    if group_by.is_empty() || !keeps_order || !table.get_column_aliases().is_empty() {
+        return false;
+    }
+
+    let sorted = match table.get_subquery() {
+        Some(subquery) => get_sorted_columns(subquery),
+        None => {
+            return false;
+        }
+    };
+
+    let table_alias = table.get_alias();
+    let grouped = group_by
+        .iter()
+        .map(|expr| match expr {
+            Expr::Identifier(Ident {
+                value,
+                quote_style: None,
+            }) => Some(value.as_str()),
+            Expr::CompoundIdentifier(idents)
+                if idents.len() == 2 && &idents[0].value == table_alias =>
+            {
+                Some(idents[1].value.as_str())
+            }
+            _ => None,
+        })
+        .collect::<Option<HashSet<&str>>>();
+
+    match grouped {
+        Some(grouped) if grouped.len() <= sorted.len() => {
+            sorted
+                .into_iter()
+                .take(grouped.len())
+                .collect::<HashSet<_>>()
+                == grouped
+        }
+        _ => false,
+    }
+}
+
+/// Output columns of `query` which its rows are sorted by, in the order of `ORDER BY` until a
+/// key which is not an output column.
+fn get_sorted_columns(query: &Query) -> Vec<&str> {
+    let statement = match &query.body {
+        SetExpr::Select(statement) => statement,
+        _ => {
+            return vec![];
+        }
+    };
+
+    match statement.from.as_slice() {
+        [table] if table.joins.is_empty() => {}
+        _ => {
+            return vec![];
+        }
+    }
+
+    query
+        .order_by
+        .iter()
+        .map_while(|OrderByExpr { expr, .. }| get_output_column(statement, expr))
+        .fold(vec![], |mut sorted, column| {
+            if !sorted.contains(&column) {
+                sorted.push(column);
+            }
+
+            sorted
+        })
+}
+
+/// Output column which the rows of `query` are sorted by in ascending order, the first key of
+/// `ORDER BY` if it is an output column.
+pub fn get_ascending_column(query: &Query) -> Option<&str> {
+    match get_sorted_columns(query).first().copied() {
+        Some(column) if query.order_by[0].asc != Some(false) => Some(column),
+        _ => None,
+    }
+}
+
+fn get_output_column<'a>(statement: &'a Select, expr: &'a Expr) -> Option<&'a str> {
+    let (column, qualified) = match expr {
+        Expr::Identifier(Ident {
+            value,
+            quote_style: None,
+        }) => (value, false),
+        Expr::CompoundIdentifier(idents) if idents.len() == 2 => (&idents[1].value, true),
+        _ => {
+            return None;
+        }
+    };
+    let is_column = |expr: &Expr| match expr {
+        Expr::Identifier(ident) => ident.quote_style.is_none() && &ident.value == column,
+        Expr::CompoundIdentifier(idents) => idents.len() == 2 && &idents[1].value == column,
+        _ => false,
+    };
+
+    let aliased = statement.projection.iter().any(|item| match item {
+        SelectItem::ExprWithAlias { alias, .. } => &alias.value == column,
+        _ => false,
+    });
+
+    match (aliased, qualified) {
+        (true, false) => return Some(column),
+        (true, true) => return None,
+        _ => {}
+    }
+
+    statement.projection.iter().find_map(|item| match item {
+        SelectItem::Wildcard | SelectItem::QualifiedWildcard(_) => Some(column.as_str()),
+        SelectItem::UnnamedExpr(expr) if is_column(expr) => Some(column.as_str()),
+        SelectItem::ExprWithAlias { expr, alias } if is_column(expr) => Some(alias.value.as_str()),
+        _ => None,
+    })
+}
@@ -1,3 +1,4 @@
+use boolinator::Boolinator;
 use im_rc::HashMap;
 use iter_enum::Iterator;
 use serde::Serialize;
@@ -7,11 +8,15 @@ use std::iter::once;
 use std::rc::Rc;
 use thiserror::Error;
 
-use sqlparser::ast::{Expr, Function, Ident, SelectItem};
+use sqlparser::ast::{BinaryOperator, Expr, Function, Ident, ObjectName, SelectItem};
 
+use super::aggregate::split_filter;
 use super::context::{AggregateContext, BlendContext, FilterContext};
-use super::evaluate::{evaluate, Evaluated};
+use super::evaluate::evaluate;
+use super::execute::ExecuteError;
+use super::filter::get_ilike_pattern;
 use crate::data::{get_name, Row, Value};
+use crate::parse::{JSON_FIELD, JSON_TEXT};
 use crate::result::Result;
 use crate::store::Store;
 
@@ -27,11 +32,19 @@ pub enum BlendError {
     TableNotFound(String),
 }
 
-pub struct Blend<'a, T: 'static + Debug> {
+pub struct Blend<'a, T: 'static + Debug + PartialEq> {
     storage: &'a dyn Store<T>,
     fields: &'a [SelectItem],
+    using_columns: Vec<UsingColumns<'a>>,
+    width: Option<usize>,
 }
 
+/// Table alias with its columns merged by `USING`, they are shown once in `SELECT *`.
+pub type UsingColumns<'a> = (&'a str, &'a [Ident]);
+
+/// Table alias with its columns.
+pub type TableColumns<'a> = (&'a str, &'a [Ident]);
+
 #[derive(Iterator)]
 enum Blended<I1, I2, I3, I4> {
     All(I1),
@@ -47,14 +60,35 @@ struct Context<'a> {
     next: Option<Box<Context<'a>>>,
 }
 
-impl<'a, T: 'static + Debug> Blend<'a, T> {
-    pub fn new(storage: &'a dyn Store<T>, fields: &'a [SelectItem]) -> Self {
-        Self { storage, fields }
+impl<'a, T: 'static + Debug + PartialEq> Blend<'a, T> {
+    pub fn new(
+        storage: &'a dyn Store<T>,
+        fields: &'a [SelectItem],
+        using_columns: Vec<UsingColumns<'a>>,
+    ) -> Self {
+        Self {
+            storage,
+            fields,
+            using_columns,
+            width: None,
+        }
+    }
+
+    /// Number of output columns, usually the number of `get_labels`, which every blended row
+    /// should have.
+    pub fn with_width(self, width: usize) -> Self {
+        Self {
+            width: Some(width),
+            ..self
+        }
     }
 
+    /// Each row of the joined tables should have as many values as the columns of its table, and
+    /// the blended row as many as `width`, otherwise the row is not blended but fails with
+    /// `ExecuteError::RowWidthMismatch`.
     pub fn apply(&self, context: Result<AggregateContext<'a>>) -> Result<Row> {
         let AggregateContext { aggregated, next } = context?;
-        let context = Self::prepare(next);
+        let context = Self::prepare(next)?;
 
         let values = self
             .blend(aggregated, context)?
@@ -63,12 +97,21 @@ impl<'a, T: 'static + Debug> Blend<'a, T> {
                 Ok(value) => value,
                 Err(value) => (*value).clone(),
             })
-            .collect();
+            .collect::<Vec<_>>();
 
-        Ok(Row(values))
+        match self.width {
+            Some(width) if width != values.len() => Err(ExecuteError::RowWidthMismatch {
+                expected: width,
+                found: values.len(),
+            }
+            .into()),
+            _ => Ok(Row(values)),
+        }
     }
 
-    fn prepare(context: Rc<BlendContext<'a>>) -> Context<'a> {
+    fn prepare(context: Rc<BlendContext<'a>>) -> Result<Context<'a>> {
+        check_width(&context)?;
+
         match Rc::try_unwrap(context) {
             Ok(BlendContext {
                 table_alias,
@@ -82,14 +125,14 @@ impl<'a, T: 'static + Debug> Blend<'a, T> {
 
                     values.into_iter().map(Rc::new).collect()
                 });
-                let next = next.map(|c| Box::new(Self::prepare(c)));
+                let next = next.map(|c| Self::prepare(c).map(Box::new)).transpose()?;
 
-                Context {
+                Ok(Context {
                     table_alias,
                     columns,
                     values,
                     next,
-                }
+                })
             }
             Err(context) => {
                 let BlendContext {
@@ -106,19 +149,21 @@ impl<'a, T: 'static + Debug> Blend<'a, T> {
 
                     values.clone().into_iter().map(Rc::new).collect()
                 });
-                let next = next.as_ref().map(|c| {
-                    let c = Rc::clone(c);
-                    let c = Self::prepare(c);
+                let next = next
+                    .as_ref()
+                    .map(|c| {
+                        let c = Rc::clone(c);
 
-                    Box::new(c)
-                });
+                        Self::prepare(c).map(Box::new)
+                    })
+                    .transpose()?;
 
-                Context {
+                Ok(Context {
                     table_alias,
                     columns,
                     values,
                     next,
-                }
+                })
             }
         }
     }
@@ -138,7 +183,9 @@ impl<'a, T: 'static + Debug> Blend<'a, T> {
             .iter()
             .flat_map(|item| match item {
                 SelectItem::Wildcard => {
-                    let values = get_all_values(&context).into_iter().map(Ok);
+                    let values = get_all_values(&context, &self.using_columns)
+                        .into_iter()
+                        .map(Ok);
 
                     Blended::All(values)
                 }
@@ -155,50 +202,240 @@ impl<'a, T: 'static + Debug> Blend<'a, T> {
                         None => err!(BlendError::TableNotFound(table_alias.to_string())),
                     }
                 }
-                SelectItem::UnnamedExpr(expr) => match expr {
-                    Expr::Identifier(ident) => match get_value(&context, &ident.value) {
-                        Some(value) => Blended::Single(once(Ok(value))),
-                        None => err!(BlendError::ColumnNotFound(ident.to_string())),
-                    },
-                    Expr::CompoundIdentifier(idents) => {
-                        if idents.len() != 2 {
-                            return err!(BlendError::FieldDefinitionNotSupported);
-                        }
-
-                        let table_alias = &idents[0].value;
-                        let column = &idents[1].value;
-
-                        match get_alias_value(&context, table_alias, column) {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    match expr {
+                        Expr::Identifier(ident) => match get_value(&context, &ident.value) {
                             Some(value) => Blended::Single(once(Ok(value))),
-                            None => err!(BlendError::ColumnNotFound(format!(
-                                "{}.{}",
-                                table_alias, column
-                            ))),
+                            None => err!(BlendError::ColumnNotFound(ident.to_string())),
+                        },
+                        Expr::CompoundIdentifier(idents) => {
+                            if idents.len() != 2 {
+                                return err!(BlendError::FieldDefinitionNotSupported);
+                            }
+
+                            let table_alias = &idents[0].value;
+                            let column = &idents[1].value;
+
+                            match get_alias_value(&context, table_alias, column) {
+                                Some(value) => Blended::Single(once(Ok(value))),
+                                None => err!(BlendError::ColumnNotFound(format!(
+                                    "{}.{}",
+                                    table_alias, column
+                                ))),
+                            }
                         }
-                    }
-                    Expr::BinaryOp { .. } | Expr::Function(_) => {
-                        let value = evaluate_blended(
-                            self.storage,
-                            None,
-                            &context,
-                            aggregated.as_ref(),
-                            expr,
-                        )
-                        .map(Rc::new);
-
-                        Blended::Single(once(value))
-                    }
-                    Expr::Value(literal) => {
-                        let value = Value::try_from(literal).map(Rc::new);
+                        Expr::BinaryOp { .. }
+                        | Expr::Nested(_)
+                        | Expr::Cast { .. }
+                        | Expr::TypedString { .. }
+                        | Expr::Case { .. }
+                        | Expr::Function(_)
+                        | Expr::Subquery(_) => {
+                            let value = evaluate_blended(
+                                self.storage,
+                                None,
+                                &context,
+                                aggregated.as_ref(),
+                                expr,
+                            )
+                            .map(Rc::new);
+
+                            Blended::Single(once(value))
+                        }
+                        Expr::Value(literal) => {
+                            let value = Value::try_from(literal).map(Rc::new);
 
-                        Blended::Single(once(value))
+                            Blended::Single(once(value))
+                        }
+                        _ => err!(BlendError::FieldDefinitionNotSupported),
                     }
-                    _ => err!(BlendError::FieldDefinitionNotSupported),
-                },
-                _ => err!(BlendError::FieldDefinitionNotSupported),
+                }
             })
             .collect::<Result<_>>()
     }
+
+    /// Output column names, an alias is used if exists, or it falls back to the column name of
+    /// the field. Wildcards are expanded with `tables` which are in `FROM` order.
+    pub fn get_labels(&self, tables: &[TableColumns<'_>]) -> Result<Vec<String>> {
+        let labels = self
+            .fields
+            .iter()
+            .map(|item| match item {
+                SelectItem::Wildcard | SelectItem::QualifiedWildcard(_) => {
+                    let labels = self
+                        .get_wildcard_columns(item, tables)?
+                        .into_iter()
+                        .map(|sources| sources[0].1.value.clone())
+                        .collect();
+
+                    Ok(labels)
+                }
+                SelectItem::UnnamedExpr(expr) => Ok(vec![get_label(expr)]),
+                SelectItem::ExprWithAlias { alias, .. } => Ok(vec![alias.value.clone()]),
+            })
+            .collect::<Result<Vec<Vec<_>>>>()?
+            .concat();
+
+        Ok(labels)
+    }
+
+    /// Fields with each wildcard expanded to the qualified columns it covers, so there is a field
+    /// for every label.
+    pub fn get_fields(&self, tables: &[TableColumns<'_>]) -> Result<Vec<SelectItem>> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|item| match item {
+                SelectItem::Wildcard | SelectItem::QualifiedWildcard(_) => {
+                    let fields = self
+                        .get_wildcard_columns(item, tables)?
+                        .into_iter()
+                        .map(|sources| {
+                            let mut args = sources
+                                .into_iter()
+                                .map(|(table_alias, column)| {
+                                    let idents = vec![Ident::new(table_alias), column.clone()];
+
+                                    Expr::CompoundIdentifier(idents)
+                                })
+                                .collect::<Vec<_>>();
+                            let expr = match args.len() {
+                                1 => args.remove(0),
+                                _ => Expr::Function(Function {
+                                    name: ObjectName(vec![Ident::new("COALESCE")]),
+                                    args,
+                                    over: None,
+                                    distinct: false,
+                                }),
+                            };
+
+                            SelectItem::UnnamedExpr(expr)
+                        })
+                        .collect();
+
+                    Ok(fields)
+                }
+                _ => Ok(vec![item.clone()]),
+            })
+            .collect::<Result<Vec<Vec<_>>>>()?
+            .concat();
+
+        Ok(fields)
+    }
+
+    /// Columns covered by a wildcard `item`, each with the aliases of the tables it comes from.
+    /// A column merged by `USING` comes from the left table and then each table joined with it,
+    /// it is shown once in `*` as `COALESCE` of them.
+    fn get_wildcard_columns<'b>(
+        &self,
+        item: &SelectItem,
+        tables: &[TableColumns<'b>],
+    ) -> Result<Vec<Vec<(&'b str, &'b Ident)>>> {
+        match item {
+            SelectItem::QualifiedWildcard(alias) => {
+                let table_alias = get_name(alias)?;
+
+                tables
+                    .iter()
+                    .find(|(alias, _)| alias == table_alias)
+                    .map(|(alias, columns)| {
+                        columns
+                            .iter()
+                            .map(|column| vec![(*alias, column)])
+                            .collect()
+                    })
+                    .ok_or_else(|| BlendError::TableNotFound(table_alias.to_string()).into())
+            }
+            _ => {
+                let columns = tables.iter().fold(
+                    vec![],
+                    |columns: Vec<Vec<(&'b str, &'b Ident)>>, (table_alias, table_columns)| {
+                        let merged = self.get_using_columns(table_alias);
+
+                        table_columns.iter().fold(columns, |mut columns, column| {
+                            let left = merged.contains(column).and_option_from(|| {
+                                columns
+                                    .iter_mut()
+                                    .rev()
+                                    .find(|sources| sources[0].1 == column)
+                            });
+
+                            match left {
+                                Some(sources) => sources.push((table_alias, column)),
+                                None => columns.push(vec![(table_alias, column)]),
+                            }
+
+                            columns
+                        })
+                    },
+                );
+
+                Ok(columns)
+            }
+        }
+    }
+
+    fn get_using_columns(&self, table_alias: &str) -> &[Ident] {
+        self.using_columns
+            .iter()
+            .find(|(alias, _)| alias == &table_alias)
+            .map_or(&[][..], |(_, columns)| columns)
+    }
+}
+
+fn get_label(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .map_or_else(|| expr.to_string(), |ident| ident.value.clone()),
+        Expr::Function(func) => match split_filter(&func.args) {
+            (args, Some(filter)) => {
+                let func = Function {
+                    args: args.to_vec(),
+                    ..func.clone()
+                };
+
+                format!("{} FILTER (WHERE {})", func, filter)
+            }
+            (_, None) => format_arrows(expr),
+        },
+        Expr::BinaryOp { left, op, right } => match (op, get_ilike_pattern(right)) {
+            (BinaryOperator::Like, Some(pattern)) => format!("{} ILIKE {}", left, pattern),
+            (BinaryOperator::NotLike, Some(pattern)) => format!("{} NOT ILIKE {}", left, pattern),
+            _ => expr.to_string(),
+        },
+        _ => expr.to_string(),
+    }
+}
+
+/// `%->(a, 'b')` is written back as `a->'b'`, as the arrows are parsed into functions.
+fn format_arrows(expr: &Expr) -> String {
+    match expr {
+        Expr::Function(Function { name, args, .. }) if args.len() == 2 => match get_name(name) {
+            Ok(name) if name == JSON_FIELD || name == JSON_TEXT => {
+                let arrow = name.trim_start_matches('%');
+
+                format!("{}{}{}", format_arrows(&args[0]), arrow, args[1])
+            }
+            _ => expr.to_string(),
+        },
+        _ => expr.to_string(),
+    }
+}
+
+/// Rows padded for outer joins have no values, so they always fit.
+fn check_width(context: &BlendContext<'_>) -> Result<()> {
+    let BlendContext { columns, row, .. } = context;
+
+    match row {
+        Some(Row(values)) if values.len() != columns.len() => Err(ExecuteError::RowWidthMismatch {
+            expected: columns.len(),
+            found: values.len(),
+        }
+        .into()),
+        _ => Ok(()),
+    }
 }
 
 fn get_value(context: &Context<'_>, target: &str) -> Option<Rc<Value>> {
@@ -241,26 +478,55 @@ fn get_alias_value(context: &Context<'_>, alias: &str, target: &str) -> Option<R
     }
 }
 
-fn get_all_values(context: &Context<'_>) -> Vec<Rc<Value>> {
+fn get_all_values(context: &Context<'_>, using_columns: &[UsingColumns<'_>]) -> Vec<Rc<Value>> {
+    get_all_columns(context, using_columns)
+        .into_iter()
+        .map(|(_, value)| value)
+        .collect()
+}
+
+/// Columns of every table in `FROM` order with their values. A column merged by `USING` is
+/// shown once with the value of `COALESCE(left, right)`, so the rows padded for `RIGHT` or `FULL`
+/// joins keep the value of the right table.
+fn get_all_columns<'b>(
+    context: &'b Context<'_>,
+    using_columns: &[UsingColumns<'_>],
+) -> Vec<(&'b Ident, Rc<Value>)> {
     let Context {
+        table_alias,
         values,
         next,
         columns,
-        ..
     } = context;
 
-    let values: Vec<Rc<Value>> = match values {
-        Some(values) => values.iter().map(Rc::clone).collect(),
-        None => columns.iter().map(|_| Rc::new(Value::Empty)).collect(),
+    let merged = using_columns
+        .iter()
+        .find(|(alias, _)| alias == table_alias)
+        .map_or(&[][..], |(_, columns)| columns);
+    let get_value = |index: usize| match values {
+        Some(values) => Rc::clone(&values[index]),
+        None => Rc::new(Value::Empty),
     };
+    let previous = next
+        .as_ref()
+        .map_or_else(Vec::new, |next| get_all_columns(next, using_columns));
 
-    match next.as_ref() {
-        Some(next) => get_all_values(next)
-            .into_iter()
-            .chain(values.into_iter())
-            .collect(),
-        None => values,
-    }
+    columns
+        .iter()
+        .enumerate()
+        .fold(previous, |mut all, (index, column)| {
+            let left = merged
+                .contains(column)
+                .and_option_from(|| all.iter_mut().rev().find(|(target, _)| *target == column));
+
+            match left {
+                Some((_, value)) if !value.is_some() => *value = get_value(index),
+                Some(_) => {}
+                None => all.push((column, get_value(index))),
+            }
+
+            all
+        })
 }
 
 fn get_alias_values(context: &Context<'_>, alias: &str) -> Option<Vec<Rc<Value>>> {
@@ -283,7 +549,7 @@ fn get_alias_values(context: &Context<'_>, alias: &str) -> Option<Vec<Rc<Value>>
     }
 }
 
-fn evaluate_blended<T: 'static + Debug>(
+fn evaluate_blended<T: 'static + Debug + PartialEq>(
     storage: &dyn Store<T>,
     filter_context: Option<&FilterContext<'_>>,
     context: &Context<'_>,
@@ -298,25 +564,16 @@ fn evaluate_blended<T: 'static + Debug>(
     } = context;
 
     // TODO: Remove clone
-    let row = values.as_ref().map(|values| {
-        let values = values.iter().map(|v| Value::clone(&v)).collect();
-
-        Row(values)
-    });
+    let row = match values {
+        Some(values) => Row(values.iter().map(|v| Value::clone(v)).collect()),
+        None => Row(columns.iter().map(|_| Value::Empty).collect()),
+    };
 
-    let row_context = row
-        .as_ref()
-        .map(|row| FilterContext::new(table_alias, &columns, row, filter_context));
-    let filter_context = row_context.as_ref().or(filter_context);
+    let row_context = FilterContext::new(table_alias, columns, &row, filter_context);
+    let filter_context = Some(&row_context);
 
     match next {
         Some(context) => evaluate_blended(storage, filter_context, context, aggregated, expr),
-        None => match evaluate(storage, filter_context, aggregated, expr)? {
-            Evaluated::LiteralRef(v) => Value::try_from(v),
-            Evaluated::Literal(v) => Value::try_from(&v),
-            Evaluated::StringRef(v) => Ok(Value::Str(v.to_string())),
-            Evaluated::ValueRef(v) => Ok(v.clone()),
-            Evaluated::Value(v) => Ok(v),
-        },
+        None => evaluate(storage, filter_context, aggregated, expr).and_then(Value::try_from),
     }
 }
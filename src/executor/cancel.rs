@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::execute::ExecuteError;
+use crate::result::Result;
+
+/// Number of rows scanned between two checks of the cancellation flag.
+pub const CANCEL_CHECK_INTERVAL: usize = 64;
+
+/// Fails with `ExecuteError::Cancelled` if the flag of `ExecuteOptions::cancelled` is set, it is
+/// checked before a scan starts.
+pub fn check_cancelled(cancelled: Option<&AtomicBool>) -> Result<()> {
+    match cancelled {
+        Some(cancelled) if cancelled.load(Ordering::Relaxed) => Err(ExecuteError::Cancelled.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Rows of a scan which fail with `ExecuteError::Cancelled` once the flag is set, it is checked
+/// every `CANCEL_CHECK_INTERVAL` rows and the scan stops at the failure. So even a scan which
+/// skips every row can be cancelled.
+pub fn cancellable<R>(
+    rows: impl Iterator<Item = Result<R>>,
+    cancelled: Option<Arc<AtomicBool>>,
+) -> impl Iterator<Item = Result<R>> {
+    rows.enumerate().scan(false, move |stopped, (i, item)| {
+        if *stopped {
+            return None;
+        }
+
+        if i % CANCEL_CHECK_INTERVAL == 0 && check_cancelled(cancelled.as_deref()).is_err() {
+            *stopped = true;
+
+            return Some(Err(ExecuteError::Cancelled.into()));
+        }
+
+        Some(item)
+    })
+}
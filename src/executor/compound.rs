@@ -0,0 +1,143 @@
+use boolinator::Boolinator;
+use im_rc::HashMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use sqlparser::ast::{Expr, Ident, Query, SelectItem, SetExpr, SetOperator};
+
+use super::context::{AggregateContext, BlendContext, FilterContext};
+use super::distinct::{get_key, Distinct};
+use super::execute::ExecuteError;
+use super::limit::Limit;
+use super::select::{select, select_statement, ResultSet, SelectError};
+use super::sort::Sort;
+use crate::data::Row;
+use crate::result::Result;
+use crate::store::Store;
+
+/// Runs a query whose body is a set operation, `UNION`, `INTERSECT` or `EXCEPT`.
+/// `ORDER BY`, `LIMIT` and `OFFSET` are applied to the combined rows, and `ORDER BY` can refer
+/// to the labels of the first arm.
+pub fn compound<'a, T: 'static + Debug + PartialEq>(
+    storage: &'a dyn Store<T>,
+    query: &'a Query,
+    filter_context: Option<&'a FilterContext<'a>>,
+) -> Result<ResultSet<impl Iterator<Item = Result<Row>>>> {
+    let (labels, rows) = combine(storage, &query.body, filter_context)?;
+
+    // `ORDER BY` position refers to the combined columns by their labels
+    let fields = labels
+        .iter()
+        .map(|label| SelectItem::UnnamedExpr(Expr::Identifier(Ident::new(label))))
+        .collect::<Vec<_>>();
+    let sort = Sort::new(storage, &query.order_by, fields, 0, filter_context);
+    let limit = Limit::new(
+        query.limit.as_ref(),
+        query.offset.as_ref(),
+        query.fetch.as_ref(),
+    )?;
+    let columns = labels.iter().map(Ident::new).collect::<Vec<_>>();
+    let columns = Rc::new(columns);
+
+    let rows = rows.into_iter().map(|row| {
+        let next = Rc::new(BlendContext {
+            table_alias: "",
+            columns: Rc::clone(&columns),
+            row: Some(row),
+            next: None,
+        });
+
+        Ok(AggregateContext {
+            aggregated: None,
+            next,
+        })
+    });
+
+    let rows = limit
+        .apply(sort.apply(rows)?)
+        .map(|aggregate_context| {
+            let AggregateContext { next, .. } = aggregate_context?;
+
+            next.row
+                .clone()
+                .ok_or_else(|| SelectError::Unreachable.into())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ResultSet {
+        labels,
+        rows: rows.into_iter().map(Ok),
+    })
+}
+
+fn combine<'a, T: 'static + Debug + PartialEq>(
+    storage: &'a dyn Store<T>,
+    set_expr: &'a SetExpr,
+    filter_context: Option<&'a FilterContext<'a>>,
+) -> Result<(Vec<String>, Vec<Row>)> {
+    match set_expr {
+        SetExpr::Select(statement) => {
+            let limit = Limit::new(None, None, None)?;
+            let ResultSet { labels, rows } =
+                select_statement(storage, statement, &[], limit, filter_context)?;
+
+            Ok((labels, rows.collect::<Result<_>>()?))
+        }
+        SetExpr::Query(query) => {
+            let ResultSet { labels, rows } = select(storage, query, filter_context)?;
+
+            Ok((labels, rows.collect::<Result<_>>()?))
+        }
+        SetExpr::SetOperation {
+            op,
+            all,
+            left,
+            right,
+        } => {
+            let (labels, left_rows) = combine(storage, left, filter_context)?;
+            let (right_labels, right_rows) = combine(storage, right, filter_context)?;
+
+            if labels.len() != right_labels.len() {
+                return Err(ExecuteError::ColumnCountMismatch.into());
+            }
+
+            let rows = match op {
+                SetOperator::Union => left_rows.into_iter().chain(right_rows).collect(),
+                SetOperator::Intersect => filter_rows(left_rows, &right_rows, *all, true),
+                SetOperator::Except => filter_rows(left_rows, &right_rows, *all, false),
+            };
+            let rows = Distinct::new(!all)
+                .apply(rows.into_iter().map(Ok))
+                .collect::<Result<_>>()?;
+
+            Ok((labels, rows))
+        }
+        SetExpr::Values(_) => Err(ExecuteError::QueryNotSupported.into()),
+    }
+}
+
+/// Keeps rows of `left` which are found in `right` for `INTERSECT`, or not found for `EXCEPT`.
+/// With `ALL`, each row of `right` cancels out only one matching row of `left`.
+fn filter_rows(left: Vec<Row>, right: &[Row], all: bool, intersect: bool) -> Vec<Row> {
+    let counts = right
+        .iter()
+        .map(get_key)
+        .fold(HashMap::new(), |counts, key| {
+            counts.update_with(key, 1, |count, one| count + one)
+        });
+
+    left.into_iter()
+        .scan(counts, |counts, row| {
+            let key = get_key(&row);
+            let count = counts.get(&key).copied().unwrap_or(0);
+            let found = count > 0;
+
+            if all && found {
+                counts.insert(key, count - 1);
+            }
+
+            Some((found == intersect).as_some(row))
+        })
+        .flatten()
+        .collect()
+}
@@ -28,7 +28,10 @@ impl<'a> BlendContext<'a> {
             self.columns
                 .iter()
                 .position(|column| column.value == target)
-                .and_then(|index| self.row.as_ref().and_then(|row| row.get_value(index)))
+                .and_then(|index| match &self.row {
+                    Some(row) => row.get_value(index),
+                    None => Some(&Value::Empty),
+                })
         };
 
         match get_value() {
@@ -49,7 +52,10 @@ impl<'a> BlendContext<'a> {
             self.columns
                 .iter()
                 .position(|column| column.value == target)
-                .and_then(|index| self.row.as_ref().and_then(|row| row.get_value(index)))
+                .and_then(|index| match &self.row {
+                    Some(row) => row.get_value(index),
+                    None => Some(&Value::Empty),
+                })
         };
 
         match get_value() {
@@ -1,24 +1,36 @@
 use serde::Serialize;
+use std::cell::RefCell;
 use std::fmt::Debug;
 use thiserror::Error;
 
 use sqlparser::ast::Ident;
 
 use crate::data::{Row, Value};
+use crate::executor::fetch::TableData;
+use crate::executor::options::ExecuteOptions;
 use crate::result::Result;
 
 #[derive(Error, Serialize, Debug, PartialEq)]
 pub enum FilterContextError {
     #[error("value not found")]
     ValueNotFound,
+
+    #[error("value not bound yet")]
+    ValueNotBound,
 }
 
+/// Column looked up by `FilterContext`, with its table alias if it is qualified.
+pub type ColumnRef = (Option<String>, String);
+
 #[derive(Debug)]
 pub struct FilterContext<'a> {
     table_alias: &'a str,
     columns: &'a [Ident],
-    row: &'a Row,
+    row: Option<&'a Row>,
     next: Option<&'a FilterContext<'a>>,
+    cte: Option<(&'a str, &'a TableData<'a>)>,
+    reads: Option<&'a RefCell<Vec<(ColumnRef, Value)>>>,
+    options: Option<&'a ExecuteOptions>,
 }
 
 impl<'a> FilterContext<'a> {
@@ -31,25 +43,117 @@ impl<'a> FilterContext<'a> {
         Self {
             table_alias,
             columns,
-            row,
+            row: Some(row),
+            next,
+            cte: None,
+            reads: None,
+            options: None,
+        }
+    }
+
+    /// Columns of the table are known but their values are not, so looking them up fails
+    /// rather than falling back to `next`.
+    pub fn unbound(
+        table_alias: &'a str,
+        columns: &'a [Ident],
+        next: Option<&'a FilterContext<'a>>,
+    ) -> Self {
+        Self {
+            table_alias,
+            columns,
+            row: None,
             next,
+            cte: None,
+            reads: None,
+            options: None,
+        }
+    }
+
+    /// Table of `WITH` clause, which is visible to the query and its subqueries by `name`.
+    pub fn with_cte(
+        name: &'a str,
+        table_data: &'a TableData<'a>,
+        next: Option<&'a FilterContext<'a>>,
+    ) -> Self {
+        Self {
+            table_alias: name,
+            columns: &[],
+            row: None,
+            next,
+            cte: Some((name, table_data)),
+            reads: None,
+            options: None,
+        }
+    }
+
+    /// No table of its own, every value found in `next` is recorded to `reads` with the column
+    /// it was looked up by.
+    pub fn recorded(
+        reads: &'a RefCell<Vec<(ColumnRef, Value)>>,
+        next: Option<&'a FilterContext<'a>>,
+    ) -> Self {
+        Self {
+            table_alias: "",
+            columns: &[],
+            row: None,
+            next,
+            cte: None,
+            reads: Some(reads),
+            options: None,
+        }
+    }
+
+    /// No table of its own, `options` of the statement are given to it and every subquery of it
+    /// through the contexts chained on top of this one.
+    pub fn with_options(options: &'a ExecuteOptions) -> Self {
+        Self {
+            table_alias: "",
+            columns: &[],
+            row: None,
+            next: None,
+            cte: None,
+            reads: None,
+            options: Some(options),
+        }
+    }
+
+    pub fn get_options(&self) -> Option<&'a ExecuteOptions> {
+        self.options
+            .or_else(|| self.next.and_then(|context| context.get_options()))
+    }
+
+    /// Only the statement itself runs with the context of its options, so its subqueries are
+    /// validated anyway.
+    pub fn columns_validated(&self) -> bool {
+        self.options
+            .is_some_and(|options| options.columns_validated)
+    }
+
+    pub fn get_cte(&self, target: &str) -> Option<&'a TableData<'a>> {
+        match self.cte {
+            Some((name, table_data)) if name == target => Some(table_data),
+            _ => self.next.and_then(|context| context.get_cte(target)),
         }
     }
 
     pub fn get_value(&self, target: &str) -> Result<&'a Value> {
         let get_value = || {
-            self.columns
+            let index = self
+                .columns
                 .iter()
-                .position(|column| column.value == target)
-                .and_then(|index| self.row.get_value(index))
+                .position(|column| column.value == target)?;
+
+            self.get_row_value(index)
         };
 
         match get_value() {
             None => match self.next {
                 None => Err(FilterContextError::ValueNotFound.into()),
-                Some(context) => context.get_value(target),
+                Some(context) => context
+                    .get_value(target)
+                    .map(|value| self.record(None, target, value)),
             },
-            Some(value) => Ok(value),
+            Some(value) => value,
         }
     }
 
@@ -59,18 +163,50 @@ impl<'a> FilterContext<'a> {
                 return None;
             }
 
-            self.columns
+            let index = self
+                .columns
                 .iter()
-                .position(|column| column.value == target)
-                .and_then(|index| self.row.get_value(index))
+                .position(|column| column.value == target)?;
+
+            self.get_row_value(index)
         };
 
         match get_value() {
             None => match self.next {
                 None => Err(FilterContextError::ValueNotFound.into()),
-                Some(context) => context.get_alias_value(table_alias, target),
+                Some(context) => context
+                    .get_alias_value(table_alias, target)
+                    .map(|value| self.record(Some(table_alias), target, value)),
             },
-            Some(value) => Ok(value),
+            Some(value) => value,
+        }
+    }
+
+    /// Whether the column belongs to one of the tables, whether its value is bound or not.
+    pub fn contains(&self, table_alias: Option<&str>, target: &str) -> bool {
+        let found = table_alias.is_none_or(|table_alias| table_alias == self.table_alias)
+            && self.columns.iter().any(|column| column.value == target);
+
+        found
+            || self
+                .next
+                .is_some_and(|context| context.contains(table_alias, target))
+    }
+
+    fn record(&self, table_alias: Option<&str>, target: &str, value: &'a Value) -> &'a Value {
+        if let Some(reads) = self.reads {
+            let column = (table_alias.map(str::to_owned), target.to_owned());
+
+            reads.borrow_mut().push((column, value.clone()));
+        }
+
+        value
+    }
+
+    fn get_row_value(&self, index: usize) -> Option<Result<&'a Value>> {
+        match self.row {
+            Some(row) => row.get_value(index).map(Ok),
+            None => Some(Err(FilterContextError::ValueNotBound.into())),
         }
     }
 }
@@ -5,5 +5,5 @@ mod union_context;
 
 pub use aggregate_context::AggregateContext;
 pub use blend_context::{BlendContext, BlendContextError};
-pub use filter_context::{FilterContext, FilterContextError};
+pub use filter_context::{ColumnRef, FilterContext, FilterContextError};
 pub use union_context::{UnionContext, UnionContextError};
@@ -3,7 +3,8 @@ use std::fmt::Debug;
 use thiserror::Error;
 
 use super::{BlendContext, FilterContext};
-use crate::data::Value;
+use crate::data::{Row, Value};
+use crate::executor::subquery_cache::SubqueryCache;
 use crate::result::Result;
 
 // TODO: add error test case
@@ -15,8 +16,9 @@ pub enum UnionContextError {
 
 #[derive(Clone)]
 pub struct UnionContext<'a> {
-    pub filter_context: Option<&'a FilterContext<'a>>,
+    filter_context: Option<&'a FilterContext<'a>>,
     blend_context: Option<&'a BlendContext<'a>>,
+    subquery_cache: Option<&'a SubqueryCache>,
 }
 
 impl<'a> UnionContext<'a> {
@@ -27,12 +29,27 @@ impl<'a> UnionContext<'a> {
         Self {
             filter_context,
             blend_context,
+            subquery_cache: None,
         }
     }
 
+    /// Scalar subqueries are run through `subquery_cache`, see `SubqueryCache`.
+    pub fn with_subquery_cache(self, subquery_cache: Option<&'a SubqueryCache>) -> Self {
+        Self {
+            subquery_cache,
+            ..self
+        }
+    }
+
+    pub fn get_subquery_cache(&self) -> Option<&'a SubqueryCache> {
+        self.subquery_cache
+    }
+
+    /// `blend_context` is looked up first, a column of the query shadows a column of the same
+    /// name in the outer queries.
     pub fn get_value(&self, target: &str) -> Result<&'a Value> {
         match (self.filter_context, self.blend_context) {
-            (Some(fc), Some(bc)) => fc.get_value(target).or_else(|_| bc.get_value(target)),
+            (Some(fc), Some(bc)) => bc.get_value(target).or_else(|_| fc.get_value(target)),
             (Some(fc), None) => fc.get_value(target),
             (None, Some(bc)) => bc.get_value(target),
             (None, None) => Err(UnionContextError::ValueNotFound.into()),
@@ -41,12 +58,43 @@ impl<'a> UnionContext<'a> {
 
     pub fn get_alias_value(&self, table_alias: &str, target: &str) -> Result<&'a Value> {
         match (self.filter_context, self.blend_context) {
-            (Some(fc), Some(bc)) => fc
+            (Some(fc), Some(bc)) => bc
                 .get_alias_value(table_alias, target)
-                .or_else(|_| bc.get_alias_value(table_alias, target)),
+                .or_else(|_| fc.get_alias_value(table_alias, target)),
             (Some(fc), None) => fc.get_alias_value(table_alias, target),
             (None, Some(bc)) => bc.get_alias_value(table_alias, target),
             (None, None) => Err(UnionContextError::ValueNotFound.into()),
         }
     }
+
+    /// Subqueries only take `FilterContext`, so the rows of `blend_context` are chained in front
+    /// of `filter_context` while `f` runs. Subqueries can refer to the outer row while the
+    /// outer query is evaluating its `SELECT` list or `ORDER BY`, not only `WHERE`.
+    pub fn with_filter_context<R>(&self, f: impl FnOnce(Option<&FilterContext<'_>>) -> R) -> R {
+        chain_blended(self.filter_context, self.blend_context, f)
+    }
+}
+
+fn chain_blended<R>(
+    filter_context: Option<&FilterContext<'_>>,
+    blend_context: Option<&BlendContext<'_>>,
+    f: impl FnOnce(Option<&FilterContext<'_>>) -> R,
+) -> R {
+    let BlendContext {
+        table_alias,
+        columns,
+        row,
+        next,
+    } = match blend_context {
+        Some(blend_context) => blend_context,
+        None => {
+            return f(filter_context);
+        }
+    };
+
+    let empty_row = Row(columns.iter().map(|_| Value::Empty).collect());
+    let row = row.as_ref().unwrap_or(&empty_row);
+    let context = FilterContext::new(table_alias, columns, row, filter_context);
+
+    chain_blended(Some(&context), next.as_deref(), f)
 }
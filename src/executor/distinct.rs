@@ -0,0 +1,142 @@
+use boolinator::Boolinator;
+use im_rc::HashSet;
+use iter_enum::Iterator;
+
+use sqlparser::ast::{Expr, Function, Ident, ObjectName, OrderByExpr, Select, SelectItem};
+
+use super::select::SelectError;
+use crate::data::{Row, Value};
+use crate::parse::DISTINCT_ON;
+use crate::result::Result;
+
+#[derive(Iterator)]
+enum Distincted<I1, I2> {
+    Applied(I1),
+    Skipped(I2),
+}
+
+/// Hashable form of `Value`, every `NULL` is treated as equal to each other.
+/// Unlike `=` which is unknown for `NULL`, deduplication of `DISTINCT`, `UNION`, `INTERSECT` and
+/// `EXCEPT` keeps a single `(1, NULL)` out of many.
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub enum DistinctKey {
+    Bool(bool),
+    I64(i64),
+    F64(u64),
+    Str(String),
+    Date(i32),
+    Timestamp(i64),
+    Json(String),
+    Bytes(Vec<u8>),
+    Null,
+}
+
+impl From<&Value> for DistinctKey {
+    fn from(value: &Value) -> Self {
+        use Value::*;
+
+        match value {
+            Bool(v) | OptBool(Some(v)) => DistinctKey::Bool(*v),
+            I64(v) | OptI64(Some(v)) => DistinctKey::I64(*v),
+            // -0.0 and 0.0 are equal, but their bits are not
+            F64(v) | OptF64(Some(v)) if *v == 0.0 => DistinctKey::F64(0.0_f64.to_bits()),
+            F64(v) | OptF64(Some(v)) => DistinctKey::F64(v.to_bits()),
+            Str(v) | OptStr(Some(v)) => DistinctKey::Str(v.clone()),
+            Date(v) | OptDate(Some(v)) => DistinctKey::Date(*v),
+            Timestamp(v) | OptTimestamp(Some(v)) => DistinctKey::Timestamp(*v),
+            Json(v) | OptJson(Some(v)) => DistinctKey::Json(v.clone()),
+            Bytes(v) | OptBytes(Some(v)) => DistinctKey::Bytes(v.clone()),
+            Empty | OptBool(None) | OptI64(None) | OptF64(None) | OptStr(None) | OptDate(None)
+            | OptTimestamp(None) | OptJson(None) | OptBytes(None) => DistinctKey::Null,
+        }
+    }
+}
+
+pub fn get_key(Row(values): &Row) -> Vec<DistinctKey> {
+    values.iter().map(DistinctKey::from).collect()
+}
+
+/// `DISTINCT ON (...)` is parsed as a function `ON` at the first item of `SELECT`, see `parse`.
+/// Returns the number of leading `ORDER BY` keys to be distinct on, which should be the same
+/// expressions as `DISTINCT ON` in any order, and the rest of `SELECT` items.
+pub fn get_distinct_on<'a>(
+    statement: &'a Select,
+    order_by: &[OrderByExpr],
+) -> Result<(Option<usize>, &'a [SelectItem])> {
+    let Select {
+        distinct,
+        projection,
+        ..
+    } = statement;
+
+    let exprs = match projection.first() {
+        Some(SelectItem::UnnamedExpr(Expr::Function(Function {
+            name: ObjectName(name),
+            args,
+            over: None,
+            distinct: false,
+        }))) if *distinct && is_on(name) => args,
+        _ => {
+            return Ok((None, projection));
+        }
+    };
+
+    let keys = order_by
+        .get(..exprs.len())
+        .ok_or(SelectError::DistinctOnNotMatchingOrderBy)?
+        .iter()
+        .map(|OrderByExpr { expr, .. }| expr)
+        .collect::<Vec<_>>();
+
+    if !exprs.iter().all(|expr| keys.contains(&expr))
+        || !keys.iter().all(|expr| exprs.contains(expr))
+    {
+        return Err(SelectError::DistinctOnNotMatchingOrderBy.into());
+    }
+
+    Ok((Some(exprs.len()), &projection[1..]))
+}
+
+fn is_on(name: &[Ident]) -> bool {
+    match name {
+        [Ident {
+            value,
+            quote_style: None,
+        }] => value == DISTINCT_ON,
+        _ => false,
+    }
+}
+
+pub struct Distinct {
+    distinct: bool,
+}
+
+impl Distinct {
+    pub fn new(distinct: bool) -> Self {
+        Self { distinct }
+    }
+
+    pub fn apply(
+        &self,
+        rows: impl Iterator<Item = Result<Row>>,
+    ) -> impl Iterator<Item = Result<Row>> {
+        if !self.distinct {
+            return Distincted::Skipped(rows);
+        }
+
+        let rows = rows
+            .scan(HashSet::new(), |keys, row| {
+                let row = match row {
+                    Ok(row) => row,
+                    Err(error) => {
+                        return Some(Some(Err(error)));
+                    }
+                };
+
+                Some(keys.insert(get_key(&row)).is_none().as_some(Ok(row)))
+            })
+            .flatten();
+
+        Distincted::Applied(rows)
+    }
+}
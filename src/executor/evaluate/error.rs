@@ -4,8 +4,11 @@ use thiserror::Error;
 
 #[derive(Error, Serialize, Debug, PartialEq)]
 pub enum EvaluateError {
-    #[error("nested select row not found")]
-    NestedSelectRowNotFound,
+    #[error("nested select should return exactly one column, but returned {0}")]
+    NestedSelectColumnCountNotOne(usize),
+
+    #[error("nested select returned more than one row")]
+    NestedSelectMultipleRows,
 
     #[error("literal add on non-numeric")]
     LiteralAddOnNonNumeric,
@@ -28,6 +31,25 @@ pub enum EvaluateError {
     #[error("unreachable, aggregated field does not exist")]
     UnreachableEmptyAggregated,
 
+    #[error("function not supported: {0}")]
+    FunctionNotSupported(String),
+
+    #[error("function {0} requires string value")]
+    FunctionRequiresStringValue(String),
+
+    #[error("function {name} expects {expected} params, but {found} were found")]
+    NumberOfFunctionParamsNotMatching {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("operator {0} requires json value")]
+    OperatorRequiresJsonValue(String),
+
+    #[error("json key should be text or integer, but found {0}")]
+    InvalidJsonKey(String),
+
     #[error("unimplemented")]
     Unimplemented,
 }
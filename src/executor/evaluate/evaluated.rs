@@ -1,10 +1,11 @@
 use std::cmp::Ordering;
+use std::convert::TryFrom;
 
-use sqlparser::ast::Value as AstValue;
+use sqlparser::ast::{DataType, Value as AstValue};
 
 use crate::data;
-use crate::data::Value;
-use crate::result::Result;
+use crate::data::{Value, ValueError};
+use crate::result::{Error, Result};
 
 use super::EvaluateError;
 
@@ -16,6 +17,20 @@ pub enum Evaluated<'a> {
     Value(Value),
 }
 
+impl TryFrom<Evaluated<'_>> for Value {
+    type Error = Error;
+
+    fn try_from(evaluated: Evaluated<'_>) -> Result<Self> {
+        match evaluated {
+            Evaluated::LiteralRef(v) => Value::try_from(v),
+            Evaluated::Literal(v) => Value::try_from(&v),
+            Evaluated::StringRef(v) => Ok(Value::Str(v.to_string())),
+            Evaluated::ValueRef(v) => Ok(v.clone()),
+            Evaluated::Value(v) => Ok(v),
+        }
+    }
+}
+
 impl<'a> PartialEq for Evaluated<'a> {
     fn eq(&self, other: &Evaluated<'a>) -> bool {
         let eq_ast = |l: &AstValue, r| match l {
@@ -37,33 +52,35 @@ impl<'a> PartialEq for Evaluated<'a> {
                     StringRef(r) => eq_ast(l, r),
                     ValueRef(r) => r == l,
                     Value(r) => &r == l,
-                    Literal(_) => panic!(),
+                    Literal(r) => *l == r,
                 },
                 StringRef(l) => match other {
                     LiteralRef(r) => eq_ast(r, l),
                     StringRef(r) => l == r,
                     ValueRef(r) => eq_val(r, l),
-                    Value(r) => eq_val(&r, l),
-                    Literal(_) => false,
+                    Value(r) => eq_val(r, l),
+                    Literal(r) => eq_ast(r, l),
                 },
                 ValueRef(l) => match other {
                     LiteralRef(r) => l == r,
                     Literal(r) => l == &r,
                     StringRef(r) => eq_val(l, r),
-                    ValueRef(r) => l == r,
-                    Value(r) => l == &r,
+                    ValueRef(r) => l.eq_coerced(r),
+                    Value(r) => l.eq_coerced(r),
                 },
                 Value(l) => match other {
                     LiteralRef(r) => &l == r,
-                    StringRef(r) => eq_val(&l, r),
-                    ValueRef(r) => &l == r,
-                    Value(r) => l == r,
-                    Literal(_) => panic!(),
+                    StringRef(r) => eq_val(l, r),
+                    ValueRef(r) => l.eq_coerced(r),
+                    Value(r) => l.eq_coerced(r),
+                    Literal(r) => l == r,
                 },
                 Literal(l) => match other {
+                    LiteralRef(r) => l == *r,
+                    Literal(r) => l == r,
+                    StringRef(r) => eq_ast(l, r),
                     ValueRef(r) => r == &l,
-                    StringRef(_) => false,
-                    _ => panic!(),
+                    Value(r) => r == l,
                 },
             }
         }
@@ -119,7 +136,10 @@ fn literal_partial_cmp(a: &AstValue, b: &AstValue) -> Option<Ordering> {
     match (a, b) {
         (AstValue::Number(l), AstValue::Number(r)) => match (l.parse::<i64>(), r.parse::<i64>()) {
             (Ok(l), Ok(r)) => Some(l.cmp(&r)),
-            _ => None,
+            _ => match (l.parse::<f64>(), r.parse::<f64>()) {
+                (Ok(l), Ok(r)) => l.partial_cmp(&r),
+                _ => None,
+            },
         },
         (AstValue::SingleQuotedString(l), AstValue::SingleQuotedString(r)) => Some(l.cmp(r)),
         _ => None,
@@ -133,26 +153,26 @@ impl<'a> Evaluated<'a> {
         let unreachable = || Err(EvaluateError::UnreachableEvaluatedArithmetic.into());
 
         let add_literal = |l, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => literal_add(l, r).map(Evaluated::Literal),
-            Literal(r) => literal_add(l, &r).map(Evaluated::Literal),
-            ValueRef(r) => r.add(&r.clone_by(l)?).map(Evaluated::Value),
-            Value(r) => r.add(&r.clone_by(l)?).map(Evaluated::Value),
+            LiteralRef(r) => literal_add(l, r),
+            Literal(r) => literal_add(l, r),
+            ValueRef(r) => r.add(&cast_literal(r, l)?).map(Evaluated::Value),
+            Value(r) => r.add(&cast_literal(r, l)?).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         let add_value = |l: &data::Value, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => l.add(&l.clone_by(r)?).map(Evaluated::Value),
-            Literal(r) => l.add(&l.clone_by(&r)?).map(Evaluated::Value),
+            LiteralRef(r) => l.add(&cast_literal(l, r)?).map(Evaluated::Value),
+            Literal(r) => l.add(&cast_literal(l, r)?).map(Evaluated::Value),
             ValueRef(r) => l.add(r).map(Evaluated::Value),
-            Value(r) => l.add(&r).map(Evaluated::Value),
+            Value(r) => l.add(r).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         match self {
             LiteralRef(l) => add_literal(l, other),
-            Literal(l) => add_literal(&l, other),
+            Literal(l) => add_literal(l, other),
             ValueRef(l) => add_value(l, other),
-            Value(l) => add_value(&l, other),
+            Value(l) => add_value(l, other),
             StringRef(_) => unreachable(),
         }
     }
@@ -163,26 +183,26 @@ impl<'a> Evaluated<'a> {
         let unreachable = || Err(EvaluateError::UnreachableEvaluatedArithmetic.into());
 
         let subtract_literal = |l, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => literal_subtract(l, r).map(Evaluated::Literal),
-            Literal(r) => literal_subtract(l, &r).map(Evaluated::Literal),
-            ValueRef(r) => (r.clone_by(l)?).subtract(r).map(Evaluated::Value),
-            Value(r) => (r.clone_by(l)?).subtract(r).map(Evaluated::Value),
+            LiteralRef(r) => literal_subtract(l, r),
+            Literal(r) => literal_subtract(l, r),
+            ValueRef(r) => (cast_literal(r, l)?).subtract(r).map(Evaluated::Value),
+            Value(r) => (cast_literal(r, l)?).subtract(r).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         let subtract_value = |l: &data::Value, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => l.subtract(&l.clone_by(r)?).map(Evaluated::Value),
-            Literal(r) => l.subtract(&l.clone_by(&r)?).map(Evaluated::Value),
+            LiteralRef(r) => l.subtract(&cast_literal(l, r)?).map(Evaluated::Value),
+            Literal(r) => l.subtract(&cast_literal(l, r)?).map(Evaluated::Value),
             ValueRef(r) => l.subtract(r).map(Evaluated::Value),
-            Value(r) => l.subtract(&r).map(Evaluated::Value),
+            Value(r) => l.subtract(r).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         match self {
             LiteralRef(l) => subtract_literal(l, other),
-            Literal(l) => subtract_literal(&l, other),
+            Literal(l) => subtract_literal(l, other),
             ValueRef(l) => subtract_value(l, other),
-            Value(l) => subtract_value(&l, other),
+            Value(l) => subtract_value(l, other),
             StringRef(_) => unreachable(),
         }
     }
@@ -193,26 +213,26 @@ impl<'a> Evaluated<'a> {
         let unreachable = || Err(EvaluateError::UnreachableEvaluatedArithmetic.into());
 
         let multiply_literal = |l, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => literal_multiply(l, r).map(Evaluated::Literal),
-            Literal(r) => literal_multiply(l, &r).map(Evaluated::Literal),
-            ValueRef(r) => (r.clone_by(l)?).multiply(r).map(Evaluated::Value),
-            Value(r) => (r.clone_by(l)?).multiply(r).map(Evaluated::Value),
+            LiteralRef(r) => literal_multiply(l, r),
+            Literal(r) => literal_multiply(l, r),
+            ValueRef(r) => (cast_literal(r, l)?).multiply(r).map(Evaluated::Value),
+            Value(r) => (cast_literal(r, l)?).multiply(r).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         let multiply_value = |l: &data::Value, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => l.multiply(&l.clone_by(r)?).map(Evaluated::Value),
-            Literal(r) => l.multiply(&l.clone_by(&r)?).map(Evaluated::Value),
+            LiteralRef(r) => l.multiply(&cast_literal(l, r)?).map(Evaluated::Value),
+            Literal(r) => l.multiply(&cast_literal(l, r)?).map(Evaluated::Value),
             ValueRef(r) => l.multiply(r).map(Evaluated::Value),
-            Value(r) => l.multiply(&r).map(Evaluated::Value),
+            Value(r) => l.multiply(r).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         match self {
             LiteralRef(l) => multiply_literal(l, other),
-            Literal(l) => multiply_literal(&l, other),
+            Literal(l) => multiply_literal(l, other),
             ValueRef(l) => multiply_value(l, other),
-            Value(l) => multiply_value(&l, other),
+            Value(l) => multiply_value(l, other),
             StringRef(_) => unreachable(),
         }
     }
@@ -223,30 +243,72 @@ impl<'a> Evaluated<'a> {
         let unreachable = || Err(EvaluateError::UnreachableEvaluatedArithmetic.into());
 
         let divide_literal = |l, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => literal_divide(l, r).map(Evaluated::Literal),
-            Literal(r) => literal_divide(l, &r).map(Evaluated::Literal),
-            ValueRef(r) => (r.clone_by(l)?).divide(r).map(Evaluated::Value),
-            Value(r) => (r.clone_by(l)?).divide(r).map(Evaluated::Value),
+            LiteralRef(r) => literal_divide(l, r),
+            Literal(r) => literal_divide(l, r),
+            ValueRef(r) => (cast_literal(r, l)?).divide(r).map(Evaluated::Value),
+            Value(r) => (cast_literal(r, l)?).divide(r).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         let divide_value = |l: &data::Value, other: &Evaluated<'a>| match other {
-            LiteralRef(r) => l.divide(&l.clone_by(r)?).map(Evaluated::Value),
-            Literal(r) => l.divide(&l.clone_by(&r)?).map(Evaluated::Value),
+            LiteralRef(r) => l.divide(&cast_literal(l, r)?).map(Evaluated::Value),
+            Literal(r) => l.divide(&cast_literal(l, r)?).map(Evaluated::Value),
             ValueRef(r) => l.divide(r).map(Evaluated::Value),
-            Value(r) => l.divide(&r).map(Evaluated::Value),
+            Value(r) => l.divide(r).map(Evaluated::Value),
             StringRef(_) => unreachable(),
         };
 
         match self {
             LiteralRef(l) => divide_literal(l, other),
-            Literal(l) => divide_literal(&l, other),
+            Literal(l) => divide_literal(l, other),
             ValueRef(l) => divide_value(l, other),
-            Value(l) => divide_value(&l, other),
+            Value(l) => divide_value(l, other),
+            StringRef(_) => unreachable(),
+        }
+    }
+
+    pub fn modulo(&self, other: &Evaluated<'a>) -> Result<Evaluated<'a>> {
+        use Evaluated::*;
+
+        let unreachable = || Err(EvaluateError::UnreachableEvaluatedArithmetic.into());
+
+        let modulo_literal = |l, other: &Evaluated<'a>| match other {
+            LiteralRef(r) => literal_modulo(l, r),
+            Literal(r) => literal_modulo(l, r),
+            ValueRef(r) => (cast_literal(r, l)?).modulo(r).map(Evaluated::Value),
+            Value(r) => (cast_literal(r, l)?).modulo(r).map(Evaluated::Value),
+            StringRef(_) => unreachable(),
+        };
+
+        let modulo_value = |l: &data::Value, other: &Evaluated<'a>| match other {
+            LiteralRef(r) => l.modulo(&cast_literal(l, r)?).map(Evaluated::Value),
+            Literal(r) => l.modulo(&cast_literal(l, r)?).map(Evaluated::Value),
+            ValueRef(r) => l.modulo(r).map(Evaluated::Value),
+            Value(r) => l.modulo(r).map(Evaluated::Value),
+            StringRef(_) => unreachable(),
+        };
+
+        match self {
+            LiteralRef(l) => modulo_literal(l, other),
+            Literal(l) => modulo_literal(l, other),
+            ValueRef(l) => modulo_value(l, other),
+            Value(l) => modulo_value(l, other),
             StringRef(_) => unreachable(),
         }
     }
 
+    /// `||` of SQL, both sides are concatenated as `TEXT`, `NULL` if either side is `NULL`.
+    pub fn concat(&self, other: &Evaluated<'a>) -> Result<Evaluated<'a>> {
+        if !self.is_some() || !other.is_some() {
+            return Ok(Evaluated::Value(data::Value::Empty));
+        }
+
+        let l = get_text(self)?;
+        let r = get_text(other)?;
+
+        Ok(Evaluated::Value(data::Value::Str(l + &r)))
+    }
+
     pub fn is_some(&self) -> bool {
         match self {
             Evaluated::ValueRef(v) => v.is_some(),
@@ -258,53 +320,104 @@ impl<'a> Evaluated<'a> {
     }
 }
 
-fn literal_add(a: &AstValue, b: &AstValue) -> Result<AstValue> {
-    match (a, b) {
+fn get_text(evaluated: &Evaluated<'_>) -> Result<String> {
+    let value = match evaluated {
+        Evaluated::StringRef(v) => {
+            return Ok(v.to_string());
+        }
+        Evaluated::LiteralRef(v) => Value::try_from(*v)?.cast(&DataType::Text)?,
+        Evaluated::Literal(v) => Value::try_from(v)?.cast(&DataType::Text)?,
+        Evaluated::ValueRef(v) => v.cast(&DataType::Text)?,
+        Evaluated::Value(v) => v.cast(&DataType::Text)?,
+    };
+
+    match value {
+        Value::Str(v) => Ok(v),
+        _ => Err(EvaluateError::UnreachableEvaluatedArithmetic.into()),
+    }
+}
+
+/// Literal takes the type of the value it is calculated with, except a float literal calculated
+/// with an integer value which stays as a float.
+fn cast_literal(value: &data::Value, literal: &AstValue) -> Result<data::Value> {
+    match literal {
+        AstValue::Number(v) if v.parse::<i64>().is_err() => data::Value::try_from(literal),
+        _ => value.clone_by(literal),
+    }
+}
+
+fn literal_add<'a>(left: &AstValue, right: &AstValue) -> Result<Evaluated<'a>> {
+    match (left, right) {
         (AstValue::Number(a), AstValue::Number(b)) => match (a.parse::<i64>(), b.parse::<i64>()) {
-            (Ok(a), Ok(b)) => Ok(AstValue::Number((a + b).to_string())),
-            _ => panic!(),
+            (Ok(a), Ok(b)) => Ok(Evaluated::Literal(AstValue::Number((a + b).to_string()))),
+            _ => data::Value::try_from(left)?
+                .add(&data::Value::try_from(right)?)
+                .map(Evaluated::Value),
         },
         (AstValue::Null, AstValue::Number(_)) | (AstValue::Number(_), AstValue::Null) => {
-            Ok(AstValue::Null)
+            Ok(Evaluated::Literal(AstValue::Null))
         }
         _ => Err(EvaluateError::UnreachableLiteralArithmetic.into()),
     }
 }
 
-fn literal_subtract(a: &AstValue, b: &AstValue) -> Result<AstValue> {
-    match (a, b) {
+fn literal_subtract<'a>(left: &AstValue, right: &AstValue) -> Result<Evaluated<'a>> {
+    match (left, right) {
         (AstValue::Number(a), AstValue::Number(b)) => match (a.parse::<i64>(), b.parse::<i64>()) {
-            (Ok(a), Ok(b)) => Ok(AstValue::Number((a - b).to_string())),
-            _ => panic!(),
+            (Ok(a), Ok(b)) => Ok(Evaluated::Literal(AstValue::Number((a - b).to_string()))),
+            _ => data::Value::try_from(left)?
+                .subtract(&data::Value::try_from(right)?)
+                .map(Evaluated::Value),
         },
         (AstValue::Null, AstValue::Number(_)) | (AstValue::Number(_), AstValue::Null) => {
-            Ok(AstValue::Null)
+            Ok(Evaluated::Literal(AstValue::Null))
         }
         _ => Err(EvaluateError::UnreachableLiteralArithmetic.into()),
     }
 }
 
-fn literal_multiply(a: &AstValue, b: &AstValue) -> Result<AstValue> {
-    match (a, b) {
+fn literal_multiply<'a>(left: &AstValue, right: &AstValue) -> Result<Evaluated<'a>> {
+    match (left, right) {
         (AstValue::Number(a), AstValue::Number(b)) => match (a.parse::<i64>(), b.parse::<i64>()) {
-            (Ok(a), Ok(b)) => Ok(AstValue::Number((a * b).to_string())),
-            _ => panic!(),
+            (Ok(a), Ok(b)) => Ok(Evaluated::Literal(AstValue::Number((a * b).to_string()))),
+            _ => data::Value::try_from(left)?
+                .multiply(&data::Value::try_from(right)?)
+                .map(Evaluated::Value),
         },
         (AstValue::Null, AstValue::Number(_)) | (AstValue::Number(_), AstValue::Null) => {
-            Ok(AstValue::Null)
+            Ok(Evaluated::Literal(AstValue::Null))
         }
         _ => Err(EvaluateError::UnreachableLiteralArithmetic.into()),
     }
 }
 
-fn literal_divide(a: &AstValue, b: &AstValue) -> Result<AstValue> {
-    match (a, b) {
+fn literal_divide<'a>(left: &AstValue, right: &AstValue) -> Result<Evaluated<'a>> {
+    match (left, right) {
+        (AstValue::Number(a), AstValue::Number(b)) => match (a.parse::<i64>(), b.parse::<i64>()) {
+            (Ok(_), Ok(0)) => Err(ValueError::DivisorShouldNotBeZero.into()),
+            (Ok(a), Ok(b)) => Ok(Evaluated::Literal(AstValue::Number((a / b).to_string()))),
+            _ => data::Value::try_from(left)?
+                .divide(&data::Value::try_from(right)?)
+                .map(Evaluated::Value),
+        },
+        (AstValue::Null, AstValue::Number(_)) | (AstValue::Number(_), AstValue::Null) => {
+            Ok(Evaluated::Literal(AstValue::Null))
+        }
+        _ => Err(EvaluateError::UnreachableLiteralArithmetic.into()),
+    }
+}
+
+fn literal_modulo<'a>(left: &AstValue, right: &AstValue) -> Result<Evaluated<'a>> {
+    match (left, right) {
         (AstValue::Number(a), AstValue::Number(b)) => match (a.parse::<i64>(), b.parse::<i64>()) {
-            (Ok(a), Ok(b)) => Ok(AstValue::Number((a / b).to_string())),
-            _ => panic!(),
+            (Ok(_), Ok(0)) => Err(ValueError::DivisorShouldNotBeZero.into()),
+            (Ok(a), Ok(b)) => Ok(Evaluated::Literal(AstValue::Number((a % b).to_string()))),
+            _ => data::Value::try_from(left)?
+                .modulo(&data::Value::try_from(right)?)
+                .map(Evaluated::Value),
         },
         (AstValue::Null, AstValue::Number(_)) | (AstValue::Number(_), AstValue::Null) => {
-            Ok(AstValue::Null)
+            Ok(Evaluated::Literal(AstValue::Null))
         }
         _ => Err(EvaluateError::UnreachableLiteralArithmetic.into()),
     }
@@ -0,0 +1,236 @@
+use sqlparser::ast::{Expr, Value as AstValue};
+
+use super::{EvaluateError, Evaluated};
+use crate::data::{parse_json, Json, Value, ValueError};
+use crate::parse::{ILIKE_PATTERN, JSON_FIELD, JSON_TEXT};
+use crate::result::Result;
+
+/// Scalar functions, they are evaluated per row unlike aggregate functions. `JSON_FIELD` and
+/// `JSON_TEXT` are the operators `->` and `->>` extracting a field of `JSON`, which are parsed as
+/// functions, see `parse`, and `ILIKE_PATTERN` is the pattern of `ILIKE`, which is parsed as
+/// `LIKE` of it.
+pub const FUNCTIONS: [&str; 11] = [
+    "UPPER",
+    "LOWER",
+    "LENGTH",
+    "TRIM",
+    "LTRIM",
+    "RTRIM",
+    "COALESCE",
+    "NULLIF",
+    JSON_FIELD,
+    JSON_TEXT,
+    ILIKE_PATTERN,
+];
+
+pub fn is_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name.to_uppercase().as_str())
+}
+
+/// `name` should be one of `FUNCTIONS`. Arguments are evaluated by `eval` only when they are
+/// needed, so `COALESCE` stops at the first non-`NULL` argument.
+pub fn evaluate_function<'a>(
+    name: &str,
+    args: &'a [Expr],
+    eval: impl Fn(&'a Expr) -> Result<Evaluated<'a>>,
+) -> Result<Evaluated<'a>> {
+    let name = name.to_uppercase();
+    let operator = name.trim_start_matches('%');
+    let check_params = |expected: usize| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(EvaluateError::NumberOfFunctionParamsNotMatching {
+                name: operator.to_owned(),
+                expected,
+                found: args.len(),
+            })
+        }
+    };
+
+    match name.as_str() {
+        "COALESCE" => {
+            if args.is_empty() {
+                check_params(1)?;
+            }
+
+            args.iter()
+                .map(eval)
+                .find(|evaluated| match evaluated {
+                    Ok(evaluated) => evaluated.is_some(),
+                    Err(_) => true,
+                })
+                .unwrap_or(Ok(Evaluated::Value(Value::Empty)))
+        }
+        "NULLIF" => {
+            check_params(2)?;
+
+            let l = eval(&args[0])?;
+            let r = eval(&args[1])?;
+
+            Ok(if l == r {
+                Evaluated::Value(Value::Empty)
+            } else {
+                l
+            })
+        }
+        ILIKE_PATTERN => {
+            check_params(1)?;
+
+            eval(&args[0])
+        }
+        JSON_FIELD | JSON_TEXT => {
+            check_params(2)?;
+
+            let json = get_json(operator, &eval(&args[0])?)?;
+            let key = get_key(&eval(&args[1])?)?;
+            let field = match (&json, key) {
+                (Some(json), Some(key)) => json.get(&key),
+                _ => None,
+            };
+            let value = if name == JSON_FIELD {
+                Value::OptJson(field.map(ToString::to_string))
+            } else {
+                Value::OptStr(field.and_then(Json::to_text))
+            };
+
+            Ok(Evaluated::Value(value))
+        }
+        _ => {
+            check_params(1)?;
+
+            let arg = eval(&args[0])?;
+
+            if let ("LENGTH", Some(length)) = (name.as_str(), get_bytes_length(&arg)) {
+                return Ok(Evaluated::Value(length));
+            }
+
+            let arg = get_string(&name, &arg)?;
+            let value = match name.as_str() {
+                "UPPER" => arg.map_string(str::to_uppercase),
+                "LOWER" => arg.map_string(str::to_lowercase),
+                "TRIM" => arg.map_string(|s| s.trim().to_owned()),
+                "LTRIM" => arg.map_string(|s| s.trim_start().to_owned()),
+                "RTRIM" => arg.map_string(|s| s.trim_end().to_owned()),
+                "LENGTH" => arg.length(),
+                _ => {
+                    return Err(EvaluateError::FunctionNotSupported(name).into());
+                }
+            };
+
+            Ok(Evaluated::Value(value))
+        }
+    }
+}
+
+enum StringArg<'a> {
+    Str(&'a str),
+    OptStr(Option<&'a str>),
+}
+
+impl StringArg<'_> {
+    fn map_string(&self, f: impl Fn(&str) -> String) -> Value {
+        match self {
+            StringArg::Str(s) => Value::Str(f(s)),
+            StringArg::OptStr(s) => Value::OptStr(s.map(f)),
+        }
+    }
+
+    fn length(&self) -> Value {
+        let length = |s: &str| s.chars().count() as i64;
+
+        match self {
+            StringArg::Str(s) => Value::I64(length(s)),
+            StringArg::OptStr(s) => Value::OptI64(s.map(length)),
+        }
+    }
+}
+
+/// Number of bytes of `BYTEA`, `None` if it is not `BYTEA`.
+fn get_bytes_length(evaluated: &Evaluated<'_>) -> Option<Value> {
+    let value = |value: &Value| match value {
+        Value::Bytes(v) => Some(Value::I64(v.len() as i64)),
+        Value::OptBytes(v) => Some(Value::OptI64(v.as_ref().map(|v| v.len() as i64))),
+        _ => None,
+    };
+
+    match evaluated {
+        Evaluated::LiteralRef(AstValue::HexStringLiteral(s))
+        | Evaluated::Literal(AstValue::HexStringLiteral(s)) => Some(Value::I64(s.len() as i64 / 2)),
+        Evaluated::ValueRef(v) => value(v),
+        Evaluated::Value(v) => value(v),
+        _ => None,
+    }
+}
+
+/// Document of `JSON` or of a string, `None` if it is `NULL`.
+fn get_json(name: &str, evaluated: &Evaluated<'_>) -> Result<Option<Json>> {
+    let parse = |s: &str| {
+        parse_json(s)
+            .map(Some)
+            .ok_or_else(|| ValueError::FailedToParseJson(s.to_owned()).into())
+    };
+    let value = |value: &Value| match value {
+        Value::Json(s) | Value::OptJson(Some(s)) | Value::Str(s) | Value::OptStr(Some(s)) => {
+            parse(s)
+        }
+        value if !value.is_some() => Ok(None),
+        _ => Err(EvaluateError::OperatorRequiresJsonValue(name.to_owned()).into()),
+    };
+
+    match evaluated {
+        Evaluated::LiteralRef(AstValue::SingleQuotedString(s))
+        | Evaluated::Literal(AstValue::SingleQuotedString(s)) => parse(s),
+        Evaluated::LiteralRef(AstValue::Null) | Evaluated::Literal(AstValue::Null) => Ok(None),
+        Evaluated::StringRef(s) => parse(s),
+        Evaluated::ValueRef(v) => value(v),
+        Evaluated::Value(v) => value(v),
+        _ => Err(EvaluateError::OperatorRequiresJsonValue(name.to_owned()).into()),
+    }
+}
+
+/// Name of a field or index of an element, `None` if it is `NULL`.
+fn get_key(evaluated: &Evaluated<'_>) -> Result<Option<String>> {
+    let literal = |literal: &AstValue| match literal {
+        AstValue::SingleQuotedString(s) => Ok(Some(s.clone())),
+        AstValue::Number(n) if n.parse::<i64>().is_ok() => Ok(Some(n.clone())),
+        AstValue::Null => Ok(None),
+        _ => Err(EvaluateError::InvalidJsonKey(literal.to_string()).into()),
+    };
+    let value = |value: &Value| match value {
+        Value::Str(s) | Value::OptStr(Some(s)) => Ok(Some(s.clone())),
+        Value::I64(n) | Value::OptI64(Some(n)) => Ok(Some(n.to_string())),
+        value if !value.is_some() => Ok(None),
+        value => Err(EvaluateError::InvalidJsonKey(format!("{:?}", value)).into()),
+    };
+
+    match evaluated {
+        Evaluated::LiteralRef(l) => literal(l),
+        Evaluated::Literal(l) => literal(l),
+        Evaluated::StringRef(s) => Ok(Some(s.to_string())),
+        Evaluated::ValueRef(v) => value(v),
+        Evaluated::Value(v) => value(v),
+    }
+}
+
+fn get_string<'a>(name: &str, evaluated: &'a Evaluated<'_>) -> Result<StringArg<'a>> {
+    let literal = |literal: &'a AstValue| match literal {
+        AstValue::SingleQuotedString(s) => Ok(StringArg::Str(s)),
+        AstValue::Null => Ok(StringArg::OptStr(None)),
+        _ => Err(EvaluateError::FunctionRequiresStringValue(name.to_owned()).into()),
+    };
+    let value = |value: &'a Value| match value {
+        Value::Str(s) => Ok(StringArg::Str(s)),
+        Value::OptStr(s) => Ok(StringArg::OptStr(s.as_deref())),
+        value if !value.is_some() => Ok(StringArg::OptStr(None)),
+        _ => Err(EvaluateError::FunctionRequiresStringValue(name.to_owned()).into()),
+    };
+
+    match evaluated {
+        Evaluated::LiteralRef(l) => literal(l),
+        Evaluated::Literal(l) => literal(l),
+        Evaluated::StringRef(s) => Ok(StringArg::Str(s)),
+        Evaluated::ValueRef(v) => value(v),
+        Evaluated::Value(v) => value(v),
+    }
+}
@@ -1,21 +1,28 @@
 mod error;
 mod evaluated;
+mod function;
 
+use boolinator::Boolinator;
 use im_rc::HashMap;
+use std::convert::TryFrom;
 use std::fmt::Debug;
 
-use sqlparser::ast::{BinaryOperator, Expr, Function, Value as AstValue};
+use sqlparser::ast::{BinaryOperator, Expr, Function, Query, Value as AstValue};
 
 use super::context::{FilterContext, UnionContext};
-use super::select::select;
-use crate::data::Value;
+use super::filter::check_union_expr;
+use super::select::{select, ResultSet};
+use crate::data::{get_name, Value};
 use crate::result::Result;
 use crate::store::Store;
 
 pub use error::EvaluateError;
 pub use evaluated::Evaluated;
+pub use function::is_function;
 
-pub fn evaluate<'a, T: 'static + Debug>(
+use function::evaluate_function;
+
+pub fn evaluate<'a, T: 'static + Debug + PartialEq>(
     storage: &'a dyn Store<T>,
     filter_context: Option<&'a FilterContext<'a>>,
     aggregated: Option<&HashMap<&Function, Value>>,
@@ -26,7 +33,7 @@ pub fn evaluate<'a, T: 'static + Debug>(
     evaluate_union(storage, context, aggregated, expr)
 }
 
-pub fn evaluate_union<'a, T: 'static + Debug>(
+pub fn evaluate_union<'a, T: 'static + Debug + PartialEq>(
     storage: &'a dyn Store<T>,
     context: UnionContext<'a>,
     aggregated: Option<&HashMap<&Function, Value>>,
@@ -39,6 +46,7 @@ pub fn evaluate_union<'a, T: 'static + Debug>(
             AstValue::Number(_)
             | AstValue::Boolean(_)
             | AstValue::SingleQuotedString(_)
+            | AstValue::HexStringLiteral(_)
             | AstValue::Null => Ok(Evaluated::LiteralRef(value)),
             _ => Err(EvaluateError::Unimplemented.into()),
         },
@@ -46,7 +54,7 @@ pub fn evaluate_union<'a, T: 'static + Debug>(
             Some(_) => Ok(Evaluated::StringRef(&ident.value)),
             None => context.get_value(&ident.value).map(Evaluated::ValueRef),
         },
-        Expr::Nested(expr) => eval(&expr),
+        Expr::Nested(expr) => eval(expr),
         Expr::CompoundIdentifier(idents) => {
             if idents.len() != 2 {
                 return Err(EvaluateError::UnsupportedCompoundIdentifier(expr.to_string()).into());
@@ -59,11 +67,22 @@ pub fn evaluate_union<'a, T: 'static + Debug>(
                 .get_alias_value(table_alias, column)
                 .map(Evaluated::ValueRef)
         }
-        Expr::Subquery(query) => select(storage, &query, context.filter_context)?
-            .map(|row| row?.take_first_value())
-            .map(|value| value.map(Evaluated::Value))
-            .next()
-            .ok_or(EvaluateError::NestedSelectRowNotFound)?,
+        Expr::Subquery(query) => context.with_filter_context(|filter_context| {
+            match context.get_subquery_cache() {
+                Some(cache) => cache.select(storage, query, filter_context),
+                None => select_scalar(storage, query, filter_context),
+            }
+            .map(Evaluated::Value)
+        }),
+        Expr::BinaryOp {
+            op: BinaryOperator::Like,
+            ..
+        }
+        | Expr::BinaryOp {
+            op: BinaryOperator::NotLike,
+            ..
+        } => check_union_expr(storage, context, aggregated, expr)
+            .map(|matched| Evaluated::Value(matched.map_or(Value::Empty, Value::Bool))),
         Expr::BinaryOp { op, left, right } => {
             let l = eval(left)?;
             let r = eval(right)?;
@@ -73,9 +92,54 @@ pub fn evaluate_union<'a, T: 'static + Debug>(
                 BinaryOperator::Minus => l.subtract(&r),
                 BinaryOperator::Multiply => l.multiply(&r),
                 BinaryOperator::Divide => l.divide(&r),
+                BinaryOperator::Modulus => l.modulo(&r),
+                BinaryOperator::StringConcat => l.concat(&r),
                 _ => Err(EvaluateError::Unimplemented.into()),
             }
         }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            let operand = operand.as_ref().map(|operand| eval(operand)).transpose()?;
+            let matched = conditions
+                .iter()
+                .zip(results.iter())
+                .map(|(condition, result)| {
+                    let matched = match &operand {
+                        Some(operand) => {
+                            let value = eval(condition)?;
+
+                            operand.is_some() && value.is_some() && operand == &value
+                        }
+                        None => {
+                            check_union_expr(storage, context.clone(), aggregated, condition)?
+                                == Some(true)
+                        }
+                    };
+
+                    Ok(matched.as_some(result))
+                })
+                .find_map(Result::transpose)
+                .transpose()?;
+
+            match matched.or(else_result.as_deref()) {
+                Some(result) => eval(result),
+                None => Ok(Evaluated::Value(Value::Empty)),
+            }
+        }
+        Expr::TypedString { data_type, value } => Value::Str(value.clone())
+            .cast(data_type)
+            .map(Evaluated::Value),
+        Expr::Cast { expr, data_type } => eval(expr)
+            .and_then(Value::try_from)
+            .and_then(|value| value.cast(data_type))
+            .map(Evaluated::Value),
+        Expr::Function(func) if is_function(get_name(&func.name)?) => {
+            evaluate_function(get_name(&func.name)?, &func.args, eval)
+        }
         Expr::Function(func) => aggregated
             .as_ref()
             .map(|aggregated| match aggregated.get(func) {
@@ -86,3 +150,28 @@ pub fn evaluate_union<'a, T: 'static + Debug>(
         _ => Err(EvaluateError::Unimplemented.into()),
     }
 }
+
+/// Value of a scalar subquery, which should return a single column and at most a single row.
+/// The value is `NULL` if no row is returned.
+pub fn select_scalar<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    query: &Query,
+    filter_context: Option<&FilterContext<'_>>,
+) -> Result<Value> {
+    let ResultSet { labels, rows } = select(storage, query, filter_context)?;
+
+    if labels.len() != 1 {
+        return Err(EvaluateError::NestedSelectColumnCountNotOne(labels.len()).into());
+    }
+
+    let rows = rows.take(2).collect::<Result<Vec<_>>>()?;
+
+    if rows.len() > 1 {
+        return Err(EvaluateError::NestedSelectMultipleRows.into());
+    }
+
+    match rows.into_iter().next() {
+        Some(row) => row.take_first_value(),
+        None => Ok(Value::Empty),
+    }
+}
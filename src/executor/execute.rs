@@ -1,16 +1,24 @@
 use serde::Serialize;
 use std::fmt::Debug;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use thiserror::Error;
 
-use sqlparser::ast::{ObjectType, Statement};
+use sqlparser::ast::{
+    ColumnDef, ColumnOption, ColumnOptionDef, ObjectType, SetExpr, Statement, TableConstraint,
+};
 
+use super::cancel::check_cancelled;
+use super::context::FilterContext;
+use super::explain::{explain, explain_analyze, Plan};
 use super::fetch::{fetch, fetch_columns};
 use super::filter::Filter;
-use super::select::select;
+use super::options::ExecuteOptions;
+use super::select::{select, ResultSet};
 use super::update::Update;
 use crate::data::{get_name, Row, Schema};
-use crate::parse::Query;
-use crate::result::{MutResult, Result};
+use crate::parse::{parse, Explain, Query};
+use crate::result::{Error, MutResult, Result};
 use crate::store::{Store, StoreMut};
 
 #[derive(Error, Serialize, Debug, PartialEq)]
@@ -20,24 +28,133 @@ pub enum ExecuteError {
 
     #[error("drop type not supported")]
     DropTypeNotSupported,
+
+    #[error("each arm of compound select should have the same number of columns")]
+    ColumnCountMismatch,
+
+    #[error("query cancelled")]
+    Cancelled,
+
+    #[error("column not found: {column}, available columns: {}", .available.join(", "))]
+    ColumnNotFound {
+        column: String,
+        available: Vec<String>,
+    },
+
+    #[error("column reference is ambiguous: {0}")]
+    AmbiguousColumn(String),
+
+    #[error("cached plan should be a single statement, found {0}")]
+    NotSingleStatement(usize),
+
+    #[error("INSERT has {expected} columns, but SELECT returned {found}")]
+    InsertColumnCountMismatch { expected: usize, found: usize },
+
+    #[error("row has {found} values, but {expected} columns are expected")]
+    RowWidthMismatch { expected: usize, found: usize },
+
+    #[error("statement {index} of the batch failed: {error}")]
+    BatchStatementFailed { index: usize, error: Box<Error> },
 }
 
 #[derive(Serialize, Debug, PartialEq)]
 pub enum Payload {
     Create,
     Insert(usize),
-    Select(Vec<Row>),
+    Select { labels: Vec<String>, rows: Vec<Row> },
     Delete(usize),
     Update(usize),
     DropTable,
+    Explain(Plan),
 }
 
-pub fn execute<T: 'static + Debug, U: Store<T> + StoreMut<T>>(
+pub fn execute<T: 'static + Debug + PartialEq, U: Store<T> + StoreMut<T>>(
     storage: U,
     query: &Query,
 ) -> MutResult<U, Payload> {
-    let Query(query) = query;
-    let prepared = match prepare(&storage, query) {
+    execute_with_options(storage, query, ExecuteOptions::default())
+}
+
+/// Same as `execute`, but with `options`, e.g. to force the algorithm of joins or to cancel the
+/// query from another thread. See `ExecuteOptions`.
+pub fn execute_with_options<T: 'static + Debug + PartialEq, U: Store<T> + StoreMut<T>>(
+    storage: U,
+    query: &Query,
+    options: ExecuteOptions,
+) -> MutResult<U, Payload> {
+    let explained = match query.1 {
+        Some(Explain::Plan) => Some(explain(&storage, query)),
+        Some(Explain::Analyze) => Some(explain_analyze(&storage, query)),
+        None => None,
+    };
+
+    match explained {
+        Some(Ok(plan)) => return Ok((storage, Payload::Explain(plan))),
+        Some(Err(error)) => return Err((storage, error)),
+        None => {}
+    }
+
+    let Query(query, _) = query;
+    let prepared = prepare(&storage, query, &options);
+
+    apply(storage, prepared)
+}
+
+/// Same as `execute`, but the query fails with `ExecuteError::Cancelled` once `cancelled` is set,
+/// see `ExecuteOptions::cancelled`.
+pub fn execute_cancellable<T: 'static + Debug + PartialEq, U: Store<T> + StoreMut<T>>(
+    storage: U,
+    query: &Query,
+    cancelled: Arc<AtomicBool>,
+) -> MutResult<U, Payload> {
+    let options = ExecuteOptions {
+        cancelled: Some(cancelled),
+        ..ExecuteOptions::default()
+    };
+
+    execute_with_options(storage, query, options)
+}
+
+/// Parses `sql` and executes its statements in order, returning a payload for each of them. The
+/// whole script is parsed first, so nothing runs if any part of it fails to parse. A failing
+/// statement aborts the rest of the batch, the statements before it stay applied, and its
+/// position from 0 is given by `ExecuteError::BatchStatementFailed`.
+pub fn execute_batch<T: 'static + Debug + PartialEq, U: Store<T> + StoreMut<T>>(
+    storage: U,
+    sql: &str,
+) -> MutResult<U, Vec<Payload>> {
+    let queries = match parse(sql) {
+        Ok(queries) => queries,
+        Err(error) => {
+            return Err((storage, error.into()));
+        }
+    };
+
+    queries.iter().enumerate().try_fold(
+        (storage, vec![]),
+        |(storage, mut payloads), (index, query)| match execute(storage, query) {
+            Ok((storage, payload)) => {
+                payloads.push(payload);
+
+                Ok((storage, payloads))
+            }
+            Err((storage, error)) => {
+                let error = ExecuteError::BatchStatementFailed {
+                    index,
+                    error: Box::new(error),
+                };
+
+                Err((storage, error.into()))
+            }
+        },
+    )
+}
+
+fn apply<T: 'static + Debug + PartialEq, U: Store<T> + StoreMut<T>>(
+    storage: U,
+    prepared: Result<Prepared<'_, T>>,
+) -> MutResult<U, Payload> {
+    let prepared = match prepared {
         Ok(prepared) => prepared,
         Err(error) => {
             return Err((storage, error));
@@ -51,7 +168,7 @@ pub fn execute<T: 'static + Debug, U: Store<T> + StoreMut<T>>(
         Prepared::Insert(table_name, rows) => rows
             .into_iter()
             .try_fold((storage, 0), |(storage, num), row| {
-                let (storage, key) = storage.generate_id(&table_name)?;
+                let (storage, key) = storage.generate_id(table_name)?;
                 let (storage, _) = storage.insert_data(&key, row)?;
 
                 Ok((storage, num + 1))
@@ -92,7 +209,7 @@ pub fn execute<T: 'static + Debug, U: Store<T> + StoreMut<T>>(
 
             Ok((storage, Payload::DropTable))
         }
-        Prepared::Select(rows) => Ok((storage, Payload::Select(rows))),
+        Prepared::Select(labels, rows) => Ok((storage, Payload::Select { labels, rows })),
     }
 }
 
@@ -101,27 +218,92 @@ enum Prepared<'a, T> {
     Insert(&'a str, Vec<Row>),
     Delete(Vec<T>),
     Update(Vec<(T, Row)>),
-    Select(Vec<Row>),
+    Select(Vec<String>, Vec<Row>),
     DropTable(Vec<&'a str>),
 }
 
-fn prepare<'a, T: 'static + Debug>(
+/// `PRIMARY KEY (...)` of the table is kept as the option of each of its columns, the other
+/// table constraints are not supported and ignored.
+fn get_column_defs(
+    columns: &[ColumnDef],
+    constraints: &[TableConstraint],
+) -> Result<Vec<ColumnDef>> {
+    let primary_key = constraints
+        .iter()
+        .filter_map(|constraint| match constraint {
+            TableConstraint::Unique {
+                columns,
+                is_primary: true,
+                ..
+            } => Some(columns),
+            _ => None,
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    if let Some(column) = primary_key.iter().find(|column| {
+        columns
+            .iter()
+            .all(|ColumnDef { name, .. }| name != **column)
+    }) {
+        return Err(ExecuteError::ColumnNotFound {
+            column: column.value.clone(),
+            available: columns
+                .iter()
+                .map(|ColumnDef { name, .. }| name.value.clone())
+                .collect(),
+        }
+        .into());
+    }
+
+    let column_defs = columns
+        .iter()
+        .map(|column_def| {
+            let mut column_def = column_def.clone();
+
+            if primary_key.contains(&&column_def.name) {
+                column_def.options.push(ColumnOptionDef {
+                    name: None,
+                    option: ColumnOption::Unique { is_primary: true },
+                });
+            }
+
+            column_def
+        })
+        .collect();
+
+    Ok(column_defs)
+}
+
+fn prepare<'a, T: 'static + Debug + PartialEq>(
     storage: &impl Store<T>,
     sql_query: &'a Statement,
+    options: &ExecuteOptions,
 ) -> Result<Prepared<'a, T>> {
+    check_cancelled(options.cancelled.as_deref())?;
+
+    let context = FilterContext::with_options(options);
+    let filter_context = Some(&context);
+
     match sql_query {
-        Statement::CreateTable { name, columns, .. } => {
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            ..
+        } => {
             let schema = Schema {
                 table_name: get_name(name)?.clone(),
-                column_defs: columns.clone(),
+                column_defs: get_column_defs(columns, constraints)?,
             };
 
             Ok(Prepared::Create(schema))
         }
         Statement::Query(query) => {
-            let rows = select(storage, &query, None)?.collect::<Result<_>>()?;
+            let ResultSet { labels, rows } = select(storage, query, filter_context)?;
+            let rows = rows.collect::<Result<_>>()?;
 
-            Ok(Prepared::Select(rows))
+            Ok(Prepared::Select(labels, rows))
         }
         Statement::Insert {
             table_name,
@@ -130,7 +312,26 @@ fn prepare<'a, T: 'static + Debug>(
         } => {
             let table_name = get_name(table_name)?;
             let Schema { column_defs, .. } = storage.fetch_schema(table_name)?;
-            let rows = Row::new(column_defs, columns, source)?;
+            let rows = match &source.body {
+                SetExpr::Values(_) => Row::new(column_defs, columns, source)?,
+                _ => {
+                    let ResultSet { labels, rows } = select(storage, source, filter_context)?;
+                    let expected = match columns.len() {
+                        0 => column_defs.len(),
+                        n => n,
+                    };
+
+                    if labels.len() != expected {
+                        return Err(ExecuteError::InsertColumnCountMismatch {
+                            expected,
+                            found: labels.len(),
+                        }
+                        .into());
+                    }
+
+                    Row::from_rows(column_defs, columns, rows.collect::<Result<_>>()?)?
+                }
+            };
 
             Ok(Prepared::Insert(table_name, rows))
         }
@@ -142,15 +343,21 @@ fn prepare<'a, T: 'static + Debug>(
             let table_name = get_name(table_name)?;
             let columns = fetch_columns(storage, table_name)?;
             let update = Update::new(storage, table_name, assignments, &columns)?;
-            let filter = Filter::new(storage, selection.as_ref(), None, None);
+            let filter = Filter::new(storage, selection.as_ref(), filter_context, None);
 
-            let rows = fetch(storage, table_name, &columns, filter)?
-                .map(|item| {
-                    let (_, key, row) = item?;
+            let rows = fetch(
+                storage,
+                table_name,
+                &columns,
+                filter,
+                options.cancelled.clone(),
+            )?
+            .map(|item| {
+                let (_, key, row) = item?;
 
-                    Ok((key, update.apply(row)?))
-                })
-                .collect::<Result<_>>()?;
+                Ok((key, update.apply(row)?))
+            })
+            .collect::<Result<_>>()?;
 
             Ok(Prepared::Update(rows))
         }
@@ -160,11 +367,17 @@ fn prepare<'a, T: 'static + Debug>(
         } => {
             let table_name = get_name(table_name)?;
             let columns = fetch_columns(storage, table_name)?;
-            let filter = Filter::new(storage, selection.as_ref(), None, None);
+            let filter = Filter::new(storage, selection.as_ref(), filter_context, None);
 
-            let rows = fetch(storage, table_name, &columns, filter)?
-                .map(|item| item.map(|(_, key, _)| key))
-                .collect::<Result<_>>()?;
+            let rows = fetch(
+                storage,
+                table_name,
+                &columns,
+                filter,
+                options.cancelled.clone(),
+            )?
+            .map(|item| item.map(|(_, key, _)| key))
+            .collect::<Result<_>>()?;
 
             Ok(Prepared::Delete(rows))
         }
@@ -0,0 +1,721 @@
+use boolinator::Boolinator;
+use serde::Serialize;
+use std::cmp::{max, min};
+use std::fmt;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::slice;
+use std::time::Duration;
+
+use sqlparser::ast::{
+    Expr, Ident, JoinConstraint, JoinOperator, OrderByExpr, Query as AstQuery, Select, SetExpr,
+    SetOperator, Statement, TableWithJoins,
+};
+
+use super::aggregate::{is_sorted_by_group, Aggregate};
+use super::blend::Blend;
+use super::context::BlendContext;
+use super::distinct::get_distinct_on;
+use super::execute::ExecuteError;
+use super::fetch::{apply_column_aliases, fetch_columns};
+use super::join::{
+    estimate_joined_rows, get_cross_join_condition, get_join_algorithm, get_join_type,
+    get_lateral_join_algorithm, get_sorted_column, keeps_order, JoinAlgorithm, JoinClause,
+};
+use super::limit::Limit;
+use super::profile::{Profile, Stage};
+use super::select::{
+    check_table_aliases, get_all_using_columns, get_join_clauses, select_profiled, ResultSet,
+    SelectError,
+};
+use super::window::Window;
+use crate::data::Table;
+use crate::parse::Query;
+use crate::result::{Error, Result};
+use crate::store::Store;
+
+/// Alias and columns of each table of a plan.
+type ExplainedTables<'a> = Vec<(&'a str, Rc<Vec<Ident>>)>;
+
+/// A stage of the plan, stages run from the leaves to the root.
+#[derive(Serialize, Debug, PartialEq)]
+pub enum PlanNode {
+    Scan {
+        table: String,
+        alias: String,
+    },
+    Derived {
+        alias: String,
+    },
+    /// Tables joined in parentheses, the join is its child.
+    Nested,
+    Join {
+        join_type: String,
+        algorithm: JoinAlgorithm,
+        constraint: Option<String>,
+    },
+    Filter(String),
+    Aggregate {
+        group_by: Vec<String>,
+        having: Option<String>,
+        streaming: bool,
+    },
+    Window(Vec<String>),
+    Sort(Vec<String>),
+    Project(Vec<String>),
+    Distinct,
+    DistinctOn(Vec<String>),
+    Limit {
+        limit: Option<usize>,
+        offset: Option<usize>,
+    },
+    SetOperation {
+        op: String,
+        all: bool,
+    },
+}
+
+/// `estimated_rows` is naive for now: a scan counts the stored rows, a filter passes every row,
+/// a join keeps the larger side unless it is a cross join, and an aggregate without `GROUP BY`
+/// returns a single row. `actual_rows` and `elapsed` stay `None` unless the query is run by
+/// `explain_analyze`.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Plan {
+    pub node: PlanNode,
+    pub estimated_rows: usize,
+    pub actual_rows: Option<usize>,
+    pub elapsed: Option<Duration>,
+    pub children: Vec<Plan>,
+}
+
+impl Plan {
+    fn new(node: PlanNode, estimated_rows: usize, children: Vec<Plan>) -> Self {
+        Self {
+            node,
+            estimated_rows,
+            actual_rows: None,
+            elapsed: None,
+            children,
+        }
+    }
+
+    fn wrap(self, node: PlanNode, estimated_rows: usize) -> Self {
+        Self::new(node, estimated_rows, vec![self])
+    }
+
+    fn with_stats(self, stats: Option<(usize, Duration)>) -> Self {
+        let (actual_rows, elapsed) = match stats {
+            Some((rows, elapsed)) => (Some(rows), Some(elapsed)),
+            None => (None, None),
+        };
+
+        Self {
+            actual_rows,
+            elapsed,
+            ..self
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{}{} (estimated rows: {}",
+            "  ".repeat(depth),
+            self.node,
+            self.estimated_rows
+        )?;
+
+        if let Some(actual_rows) = self.actual_rows {
+            write!(f, ", actual rows: {}", actual_rows)?;
+        }
+
+        if let Some(elapsed) = self.elapsed {
+            write!(f, ", time: {:?}", elapsed)?;
+        }
+
+        writeln!(f, ")")?;
+
+        self.children
+            .iter()
+            .try_for_each(|child| child.fmt_indented(f, depth + 1))
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+impl fmt::Display for PlanNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanNode::Scan { table, alias } if table == alias => write!(f, "Scan {}", table),
+            PlanNode::Scan { table, alias } => write!(f, "Scan {} AS {}", table, alias),
+            PlanNode::Derived { alias } => write!(f, "Derived AS {}", alias),
+            PlanNode::Nested => write!(f, "Nested"),
+            PlanNode::Join {
+                join_type,
+                algorithm,
+                constraint,
+            } => {
+                let algorithm = match algorithm {
+                    JoinAlgorithm::Hash => "Hash Join",
+                    JoinAlgorithm::ReversedHash => "Reversed Hash Join",
+                    JoinAlgorithm::NestedLoop => "Nested Loop Join",
+                    JoinAlgorithm::Lookup => "Index Lookup Join",
+                    JoinAlgorithm::Merge => "Merge Join",
+                };
+
+                write!(f, "{} ({})", algorithm, join_type)?;
+
+                match constraint {
+                    Some(constraint) => write!(f, " {}", constraint),
+                    None => Ok(()),
+                }
+            }
+            PlanNode::Filter(expr) => write!(f, "Filter: {}", expr),
+            PlanNode::Aggregate {
+                group_by,
+                having,
+                streaming,
+            } => {
+                if *streaming {
+                    write!(f, "Stream ")?;
+                }
+
+                write!(f, "Aggregate")?;
+
+                if !group_by.is_empty() {
+                    write!(f, " GROUP BY {}", group_by.join(", "))?;
+                }
+
+                match having {
+                    Some(having) => write!(f, " HAVING {}", having),
+                    None => Ok(()),
+                }
+            }
+            PlanNode::Window(windows) => write!(f, "Window: {}", windows.join(", ")),
+            PlanNode::Sort(keys) => write!(f, "Sort: {}", keys.join(", ")),
+            PlanNode::Project(labels) => write!(f, "Project: {}", labels.join(", ")),
+            PlanNode::Distinct => write!(f, "Distinct"),
+            PlanNode::DistinctOn(keys) => write!(f, "Distinct On: {}", keys.join(", ")),
+            PlanNode::Limit { limit, offset } => {
+                write!(f, "Limit")?;
+
+                if let Some(limit) = limit {
+                    write!(f, " {}", limit)?;
+                }
+
+                match offset {
+                    Some(offset) => write!(f, " OFFSET {}", offset),
+                    None => Ok(()),
+                }
+            }
+            PlanNode::SetOperation { op, all: true } => write!(f, "{} ALL", op),
+            PlanNode::SetOperation { op, all: false } => write!(f, "{}", op),
+        }
+    }
+}
+
+/// Describes the stages which `SELECT` would run without running it, only schemas and the
+/// numbers of stored rows are read from the storage.
+pub fn explain<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    query: &Query,
+) -> Result<Plan> {
+    match query {
+        Query(Statement::Query(query), _) => explain_query(storage, query).map(|(plan, _)| plan),
+        _ => Err(ExecuteError::QueryNotSupported.into()),
+    }
+}
+
+/// Runs `SELECT` and annotates each stage of its plan with the rows it produced and the time
+/// spent in it, without the time of the stages it pulls rows from. Stages of subqueries are not
+/// annotated, and a `DISTINCT ON` is annotated as one stage with the sort it runs.
+pub fn explain_analyze<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    query: &Query,
+) -> Result<Plan> {
+    let query = match query {
+        Query(Statement::Query(query), _) if query.ctes.is_empty() => query,
+        _ => return Err(ExecuteError::QueryNotSupported.into()),
+    };
+    let statement = match &query.body {
+        SetExpr::Select(statement) => statement,
+        _ => return Err(ExecuteError::QueryNotSupported.into()),
+    };
+
+    let (plan, _) = explain_query(storage, query)?;
+    let profile = Profile::default();
+    let limit = Limit::new(
+        query.limit.as_ref(),
+        query.offset.as_ref(),
+        query.fetch.as_ref(),
+    )?;
+    let ResultSet { rows, .. } =
+        select_profiled(storage, statement, &query.order_by, limit, None, &profile)?;
+
+    rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(annotate(plan, &profile))
+}
+
+fn annotate(plan: Plan, profile: &Profile) -> Plan {
+    let stage = match plan.node {
+        PlanNode::Scan { .. }
+        | PlanNode::Derived { .. }
+        | PlanNode::Nested
+        | PlanNode::Join { .. } => {
+            let index = count_joins(&plan);
+
+            return annotate_table(plan, profile, index);
+        }
+        PlanNode::SetOperation { .. } => {
+            return plan;
+        }
+        PlanNode::Filter(_) => Stage::Filter,
+        PlanNode::Aggregate { .. } => Stage::Aggregate,
+        PlanNode::Window(_) => Stage::Window,
+        PlanNode::Sort(_) => Stage::Sort,
+        PlanNode::Project(_) => Stage::Project,
+        PlanNode::Distinct => Stage::Distinct,
+        PlanNode::DistinctOn(_) => Stage::DistinctOn,
+        PlanNode::Limit { .. } => Stage::Limit,
+    };
+
+    let Plan {
+        node,
+        estimated_rows,
+        children,
+        ..
+    } = plan;
+    let children = children
+        .into_iter()
+        .map(|child| annotate(child, profile))
+        .collect();
+
+    Plan::new(node, estimated_rows, children).with_stats(profile.get(stage))
+}
+
+/// `index` is the position of the last table joined in `plan`, joined tables are the left child
+/// of each join.
+fn annotate_table(plan: Plan, profile: &Profile, index: usize) -> Plan {
+    match plan.node {
+        PlanNode::Join { .. } => {
+            let Plan {
+                node,
+                estimated_rows,
+                children,
+                ..
+            } = plan;
+            let children = children
+                .into_iter()
+                .enumerate()
+                .map(|(i, child)| match i {
+                    0 => annotate_table(child, profile, index - 1),
+                    _ => annotate_table(child, profile, index),
+                })
+                .collect();
+
+            Plan::new(node, estimated_rows, children).with_stats(profile.get(Stage::Join(index)))
+        }
+        _ => plan.with_stats(profile.get(Stage::Scan(index))),
+    }
+}
+
+fn count_joins(plan: &Plan) -> usize {
+    match (&plan.node, plan.children.first()) {
+        (PlanNode::Join { .. }, Some(joined)) => 1 + count_joins(joined),
+        _ => 0,
+    }
+}
+
+fn explain_query<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    query: &AstQuery,
+) -> Result<(Plan, Vec<String>)> {
+    if !query.ctes.is_empty() {
+        return Err(ExecuteError::QueryNotSupported.into());
+    }
+
+    let limit = Limit::new(
+        query.limit.as_ref(),
+        query.offset.as_ref(),
+        query.fetch.as_ref(),
+    )?;
+
+    match &query.body {
+        SetExpr::Select(statement) => explain_select(storage, statement, &query.order_by, limit),
+        body => {
+            let (plan, labels) = explain_set_expr(storage, body)?;
+            let plan = explain_sort(plan, &query.order_by);
+            let plan = explain_limit(plan, &limit);
+
+            Ok((plan, labels))
+        }
+    }
+}
+
+fn explain_set_expr<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    set_expr: &SetExpr,
+) -> Result<(Plan, Vec<String>)> {
+    match set_expr {
+        SetExpr::Select(statement) => {
+            explain_select(storage, statement, &[], Limit::new(None, None, None)?)
+        }
+        SetExpr::Query(query) => explain_query(storage, query),
+        SetExpr::SetOperation {
+            op,
+            all,
+            left,
+            right,
+        } => {
+            let (left, labels) = explain_set_expr(storage, left)?;
+            let (right, _) = explain_set_expr(storage, right)?;
+
+            let estimated_rows = match op {
+                SetOperator::Union => left.estimated_rows + right.estimated_rows,
+                SetOperator::Intersect => min(left.estimated_rows, right.estimated_rows),
+                SetOperator::Except => left.estimated_rows,
+            };
+            let node = PlanNode::SetOperation {
+                op: op.to_string(),
+                all: *all,
+            };
+
+            Ok((Plan::new(node, estimated_rows, vec![left, right]), labels))
+        }
+        SetExpr::Values(_) => Err(ExecuteError::QueryNotSupported.into()),
+    }
+}
+
+fn explain_select<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    statement: &Select,
+    order_by: &[OrderByExpr],
+    limit: Limit,
+) -> Result<(Plan, Vec<String>)> {
+    let Select {
+        from: tables,
+        selection: where_clause,
+        group_by,
+        having,
+        distinct,
+        ..
+    } = statement;
+    let (distinct_on, projection) = get_distinct_on(statement, order_by)?;
+
+    let relation = match tables.first() {
+        Some(TableWithJoins { relation, .. }) => relation,
+        None => {
+            return Err(SelectError::Unreachable.into());
+        }
+    };
+    let table = Table::new(relation)?;
+    let joins = get_join_clauses(tables);
+
+    check_table_aliases(&table, &joins)?;
+
+    let (plan, tables) = explain_joins(storage, &table, &joins, where_clause.as_ref())?;
+
+    let plan = match where_clause {
+        Some(expr) => {
+            let estimated_rows = plan.estimated_rows;
+
+            plan.wrap(PlanNode::Filter(expr.to_string()), estimated_rows)
+        }
+        None => plan,
+    };
+
+    let aggregate = Aggregate::new(storage, projection, group_by, having.as_ref(), None)
+        .with_order_by(order_by)
+        .with_sorted_input(is_sorted_by_group(group_by, &table, &joins));
+    let plan = if aggregate.check_aggregate() {
+        let estimated_rows = if group_by.is_empty() {
+            1
+        } else {
+            plan.estimated_rows
+        };
+        let node = PlanNode::Aggregate {
+            group_by: group_by.iter().map(ToString::to_string).collect(),
+            having: having.as_ref().map(ToString::to_string),
+            streaming: aggregate.is_streaming(),
+        };
+
+        plan.wrap(node, estimated_rows)
+    } else {
+        plan
+    };
+
+    let windows = Window::new(storage, projection, order_by, None).get_windows();
+    let plan = if windows.is_empty() {
+        plan
+    } else {
+        let estimated_rows = plan.estimated_rows;
+        let windows = windows.iter().map(ToString::to_string).collect();
+
+        plan.wrap(PlanNode::Window(windows), estimated_rows)
+    };
+
+    let plan = explain_sort(plan, order_by);
+    let plan = match distinct_on {
+        Some(distinct_on) => {
+            let estimated_rows = plan.estimated_rows;
+            let keys = order_by[..distinct_on]
+                .iter()
+                .map(|OrderByExpr { expr, .. }| expr.to_string())
+                .collect();
+
+            plan.wrap(PlanNode::DistinctOn(keys), estimated_rows)
+        }
+        None => plan,
+    };
+
+    let using_columns = get_all_using_columns(&table, &joins)?;
+    let labels = {
+        let tables = tables
+            .iter()
+            .map(|(table_alias, columns)| (*table_alias, columns.as_slice()))
+            .collect::<Vec<_>>();
+
+        Blend::new(storage, projection, using_columns).get_labels(&tables)?
+    };
+    let estimated_rows = plan.estimated_rows;
+    let plan = plan.wrap(PlanNode::Project(labels.clone()), estimated_rows);
+
+    let plan = if *distinct && distinct_on.is_none() {
+        let estimated_rows = plan.estimated_rows;
+
+        plan.wrap(PlanNode::Distinct, estimated_rows)
+    } else {
+        plan
+    };
+
+    Ok((explain_limit(plan, &limit), labels))
+}
+
+/// Plan of `table` joined with `joins`, with the alias and columns of each joined table in the
+/// order they are joined. Tables of a nested join are listed one by one.
+fn explain_joins<'a, T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    table: &Table<'a>,
+    joins: &[JoinClause<'a>],
+    where_clause: Option<&Expr>,
+) -> Result<(Plan, ExplainedTables<'a>)> {
+    let (plan, tables) = explain_table(storage, table)?;
+    let empty_context = get_empty_context(&tables, None)?;
+    let rows_estimate = get_row_count_estimate(storage, table, &plan);
+    let sorted_column = match tables.as_slice() {
+        [(table_alias, columns)] => get_sorted_column(table, columns)
+            .or_else(|| get_ordered_column(storage, table))
+            .map(|index| (*table_alias, index)),
+        _ => None,
+    };
+    let init = (plan, empty_context, tables, rows_estimate, sorted_column);
+
+    joins
+        .iter()
+        .try_fold(
+            init,
+            |(plan, empty_context, mut tables, rows_estimate, sorted_column),
+             (relation, join_operator)| {
+                let table = Table::new(relation)?;
+                let table_alias = table.get_alias();
+                let (target, target_tables) = explain_table(storage, &table)?;
+                let columns = target_tables
+                    .iter()
+                    .flat_map(|(_, columns)| columns.iter().cloned())
+                    .collect::<Vec<_>>();
+                let target_estimate = get_row_count_estimate(storage, &table, &target);
+                let cross_condition = (!table.is_lateral()).and_option_from(|| {
+                    get_cross_join_condition(
+                        join_operator,
+                        where_clause,
+                        table_alias,
+                        &columns,
+                        &empty_context,
+                    )
+                });
+                let algorithm = if table.is_lateral() {
+                    get_lateral_join_algorithm(join_operator, None)?
+                } else {
+                    get_join_algorithm(
+                        join_operator,
+                        cross_condition,
+                        table_alias,
+                        &columns,
+                        &empty_context,
+                        get_indexed_column(storage, &table),
+                        sorted_column,
+                        get_sorted_column(&table, &columns)
+                            .or_else(|| get_ordered_column(storage, &table)),
+                        rows_estimate,
+                        target_estimate,
+                        None,
+                    )?
+                };
+                let rows_estimate =
+                    estimate_joined_rows(join_operator, rows_estimate, target_estimate);
+
+                let estimated_rows = match join_operator {
+                    JoinOperator::CrossJoin => plan.estimated_rows * target.estimated_rows,
+                    _ => max(plan.estimated_rows, target.estimated_rows),
+                };
+                let node = PlanNode::Join {
+                    join_type: get_join_type(join_operator).to_owned(),
+                    algorithm,
+                    constraint: get_constraint(join_operator)
+                        .or_else(|| cross_condition.map(|expr| format!("ON {}", expr))),
+                };
+                let plan = Plan::new(node, estimated_rows, vec![plan, target]);
+
+                let empty_context = get_empty_context(&target_tables, Some(empty_context))?;
+                tables.extend(target_tables);
+
+                let sorted_column = keeps_order(join_operator).and_option(sorted_column);
+
+                Ok::<_, Error>((plan, empty_context, tables, rows_estimate, sorted_column))
+            },
+        )
+        .map(|(plan, _, tables, _, _)| (plan, tables))
+}
+
+/// Contexts of `tables` without rows on top of `next`.
+fn get_empty_context<'a>(
+    tables: &[(&'a str, Rc<Vec<Ident>>)],
+    next: Option<Rc<BlendContext<'a>>>,
+) -> Result<Rc<BlendContext<'a>>> {
+    tables
+        .iter()
+        .fold(next, |next, (table_alias, columns)| {
+            Some(Rc::new(BlendContext {
+                table_alias,
+                columns: Rc::clone(columns),
+                row: None,
+                next,
+            }))
+        })
+        .ok_or_else(|| SelectError::Unreachable.into())
+}
+
+/// Estimate which `SELECT` chooses join algorithms by, derived tables and nested joins are
+/// estimated by their plans and stored tables by the storage.
+fn get_row_count_estimate<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    table: &Table<'_>,
+    plan: &Plan,
+) -> Option<usize> {
+    match table.get_subquery() {
+        Some(_) => Some(plan.estimated_rows),
+        None if table.get_nested().is_some() => Some(plan.estimated_rows),
+        None => storage.row_count_estimate(table.get_name()),
+    }
+}
+
+fn get_indexed_column<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    table: &Table<'_>,
+) -> Option<usize> {
+    match table.get_subquery() {
+        None if table.get_nested().is_none() => storage.get_indexed_column(table.get_name()),
+        _ => None,
+    }
+}
+
+fn get_ordered_column<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    table: &Table<'_>,
+) -> Option<usize> {
+    match table.get_subquery() {
+        None if table.get_nested().is_none() => storage.get_ordered_column(table.get_name()),
+        _ => None,
+    }
+}
+
+fn explain_table<'a, T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    table: &Table<'a>,
+) -> Result<(Plan, ExplainedTables<'a>)> {
+    if let Some(nested) = table.get_nested() {
+        let table = Table::new(&nested.relation)?;
+        let joins = get_join_clauses(slice::from_ref(nested));
+        let (plan, tables) = explain_joins(storage, &table, &joins, None)?;
+        let estimated_rows = plan.estimated_rows;
+
+        return Ok((plan.wrap(PlanNode::Nested, estimated_rows), tables));
+    }
+
+    let alias = table.get_alias().to_owned();
+
+    let (plan, columns) = match table.get_subquery() {
+        Some(subquery) => {
+            let (plan, labels) = explain_query(storage, subquery)?;
+            let columns = labels.into_iter().map(Ident::new).collect();
+            let estimated_rows = plan.estimated_rows;
+
+            (
+                plan.wrap(PlanNode::Derived { alias }, estimated_rows),
+                columns,
+            )
+        }
+        None => {
+            let table_name = table.get_name();
+            let columns = fetch_columns(storage, table_name)?;
+            let estimated_rows = storage.scan_data(table_name)?.count();
+            let node = PlanNode::Scan {
+                table: table_name.to_owned(),
+                alias,
+            };
+
+            (Plan::new(node, estimated_rows, vec![]), columns)
+        }
+    };
+
+    let columns = apply_column_aliases(table, columns)?;
+
+    Ok((plan, vec![(table.get_alias().as_str(), Rc::new(columns))]))
+}
+
+fn explain_sort(plan: Plan, order_by: &[OrderByExpr]) -> Plan {
+    if order_by.is_empty() {
+        return plan;
+    }
+
+    let estimated_rows = plan.estimated_rows;
+    let keys = order_by.iter().map(ToString::to_string).collect();
+
+    plan.wrap(PlanNode::Sort(keys), estimated_rows)
+}
+
+fn explain_limit(plan: Plan, limit: &Limit) -> Plan {
+    let (limit, offset) = (limit.get_limit(), limit.get_offset());
+
+    if limit.is_none() && offset.is_none() {
+        return plan;
+    }
+
+    let estimated_rows = plan.estimated_rows.saturating_sub(offset.unwrap_or(0));
+    let estimated_rows = limit.map_or(estimated_rows, |limit| min(limit, estimated_rows));
+
+    plan.wrap(PlanNode::Limit { limit, offset }, estimated_rows)
+}
+
+fn get_constraint(join_operator: &JoinOperator) -> Option<String> {
+    match join_operator {
+        JoinOperator::Inner(constraint)
+        | JoinOperator::LeftOuter(constraint)
+        | JoinOperator::RightOuter(constraint)
+        | JoinOperator::FullOuter(constraint) => match constraint {
+            JoinConstraint::On(expr) => Some(format!("ON {}", expr)),
+            JoinConstraint::Using(columns) => {
+                let columns = columns.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+                Some(format!("USING ({})", columns.join(", ")))
+            }
+            JoinConstraint::Natural => None,
+        },
+        JoinOperator::CrossJoin | JoinOperator::CrossApply | JoinOperator::OuterApply => None,
+    }
+}
@@ -1,14 +1,25 @@
 use boolinator::Boolinator;
+use im_rc::HashMap;
+use iter_enum::Iterator;
 use std::fmt::Debug;
+use std::iter::successors;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
-use sqlparser::ast::{ColumnDef, Ident};
+use sqlparser::ast::{ColumnDef, Ident, Query, TableWithJoins};
 
+use super::cancel::{cancellable, check_cancelled};
+use super::context::{BlendContext, FilterContext, UnionContext};
 use super::filter::Filter;
-use crate::data::Row;
+use super::join::{Join, JoinClause};
+use super::profile::NoProfile;
+use super::select::{fetch_join_data, get_join_clauses, select, ResultSet, SelectError};
+use crate::data::{Row, Table, TableError, Value};
 use crate::result::Result;
-use crate::store::Store;
+use crate::store::{Predicate, PredicateOp, RowIter, Store};
 
-pub fn fetch_columns<T: 'static + Debug>(
+pub fn fetch_columns<T: 'static + Debug + PartialEq>(
     storage: &dyn Store<T>,
     table_name: &str,
 ) -> Result<Vec<Ident>> {
@@ -20,18 +31,50 @@ pub fn fetch_columns<T: 'static + Debug>(
         .collect::<Vec<Ident>>())
 }
 
-pub fn fetch<'a, T: 'static + Debug>(
+/// Columns of stored tables by table name, so a table which appears more than once in `FROM`
+/// and `JOIN`, e.g. a self join, fetches its schema only once.
+pub type ColumnsCache<'a> = HashMap<&'a str, Vec<Ident>>;
+
+pub fn fetch_columns_cache<'a, 'b, T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    tables: impl Iterator<Item = &'b Table<'a>>,
+    filter_context: Option<&FilterContext<'_>>,
+) -> Result<ColumnsCache<'a>>
+where
+    'a: 'b,
+{
+    tables
+        .filter(|table| table.get_subquery().is_none() && table.get_nested().is_none())
+        .try_fold(HashMap::new(), |cache, table| {
+            let table_name = table.get_name().as_str();
+            let cte = filter_context.and_then(|context| context.get_cte(table_name));
+
+            if cache.contains_key(table_name) || cte.is_some() {
+                return Ok(cache);
+            }
+
+            let columns = fetch_columns(storage, table_name)?;
+
+            Ok(cache.update(table_name, columns))
+        })
+}
+
+pub fn fetch<'a, T: 'static + Debug + PartialEq>(
     storage: &dyn Store<T>,
     table_name: &'a str,
     columns: &'a [Ident],
     filter: Filter<'a, T>,
+    cancelled: Option<Arc<AtomicBool>>,
 ) -> Result<impl Iterator<Item = Result<(&'a [Ident], T, Row)>> + 'a> {
-    let rows = storage.scan_data(table_name)?.filter_map(move |item| {
+    check_cancelled(cancelled.as_deref())?;
+
+    let rows = storage.scan_data(table_name)?;
+    let rows = cancellable(rows, cancelled).filter_map(move |item| {
         item.map_or_else(
             |error| Some(Err(error)),
             |(key, row)| {
                 filter
-                    .check(&table_name, columns, &row)
+                    .check(table_name, columns, &row)
                     .map(|pass| pass.as_some((columns, key, row)))
                     .transpose()
             },
@@ -40,3 +83,499 @@ pub fn fetch<'a, T: 'static + Debug>(
 
     Ok(rows)
 }
+
+/// Column aliases of a derived table, e.g. `(SELECT ...) AS t (a, b)`, rename its first columns.
+pub fn apply_column_aliases(table: &Table<'_>, columns: Vec<Ident>) -> Result<Vec<Ident>> {
+    rename_columns(table.get_alias(), table.get_column_aliases(), columns)
+}
+
+pub fn rename_columns(
+    table_alias: &str,
+    column_aliases: &[Ident],
+    columns: Vec<Ident>,
+) -> Result<Vec<Ident>> {
+    if column_aliases.len() > columns.len() {
+        return Err(TableError::TooManyColumnAliases {
+            table: table_alias.to_owned(),
+            expected: columns.len(),
+            found: column_aliases.len(),
+        }
+        .into());
+    }
+
+    let columns = column_aliases
+        .iter()
+        .cloned()
+        .chain(columns.into_iter().skip(column_aliases.len()))
+        .collect();
+
+    Ok(columns)
+}
+
+/// Alias and columns of each table of a nested join, in the order they are joined.
+type NestedTables<'a> = Rc<Vec<(&'a str, Rc<Vec<Ident>>)>>;
+
+#[derive(Clone, Debug)]
+enum Source<'a> {
+    /// Table name with the flag which cancels its scans, see `ExecuteOptions::cancelled`.
+    Stored(&'a str, Option<Arc<AtomicBool>>),
+    Derived(Rc<Vec<Row>>),
+    Lateral(&'a Query),
+    Nested(Rc<Vec<Row>>, NestedTables<'a>),
+}
+
+#[derive(Iterator)]
+enum Scanned<I1, I2> {
+    Stored(I1),
+    Derived(I2),
+}
+
+#[derive(Iterator)]
+enum LateralScanned<I1, I2> {
+    Lateral(I1),
+    Scanned(I2),
+}
+
+/// Columns and rows of a table in `FROM` or `JOIN`. Rows of a derived table or a table of `WITH`
+/// clause are materialized once by running its subquery, rows of a stored table are scanned
+/// whenever they are needed. The subquery of a `LATERAL` derived table runs again for each row
+/// of the tables joined before it. Tables joined in parentheses are joined once too, each of
+/// their rows has the columns of every joined table, which `blend` splits back to the tables.
+#[derive(Clone, Debug)]
+pub struct TableData<'a> {
+    pub columns: Rc<Vec<Ident>>,
+    source: Source<'a>,
+}
+
+impl<'a> TableData<'a> {
+    pub fn new<T: 'static + Debug + PartialEq>(
+        storage: &'a dyn Store<T>,
+        table: &Table<'a>,
+        filter_context: Option<&'a FilterContext<'a>>,
+        columns_cache: &ColumnsCache<'a>,
+    ) -> Result<Self> {
+        if let Some(nested) = table.get_nested() {
+            return Self::nested(storage, nested, filter_context, columns_cache);
+        }
+
+        let (columns, source) = match table.get_subquery() {
+            Some(subquery) => {
+                let ResultSet { labels, rows } = select(storage, subquery, filter_context)?;
+                let columns = labels.into_iter().map(Ident::new).collect::<Vec<_>>();
+                let rows = rows.collect::<Result<_>>()?;
+
+                (columns, Source::Derived(Rc::new(rows)))
+            }
+            None => {
+                let table_name = table.get_name();
+                let cte = filter_context.and_then(|context| context.get_cte(table_name));
+                let cancelled = filter_context
+                    .and_then(FilterContext::get_options)
+                    .and_then(|options| options.cancelled.clone());
+
+                match (cte, columns_cache.get(table_name.as_str())) {
+                    (Some(TableData { columns, source }), _) => {
+                        (columns.as_ref().clone(), source.clone())
+                    }
+                    (None, Some(columns)) => {
+                        (columns.clone(), Source::Stored(table_name, cancelled))
+                    }
+                    (None, None) => (
+                        fetch_columns(storage, table_name)?,
+                        Source::Stored(table_name, cancelled),
+                    ),
+                }
+            }
+        };
+
+        let columns = apply_column_aliases(table, columns)?;
+
+        Ok(Self {
+            columns: Rc::new(columns),
+            source,
+        })
+    }
+
+    /// `context` has the tables joined before the `LATERAL` derived table, without rows. Its
+    /// columns are the labels of the subquery, which runs once here while those tables are
+    /// padded with `NULL`, and its rows are left to `scan_lateral`.
+    pub fn lateral<T: 'static + Debug + PartialEq>(
+        storage: &'a dyn Store<T>,
+        table: &Table<'a>,
+        filter_context: Option<&'a FilterContext<'a>>,
+        context: &BlendContext<'_>,
+    ) -> Result<Self> {
+        let subquery = match table.get_subquery() {
+            Some(subquery) => subquery,
+            None => {
+                return Err(SelectError::Unreachable.into());
+            }
+        };
+        let labels = UnionContext::new(filter_context, Some(context)).with_filter_context(
+            |filter_context| {
+                select(storage, subquery, filter_context).map(|ResultSet { labels, .. }| labels)
+            },
+        )?;
+        let columns = labels.into_iter().map(Ident::new).collect::<Vec<_>>();
+        let columns = apply_column_aliases(table, columns)?;
+
+        Ok(Self {
+            columns: Rc::new(columns),
+            source: Source::Lateral(subquery),
+        })
+    }
+
+    /// Rows of the nested join are joined here as `SELECT` joins its tables, and the columns are
+    /// the columns of every joined table in order.
+    fn nested<T: 'static + Debug + PartialEq>(
+        storage: &'a dyn Store<T>,
+        nested: &'a TableWithJoins,
+        filter_context: Option<&'a FilterContext<'a>>,
+        columns_cache: &ColumnsCache<'a>,
+    ) -> Result<Self> {
+        let table = Table::new(&nested.relation)?;
+        let joins = get_join_clauses(std::slice::from_ref(nested));
+        let join_tables = joins
+            .iter()
+            .map(|(relation, _)| Table::new(relation))
+            .collect::<Result<Vec<_>>>()?;
+
+        let data = Self::new(storage, &table, filter_context, columns_cache)?;
+        let empty_context = Rc::new(data.blend(table.get_alias(), None, None));
+        let join_data = fetch_join_data(
+            storage,
+            &empty_context,
+            &join_tables,
+            filter_context,
+            columns_cache,
+        )?;
+        let tables = data
+            .get_nested_tables(table.get_alias())
+            .into_iter()
+            .chain(
+                join_tables
+                    .iter()
+                    .zip(join_data.iter())
+                    .flat_map(|(table, data)| data.get_nested_tables(table.get_alias())),
+            )
+            .collect::<Vec<_>>();
+        let columns = tables
+            .iter()
+            .flat_map(|(_, columns)| columns.iter().cloned())
+            .collect();
+        let rows = join_nested(
+            storage,
+            table.get_alias(),
+            data,
+            joins,
+            empty_context,
+            join_data,
+            filter_context,
+        )?;
+
+        Ok(Self {
+            columns: Rc::new(columns),
+            source: Source::Nested(Rc::new(rows), Rc::new(tables)),
+        })
+    }
+
+    pub fn derived(columns: Vec<Ident>, rows: Vec<Row>) -> Self {
+        Self {
+            columns: Rc::new(columns),
+            source: Source::Derived(Rc::new(rows)),
+        }
+    }
+
+    /// Rows of derived tables are already known, stored tables are left to the storage.
+    pub fn row_count_estimate<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+    ) -> Option<usize> {
+        match &self.source {
+            Source::Stored(table_name, _) => storage.row_count_estimate(table_name),
+            Source::Derived(rows) | Source::Nested(rows, _) => Some(rows.len()),
+            Source::Lateral(_) => None,
+        }
+    }
+
+    /// Position of the indexed column of a stored table, see `Store::get_indexed_column`.
+    pub fn get_indexed_column<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+    ) -> Option<usize> {
+        match &self.source {
+            Source::Stored(table_name, _) => storage.get_indexed_column(table_name),
+            _ => None,
+        }
+    }
+
+    /// Position of the column which the rows of a stored table are sorted by, see
+    /// `Store::get_ordered_column`.
+    pub fn get_ordered_column<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+    ) -> Option<usize> {
+        match &self.source {
+            Source::Stored(table_name, _) => storage.get_ordered_column(table_name),
+            _ => None,
+        }
+    }
+
+    /// Rows of which the indexed column can be equal to `value`, fetched by their keys instead
+    /// of scanning the table. `None` if it is not a stored table or the index cannot be looked
+    /// up by the value.
+    pub fn fetch_by_index<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+        value: &Value,
+    ) -> Result<Option<Vec<Row>>> {
+        let (table_name, cancelled) = match &self.source {
+            Source::Stored(table_name, cancelled) => (table_name, cancelled),
+            _ => {
+                return Ok(None);
+            }
+        };
+
+        check_cancelled(cancelled.as_deref())?;
+
+        get_rows_by_index(storage, table_name, value)?
+            .map(|rows| rows.into_iter().map(|(_, row)| row).collect())
+            .map(Ok)
+            .transpose()
+    }
+
+    pub fn is_lateral(&self) -> bool {
+        matches!(self.source, Source::Lateral(_))
+    }
+
+    pub fn is_nested(&self) -> bool {
+        matches!(self.source, Source::Nested(..))
+    }
+
+    /// Alias and columns of each table which the rows come from, the tables of a nested join or
+    /// the table itself by `table_alias`.
+    pub fn get_tables<'b>(&'b self, table_alias: &'a str) -> Vec<(&'a str, &'b [Ident])> {
+        match &self.source {
+            Source::Nested(_, tables) => tables
+                .iter()
+                .map(|(table_alias, columns)| (*table_alias, columns.as_slice()))
+                .collect(),
+            _ => vec![(table_alias, self.columns.as_slice())],
+        }
+    }
+
+    fn get_nested_tables(&self, table_alias: &'a str) -> Vec<(&'a str, Rc<Vec<Ident>>)> {
+        match &self.source {
+            Source::Nested(_, tables) => tables.as_ref().clone(),
+            _ => vec![(table_alias, Rc::clone(&self.columns))],
+        }
+    }
+
+    /// Context of the row on top of `next`, a row of a nested join is split to a context for
+    /// each of its tables.
+    pub fn blend(
+        &self,
+        table_alias: &'a str,
+        row: Option<Row>,
+        next: Option<Rc<BlendContext<'a>>>,
+    ) -> BlendContext<'a> {
+        let tables = match &self.source {
+            Source::Nested(_, tables) => tables,
+            _ => {
+                return BlendContext {
+                    table_alias,
+                    columns: Rc::clone(&self.columns),
+                    row,
+                    next,
+                };
+            }
+        };
+
+        let mut values = row.map(|Row(values)| values.into_iter());
+        let mut contexts = tables
+            .iter()
+            .map(|(table_alias, columns)| BlendContext {
+                table_alias,
+                columns: Rc::clone(columns),
+                row: values
+                    .as_mut()
+                    .map(|values| Row(values.take(columns.len()).collect())),
+                next: None,
+            })
+            .collect::<Vec<_>>();
+        let last = contexts.pop();
+        let next = contexts.into_iter().fold(next, |next, context| {
+            Some(Rc::new(BlendContext { next, ..context }))
+        });
+
+        match last {
+            Some(last) => BlendContext { next, ..last },
+            None => BlendContext {
+                table_alias,
+                columns: Rc::clone(&self.columns),
+                row: None,
+                next,
+            },
+        }
+    }
+
+    /// Rows joined to the row of `blend_context`, the subquery of a `LATERAL` derived table runs
+    /// with the row, the other tables are scanned as `scan` does.
+    pub fn scan_lateral<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+        filter_context: Option<&FilterContext<'_>>,
+        blend_context: &BlendContext<'_>,
+    ) -> Result<impl Iterator<Item = Result<Row>>> {
+        let subquery = match self.source {
+            Source::Lateral(subquery) => subquery,
+            _ => {
+                return self.scan(storage).map(LateralScanned::Scanned);
+            }
+        };
+
+        let rows = UnionContext::new(filter_context, Some(blend_context)).with_filter_context(
+            |filter_context| {
+                select(storage, subquery, filter_context)?
+                    .rows
+                    .collect::<Result<Vec<_>>>()
+            },
+        )?;
+
+        Ok(LateralScanned::Lateral(rows.into_iter().map(Ok)))
+    }
+
+    pub fn scan<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+    ) -> Result<impl Iterator<Item = Result<Row>>> {
+        self.scan_pushed(storage, &[], None, None)
+    }
+
+    /// `predicates`, `limit` or `projection` are passed to the storage, in the order of
+    /// preference when more than one are given. All of them are ignored by derived tables.
+    pub fn scan_pushed<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+        predicates: &[Predicate],
+        projection: Option<&[usize]>,
+        limit: Option<usize>,
+    ) -> Result<impl Iterator<Item = Result<Row>>> {
+        match &self.source {
+            Source::Stored(table_name, cancelled) => {
+                check_cancelled(cancelled.as_deref())?;
+
+                let rows = match (predicates, limit, projection) {
+                    ([], Some(limit), _) => storage.scan_limited(table_name, limit)?,
+                    ([], None, None) => storage.scan_data(table_name)?,
+                    ([], None, Some(columns)) => storage.scan_projected(table_name, columns)?,
+                    _ => match fetch_by_index(storage, table_name, predicates)? {
+                        Some(rows) => rows,
+                        None => storage.scan_filtered(table_name, predicates)?,
+                    },
+                };
+                let rows =
+                    cancellable(rows, cancelled.clone()).map(|item| item.map(|(_, row)| row));
+
+                Ok(Scanned::Stored(rows))
+            }
+            Source::Derived(rows) | Source::Nested(rows, _) => {
+                let rows = Rc::clone(rows);
+
+                Ok(Scanned::Derived(
+                    (0..rows.len()).map(move |i| Ok(rows[i].clone())),
+                ))
+            }
+            Source::Lateral(_) => Err(SelectError::Unreachable.into()),
+        }
+    }
+}
+
+/// Rows of the table scanned for `predicates` are fetched by the keys found in the index, if one
+/// of them compares the indexed column for equality, see `Store::get_indexed_column`.
+fn fetch_by_index<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    table_name: &str,
+    predicates: &[Predicate],
+) -> Result<Option<RowIter<T>>> {
+    let is_equality = |predicate: &&Predicate| predicate.op == PredicateOp::Eq;
+
+    if !predicates.iter().any(|predicate| is_equality(&predicate)) {
+        return Ok(None);
+    }
+
+    let predicate = storage.get_indexed_column(table_name).and_then(|column| {
+        predicates
+            .iter()
+            .filter(is_equality)
+            .find(|predicate| predicate.index == column)
+    });
+    let rows = match predicate {
+        Some(predicate) => get_rows_by_index(storage, table_name, &predicate.value)?,
+        None => None,
+    };
+    let rows = rows.map(|rows| {
+        let predicates = predicates.to_vec();
+        let rows = rows
+            .into_iter()
+            .filter(move |(_, row)| predicates.iter().all(|predicate| predicate.check(row)))
+            .map(Ok);
+
+        Box::new(rows) as RowIter<T>
+    });
+
+    Ok(rows)
+}
+
+fn get_rows_by_index<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    table_name: &str,
+    value: &Value,
+) -> Result<Option<Vec<(T, Row)>>> {
+    let keys = match storage.get_keys_by_index(table_name, value)? {
+        Some(keys) => keys,
+        None => {
+            return Ok(None);
+        }
+    };
+
+    keys.iter()
+        .filter_map(|key| storage.get_data_by_key(table_name, key).transpose())
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// Rows of the other tables of a nested join joined to the rows of its first table, then each
+/// row of the joined tables is put together back to a single row.
+fn join_nested<'a, T: 'static + Debug + PartialEq>(
+    storage: &'a dyn Store<T>,
+    table_alias: &'a str,
+    data: TableData<'a>,
+    joins: Vec<JoinClause<'a>>,
+    empty_context: Rc<BlendContext<'a>>,
+    join_data: Vec<TableData<'a>>,
+    filter_context: Option<&'a FilterContext<'a>>,
+) -> Result<Vec<Row>> {
+    let rows_estimate = data.row_count_estimate(storage);
+    let rows = data
+        .scan(storage)?
+        .map(move |row| Ok(data.blend(table_alias, Some(row?), None)));
+
+    Join::new(storage, joins, filter_context)
+        .apply(rows, empty_context, join_data, rows_estimate, &NoProfile)?
+        .map(|blend_context| {
+            let blend_context = blend_context?;
+            let mut rows = successors(Some(blend_context.as_ref()), |context| {
+                context.next.as_deref()
+            })
+            .map(|BlendContext { columns, row, .. }| match row {
+                Some(Row(values)) => values.clone(),
+                None => vec![Value::Empty; columns.len()],
+            })
+            .collect::<Vec<_>>();
+            rows.reverse();
+
+            Ok(Row(rows.concat()))
+        })
+        .collect()
+}
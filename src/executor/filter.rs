@@ -1,32 +1,52 @@
 use boolinator::Boolinator;
 use im_rc::HashMap;
 use serde::Serialize;
+use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::fmt::Debug;
+use std::iter::once;
 use thiserror::Error;
 
-use sqlparser::ast::{BinaryOperator, Expr, Function, Ident, UnaryOperator};
+use sqlparser::ast::{
+    BinaryOperator, Expr, Function, Ident, Query, UnaryOperator, Value as AstValue,
+};
 
-use super::context::{BlendContext, FilterContext};
-use super::evaluate::{evaluate, Evaluated};
-use super::select::select;
-use crate::data::{Row, Value};
+use super::context::{BlendContext, FilterContext, UnionContext};
+use super::evaluate::{evaluate_union, Evaluated};
+use super::select::{select, ResultSet};
+use super::subquery_cache::SubqueryCache;
+use crate::data::{get_name, Row, Value};
+use crate::parse::ILIKE_PATTERN;
 use crate::result::Result;
-use crate::store::Store;
+use crate::store::{Predicate, PredicateOp, Store};
 
 #[derive(Error, Serialize, Debug, PartialEq)]
 pub enum FilterError {
     #[error("unimplemented")]
     Unimplemented,
+
+    #[error("LIKE on non-string value")]
+    LikeOnNonString,
+
+    #[error("subquery of IN should return exactly one column, but returned {0}")]
+    InSubqueryColumnCountNotOne(usize),
+
+    #[error("subquery of ANY or ALL should return exactly one column, but returned {0}")]
+    QuantifiedSubqueryColumnCountNotOne(usize),
+
+    #[error("boolean value required as a condition: {0}")]
+    BooleanValueRequired(String),
 }
 
-pub struct Filter<'a, T: 'static + Debug> {
+pub struct Filter<'a, T: 'static + Debug + PartialEq> {
     storage: &'a dyn Store<T>,
     where_clause: Option<&'a Expr>,
     context: Option<&'a FilterContext<'a>>,
     aggregated: Option<&'a HashMap<&'a Function, Value>>,
+    subquery_cache: Option<SubqueryCache>,
 }
 
-impl<'a, T: 'static + Debug> Filter<'a, T> {
+impl<'a, T: 'static + Debug + PartialEq> Filter<'a, T> {
     pub fn new(
         storage: &'a dyn Store<T>,
         where_clause: Option<&'a Expr>,
@@ -38,6 +58,16 @@ impl<'a, T: 'static + Debug> Filter<'a, T> {
             where_clause,
             context,
             aggregated,
+            subquery_cache: None,
+        }
+    }
+
+    /// Scalar subqueries of the filter are cached by `subquery_cache` across the rows it checks,
+    /// see `SubqueryCache`.
+    pub fn with_subquery_cache(self, subquery_cache: SubqueryCache) -> Self {
+        Self {
+            subquery_cache: Some(subquery_cache),
+            ..self
         }
     }
 
@@ -45,7 +75,14 @@ impl<'a, T: 'static + Debug> Filter<'a, T> {
         let context = FilterContext::new(table_alias, columns, row, self.context);
 
         match self.where_clause {
-            Some(expr) => check_expr(self.storage, Some(context).as_ref(), self.aggregated, expr),
+            Some(expr) => check_expr(
+                self.storage,
+                Some(context).as_ref(),
+                self.aggregated,
+                self.subquery_cache.as_ref(),
+                expr,
+            )
+            .map(|pass| pass.unwrap_or(false)),
             None => Ok(true),
         }
     }
@@ -57,6 +94,7 @@ impl<'a, T: 'static + Debug> Filter<'a, T> {
                 self.context,
                 blend_context,
                 self.aggregated,
+                self.subquery_cache.as_ref(),
                 expr,
             ),
             None => Ok(true),
@@ -64,12 +102,102 @@ impl<'a, T: 'static + Debug> Filter<'a, T> {
     }
 }
 
-pub struct BlendedFilter<'a, T: 'static + Debug> {
+/// Conjuncts of `WHERE` clause which compare a column of the first table in `FROM` with a
+/// literal, so they can be pushed down to the scan of the table. An unqualified column is
+/// pushed only if none of `joined_columns` has the same name.
+pub fn get_predicates(
+    where_clause: &Expr,
+    table_alias: &str,
+    columns: &[Ident],
+    joined_columns: &[Ident],
+) -> Vec<Predicate> {
+    let get_index = |expr: &Expr| match expr {
+        Expr::Identifier(ident) if joined_columns.iter().all(|c| c.value != ident.value) => columns
+            .iter()
+            .position(|column| column.value == ident.value),
+        Expr::CompoundIdentifier(idents) if idents.len() == 2 && idents[0].value == table_alias => {
+            columns
+                .iter()
+                .position(|column| column.value == idents[1].value)
+        }
+        _ => None,
+    };
+    let get_literal = |expr: &Expr| match expr {
+        Expr::Value(
+            literal @ AstValue::Number(_)
+            | literal @ AstValue::SingleQuotedString(_)
+            | literal @ AstValue::Boolean(_),
+        ) => Value::try_from(literal).ok(),
+        _ => None,
+    };
+
+    match where_clause {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            let mut predicates = get_predicates(left, table_alias, columns, joined_columns);
+            predicates.extend(get_predicates(right, table_alias, columns, joined_columns));
+
+            predicates
+        }
+        Expr::BinaryOp { left, op, right } => {
+            let (op, flipped) = match op {
+                BinaryOperator::Eq => (PredicateOp::Eq, PredicateOp::Eq),
+                BinaryOperator::NotEq => (PredicateOp::NotEq, PredicateOp::NotEq),
+                BinaryOperator::Lt => (PredicateOp::Lt, PredicateOp::Gt),
+                BinaryOperator::LtEq => (PredicateOp::LtEq, PredicateOp::GtEq),
+                BinaryOperator::Gt => (PredicateOp::Gt, PredicateOp::Lt),
+                BinaryOperator::GtEq => (PredicateOp::GtEq, PredicateOp::LtEq),
+                _ => {
+                    return vec![];
+                }
+            };
+
+            let predicate = match (get_index(left), get_literal(right)) {
+                (Some(index), Some(value)) => Some(Predicate { index, op, value }),
+                _ => match (get_literal(left), get_index(right)) {
+                    (Some(value), Some(index)) => Some(Predicate {
+                        index,
+                        op: flipped,
+                        value,
+                    }),
+                    _ => None,
+                },
+            };
+
+            predicate.into_iter().collect()
+        }
+        Expr::Nested(expr) => get_predicates(expr, table_alias, columns, joined_columns),
+        _ => vec![],
+    }
+}
+
+/// Expressions joined by `AND` at the top level of `WHERE` clause.
+pub fn get_conjuncts(where_clause: &Expr) -> Vec<&Expr> {
+    match where_clause {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            let mut conjuncts = get_conjuncts(left);
+            conjuncts.extend(get_conjuncts(right));
+
+            conjuncts
+        }
+        Expr::Nested(expr) => get_conjuncts(expr),
+        expr => vec![expr],
+    }
+}
+
+pub struct BlendedFilter<'a, T: 'static + Debug + PartialEq> {
     filter: &'a Filter<'a, T>,
     context: Option<&'a BlendContext<'a>>,
 }
 
-impl<'a, T: 'static + Debug> BlendedFilter<'a, T> {
+impl<'a, T: 'static + Debug + PartialEq> BlendedFilter<'a, T> {
     pub fn new(filter: &'a Filter<'a, T>, context: Option<&'a BlendContext<'a>>) -> Self {
         Self { filter, context }
     }
@@ -82,53 +210,104 @@ impl<'a, T: 'static + Debug> BlendedFilter<'a, T> {
                     where_clause,
                     context: next,
                     aggregated,
+                    subquery_cache,
                 },
             context: blend_context,
         } = self;
 
         let filter_context = FilterContext::new(table_alias, columns, row, *next);
         let filter_context = Some(&filter_context);
+        let subquery_cache = subquery_cache.as_ref();
 
         where_clause.map_or(Ok(true), |expr| match blend_context {
-            Some(blend_context) => {
-                check_blended_expr(*storage, filter_context, blend_context, *aggregated, expr)
-            }
-            None => check_expr(*storage, filter_context, *aggregated, expr),
+            Some(blend_context) => check_blended_expr(
+                *storage,
+                filter_context,
+                blend_context,
+                *aggregated,
+                subquery_cache,
+                expr,
+            ),
+            None => check_expr(*storage, filter_context, *aggregated, subquery_cache, expr)
+                .map(|pass| pass.unwrap_or(false)),
         })
     }
 }
 
-fn check_expr<T: 'static + Debug>(
+/// Returns `None` if the condition is `UNKNOWN`, e.g. comparisons with `NULL`.
+fn check_expr<T: 'static + Debug + PartialEq>(
     storage: &dyn Store<T>,
     filter_context: Option<&FilterContext<'_>>,
     aggregated: Option<&HashMap<&Function, Value>>,
+    subquery_cache: Option<&SubqueryCache>,
     expr: &Expr,
-) -> Result<bool> {
-    let evaluate = |expr| evaluate(storage, filter_context, aggregated, expr);
-    let check = |expr| check_expr(storage, filter_context, aggregated, expr);
+) -> Result<Option<bool>> {
+    let context = UnionContext::new(filter_context, None).with_subquery_cache(subquery_cache);
+
+    check_union_expr(storage, context, aggregated, expr)
+}
+
+pub fn check_union_expr<'a, T: 'static + Debug + PartialEq>(
+    storage: &'a dyn Store<T>,
+    context: UnionContext<'a>,
+    aggregated: Option<&HashMap<&Function, Value>>,
+    expr: &'a Expr,
+) -> Result<Option<bool>> {
+    let evaluate = |expr| evaluate_union(storage, context.clone(), aggregated, expr);
+    let check = |expr| check_union_expr(storage, context.clone(), aggregated, expr);
+
+    if let Some((left, op, all, subquery)) = get_quantified(expr) {
+        let values = context.with_filter_context(|filter_context| {
+            select_quantified(storage, subquery, filter_context)
+        })?;
+
+        return check_quantified(&evaluate(left)?, op, all, &values);
+    }
 
     match expr {
         Expr::BinaryOp { op, left, right } => {
             let zip_evaluate = || Ok((evaluate(left)?, evaluate(right)?));
             let zip_check = || Ok((check(left)?, check(right)?));
+            let compare = |compare: fn(&Evaluated<'_>, &Evaluated<'_>) -> bool| {
+                zip_evaluate()
+                    .map(|(l, r)| (l.is_some() && r.is_some()).as_some_from(|| compare(&l, &r)))
+            };
 
             match op {
-                BinaryOperator::Eq => zip_evaluate().map(|(l, r)| l == r),
-                BinaryOperator::NotEq => zip_evaluate().map(|(l, r)| l != r),
-                BinaryOperator::And => zip_check().map(|(l, r)| l && r),
-                BinaryOperator::Or => zip_check().map(|(l, r)| l || r),
-                BinaryOperator::Lt => zip_evaluate().map(|(l, r)| l < r),
-                BinaryOperator::LtEq => zip_evaluate().map(|(l, r)| l <= r),
-                BinaryOperator::Gt => zip_evaluate().map(|(l, r)| l > r),
-                BinaryOperator::GtEq => zip_evaluate().map(|(l, r)| l >= r),
+                BinaryOperator::Eq => compare(|l, r| l == r),
+                BinaryOperator::NotEq => compare(|l, r| l != r),
+                BinaryOperator::And => zip_check().map(|(l, r)| match (l, r) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                }),
+                BinaryOperator::Or => zip_check().map(|(l, r)| match (l, r) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                }),
+                BinaryOperator::Lt => compare(|l, r| l < r),
+                BinaryOperator::LtEq => compare(|l, r| l <= r),
+                BinaryOperator::Gt => compare(|l, r| l > r),
+                BinaryOperator::GtEq => compare(|l, r| l >= r),
+                BinaryOperator::Like | BinaryOperator::NotLike => {
+                    let negated = op == &BinaryOperator::NotLike;
+                    let (pattern, case_insensitive) = match get_ilike_pattern(right) {
+                        Some(pattern) => (pattern, true),
+                        None => (right.as_ref(), false),
+                    };
+
+                    like(&evaluate(left)?, &evaluate(pattern)?, case_insensitive)
+                        .map(|v| negate(negated, v))
+                }
                 _ => Err(FilterError::Unimplemented.into()),
             }
         }
-        Expr::UnaryOp { op, expr } => match op {
-            UnaryOperator::Not => check(&expr).map(|v| !v),
-            _ => Err(FilterError::Unimplemented.into()),
-        },
-        Expr::Nested(expr) => check(&expr),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => check(expr).map(|v| negate(true, v)),
+        Expr::Nested(expr) => check(expr),
         Expr::InList {
             expr,
             list,
@@ -137,34 +316,57 @@ fn check_expr<T: 'static + Debug>(
             let negated = *negated;
             let target = evaluate(expr)?;
 
-            list.iter()
-                .filter_map(|expr| {
-                    evaluate(expr).map_or_else(
-                        |error| Some(Err(error)),
-                        |evaluated| (target == evaluated).as_some(Ok(!negated)),
-                    )
-                })
-                .next()
-                .unwrap_or(Ok(negated))
+            if !target.is_some() {
+                return Ok(None);
+            }
+
+            let (found, null_found) = list.iter().try_fold::<_, _, Result<_>>(
+                (false, false),
+                |(found, null_found), expr| {
+                    if found {
+                        return Ok((found, null_found));
+                    }
+
+                    let evaluated = evaluate(expr)?;
+
+                    Ok((target == evaluated, null_found || !evaluated.is_some()))
+                },
+            )?;
+
+            Ok(negate(negated, check_in(found, null_found)))
         }
         Expr::InSubquery {
             expr,
             subquery,
             negated,
         } => {
-            let negated = *negated;
+            let values = context.with_filter_context(|filter_context| {
+                let ResultSet { labels, rows } = select(storage, subquery, filter_context)?;
+
+                if labels.len() != 1 {
+                    return Err(FilterError::InSubqueryColumnCountNotOne(labels.len()).into());
+                }
+
+                rows.map(|row| row?.take_first_value())
+                    .collect::<Result<Vec<_>>>()
+            })?;
+
+            if values.is_empty() {
+                return Ok(negate(*negated, Some(false)));
+            }
+
             let target = evaluate(expr)?;
 
-            select(storage, &subquery, filter_context)?
-                .map(|row| row?.take_first_value())
-                .filter_map(|value| {
-                    value.map_or_else(
-                        |error| Some(Err(error)),
-                        |value| (target == Evaluated::ValueRef(&value)).as_some(Ok(!negated)),
-                    )
-                })
-                .next()
-                .unwrap_or(Ok(negated))
+            if !target.is_some() {
+                return Ok(None);
+            }
+
+            let found = values
+                .iter()
+                .any(|value| target == Evaluated::ValueRef(value));
+            let null_found = values.iter().any(|value| !value.is_some());
+
+            Ok(negate(*negated, check_in(found, null_found)))
         }
         Expr::Between {
             expr,
@@ -174,21 +376,136 @@ fn check_expr<T: 'static + Debug>(
         } => {
             let negated = *negated;
             let target = evaluate(expr)?;
+            let low = evaluate(low)?;
+            let high = evaluate(high)?;
+
+            if !target.is_some() || !low.is_some() || !high.is_some() {
+                return Ok(None);
+            }
 
-            Ok(negated ^ (evaluate(low)? <= target && target <= evaluate(high)?))
+            Ok(negate(negated, Some(low <= target && target <= high)))
         }
-        Expr::Exists(query) => Ok(select(storage, query, filter_context)?.next().is_some()),
-        Expr::IsNull(expr) => Ok(!evaluate(expr)?.is_some()),
-        Expr::IsNotNull(expr) => Ok(evaluate(expr)?.is_some()),
+        Expr::Exists(query) => context.with_filter_context(|filter_context| {
+            let row = select(storage, query, filter_context)?.next().transpose()?;
+
+            Ok(Some(row.is_some()))
+        }),
+        Expr::IsNull(expr) => Ok(Some(!evaluate(expr)?.is_some())),
+        Expr::IsNotNull(expr) => Ok(Some(evaluate(expr)?.is_some())),
+        Expr::Identifier(_)
+        | Expr::CompoundIdentifier(_)
+        | Expr::Value(_)
+        | Expr::Cast { .. }
+        | Expr::Case { .. }
+        | Expr::Function(_)
+        | Expr::Subquery(_) => match Value::try_from(evaluate(expr)?)? {
+            Value::Bool(v) | Value::OptBool(Some(v)) => Ok(Some(v)),
+            value if !value.is_some() => Ok(None),
+            _ => Err(FilterError::BooleanValueRequired(expr.to_string()).into()),
+        },
         _ => Err(FilterError::Unimplemented.into()),
     }
 }
 
-pub fn check_blended_expr<T: 'static + Debug>(
+/// Every negated form, e.g. `NOT IN`, `NOT LIKE` or `NOT BETWEEN`, is checked as its positive
+/// form and then negated here, so they all follow the three-valued logic of `NOT`, which keeps
+/// `UNKNOWN` as it is.
+pub fn negate(negated: bool, result: Option<bool>) -> Option<bool> {
+    result.map(|v| v != negated)
+}
+
+/// Result of `IN` follows the standard SQL, if the target is not found but the list contains
+/// `NULL`, it is `UNKNOWN` rather than `false`. So `NOT IN` never passes once the list or the
+/// subquery result contains `NULL`.
+pub fn check_in(found: bool, null_found: bool) -> Option<bool> {
+    match (found, null_found) {
+        (true, _) => Some(true),
+        (false, true) => None,
+        (false, false) => Some(false),
+    }
+}
+
+/// Comparison of `expr` with `ANY`, `SOME` or `ALL` of a subquery, returns its left side, the
+/// operator, whether it is `ALL` and the subquery.
+pub fn get_quantified(expr: &Expr) -> Option<(&Expr, &BinaryOperator, bool, &Query)> {
+    let (left, op, func) = match expr {
+        Expr::BinaryOp { left, op, right } => match right.as_ref() {
+            Expr::Function(func) => (left.as_ref(), op, func),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let all = match get_name(&func.name).ok()?.to_uppercase().as_str() {
+        "ANY" | "SOME" => false,
+        "ALL" => true,
+        _ => return None,
+    };
+
+    match func.args.as_slice() {
+        [Expr::Subquery(subquery)] if func.over.is_none() && !func.distinct => {
+            Some((left, op, all, subquery))
+        }
+        _ => None,
+    }
+}
+
+pub fn select_quantified<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    subquery: &Query,
+    filter_context: Option<&FilterContext<'_>>,
+) -> Result<Vec<Value>> {
+    let ResultSet { labels, rows } = select(storage, subquery, filter_context)?;
+
+    if labels.len() != 1 {
+        return Err(FilterError::QuantifiedSubqueryColumnCountNotOne(labels.len()).into());
+    }
+
+    rows.map(|row| row?.take_first_value())
+        .collect::<Result<Vec<_>>>()
+}
+
+/// `ALL` of no values is `true` and `ANY` of them is `false`, even if the target is `NULL`.
+/// Otherwise `NULL` of the values makes it `UNKNOWN` unless `ALL` finds a failed comparison or
+/// `ANY` finds a passed one, like `IN` does.
+pub fn check_quantified(
+    target: &Evaluated<'_>,
+    op: &BinaryOperator,
+    all: bool,
+    values: &[Value],
+) -> Result<Option<bool>> {
+    if values.is_empty() {
+        return Ok(Some(all));
+    } else if !target.is_some() {
+        return Ok(None);
+    }
+
+    let compare: fn(&Evaluated<'_>, &Evaluated<'_>) -> bool = match op {
+        BinaryOperator::Eq => |l, r| l == r,
+        BinaryOperator::NotEq => |l, r| l != r,
+        BinaryOperator::Lt => |l, r| l < r,
+        BinaryOperator::LtEq => |l, r| l <= r,
+        BinaryOperator::Gt => |l, r| l > r,
+        BinaryOperator::GtEq => |l, r| l >= r,
+        _ => {
+            return Err(FilterError::Unimplemented.into());
+        }
+    };
+
+    let found = values
+        .iter()
+        .filter(|value| value.is_some())
+        .any(|value| compare(target, &Evaluated::ValueRef(value)) != all);
+    let null_found = values.iter().any(|value| !value.is_some());
+
+    Ok(negate(all, check_in(found, null_found)))
+}
+
+pub fn check_blended_expr<T: 'static + Debug + PartialEq>(
     storage: &dyn Store<T>,
     filter_context: Option<&FilterContext<'_>>,
     blend_context: &BlendContext<'_>,
     aggregated: Option<&HashMap<&Function, Value>>,
+    subquery_cache: Option<&SubqueryCache>,
     expr: &Expr,
 ) -> Result<bool> {
     let BlendContext {
@@ -199,15 +516,130 @@ pub fn check_blended_expr<T: 'static + Debug>(
         ..
     } = blend_context;
 
+    let empty_row = row
+        .is_none()
+        .as_some_from(|| Row(columns.iter().map(|_| Value::Empty).collect()));
     let row_context = row
         .as_ref()
-        .map(|row| FilterContext::new(table_alias, &columns, row, filter_context));
+        .or(empty_row.as_ref())
+        .map(|row| FilterContext::new(table_alias, columns, row, filter_context));
     let filter_context = row_context.as_ref().or(filter_context);
 
     match next {
-        Some(blend_context) => {
-            check_blended_expr(storage, filter_context, blend_context, aggregated, expr)
+        Some(blend_context) => check_blended_expr(
+            storage,
+            filter_context,
+            blend_context,
+            aggregated,
+            subquery_cache,
+            expr,
+        ),
+        None => check_expr(storage, filter_context, aggregated, subquery_cache, expr)
+            .map(|pass| pass.unwrap_or(false)),
+    }
+}
+
+/// Pattern of `ILIKE`, which `parse` rewrites into `LIKE` of a function `ILIKE_PATTERN`.
+pub fn get_ilike_pattern(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Function(Function { name, args, .. }) if args.len() == 1 => {
+            matches!(get_name(name), Ok(name) if name == ILIKE_PATTERN).as_some(&args[0])
         }
-        None => check_expr(storage, filter_context, aggregated, expr),
+        _ => None,
+    }
+}
+
+/// Returns `None` if either side is `NULL`. `ILIKE` is `case_insensitive`, both sides are
+/// lowercased by the Unicode rules before they are matched.
+fn like(
+    target: &Evaluated<'_>,
+    pattern: &Evaluated<'_>,
+    case_insensitive: bool,
+) -> Result<Option<bool>> {
+    if !target.is_some() || !pattern.is_some() {
+        return Ok(None);
+    }
+
+    let (target, pattern) = (get_str(target)?, get_str(pattern)?);
+    let (target, pattern) = if case_insensitive {
+        (
+            Cow::Owned(target.to_lowercase()),
+            Cow::Owned(pattern.to_lowercase()),
+        )
+    } else {
+        (Cow::Borrowed(target), Cow::Borrowed(pattern))
+    };
+    let pattern = pattern.chars().collect::<Vec<_>>();
+
+    Ok(Some(matches(&tokenize(&pattern), &target)))
+}
+
+fn get_str<'a>(evaluated: &'a Evaluated<'_>) -> Result<&'a str> {
+    match evaluated {
+        Evaluated::LiteralRef(AstValue::SingleQuotedString(v))
+        | Evaluated::Literal(AstValue::SingleQuotedString(v)) => Ok(v),
+        Evaluated::StringRef(v) => Ok(v),
+        Evaluated::ValueRef(Value::Str(v)) | Evaluated::ValueRef(Value::OptStr(Some(v))) => Ok(v),
+        Evaluated::Value(Value::Str(v)) | Evaluated::Value(Value::OptStr(Some(v))) => Ok(v),
+        _ => Err(FilterError::LikeOnNonString.into()),
     }
 }
+
+#[derive(PartialEq)]
+enum Token {
+    Any,
+    One,
+    Char(char),
+}
+
+/// `%` matches any sequence and `_` matches a single character,
+/// a backslash makes the following character matched literally.
+fn tokenize(pattern: &[char]) -> Vec<Token> {
+    let (token, rest) = match pattern {
+        [] => {
+            return vec![];
+        }
+        ['\\', c, rest @ ..] => (Token::Char(*c), rest),
+        ['%', rest @ ..] => (Token::Any, rest),
+        ['_', rest @ ..] => (Token::One, rest),
+        [c, rest @ ..] => (Token::Char(*c), rest),
+    };
+
+    once(token).chain(tokenize(rest)).collect()
+}
+
+fn matches(tokens: &[Token], target: &str) -> bool {
+    let close = |states: Vec<bool>| -> Vec<bool> {
+        once(states[0])
+            .chain(tokens.iter().zip(states.iter().skip(1)).scan(
+                states[0],
+                |prev, (token, state)| {
+                    *prev = *state || (*prev && token == &Token::Any);
+
+                    Some(*prev)
+                },
+            ))
+            .collect()
+    };
+
+    let init = close(once(true).chain(tokens.iter().map(|_| false)).collect());
+    let states = target.chars().fold(init, |states, c| {
+        let states = (0..=tokens.len())
+            .map(|i| {
+                let stay = i < tokens.len() && states[i] && tokens[i] == Token::Any;
+                let next = i > 0
+                    && states[i - 1]
+                    && match tokens[i - 1] {
+                        Token::Any | Token::One => true,
+                        Token::Char(t) => t == c,
+                    };
+
+                stay || next
+            })
+            .collect();
+
+        close(states)
+    });
+
+    states[tokens.len()]
+}
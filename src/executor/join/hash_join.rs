@@ -0,0 +1,274 @@
+use im_rc::{HashMap, Vector};
+use std::convert::TryInto;
+use std::iter::successors;
+use std::rc::Rc;
+
+use sqlparser::ast::{BinaryOperator, Expr, Ident};
+
+use super::super::context::BlendContext;
+use crate::data::{date_to_timestamp, timestamp_to_date, Row, Value};
+use crate::executor::GroupKey;
+use crate::result::Result;
+
+/// Equality between a column of the joined tables and a column of the join target, found in
+/// `ON` clause. Rows of the target table are hashed by the column once, instead of scanning the
+/// whole target table for every joined row.
+pub struct HashJoin<'a> {
+    expr: &'a Expr,
+    index: usize,
+}
+
+pub struct HashTable<'a> {
+    expr: &'a Expr,
+    rows: HashMap<GroupKey, Vector<Row>>,
+}
+
+/// Joined rows hashed by the value of `expr`, probed by the column of the target rows, which is
+/// used instead of `HashTable` when the joined rows are fewer than the target rows.
+pub struct JoinedHashTable {
+    index: usize,
+    rows: HashMap<GroupKey, Vector<usize>>,
+}
+
+impl<'a> HashJoin<'a> {
+    pub fn new(
+        where_clause: &'a Expr,
+        table_alias: &str,
+        columns: &[Ident],
+        empty_context: &BlendContext<'_>,
+    ) -> Option<Self> {
+        match where_clause {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+            } => Self::new(left, table_alias, columns, empty_context)
+                .or_else(|| Self::new(right, table_alias, columns, empty_context)),
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } => {
+                let target_index =
+                    |expr| get_target_index(table_alias, columns, empty_context, expr);
+                let is_joined = |expr| is_joined_column(table_alias, empty_context, expr);
+
+                match (target_index(left), target_index(right)) {
+                    (None, Some(index)) if is_joined(left) => Some(Self { expr: left, index }),
+                    (Some(index), None) if is_joined(right) => Some(Self { expr: right, index }),
+                    _ => None,
+                }
+            }
+            Expr::Nested(expr) => Self::new(expr, table_alias, columns, empty_context),
+            _ => None,
+        }
+    }
+
+    /// Column of the joined tables which the target rows are matched by.
+    pub fn get_expr(&self) -> &'a Expr {
+        self.expr
+    }
+
+    /// Position of the target column.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Whether `expr` is the column at `index` of the joined table of `table_alias`.
+    pub fn is_joined_by(
+        &self,
+        empty_context: &BlendContext<'_>,
+        table_alias: &str,
+        index: usize,
+    ) -> bool {
+        let column = match get_column(self.expr) {
+            Some((alias, column)) if alias.is_none_or(|alias| alias == table_alias) => column,
+            _ => {
+                return false;
+            }
+        };
+
+        successors(Some(empty_context), |context| context.next.as_deref())
+            .find(|context| context.table_alias == table_alias)
+            .and_then(|context| {
+                context
+                    .columns
+                    .iter()
+                    .position(|ident| ident.value == column)
+            })
+            == Some(index)
+    }
+
+    /// Rows of the target are hashed by the column at `index`, to be probed by `expr`.
+    pub fn from_column(expr: &'a Expr, index: usize) -> Self {
+        Self { expr, index }
+    }
+
+    /// Returns `None` if any of rows cannot be used as a hash key, e.g. `FLOAT` columns.
+    pub fn build(self, rows: Vec<Row>) -> Option<HashTable<'a>> {
+        let index = self.index;
+        let rows = rows.into_iter().try_fold(HashMap::new(), |hashed, row| {
+            let key: Result<GroupKey> = row.get_value(index)?.try_into();
+            let key = key.ok()?;
+
+            match key {
+                GroupKey::Null => Some(hashed),
+                key => Some(hashed.update_with(key, Vector::unit(row), |rows, row| rows + row)),
+            }
+        })?;
+
+        Some(HashTable {
+            expr: self.expr,
+            rows,
+        })
+    }
+
+    /// Joined rows are hashed by their positions in `rows`, `None` if any of them cannot be used
+    /// as a hash key.
+    pub fn build_joined(self, rows: &[Rc<BlendContext<'_>>]) -> Result<Option<JoinedHashTable>> {
+        let keys = rows
+            .iter()
+            .map(|blend_context| {
+                let key = get_value(self.expr, blend_context)?.map(TryInto::try_into);
+
+                Ok(key.and_then(|key: Result<GroupKey>| key.ok()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let rows = keys
+            .into_iter()
+            .enumerate()
+            .try_fold(HashMap::new(), |hashed, (i, key)| match key? {
+                GroupKey::Null => Some(hashed),
+                key => Some(hashed.update_with(key, Vector::unit(i), |rows, i| rows + i)),
+            });
+
+        Ok(rows.map(|rows| JoinedHashTable {
+            index: self.index,
+            rows,
+        }))
+    }
+}
+
+impl<'a> HashTable<'a> {
+    /// `NULL` never matches to any row.
+    pub fn probe(&self, blend_context: &BlendContext<'_>) -> Result<Vector<Row>> {
+        let rows = self
+            .get_value(blend_context)?
+            .map(|value| self.probe_value(value))
+            .unwrap_or_default();
+
+        Ok(rows)
+    }
+
+    /// Value of the joined row which the target rows are probed by.
+    pub fn get_value<'b>(&self, blend_context: &'b BlendContext<'b>) -> Result<Option<&'b Value>> {
+        get_value(self.expr, blend_context)
+    }
+
+    pub fn probe_value(&self, value: &Value) -> Vector<Row> {
+        probe_keys(value)
+            .into_iter()
+            .find_map(|key| self.rows.get(&key).cloned())
+            .unwrap_or_default()
+    }
+}
+
+impl JoinedHashTable {
+    /// Positions of the joined rows which can match to the target row, in ascending order.
+    pub fn probe(&self, row: &Row) -> Vector<usize> {
+        row.get_value(self.index)
+            .map(probe_keys)
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|key| self.rows.get(&key).cloned())
+            .unwrap_or_default()
+    }
+}
+
+pub fn get_value<'b>(
+    expr: &Expr,
+    blend_context: &'b BlendContext<'b>,
+) -> Result<Option<&'b Value>> {
+    match expr {
+        Expr::CompoundIdentifier(idents) => blend_context
+            .get_alias_value(&idents[0].value, &idents[1].value)
+            .map(Some),
+        Expr::Identifier(ident) => blend_context.get_value(&ident.value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Keys of the target rows which can be equal to the value. A float only matches to an integer
+/// of the same value, because hashed rows are never floats once the hash table is built.
+/// A date also matches to the timestamp of its midnight, and vice versa.
+fn probe_keys(value: &Value) -> Vec<GroupKey> {
+    let key: Result<GroupKey> = match value {
+        Value::F64(v) | Value::OptF64(Some(v)) if v.fract() == 0.0 => Ok(GroupKey::I64(*v as i64)),
+        value => value.try_into(),
+    };
+
+    match key {
+        Ok(GroupKey::Null) | Err(_) => vec![],
+        Ok(GroupKey::Date(v)) => vec![GroupKey::Date(v), GroupKey::Timestamp(date_to_timestamp(v))],
+        Ok(GroupKey::Timestamp(v)) if date_to_timestamp(timestamp_to_date(v)) == v => {
+            vec![GroupKey::Timestamp(v), GroupKey::Date(timestamp_to_date(v))]
+        }
+        Ok(key) => vec![key],
+    }
+}
+
+fn get_column(expr: &Expr) -> Option<(Option<&str>, &str)> {
+    match expr {
+        Expr::Identifier(ident) => Some((None, &ident.value)),
+        Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
+            Some((Some(&idents[0].value), &idents[1].value))
+        }
+        _ => None,
+    }
+}
+
+fn count_columns(context: &BlendContext<'_>, table_alias: Option<&str>, column: &str) -> usize {
+    let found = table_alias.is_none_or(|table_alias| table_alias == context.table_alias)
+        && context.columns.iter().any(|ident| ident.value == column);
+    let found = if found { 1 } else { 0 };
+
+    match &context.next {
+        Some(next) => found + count_columns(next, table_alias, column),
+        None => found,
+    }
+}
+
+fn get_target_index(
+    table_alias: &str,
+    columns: &[Ident],
+    empty_context: &BlendContext<'_>,
+    expr: &Expr,
+) -> Option<usize> {
+    let (alias, column) = get_column(expr)?;
+
+    if alias.is_some_and(|alias| alias != table_alias)
+        || count_columns(empty_context, alias, column) > 0
+    {
+        return None;
+    }
+
+    columns.iter().position(|ident| ident.value == column)
+}
+
+fn is_joined_column(table_alias: &str, empty_context: &BlendContext<'_>, expr: &Expr) -> bool {
+    match get_column(expr) {
+        Some((Some(alias), _)) if alias == table_alias => false,
+        _ => is_column(empty_context, expr),
+    }
+}
+
+/// Whether `expr` is a column of the tables in `context`, an unqualified column should belong
+/// to only one of them.
+pub fn is_column(context: &BlendContext<'_>, expr: &Expr) -> bool {
+    match get_column(expr) {
+        Some((Some(alias), column)) => count_columns(context, Some(alias), column) > 0,
+        Some((None, column)) => count_columns(context, None, column) == 1,
+        None => false,
+    }
+}
@@ -0,0 +1,94 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+
+use sqlparser::ast::Expr;
+
+use super::super::context::BlendContext;
+use super::hash_join::get_value;
+use crate::data::Row;
+use crate::result::Result;
+
+/// Target rows sorted by the column at `index`, which are merged with the joined rows sorted by
+/// `expr`. `cursor` is the position of the first target row which is not less than the value of
+/// the last joined row, so each of the target rows is passed over once while the joined rows
+/// come in ascending order.
+pub struct MergeTable<'a> {
+    expr: &'a Expr,
+    index: usize,
+    rows: Vec<Row>,
+    cursor: Cell<usize>,
+}
+
+impl<'a> MergeTable<'a> {
+    /// Rows of which the column is `NULL` never match to any row, so they are dropped. Returns
+    /// the rows back if the others are not sorted by the column, to be hashed instead.
+    pub fn new(
+        expr: &'a Expr,
+        index: usize,
+        rows: Vec<Row>,
+    ) -> std::result::Result<Self, Vec<Row>> {
+        let (rows, nulls): (Vec<_>, Vec<_>) = rows
+            .into_iter()
+            .partition(|row| row.get_value(index).is_some_and(|value| value.is_some()));
+        let sorted = rows.windows(2).all(|pair| {
+            let order = match (pair[0].get_value(index), pair[1].get_value(index)) {
+                (Some(left), Some(right)) => left.partial_cmp(right),
+                _ => None,
+            };
+
+            matches!(order, Some(Ordering::Less) | Some(Ordering::Equal))
+        });
+
+        if !sorted {
+            return Err(rows.into_iter().chain(nulls).collect());
+        }
+
+        Ok(Self {
+            expr,
+            index,
+            rows,
+            cursor: Cell::new(0),
+        })
+    }
+
+    /// Target rows of which the column equals the value of the joined row. The cursor goes back
+    /// to search from the beginning if the joined rows are not in ascending order, and every row
+    /// is returned if the value cannot be compared to the column, to be checked one by one.
+    pub fn probe(&self, blend_context: &BlendContext<'_>) -> Result<Vec<Row>> {
+        let value = match get_value(self.expr, blend_context)? {
+            Some(value) if value.is_some() => value,
+            _ => {
+                return Ok(vec![]);
+            }
+        };
+        let compare = |row: &Row| {
+            row.get_value(self.index)
+                .and_then(|target| target.partial_cmp(value))
+        };
+
+        if self.rows.first().is_some_and(|row| compare(row).is_none()) {
+            return Ok(self.rows.clone());
+        }
+
+        let is_less = |row: &Row| compare(row) == Some(Ordering::Less);
+        let cursor = match self.cursor.get() {
+            0 => 0,
+            cursor if is_less(&self.rows[cursor - 1]) => cursor,
+            _ => self.rows.partition_point(is_less),
+        };
+        let cursor = cursor
+            + self.rows[cursor..]
+                .iter()
+                .take_while(|row| is_less(row))
+                .count();
+        self.cursor.set(cursor);
+
+        let rows = self.rows[cursor..]
+            .iter()
+            .take_while(|row| compare(row) == Some(Ordering::Equal))
+            .cloned()
+            .collect();
+
+        Ok(rows)
+    }
+}
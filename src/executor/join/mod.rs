@@ -0,0 +1,981 @@
+mod hash_join;
+mod merge_join;
+mod semi_join;
+
+use boolinator::Boolinator;
+use im_rc::HashSet;
+use iter_enum::Iterator;
+use or_iterator::OrIterator;
+use serde::Serialize;
+use std::fmt::Debug;
+use std::iter::once;
+use std::rc::Rc;
+use thiserror::Error as ThisError;
+
+use sqlparser::ast::{Expr, Ident, JoinConstraint, JoinOperator, TableFactor};
+
+use super::aggregate::get_ascending_column;
+use super::context::{BlendContext, FilterContext};
+use super::fetch::TableData;
+use super::filter::{get_conjuncts, BlendedFilter, Filter};
+use super::profile::{Profiler, Stage};
+use crate::data::{Row, Table, Value};
+use crate::result::{Error, Result};
+use crate::store::Store;
+use hash_join::{get_value, HashJoin, HashTable};
+use merge_join::MergeTable;
+
+pub use semi_join::SemiJoin;
+
+#[derive(ThisError, Serialize, Debug, PartialEq)]
+pub enum JoinError {
+    #[error("unimplemented! join not supported: {0}")]
+    JoinTypeNotSupported(String),
+
+    #[error("column for using on join not found: {0}")]
+    UsingColumnNotFound(String),
+
+    #[error("unimplemented! natural on join not supported")]
+    NaturalOnJoinNotSupported,
+
+    #[error("umimplemented! failed to get table name")]
+    FailedToGetTableName,
+
+    #[error("lateral derived table cannot be joined by {0}")]
+    LateralJoinTypeNotSupported(String),
+
+    #[error("{algorithm:?} join cannot be used for {join_type}")]
+    JoinAlgorithmNotApplicable {
+        algorithm: JoinAlgorithm,
+        join_type: String,
+    },
+}
+
+/// `ReversedHash` is hash join of `INNER JOIN` which hashes the joined rows instead of the
+/// target rows, chosen when the joined rows are estimated to be fewer. `Lookup` fetches the
+/// target rows of each joined row by the index of the target column instead of scanning them,
+/// see `Store::get_indexed_column`. `Merge` merges the target rows with the joined rows when
+/// both are sorted by the columns of the equality, see `get_sorted_column`.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+pub enum JoinAlgorithm {
+    Hash,
+    ReversedHash,
+    NestedLoop,
+    Lookup,
+    Merge,
+}
+
+/// How the matched rows of the target table are joined to each of the joined rows. `Inner`
+/// joins every matched row as the join operator says, `Semi` keeps the joined row once if any
+/// target row matches, stopping at the first match, and `Anti` keeps it only if none matches.
+/// Semi and anti joins keep the joined rows as they are, without the target table, and they
+/// are only done by `INNER JOIN` or `CROSS JOIN`.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+pub enum JoinKind {
+    Inner,
+    Semi,
+    Anti,
+}
+
+/// Tables listed after the first one in `FROM` are joined as `CROSS JOIN`.
+pub static CROSS_JOIN: JoinOperator = JoinOperator::CrossJoin;
+
+pub type JoinClause<'a> = (&'a TableFactor, &'a JoinOperator);
+
+pub struct Join<'a, T: 'static + Debug + PartialEq> {
+    storage: &'a dyn Store<T>,
+    join_clauses: Vec<JoinClause<'a>>,
+    filter_context: Option<&'a FilterContext<'a>>,
+    where_clause: Option<&'a Expr>,
+    sorted_column: Option<(&'a str, usize)>,
+}
+
+type JoinItem<'a> = Result<Rc<BlendContext<'a>>>;
+type Joined<'a> = Box<dyn Iterator<Item = JoinItem<'a>> + 'a>;
+
+impl<'a, T: 'static + Debug + PartialEq> Join<'a, T> {
+    pub fn new(
+        storage: &'a dyn Store<T>,
+        join_clauses: Vec<JoinClause<'a>>,
+        filter_context: Option<&'a FilterContext<'a>>,
+    ) -> Self {
+        Self {
+            storage,
+            join_clauses,
+            filter_context,
+            where_clause: None,
+            sorted_column: None,
+        }
+    }
+
+    /// `WHERE` of the statement, which `CROSS JOIN` can be hashed by, see
+    /// `get_cross_join_condition`.
+    pub fn with_where_clause(self, where_clause: Option<&'a Expr>) -> Self {
+        Self {
+            where_clause,
+            ..self
+        }
+    }
+
+    /// Alias of the primary table and the position of its column which its rows are sorted by,
+    /// see `get_sorted_column`.
+    pub fn with_sorted_column(self, sorted_column: Option<(&'a str, usize)>) -> Self {
+        Self {
+            sorted_column,
+            ..self
+        }
+    }
+
+    /// `empty_context` is the primary table context without a row, it is used to pad the left
+    /// side of rows which are only found in the right side of `RIGHT` or `FULL` joins.
+    /// `rows_estimate` is the estimated number of `rows`, to choose the algorithm of each join.
+    pub fn apply<P: Profiler>(
+        &self,
+        rows: impl Iterator<Item = Result<BlendContext<'a>>> + 'a,
+        empty_context: Rc<BlendContext<'a>>,
+        join_data: Vec<TableData<'a>>,
+        rows_estimate: Option<usize>,
+        profiler: &'a P,
+    ) -> Result<impl Iterator<Item = JoinItem<'a>> + 'a> {
+        let rows: Joined<'a> = Box::new(rows.map(|row| row.map(Rc::new)));
+        let forced = self
+            .filter_context
+            .and_then(FilterContext::get_options)
+            .and_then(|options| options.join_algorithm);
+
+        self.join_clauses
+            .iter()
+            .zip(join_data)
+            .enumerate()
+            .try_fold(
+                (rows, empty_context, rows_estimate, self.sorted_column),
+                |(rows, empty_context, rows_estimate, sorted_column),
+                 (i, ((relation, join_operator), data))| {
+                    let table = Table::new(relation)?;
+                    let target_estimate = data.row_count_estimate(self.storage);
+                    let target_sorted_column = get_sorted_column(&table, &data.columns)
+                        .or_else(|| data.get_ordered_column(self.storage));
+                    let cross_condition = (!data.is_lateral()).and_option_from(|| {
+                        get_cross_join_condition(
+                            join_operator,
+                            self.where_clause,
+                            table.get_alias(),
+                            &data.columns,
+                            &empty_context,
+                        )
+                    });
+                    let algorithm = if data.is_lateral() {
+                        get_lateral_join_algorithm(join_operator, forced)?
+                    } else {
+                        get_join_algorithm(
+                            join_operator,
+                            cross_condition,
+                            table.get_alias(),
+                            &data.columns,
+                            &empty_context,
+                            data.get_indexed_column(self.storage),
+                            sorted_column,
+                            target_sorted_column,
+                            rows_estimate,
+                            target_estimate,
+                            forced,
+                        )?
+                    };
+                    let target = Rc::new(Target {
+                        storage: self.storage,
+                        filter_context: self.filter_context,
+                        condition: match cross_condition {
+                            Some(where_clause) => Condition::On(Some(where_clause)),
+                            None => get_condition(join_operator)?,
+                        },
+                        table_alias: table.get_alias(),
+                        data,
+                        profiler,
+                        index: i + 1,
+                    });
+
+                    let next_empty_context = target.blend(None, Rc::clone(&empty_context));
+                    let rows = profiler.time(Stage::Join(i + 1), || {
+                        join(
+                            target,
+                            join_operator,
+                            JoinKind::Inner,
+                            algorithm,
+                            rows,
+                            empty_context,
+                        )
+                    })?;
+                    let rows = profiler.instrument_boxed(Stage::Join(i + 1), rows);
+                    let rows_estimate =
+                        estimate_joined_rows(join_operator, rows_estimate, target_estimate);
+                    let sorted_column = keeps_order(join_operator).and_option(sorted_column);
+
+                    Ok((rows, next_empty_context, rows_estimate, sorted_column))
+                },
+            )
+            .map(|(rows, _, _, _)| rows)
+    }
+}
+
+enum Condition<'a> {
+    On(Option<&'a Expr>),
+    Using(&'a [Ident]),
+}
+
+fn get_condition(join_operator: &JoinOperator) -> Result<Condition<'_>> {
+    let constraint = match join_operator {
+        JoinOperator::Inner(constraint)
+        | JoinOperator::LeftOuter(constraint)
+        | JoinOperator::RightOuter(constraint)
+        | JoinOperator::FullOuter(constraint) => constraint,
+        JoinOperator::CrossJoin => {
+            return Ok(Condition::On(None));
+        }
+        JoinOperator::CrossApply | JoinOperator::OuterApply => {
+            return Err(join_type_not_supported(join_operator));
+        }
+    };
+
+    match constraint {
+        JoinConstraint::On(where_clause) => Ok(Condition::On(Some(where_clause))),
+        JoinConstraint::Using(columns) => Ok(Condition::Using(columns)),
+        JoinConstraint::Natural => Err(JoinError::NaturalOnJoinNotSupported.into()),
+    }
+}
+
+/// Position of the column which the rows of `table` are sorted by in ascending order, the first
+/// key of `ORDER BY` of a derived table, see `get_ascending_column`.
+pub fn get_sorted_column(table: &Table<'_>, columns: &[Ident]) -> Option<usize> {
+    if !table.get_column_aliases().is_empty() {
+        return None;
+    }
+
+    let column = get_ascending_column(table.get_subquery()?)?;
+
+    columns.iter().position(|ident| ident.value == column)
+}
+
+/// Joins keep the order of the joined rows, except `RIGHT` and `FULL` joins.
+pub fn keeps_order(join_operator: &JoinOperator) -> bool {
+    !matches!(
+        join_operator,
+        JoinOperator::RightOuter(_) | JoinOperator::FullOuter(_)
+    )
+}
+
+/// Equality of `WHERE` between the target table and the joined tables, which `CROSS JOIN` is
+/// done by as if it were its `ON` clause, e.g. `FROM a, b WHERE a.id = b.a_id`. The target rows
+/// are hashed by it instead of joining every pair of rows, and `WHERE` still checks it with the
+/// other conjuncts after the joins, because a later `RIGHT JOIN` can pad the rows with `NULL`.
+pub fn get_cross_join_condition<'a>(
+    join_operator: &JoinOperator,
+    where_clause: Option<&'a Expr>,
+    table_alias: &str,
+    columns: &[Ident],
+    empty_context: &BlendContext<'_>,
+) -> Option<&'a Expr> {
+    if !matches!(join_operator, JoinOperator::CrossJoin) {
+        return None;
+    }
+
+    get_conjuncts(where_clause?)
+        .into_iter()
+        .find(|expr| HashJoin::new(expr, table_alias, columns, empty_context).is_some())
+}
+
+/// Algorithm which `join` chooses for the target table, `empty_context` is the context of the
+/// joined tables without rows. Hash join still falls back to nested loop when the hashed rows
+/// cannot be hashed, e.g. `FLOAT` column. Only `INNER JOIN` and `CROSS JOIN` hash the joined rows
+/// when they are estimated to be fewer than the target rows, the other joins keep the order they
+/// are written. `CROSS JOIN` is hashed only by `cross_condition`, see `get_cross_join_condition`.
+/// The target rows are merged instead if the equality is on `sorted_column` of the joined tables
+/// and `target_sorted_column`, or looked up by `indexed_column` if the equality is on it, unless
+/// the joined rows are estimated to be more than the target rows. `forced` is used instead if
+/// the join can use it, see `ExecuteOptions::join_algorithm`.
+#[allow(clippy::too_many_arguments)]
+pub fn get_join_algorithm(
+    join_operator: &JoinOperator,
+    cross_condition: Option<&Expr>,
+    table_alias: &str,
+    columns: &[Ident],
+    empty_context: &BlendContext<'_>,
+    indexed_column: Option<usize>,
+    sorted_column: Option<(&str, usize)>,
+    target_sorted_column: Option<usize>,
+    rows_estimate: Option<usize>,
+    target_estimate: Option<usize>,
+    forced: Option<JoinAlgorithm>,
+) -> Result<JoinAlgorithm> {
+    let hash_join = |where_clause| HashJoin::new(where_clause, table_alias, columns, empty_context);
+    let hash_join = match join_operator {
+        JoinOperator::CrossJoin => cross_condition.and_then(hash_join),
+        JoinOperator::Inner(_) | JoinOperator::LeftOuter(_) => {
+            match get_condition(join_operator)? {
+                Condition::On(Some(where_clause)) => hash_join(where_clause),
+                _ => None,
+            }
+        }
+        JoinOperator::RightOuter(_) | JoinOperator::FullOuter(_) => {
+            get_condition(join_operator).map(|_| None)?
+        }
+        JoinOperator::CrossApply | JoinOperator::OuterApply => {
+            return Err(join_type_not_supported(join_operator));
+        }
+    };
+    let hashable = hash_join.is_some();
+    let indexed = hash_join
+        .as_ref()
+        .is_some_and(|hash_join| indexed_column == Some(hash_join.get_index()));
+    let mergeable = hash_join.as_ref().is_some_and(|hash_join| {
+        target_sorted_column == Some(hash_join.get_index())
+            && sorted_column.is_some_and(|(table_alias, index)| {
+                hash_join.is_joined_by(empty_context, table_alias, index)
+            })
+    });
+    let reversible = hashable
+        && matches!(
+            join_operator,
+            JoinOperator::Inner(_) | JoinOperator::CrossJoin
+        );
+    let (fewer_rows, more_rows) = match (rows_estimate, target_estimate) {
+        (Some(rows), Some(target)) => (rows < target, rows > target),
+        _ => (false, false),
+    };
+
+    match forced {
+        None if mergeable => Ok(JoinAlgorithm::Merge),
+        None if indexed && !more_rows => Ok(JoinAlgorithm::Lookup),
+        None if reversible && fewer_rows => Ok(JoinAlgorithm::ReversedHash),
+        None if hashable => Ok(JoinAlgorithm::Hash),
+        None | Some(JoinAlgorithm::NestedLoop) => Ok(JoinAlgorithm::NestedLoop),
+        Some(JoinAlgorithm::Hash) if hashable => Ok(JoinAlgorithm::Hash),
+        Some(JoinAlgorithm::ReversedHash) if reversible => Ok(JoinAlgorithm::ReversedHash),
+        Some(JoinAlgorithm::Lookup) if indexed => Ok(JoinAlgorithm::Lookup),
+        Some(JoinAlgorithm::Merge) if mergeable => Ok(JoinAlgorithm::Merge),
+        Some(algorithm) => Err(join_algorithm_not_applicable(algorithm, join_operator)),
+    }
+}
+
+/// Rows of a `LATERAL` derived table depend on each joined row, so they are joined by nested
+/// loop, and only by the joins which run its subquery for each joined row.
+pub fn get_lateral_join_algorithm(
+    join_operator: &JoinOperator,
+    forced: Option<JoinAlgorithm>,
+) -> Result<JoinAlgorithm> {
+    match join_operator {
+        JoinOperator::Inner(_) | JoinOperator::CrossJoin | JoinOperator::LeftOuter(_) => {
+            get_condition(join_operator)?;
+        }
+        _ => {
+            let join_type = get_join_type(join_operator).to_owned();
+
+            return Err(JoinError::LateralJoinTypeNotSupported(join_type).into());
+        }
+    }
+
+    match forced {
+        None | Some(JoinAlgorithm::NestedLoop) => Ok(JoinAlgorithm::NestedLoop),
+        Some(algorithm) => Err(join_algorithm_not_applicable(algorithm, join_operator)),
+    }
+}
+
+/// Estimated number of rows after the join, as many as the larger side except `CROSS JOIN`.
+pub fn estimate_joined_rows(
+    join_operator: &JoinOperator,
+    rows_estimate: Option<usize>,
+    target_estimate: Option<usize>,
+) -> Option<usize> {
+    let (rows, target) = (rows_estimate?, target_estimate?);
+
+    match join_operator {
+        JoinOperator::CrossJoin => Some(rows.saturating_mul(target)),
+        _ => Some(rows.max(target)),
+    }
+}
+
+/// Columns of the joined table which are merged by `USING`, `SELECT *` shows them only once.
+pub fn get_using_columns(join_operator: &JoinOperator) -> &[Ident] {
+    match join_operator {
+        JoinOperator::Inner(JoinConstraint::Using(columns))
+        | JoinOperator::LeftOuter(JoinConstraint::Using(columns))
+        | JoinOperator::RightOuter(JoinConstraint::Using(columns))
+        | JoinOperator::FullOuter(JoinConstraint::Using(columns)) => columns,
+        _ => &[],
+    }
+}
+
+/// `index` is the position of the table in `FROM` and `JOIN`, which its scans are recorded by.
+struct Target<'a, T: 'static + Debug + PartialEq, P> {
+    storage: &'a dyn Store<T>,
+    filter_context: Option<&'a FilterContext<'a>>,
+    condition: Condition<'a>,
+    table_alias: &'a str,
+    data: TableData<'a>,
+    profiler: &'a P,
+    index: usize,
+}
+
+impl<'a, T: 'static + Debug + PartialEq, P: Profiler> Target<'a, T, P> {
+    fn scan(&self) -> Result<impl Iterator<Item = Result<Row>>> {
+        let rows = self.data.scan(self.storage)?;
+
+        Ok(self.profiler.instrument(Stage::Scan(self.index), rows))
+    }
+
+    /// Rows of which the target column can be equal to `value`, see `TableData::fetch_by_index`.
+    /// `NULL` never matches to any row.
+    fn fetch_by_index(&self, value: &Value) -> Result<Option<impl Iterator<Item = Result<Row>>>> {
+        let rows = if value.is_some() {
+            self.data.fetch_by_index(self.storage, value)?
+        } else {
+            Some(vec![])
+        };
+
+        Ok(rows.map(|rows| {
+            let rows = rows.into_iter().map(Ok);
+
+            self.profiler.instrument(Stage::Scan(self.index), rows)
+        }))
+    }
+
+    /// Rows to be joined to the row of `blend_context`, see `TableData::scan_lateral`.
+    fn scan_lateral(
+        &self,
+        blend_context: &BlendContext<'a>,
+    ) -> Result<impl Iterator<Item = Result<Row>>> {
+        let rows = self
+            .data
+            .scan_lateral(self.storage, self.filter_context, blend_context)?;
+
+        Ok(self.profiler.instrument(Stage::Scan(self.index), rows))
+    }
+
+    fn check(&self, blend_context: &Rc<BlendContext<'a>>, row: &Row) -> Result<bool> {
+        let where_clause = match self.condition {
+            Condition::On(where_clause) => where_clause,
+            Condition::Using(columns) => {
+                return columns.iter().try_fold(true, |pass, column| {
+                    if !pass {
+                        return Ok(false);
+                    }
+
+                    let not_found = || JoinError::UsingColumnNotFound(column.value.to_owned());
+                    let left = blend_context
+                        .get_value(&column.value)
+                        .map_err(|_| not_found())?;
+                    let right = self
+                        .data
+                        .columns
+                        .iter()
+                        .position(|target| target.value == column.value)
+                        .and_then(|index| row.get_value(index))
+                        .ok_or_else(not_found)?;
+
+                    Ok(left.is_some() && right.is_some() && left == right)
+                });
+            }
+        };
+
+        let filter = Filter::new(self.storage, where_clause, self.filter_context, None);
+
+        // columns of a nested join are found by the aliases of its tables
+        if self.data.is_nested() {
+            let blended = self.blend(Some(row.clone()), Rc::clone(blend_context));
+
+            return filter.check_blended(&blended);
+        }
+
+        let blended_filter = BlendedFilter::new(&filter, Some(blend_context));
+
+        blended_filter.check(self.table_alias, &self.data.columns, row)
+    }
+
+    fn blend(&self, row: Option<Row>, next: Rc<BlendContext<'a>>) -> Rc<BlendContext<'a>> {
+        Rc::new(self.data.blend(self.table_alias, row, Some(next)))
+    }
+}
+
+/// Reversed hash join is only done by `JoinKind::Inner`, the other kinds hash the target rows.
+fn join<'a, T: 'static + Debug + PartialEq, P: Profiler>(
+    target: Rc<Target<'a, T, P>>,
+    join_operator: &JoinOperator,
+    kind: JoinKind,
+    algorithm: JoinAlgorithm,
+    rows: Joined<'a>,
+    empty_context: Rc<BlendContext<'a>>,
+) -> Result<Joined<'a>> {
+    if kind != JoinKind::Inner
+        && !matches!(
+            join_operator,
+            JoinOperator::Inner(_) | JoinOperator::CrossJoin
+        )
+    {
+        return Err(join_type_not_supported(join_operator));
+    }
+
+    match join_operator {
+        JoinOperator::Inner(_) | JoinOperator::CrossJoin | JoinOperator::LeftOuter(_) => {
+            let left_outer = matches!(join_operator, JoinOperator::LeftOuter(_));
+            let hash_join = match target.condition {
+                Condition::On(Some(where_clause)) => HashJoin::new(
+                    where_clause,
+                    target.table_alias,
+                    &target.data.columns,
+                    &empty_context,
+                ),
+                _ => None,
+            };
+            let hash_join = match (hash_join, algorithm) {
+                (Some(hash_join), JoinAlgorithm::ReversedHash) if kind == JoinKind::Inner => {
+                    return join_reversed(target, hash_join, rows);
+                }
+                (_, JoinAlgorithm::NestedLoop) => None,
+                (hash_join, _) => hash_join,
+            };
+            let probe = match hash_join {
+                Some(hash_join) if algorithm == JoinAlgorithm::Lookup => {
+                    Probe::Lookup(hash_join.get_expr())
+                }
+                Some(hash_join) => {
+                    let target_rows = target.scan()?.collect::<Result<Vec<_>>>()?;
+                    let merged = match algorithm {
+                        JoinAlgorithm::Merge => {
+                            let (expr, index) = (hash_join.get_expr(), hash_join.get_index());
+
+                            MergeTable::new(expr, index, target_rows)
+                                .map(|merge_table| Probe::Merged(Rc::new(merge_table)))
+                        }
+                        _ => Err(target_rows),
+                    };
+
+                    // rows which turn out not to be sorted are hashed
+                    merged.unwrap_or_else(|target_rows| {
+                        hash_join
+                            .build(target_rows)
+                            .map_or(Probe::Scanned, |hash_table| {
+                                Probe::Hashed(Rc::new(hash_table))
+                            })
+                    })
+                }
+                None => Probe::Scanned,
+            };
+
+            let rows = rows.flat_map(move |blend_context| {
+                let target = Rc::clone(&target);
+
+                join_row(target, probe.clone(), left_outer, kind, blend_context)
+            });
+
+            Ok(Box::new(rows))
+        }
+        JoinOperator::RightOuter(_) => {
+            let rows = rows.collect::<Result<Vec<_>>>()?;
+            let joined = target
+                .scan()?
+                .map(|row| {
+                    let row = row?;
+                    let matched = rows
+                        .iter()
+                        .filter_map(|blend_context| {
+                            target
+                                .check(blend_context, &row)
+                                .map(|pass| pass.as_some(Rc::clone(blend_context)))
+                                .transpose()
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+
+                    let joined = if matched.is_empty() {
+                        vec![target.blend(Some(row), Rc::clone(&empty_context))]
+                    } else {
+                        matched
+                            .into_iter()
+                            .map(|blend_context| target.blend(Some(row.clone()), blend_context))
+                            .collect()
+                    };
+
+                    Ok(joined)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(Box::new(joined.into_iter().flatten().map(Ok)))
+        }
+        JoinOperator::FullOuter(_) => {
+            let rows = rows.collect::<Result<Vec<_>>>()?;
+            let target_rows = target.scan()?.collect::<Result<Vec<_>>>()?;
+            let matches = rows
+                .iter()
+                .map(|blend_context| {
+                    target_rows
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, row)| {
+                            target
+                                .check(blend_context, row)
+                                .map(|pass| pass.as_some(i))
+                                .transpose()
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let matched: HashSet<usize> = matches.iter().flatten().copied().collect();
+
+            let joined = rows
+                .into_iter()
+                .zip(matches)
+                .flat_map(|(blend_context, matches)| {
+                    if matches.is_empty() {
+                        vec![target.blend(None, blend_context)]
+                    } else {
+                        matches
+                            .into_iter()
+                            .map(|i| {
+                                let row = target_rows[i].clone();
+
+                                target.blend(Some(row), Rc::clone(&blend_context))
+                            })
+                            .collect()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let unmatched = target_rows
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| !matched.contains(i))
+                .map(|(_, row)| target.blend(Some(row), Rc::clone(&empty_context)))
+                .collect::<Vec<_>>();
+
+            Ok(Box::new(joined.into_iter().chain(unmatched).map(Ok)))
+        }
+        JoinOperator::CrossApply | JoinOperator::OuterApply => {
+            Err(join_type_not_supported(join_operator))
+        }
+    }
+}
+
+/// Joined rows are hashed and probed by each of the target rows, then sorted back to the order
+/// of the joined rows, so the result is the same as the other hash join.
+fn join_reversed<'a, T: 'static + Debug + PartialEq, P: Profiler>(
+    target: Rc<Target<'a, T, P>>,
+    hash_join: HashJoin<'a>,
+    rows: Joined<'a>,
+) -> Result<Joined<'a>> {
+    let rows = rows.collect::<Result<Vec<_>>>()?;
+    let hash_table = match hash_join.build_joined(&rows)? {
+        Some(hash_table) => hash_table,
+        None => {
+            let rows = rows.into_iter().flat_map(move |blend_context| {
+                join_row(
+                    Rc::clone(&target),
+                    Probe::Scanned,
+                    false,
+                    JoinKind::Inner,
+                    Ok(blend_context),
+                )
+            });
+
+            return Ok(Box::new(rows));
+        }
+    };
+
+    let mut joined = target
+        .scan()?
+        .map(|row| {
+            let row = row?;
+
+            hash_table
+                .probe(&row)
+                .into_iter()
+                .filter_map(|i| {
+                    let blend_context = &rows[i];
+
+                    target
+                        .check(blend_context, &row)
+                        .map(|pass| {
+                            pass.as_some_from(|| {
+                                (i, target.blend(Some(row.clone()), Rc::clone(blend_context)))
+                            })
+                        })
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    joined.sort_by_key(|(i, _)| *i);
+
+    Ok(Box::new(joined.into_iter().map(|(_, row)| Ok(row))))
+}
+
+pub fn get_join_type(join_operator: &JoinOperator) -> &'static str {
+    match join_operator {
+        JoinOperator::Inner(_) => "INNER JOIN",
+        JoinOperator::LeftOuter(_) => "LEFT OUTER JOIN",
+        JoinOperator::RightOuter(_) => "RIGHT OUTER JOIN",
+        JoinOperator::FullOuter(_) => "FULL OUTER JOIN",
+        JoinOperator::CrossJoin => "CROSS JOIN",
+        JoinOperator::CrossApply => "CROSS APPLY",
+        JoinOperator::OuterApply => "OUTER APPLY",
+    }
+}
+
+fn join_type_not_supported(join_operator: &JoinOperator) -> Error {
+    JoinError::JoinTypeNotSupported(get_join_type(join_operator).to_owned()).into()
+}
+
+fn join_algorithm_not_applicable(algorithm: JoinAlgorithm, join_operator: &JoinOperator) -> Error {
+    let join_type = get_join_type(join_operator).to_owned();
+
+    JoinError::JoinAlgorithmNotApplicable {
+        algorithm,
+        join_type,
+    }
+    .into()
+}
+
+#[derive(Iterator)]
+enum JoinedRow<I1, I2, I3, I4, I5> {
+    Err(I1),
+    Inner(I2),
+    LeftOuter(I3),
+    Semi(I4),
+    Anti(I5),
+}
+
+#[derive(Iterator)]
+enum TargetRows<I1, I2, I3, I4> {
+    Hashed(I1),
+    Merged(I2),
+    Fetched(I3),
+    Scanned(I4),
+}
+
+/// How the target rows are found for each joined row, by the hash table or the sorted rows of the
+/// target table, by the index of the target column with the value of the joined column, or by
+/// scanning them.
+#[derive(Clone)]
+enum Probe<'a> {
+    Hashed(Rc<HashTable<'a>>),
+    Merged(Rc<MergeTable<'a>>),
+    Lookup(&'a Expr),
+    Scanned,
+}
+
+fn join_row<'a, T: 'static + Debug + PartialEq, P: Profiler>(
+    target: Rc<Target<'a, T, P>>,
+    probe: Probe<'a>,
+    left_outer: bool,
+    kind: JoinKind,
+    blend_context: JoinItem<'a>,
+) -> impl Iterator<Item = JoinItem<'a>> + 'a {
+    let err = |e| JoinedRow::Err(once(Err(e)));
+
+    macro_rules! try_into {
+        ($v: expr) => {
+            match $v {
+                Ok(v) => v,
+                Err(e) => {
+                    return err(e);
+                }
+            }
+        };
+    }
+
+    let blend_context = try_into!(blend_context);
+    let init_context = target.blend(None, Rc::clone(&blend_context));
+    let outer_context = Rc::clone(&blend_context);
+
+    let fetched = match probe {
+        Probe::Hashed(hash_table) => {
+            let rows = try_into!(hash_table.probe(&blend_context));
+
+            Some(TargetRows::Hashed(rows.into_iter().map(Ok)))
+        }
+        Probe::Merged(merge_table) => {
+            let rows = try_into!(merge_table.probe(&blend_context));
+
+            Some(TargetRows::Merged(rows.into_iter().map(Ok)))
+        }
+        Probe::Lookup(expr) => match try_into!(get_value(expr, &blend_context)) {
+            Some(value) => try_into!(target.fetch_by_index(value)).map(TargetRows::Fetched),
+            None => None,
+        },
+        Probe::Scanned => None,
+    };
+    let rows = match fetched {
+        Some(rows) => rows,
+        None => TargetRows::Scanned(try_into!(target.scan_lateral(&blend_context))),
+    };
+    let rows = rows.filter_map(move |row| {
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                return Some(Err(e));
+            }
+        };
+
+        target
+            .check(&blend_context, &row)
+            .map(|pass| pass.as_some(target.blend(Some(row), Rc::clone(&blend_context))))
+            .transpose()
+    });
+
+    match kind {
+        JoinKind::Inner if left_outer => JoinedRow::LeftOuter(rows.or(once(Ok(init_context)))),
+        JoinKind::Inner => JoinedRow::Inner(rows),
+        JoinKind::Semi => JoinedRow::Semi(
+            rows.take(1)
+                .map(move |row| row.map(|_| Rc::clone(&outer_context))),
+        ),
+        JoinKind::Anti => {
+            let mut rows = rows;
+            let row = match rows.next() {
+                Some(Ok(_)) => None,
+                Some(Err(e)) => Some(Err(e)),
+                None => Some(Ok(outer_context)),
+            };
+
+            JoinedRow::Anti(row.into_iter())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "memory-storage"))]
+mod tests {
+    use super::*;
+    use crate::executor::profile::NoProfile;
+    use crate::MemoryStorage;
+    use sqlparser::ast::BinaryOperator;
+
+    #[test]
+    fn join_kinds() {
+        let storage = MemoryStorage::new();
+        let column = |table: &str, column: &str| {
+            Box::new(Expr::CompoundIdentifier(vec![
+                Ident::new(table),
+                Ident::new(column),
+            ]))
+        };
+        let join_operator = JoinOperator::Inner(JoinConstraint::On(Expr::BinaryOp {
+            left: column("Item", "id"),
+            op: BinaryOperator::Eq,
+            right: column("Tag", "item_id"),
+        }));
+        let item_columns = Rc::new(vec![Ident::new("id")]);
+        let tag_rows = [(10, 1), (11, 1), (12, 3), (13, 3), (14, 5)]
+            .iter()
+            .map(|(id, item_id)| Row(vec![Value::I64(*id), Value::I64(*item_id)]))
+            .collect();
+        let tag_data = TableData::derived(vec![Ident::new("id"), Ident::new("item_id")], tag_rows);
+        let empty_context = Rc::new(BlendContext {
+            table_alias: "Item",
+            columns: Rc::clone(&item_columns),
+            row: None,
+            next: None,
+        });
+
+        let run = |kind, algorithm| {
+            let target = Rc::new(Target {
+                storage: &storage,
+                filter_context: None,
+                condition: get_condition(&join_operator).unwrap(),
+                table_alias: "Tag",
+                data: tag_data.clone(),
+                profiler: &NoProfile,
+                index: 1,
+            });
+            let rows: Joined<'_> = Box::new((1..=4).map(|id| {
+                Ok(Rc::new(BlendContext {
+                    table_alias: "Item",
+                    columns: Rc::clone(&item_columns),
+                    row: Some(Row(vec![Value::I64(id)])),
+                    next: None,
+                }))
+            }));
+            let empty_context = Rc::clone(&empty_context);
+
+            join(target, &join_operator, kind, algorithm, rows, empty_context)
+                .unwrap()
+                .map(|blend_context| {
+                    let mut values = vec![];
+                    let mut blend_context = Some(blend_context.unwrap());
+
+                    while let Some(context) = blend_context {
+                        values.extend(context.row.iter().flat_map(|Row(values)| values.clone()));
+                        blend_context = context.next.clone();
+                    }
+
+                    values
+                        .into_iter()
+                        .map(|value| match value {
+                            Value::I64(v) => v,
+                            _ => unreachable!(),
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let algorithms = [
+            JoinAlgorithm::Hash,
+            JoinAlgorithm::ReversedHash,
+            JoinAlgorithm::NestedLoop,
+            JoinAlgorithm::Lookup,
+            JoinAlgorithm::Merge,
+        ];
+
+        for algorithm in algorithms.iter() {
+            assert_eq!(
+                run(JoinKind::Inner, *algorithm),
+                vec![
+                    vec![10, 1, 1],
+                    vec![11, 1, 1],
+                    vec![12, 3, 3],
+                    vec![13, 3, 3]
+                ],
+                "{:?}",
+                algorithm
+            );
+            assert_eq!(
+                run(JoinKind::Semi, *algorithm),
+                vec![vec![1], vec![3]],
+                "{:?}",
+                algorithm
+            );
+            assert_eq!(
+                run(JoinKind::Anti, *algorithm),
+                vec![vec![2], vec![4]],
+                "{:?}",
+                algorithm
+            );
+        }
+
+        let left_outer = JoinOperator::LeftOuter(JoinConstraint::On(Expr::Value(
+            sqlparser::ast::Value::Boolean(true),
+        )));
+        let target = Rc::new(Target {
+            storage: &storage,
+            filter_context: None,
+            condition: get_condition(&left_outer).unwrap(),
+            table_alias: "Tag",
+            data: tag_data,
+            profiler: &NoProfile,
+            index: 1,
+        });
+        let rows: Joined<'_> = Box::new(std::iter::empty());
+
+        assert_eq!(
+            join(
+                target,
+                &left_outer,
+                JoinKind::Semi,
+                JoinAlgorithm::NestedLoop,
+                rows,
+                empty_context
+            )
+            .err(),
+            Some(JoinError::JoinTypeNotSupported("LEFT OUTER JOIN".to_owned()).into())
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt::Debug;
+
+use sqlparser::ast::{BinaryOperator, Expr};
+
+use super::hash_join::{get_value, is_column, HashJoin, HashTable};
+use crate::data::Value;
+use crate::executor::context::{BlendContext, FilterContext};
+use crate::executor::evaluate::Evaluated;
+use crate::executor::filter::{
+    check_in, check_quantified, get_quantified, negate, select_quantified, FilterError,
+};
+use crate::executor::select::{select, ResultSet};
+use crate::result::Result;
+use crate::store::Store;
+
+/// `IN` or `NOT IN` subquery at the top level of `WHERE` clause, which does not refer to the rows
+/// of the query. The subquery runs only once, and its result is hashed to be probed by each row
+/// like a hash join, instead of running the subquery again for every row. So does the subquery
+/// of a comparison with `ANY` or `ALL`, whose values are kept to be compared with each row.
+pub struct SemiJoin<'a> {
+    pub expr: &'a Expr,
+    probe: Probe<'a>,
+}
+
+enum Probe<'a> {
+    In {
+        table: Option<HashTable<'a>>,
+        null_found: bool,
+        negated: bool,
+    },
+    Quantified {
+        target: &'a Expr,
+        op: &'a BinaryOperator,
+        all: bool,
+        values: Vec<Value>,
+    },
+}
+
+impl<'a> SemiJoin<'a> {
+    /// `context` has the tables of the query without rows. The subquery is run while the
+    /// columns of the tables are unbound, so it fails if it refers to them, and then it is
+    /// left to `WHERE` clause. So is the subquery whose result cannot be hashed.
+    pub fn new<T: 'static + Debug + PartialEq>(
+        storage: &dyn Store<T>,
+        expr: &'a Expr,
+        context: &BlendContext<'_>,
+        filter_context: Option<&FilterContext<'_>>,
+    ) -> Option<Self> {
+        if let Some((target, op, all, subquery)) = get_quantified(expr) {
+            if !is_column(context, target) {
+                return None;
+            }
+
+            let values = with_unbound_context(context, filter_context, |filter_context| {
+                select_quantified(storage, subquery, filter_context)
+            })
+            .ok()?;
+            let probe = Probe::Quantified {
+                target,
+                op,
+                all,
+                values,
+            };
+
+            return Some(Self { expr, probe });
+        }
+
+        let (target, subquery, negated) = match expr {
+            Expr::InSubquery {
+                expr: target,
+                subquery,
+                negated,
+            } if is_column(context, target) => (target, subquery, *negated),
+            _ => {
+                return None;
+            }
+        };
+
+        let rows = with_unbound_context(context, filter_context, |filter_context| {
+            let ResultSet { labels, rows } = select(storage, subquery, filter_context)?;
+
+            if labels.len() != 1 {
+                return Err(FilterError::InSubqueryColumnCountNotOne(labels.len()).into());
+            }
+
+            rows.collect::<Result<Vec<_>>>()
+        })
+        .ok()?;
+
+        let null_found = rows
+            .iter()
+            .any(|row| row.get_value(0).is_none_or(|value| !value.is_some()));
+        let table = if rows.is_empty() {
+            None
+        } else {
+            Some(HashJoin::from_column(target, 0).build(rows)?)
+        };
+        let probe = Probe::In {
+            table,
+            null_found,
+            negated,
+        };
+
+        Some(Self { expr, probe })
+    }
+
+    pub fn check(&self, blend_context: &BlendContext<'_>) -> Result<bool> {
+        let (table, null_found, negated) = match &self.probe {
+            Probe::In {
+                table: Some(table),
+                null_found,
+                negated,
+            } => (table, *null_found, *negated),
+            Probe::In { negated, .. } => {
+                return Ok(*negated);
+            }
+            Probe::Quantified {
+                target,
+                op,
+                all,
+                values,
+            } => {
+                let target = match get_value(target, blend_context)? {
+                    Some(value) => Evaluated::ValueRef(value),
+                    None => {
+                        return Ok(false);
+                    }
+                };
+
+                return check_quantified(&target, op, *all, values)
+                    .map(|pass| pass.unwrap_or(false));
+            }
+        };
+
+        let found = match table.get_value(blend_context)? {
+            Some(value) if value.is_some() => !table.probe_value(value).is_empty(),
+            _ => {
+                return Ok(false);
+            }
+        };
+
+        Ok(negate(negated, check_in(found, null_found)).unwrap_or(false))
+    }
+}
+
+fn with_unbound_context<R>(
+    context: &BlendContext<'_>,
+    filter_context: Option<&FilterContext<'_>>,
+    f: impl FnOnce(Option<&FilterContext<'_>>) -> R,
+) -> R {
+    let BlendContext {
+        table_alias,
+        columns,
+        next,
+        ..
+    } = context;
+    let filter_context = FilterContext::unbound(table_alias, columns, filter_context);
+
+    match next {
+        Some(next) => with_unbound_context(next, Some(&filter_context), f),
+        None => f(Some(&filter_context)),
+    }
+}
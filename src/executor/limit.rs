@@ -2,7 +2,7 @@ use serde::Serialize;
 use std::fmt::Debug;
 use thiserror::Error;
 
-use sqlparser::ast::{Expr, Offset, Value as AstValue};
+use sqlparser::ast::{Expr, Fetch, Offset, Value as AstValue};
 
 use crate::result::Result;
 
@@ -10,6 +10,12 @@ use crate::result::Result;
 pub enum LimitError {
     #[error("Unreachable")]
     Unreachable,
+
+    #[error("LIMIT and FETCH cannot be used together")]
+    LimitWithFetch,
+
+    #[error("FETCH {0} is not supported")]
+    FetchNotSupported(String),
 }
 
 pub struct Limit {
@@ -18,7 +24,13 @@ pub struct Limit {
 }
 
 impl Limit {
-    pub fn new(limit: Option<&Expr>, offset: Option<&Offset>) -> Result<Self> {
+    /// `FETCH FIRST n ROWS ONLY` is the same as `LIMIT n`, and `FETCH FIRST ROW ONLY` fetches a
+    /// single row.
+    pub fn new(
+        limit: Option<&Expr>,
+        offset: Option<&Offset>,
+        fetch: Option<&Fetch>,
+    ) -> Result<Self> {
         let parse = |expr: &Expr| -> Result<usize> {
             match expr {
                 Expr::Value(AstValue::Number(v)) => {
@@ -28,7 +40,27 @@ impl Limit {
             }
         };
 
-        let limit = limit.map(|value| parse(value)).transpose()?;
+        let limit = match (limit, fetch) {
+            (Some(_), Some(_)) => {
+                return Err(LimitError::LimitWithFetch.into());
+            }
+            (limit, None) => limit.map(&parse).transpose()?,
+            (
+                None,
+                Some(Fetch {
+                    with_ties: true, ..
+                }),
+            ) => {
+                return Err(LimitError::FetchNotSupported("WITH TIES".to_owned()).into());
+            }
+            (None, Some(Fetch { percent: true, .. })) => {
+                return Err(LimitError::FetchNotSupported("PERCENT".to_owned()).into());
+            }
+            (None, Some(Fetch { quantity, .. })) => match quantity {
+                Some(quantity) => Some(parse(quantity)?),
+                None => Some(1),
+            },
+        };
         let offset = offset
             .map(|Offset { value, .. }| parse(value))
             .transpose()?;
@@ -36,12 +68,23 @@ impl Limit {
         Ok(Self { limit, offset })
     }
 
-    pub fn check(&self, i: usize) -> bool {
-        match (self.offset, self.limit) {
-            (Some(offset), Some(limit)) => i >= offset && i < offset + limit,
-            (Some(offset), None) => i >= offset,
-            (None, Some(limit)) => i < limit,
-            (None, None) => true,
-        }
+    pub fn get_limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub fn get_offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Number of rows to be read to return every row of the limit, `None` if there is no limit.
+    pub fn get_scan_limit(&self) -> Option<usize> {
+        self.limit
+            .map(|limit| limit.saturating_add(self.offset.unwrap_or(0)))
+    }
+
+    /// Rows after the limit are not read at all, so the scan stops once the limit is reached.
+    pub fn apply<I: Iterator>(&self, rows: I) -> impl Iterator<Item = I::Item> {
+        rows.skip(self.offset.unwrap_or(0))
+            .take(self.limit.unwrap_or(usize::MAX))
     }
 }
@@ -1,22 +1,44 @@
 mod aggregate;
 mod blend;
+mod cancel;
+mod compound;
 mod context;
+mod distinct;
 mod evaluate;
 mod execute;
+mod explain;
 mod fetch;
 mod filter;
 mod join;
 mod limit;
+mod options;
+mod paginate;
+mod plan_cache;
+mod profile;
+mod projection;
 mod select;
+mod sort;
+mod subquery_cache;
 mod update;
+mod validate;
+mod window;
 
 pub use aggregate::{AggregateError, GroupKey};
 pub use blend::BlendError;
+pub use cancel::CANCEL_CHECK_INTERVAL;
 pub use context::{BlendContextError, FilterContextError, UnionContextError};
 pub use evaluate::EvaluateError;
-pub use execute::{execute, ExecuteError, Payload};
+pub use execute::{
+    execute, execute_batch, execute_cancellable, execute_with_options, ExecuteError, Payload,
+};
+pub use explain::{explain, explain_analyze, Plan, PlanNode};
 pub use filter::FilterError;
-pub use join::JoinError;
+pub use join::{JoinAlgorithm, JoinError, JoinKind};
 pub use limit::LimitError;
+pub use options::ExecuteOptions;
+pub use paginate::{paginate, Page, PaginateError};
+pub use plan_cache::PlanCache;
 pub use select::SelectError;
+pub use sort::SortError;
 pub use update::UpdateError;
+pub use window::WindowError;
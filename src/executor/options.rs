@@ -0,0 +1,31 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use super::join::JoinAlgorithm;
+
+/// Options of `execute_with_options`, the defaults are what `execute` runs with. They are given
+/// to the statement and every subquery of it, see `FilterContext::with_options`.
+#[derive(Debug, Default, Clone)]
+pub struct ExecuteOptions {
+    /// Number of rows which `ORDER BY` sorts in memory. More rows than that are sorted in runs
+    /// of this size, which are written to temporary files and merged. `None` sorts every row in
+    /// memory.
+    pub sort_buffer: Option<usize>,
+
+    /// Algorithm which every join is forced to use instead of the one the planner chooses, e.g.
+    /// to compare the algorithms. Joins which cannot use it fail with
+    /// `JoinError::JoinAlgorithmNotApplicable`, e.g. `JoinAlgorithm::Merge` of rows which are not
+    /// sorted by the join columns. `None` lets the planner choose.
+    pub join_algorithm: Option<JoinAlgorithm>,
+
+    /// Whether the statement is known to refer only to existing columns, as a cached plan which
+    /// passed the validation before, so `SELECT` skips validating its columns again. Subqueries
+    /// are validated anyway.
+    pub columns_validated: bool,
+
+    /// Once it is set, e.g. by another thread aborting a long-running query, reading the storage
+    /// fails with `ExecuteError::Cancelled` and nothing is written to the storage. The flag is
+    /// checked every `CANCEL_CHECK_INTERVAL` rows a scan returns, so a storage which skips rows
+    /// in `Store::scan_filtered` is only cancelled as it returns the next rows.
+    pub cancelled: Option<Arc<AtomicBool>>,
+}
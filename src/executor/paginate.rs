@@ -0,0 +1,153 @@
+use boolinator::Boolinator;
+use serde::Serialize;
+use std::fmt::Debug;
+use std::rc::Rc;
+use thiserror::Error;
+
+use sqlparser::ast::{Select, SetExpr, Statement, TableWithJoins};
+
+use super::aggregate::Aggregate;
+use super::blend::Blend;
+use super::context::{AggregateContext, BlendContext};
+use super::execute::ExecuteError;
+use super::fetch::{apply_column_aliases, fetch_columns};
+use super::filter::Filter;
+use super::validate::validate_columns;
+use super::window::Window;
+use crate::data::{Row, Table};
+use crate::parse::Query;
+use crate::result::Result;
+use crate::store::Store;
+
+#[derive(Error, Serialize, Debug, PartialEq)]
+pub enum PaginateError {
+    #[error("page size should be greater than 0")]
+    ZeroPageSize,
+
+    #[error("paginated query should select from a single stored table")]
+    TableNotSupported,
+
+    #[error("rows of pages are in the order of keys, {0} is not supported")]
+    ClauseNotSupported(String),
+}
+
+/// Rows of a page, `next` is the cursor to fetch the page after it, `None` for the last page.
+#[derive(Debug, PartialEq)]
+pub struct Page<T> {
+    pub labels: Vec<String>,
+    pub rows: Vec<Row>,
+    pub next: Option<T>,
+}
+
+/// Runs `SELECT` from a single table in the order of its keys, and returns the first
+/// `page_size` rows of which the key is greater than `after_key`. Unlike `OFFSET`, the rows
+/// before the cursor are not scanned again, and rows inserted or deleted between the pages do
+/// not shift the rows of the next pages.
+pub fn paginate<T: 'static + Debug + Clone + Ord>(
+    storage: &dyn Store<T>,
+    query: &Query,
+    after_key: Option<T>,
+    page_size: usize,
+) -> Result<Page<T>> {
+    if page_size == 0 {
+        return Err(PaginateError::ZeroPageSize.into());
+    }
+
+    let query = match query {
+        Query(Statement::Query(query), None) => query,
+        _ => return Err(ExecuteError::QueryNotSupported.into()),
+    };
+    let statement = match &query.body {
+        SetExpr::Select(statement) => statement,
+        _ => return Err(ExecuteError::QueryNotSupported.into()),
+    };
+    let clauses = [
+        (!query.ctes.is_empty(), "WITH"),
+        (!query.order_by.is_empty(), "ORDER BY"),
+        (query.limit.is_some() || query.fetch.is_some(), "LIMIT"),
+        (query.offset.is_some(), "OFFSET"),
+        (statement.distinct, "DISTINCT"),
+        (check_aggregate(storage, statement), "aggregate"),
+    ];
+
+    if let Some((_, clause)) = clauses.iter().find(|(found, _)| *found) {
+        return Err(PaginateError::ClauseNotSupported((*clause).to_owned()).into());
+    }
+
+    let table = match statement.from.as_slice() {
+        [TableWithJoins { relation, joins }] if joins.is_empty() => Table::new(relation)?,
+        _ => return Err(PaginateError::TableNotSupported.into()),
+    };
+
+    if table.get_subquery().is_some() || table.get_nested().is_some() {
+        return Err(PaginateError::TableNotSupported.into());
+    }
+
+    let table_name = table.get_name();
+    let table_alias = table.get_alias();
+    let columns = fetch_columns(storage, table_name)?;
+    let columns = Rc::new(apply_column_aliases(&table, columns)?);
+    let tables = [(table_alias.as_str(), columns.as_slice())];
+
+    validate_columns(statement, &[], &tables, &[], None)?;
+
+    let filter = Filter::new(storage, statement.selection.as_ref(), None, None);
+    let blend = Blend::new(storage, &statement.projection, vec![]);
+    let labels = blend.get_labels(&tables)?;
+    let blend = blend.with_width(labels.len());
+
+    let mut rows = storage
+        .scan_after(table_name, after_key.as_ref())?
+        .filter_map(|item| {
+            item.and_then(|(key, row)| {
+                filter
+                    .check(table_alias, &columns, &row)
+                    .map(|pass| pass.as_some((key, row)))
+            })
+            .transpose()
+        });
+    let page = rows.by_ref().take(page_size).collect::<Result<Vec<_>>>()?;
+
+    // a row after the page is read to know whether the page is the last one
+    let next = match rows.next().transpose()? {
+        Some(_) => page.last().map(|(key, _)| key.clone()),
+        None => None,
+    };
+    let rows = page
+        .into_iter()
+        .map(|(_, row)| {
+            let next = Rc::new(BlendContext {
+                table_alias,
+                columns: Rc::clone(&columns),
+                row: Some(row),
+                next: None,
+            });
+
+            blend.apply(Ok(AggregateContext {
+                aggregated: None,
+                next,
+            }))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Page { labels, rows, next })
+}
+
+/// Rows are not grouped, so aggregates and window functions cannot be paginated.
+fn check_aggregate<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    statement: &Select,
+) -> bool {
+    let Select {
+        projection,
+        group_by,
+        having,
+        ..
+    } = statement;
+
+    Aggregate::new(storage, projection, group_by, having.as_ref(), None).check_aggregate()
+        || having.is_some()
+        || !Window::new(storage, projection, &[], None)
+            .get_windows()
+            .is_empty()
+}
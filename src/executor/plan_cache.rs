@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+
+use sqlparser::ast::{ObjectType, Statement};
+use sqlparser::dialect::keywords::Keyword;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer, Word};
+
+use super::execute::{execute, execute_with_options, ExecuteError, Payload};
+use super::options::ExecuteOptions;
+use crate::data::{get_name, Schema};
+use crate::parse::{parse, ParserError, Query};
+use crate::result::{MutResult, Result};
+use crate::store::{Store, StoreMut};
+
+/// Statements run by `PlanCache::execute` are parsed and validated once, and kept by their SQL
+/// text, of which whitespace, comments, the case of keywords and the trailing semicolon do not
+/// matter. The least recently used statement is evicted once more than `capacity` of them are
+/// kept. A cached statement keeps the schemas of the tables it refers to, and is validated again
+/// if any of them changed since. `CREATE TABLE` and `DROP TABLE` run through the cache drop the
+/// statements which mention the table.
+pub struct PlanCache {
+    capacity: usize,
+    plans: HashMap<String, CachedPlan>,
+    used: u64,
+}
+
+struct CachedPlan {
+    query: Query,
+    /// Tokens of the statement as written, so a statement which only shares the key, e.g. by
+    /// the case of an identifier which is also a keyword, is told apart.
+    tokens: Vec<Token>,
+    /// Words of the statement, which include every table it refers to.
+    words: HashSet<String>,
+    /// Schemas of the tables the statement refers to, when it was validated.
+    schemas: Vec<Schema>,
+    used: u64,
+}
+
+impl CachedPlan {
+    /// Whether the plan is the statement `sql`, which has the same key.
+    fn is_statement(&self, tokens: &[Token], sql: &str) -> bool {
+        self.tokens == tokens
+            || matches!(
+                parse_single(sql),
+                Ok(Query(statement, explain))
+                    if statement == self.query.0 && explain == self.query.1
+            )
+    }
+
+    /// Whether the tables the statement refers to still have the schemas it was validated with.
+    fn is_current<T: 'static + Debug + PartialEq, U: Store<T>>(&self, storage: &U) -> bool {
+        self.schemas.iter().all(|schema| {
+            storage
+                .fetch_schema(&schema.table_name)
+                .is_ok_and(|found| found.column_defs == schema.column_defs)
+        })
+    }
+}
+
+impl PlanCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            plans: HashMap::new(),
+            used: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the capacity, evicting the least recently used statements if it is exceeded.
+    pub fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        while self.plans.len() > capacity {
+            self.evict();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.plans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plans.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.plans.clear();
+    }
+
+    /// Executes a single statement `sql`, which is parsed and validated only if it is not found
+    /// in the cache or the schema of a table it refers to changed. A statement is cached once it
+    /// succeeds, except for `CREATE TABLE` and `DROP TABLE`.
+    pub fn execute<T: 'static + Debug + PartialEq, U: Store<T> + StoreMut<T>>(
+        &mut self,
+        storage: U,
+        sql: &str,
+    ) -> MutResult<U, Payload> {
+        let tokens = match tokenize(sql) {
+            Ok(tokens) => tokens,
+            Err(error) => {
+                return Err((storage, error.into()));
+            }
+        };
+        let key = get_key(&tokens);
+
+        self.used += 1;
+
+        if let Some(plan) = self.plans.get(&key) {
+            if !plan.is_current(&storage) {
+                self.plans.remove(&key);
+            }
+        }
+
+        match self.plans.get_mut(&key) {
+            Some(plan) if plan.is_statement(&tokens, sql) => {
+                plan.used = self.used;
+
+                let options = ExecuteOptions {
+                    columns_validated: true,
+                    ..ExecuteOptions::default()
+                };
+
+                return execute_with_options(storage, &plan.query, options);
+            }
+            _ => {}
+        }
+
+        let query = match parse_single(sql) {
+            Ok(query) => query,
+            Err(error) => {
+                return Err((storage, error));
+            }
+        };
+        let (storage, payload) = execute(storage, &query)?;
+        let Query(statement, _) = &query;
+
+        match get_defined_tables(statement) {
+            Some(tables) => self
+                .plans
+                .retain(|_, plan| tables.iter().all(|table| !plan.words.contains(*table))),
+            None if self.capacity > 0 => {
+                let words: HashSet<String> = tokens
+                    .iter()
+                    .filter_map(|token| match token {
+                        Token::Word(word) => Some(word.value.to_owned()),
+                        _ => None,
+                    })
+                    .collect();
+                let schemas = words
+                    .iter()
+                    .filter_map(|word| storage.fetch_schema(word).ok())
+                    .collect();
+
+                if !self.plans.contains_key(&key) && self.plans.len() >= self.capacity {
+                    self.evict();
+                }
+
+                let plan = CachedPlan {
+                    query,
+                    tokens,
+                    words,
+                    schemas,
+                    used: self.used,
+                };
+
+                self.plans.insert(key, plan);
+            }
+            None => {}
+        }
+
+        Ok((storage, payload))
+    }
+
+    fn evict(&mut self) {
+        let key = self
+            .plans
+            .iter()
+            .min_by_key(|(_, plan)| plan.used)
+            .map(|(key, _)| key.clone());
+
+        if let Some(key) = key {
+            self.plans.remove(&key);
+        }
+    }
+}
+
+fn tokenize(sql: &str) -> std::result::Result<Vec<Token>, ParserError> {
+    let dialect = GenericDialect {};
+    let mut tokens = Tokenizer::new(&dialect, sql)
+        .tokenize()?
+        .into_iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_)))
+        .collect::<Vec<_>>();
+
+    while tokens.last() == Some(&Token::SemiColon) {
+        tokens.pop();
+    }
+
+    Ok(tokens)
+}
+
+/// Key of the tokens, in which unquoted keywords are uppercased.
+fn get_key(tokens: &[Token]) -> String {
+    let tokens = tokens
+        .iter()
+        .map(|token| match token {
+            Token::Word(word)
+                if word.quote_style.is_none() && word.keyword != Keyword::NoKeyword =>
+            {
+                Token::Word(Word {
+                    value: word.value.to_uppercase(),
+                    ..word.clone()
+                })
+            }
+            token => token.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    format!("{:?}", tokens)
+}
+
+fn parse_single(sql: &str) -> Result<Query> {
+    let queries = parse(sql)?;
+
+    if queries.len() != 1 {
+        return Err(ExecuteError::NotSingleStatement(queries.len()).into());
+    }
+
+    Ok(queries.into_iter().next().unwrap())
+}
+
+/// Tables of which the schema is changed by `statement`, `None` if it is not a schema change.
+fn get_defined_tables(statement: &Statement) -> Option<Vec<&String>> {
+    match statement {
+        Statement::CreateTable { name, .. } => Some(get_name(name).into_iter().collect()),
+        Statement::Drop {
+            object_type: ObjectType::Table,
+            names,
+            ..
+        } => Some(
+            names
+                .iter()
+                .filter_map(|name| get_name(name).ok())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
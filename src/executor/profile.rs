@@ -0,0 +1,147 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Stage of `SELECT` which rows and time are recorded for. Tables are numbered by their
+/// positions in `FROM` and `JOIN`, `Join(i)` is the join of the table `i`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Stage {
+    Scan(usize),
+    Join(usize),
+    Filter,
+    Aggregate,
+    Window,
+    Sort,
+    DistinctOn,
+    Project,
+    Distinct,
+    Limit,
+}
+
+/// Records the stages of `SELECT`. `NoProfile` leaves the rows untouched, so running a query
+/// without `explain_analyze` costs nothing.
+pub trait Profiler {
+    type Rows<I: Iterator>: Iterator<Item = I::Item>;
+
+    fn instrument<I: Iterator>(&self, stage: Stage, rows: I) -> Self::Rows<I>;
+
+    fn instrument_boxed<'a, Item: 'a>(
+        &self,
+        stage: Stage,
+        rows: Box<dyn Iterator<Item = Item> + 'a>,
+    ) -> Box<dyn Iterator<Item = Item> + 'a>;
+
+    /// Work of the stage which is done before its rows are pulled, e.g. sorting.
+    fn time<R>(&self, stage: Stage, f: impl FnOnce() -> R) -> R;
+}
+
+pub struct NoProfile;
+
+impl Profiler for NoProfile {
+    type Rows<I: Iterator> = I;
+
+    fn instrument<I: Iterator>(&self, _: Stage, rows: I) -> I {
+        rows
+    }
+
+    fn instrument_boxed<'a, Item: 'a>(
+        &self,
+        _: Stage,
+        rows: Box<dyn Iterator<Item = Item> + 'a>,
+    ) -> Box<dyn Iterator<Item = Item> + 'a> {
+        rows
+    }
+
+    fn time<R>(&self, _: Stage, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+}
+
+#[derive(Default)]
+pub struct Stats {
+    rows: Cell<usize>,
+    elapsed: Cell<Duration>,
+}
+
+/// Rows produced and time spent by each stage. The time of a stage excludes the time of the
+/// stages it pulls rows from, which is kept in `nested` while the stage runs.
+#[derive(Default)]
+pub struct Profile {
+    stats: RefCell<HashMap<Stage, Rc<Stats>>>,
+    nested: Rc<Cell<Duration>>,
+}
+
+impl Profile {
+    pub fn get(&self, stage: Stage) -> Option<(usize, Duration)> {
+        self.stats
+            .borrow()
+            .get(&stage)
+            .map(|stats| (stats.rows.get(), stats.elapsed.get()))
+    }
+
+    fn stats(&self, stage: Stage) -> Rc<Stats> {
+        let mut stats = self.stats.borrow_mut();
+
+        Rc::clone(stats.entry(stage).or_default())
+    }
+}
+
+impl Profiler for Profile {
+    type Rows<I: Iterator> = Instrumented<I>;
+
+    fn instrument<I: Iterator>(&self, stage: Stage, rows: I) -> Instrumented<I> {
+        Instrumented {
+            rows,
+            stats: self.stats(stage),
+            nested: Rc::clone(&self.nested),
+        }
+    }
+
+    fn instrument_boxed<'a, Item: 'a>(
+        &self,
+        stage: Stage,
+        rows: Box<dyn Iterator<Item = Item> + 'a>,
+    ) -> Box<dyn Iterator<Item = Item> + 'a> {
+        Box::new(self.instrument(stage, rows))
+    }
+
+    fn time<R>(&self, stage: Stage, f: impl FnOnce() -> R) -> R {
+        measure(&self.stats(stage), &self.nested, f)
+    }
+}
+
+pub struct Instrumented<I> {
+    rows: I,
+    stats: Rc<Stats>,
+    nested: Rc<Cell<Duration>>,
+}
+
+impl<I: Iterator> Iterator for Instrumented<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rows = &mut self.rows;
+        let item = measure(&self.stats, &self.nested, || rows.next());
+
+        if item.is_some() {
+            self.stats.rows.set(self.stats.rows.get() + 1);
+        }
+
+        item
+    }
+}
+
+fn measure<R>(stats: &Stats, nested: &Cell<Duration>, f: impl FnOnce() -> R) -> R {
+    let outer = nested.replace(Duration::ZERO);
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+
+    stats
+        .elapsed
+        .set(stats.elapsed.get() + elapsed.saturating_sub(nested.get()));
+    nested.set(outer + elapsed);
+
+    result
+}
@@ -0,0 +1,159 @@
+use im_rc::HashSet;
+
+use sqlparser::ast::{
+    Expr, Function, JoinConstraint, JoinOperator, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    TableFactor, TableWithJoins,
+};
+
+type Columns<'a> = Option<HashSet<&'a str>>;
+
+/// Names of the columns which `SELECT` statement refers to, including those referred in its
+/// subqueries. Columns are found only by their names, whichever table they belong to. `None`
+/// means that any column can be needed, e.g. `SELECT *` or an expression not looked into.
+pub fn get_column_names<'a>(statement: &'a Select, order_by: &'a [OrderByExpr]) -> Columns<'a> {
+    union(
+        select_columns(statement, false),
+        union_all(
+            order_by
+                .iter()
+                .map(|OrderByExpr { expr, .. }| expr_columns(expr)),
+        ),
+    )
+}
+
+fn union<'a>(left: Columns<'a>, right: Columns<'a>) -> Columns<'a> {
+    Some(left?.union(right?))
+}
+
+fn union_all<'a>(columns: impl Iterator<Item = Columns<'a>>) -> Columns<'a> {
+    columns.fold(Some(HashSet::new()), union)
+}
+
+/// `*` of a subquery only expands columns of its own tables, so only `*` of the top level
+/// statement needs every column.
+fn select_columns(statement: &Select, nested: bool) -> Columns<'_> {
+    let Select {
+        projection,
+        from,
+        selection,
+        group_by,
+        having,
+        ..
+    } = statement;
+
+    let projection = union_all(projection.iter().map(|item| match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+            expr_columns(expr)
+        }
+        SelectItem::Wildcard if nested => Some(HashSet::new()),
+        SelectItem::Wildcard | SelectItem::QualifiedWildcard(_) => None,
+    }));
+    let from = union_all(from.iter().map(|TableWithJoins { relation, joins }| {
+        let joins = union_all(joins.iter().map(|join| {
+            let constraint = match &join.join_operator {
+                JoinOperator::Inner(constraint)
+                | JoinOperator::LeftOuter(constraint)
+                | JoinOperator::RightOuter(constraint)
+                | JoinOperator::FullOuter(constraint) => constraint,
+                _ => {
+                    return table_columns(&join.relation);
+                }
+            };
+            let constraint = match constraint {
+                JoinConstraint::On(expr) => expr_columns(expr),
+                JoinConstraint::Using(idents) => {
+                    Some(idents.iter().map(|ident| ident.value.as_str()).collect())
+                }
+                JoinConstraint::Natural => None,
+            };
+
+            union(table_columns(&join.relation), constraint)
+        }));
+
+        union(table_columns(relation), joins)
+    }));
+    let filters = union_all(
+        selection
+            .iter()
+            .chain(group_by.iter())
+            .chain(having.iter())
+            .map(expr_columns),
+    );
+
+    union(projection, union(from, filters))
+}
+
+fn table_columns(relation: &TableFactor) -> Columns<'_> {
+    match relation {
+        TableFactor::Derived { subquery, .. } => query_columns(subquery),
+        _ => Some(HashSet::new()),
+    }
+}
+
+fn query_columns(query: &Query) -> Columns<'_> {
+    let Query { body, order_by, .. } = query;
+
+    union(
+        set_expr_columns(body),
+        union_all(
+            order_by
+                .iter()
+                .map(|OrderByExpr { expr, .. }| expr_columns(expr)),
+        ),
+    )
+}
+
+fn set_expr_columns(set_expr: &SetExpr) -> Columns<'_> {
+    match set_expr {
+        SetExpr::Select(statement) => select_columns(statement, true),
+        SetExpr::Query(query) => query_columns(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            union(set_expr_columns(left), set_expr_columns(right))
+        }
+        SetExpr::Values(values) => union_all(values.0.iter().flatten().map(expr_columns)),
+    }
+}
+
+fn expr_columns<'a>(expr: &'a Expr) -> Columns<'a> {
+    let unit = |name: &'a str| Some(HashSet::unit(name));
+    let exprs = |exprs: &'a [Expr]| union_all(exprs.iter().map(expr_columns));
+
+    match expr {
+        Expr::Identifier(ident) => unit(&ident.value),
+        Expr::CompoundIdentifier(idents) => unit(&idents.last()?.value),
+        Expr::Wildcard | Expr::Value(_) | Expr::TypedString { .. } => Some(HashSet::new()),
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::Nested(expr) => expr_columns(expr),
+        Expr::InList { expr, list, .. } => union(expr_columns(expr), exprs(list)),
+        Expr::InSubquery { expr, subquery, .. } => {
+            union(expr_columns(expr), query_columns(subquery))
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => union(
+            expr_columns(expr),
+            union(expr_columns(low), expr_columns(high)),
+        ),
+        Expr::BinaryOp { left, right, .. } => union(expr_columns(left), expr_columns(right)),
+        Expr::Function(Function {
+            args, over: None, ..
+        }) => exprs(args),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => union_all(
+            operand
+                .iter()
+                .chain(else_result.iter())
+                .map(|expr| expr_columns(expr))
+                .chain(conditions.iter().chain(results.iter()).map(expr_columns)),
+        ),
+        Expr::Exists(query) | Expr::Subquery(query) => query_columns(query),
+        _ => None,
+    }
+}
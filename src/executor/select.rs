@@ -1,131 +1,530 @@
 use boolinator::Boolinator;
+use im_rc::HashSet;
+use iter_enum::Iterator;
 use serde::Serialize;
 use std::fmt::Debug;
+use std::iter::once;
+use std::ptr;
 use std::rc::Rc;
+use std::slice;
 use thiserror::Error;
 
-use sqlparser::ast::{Ident, Query, SetExpr, TableWithJoins};
+use sqlparser::ast::{
+    Cte, Ident, Join as AstJoin, OrderByExpr, Query, Select, SetExpr, TableAlias, TableWithJoins,
+};
 
-use super::aggregate::Aggregate;
-use super::blend::Blend;
+use super::aggregate::{is_sorted_by_group, Aggregate};
+use super::blend::{Blend, UsingColumns};
+use super::compound::compound;
 use super::context::{BlendContext, FilterContext};
-use super::fetch::fetch_columns;
-use super::filter::Filter;
-use super::join::Join;
+use super::distinct::{get_distinct_on, Distinct};
+use super::fetch::{fetch_columns_cache, rename_columns, ColumnsCache, TableData};
+use super::filter::{get_conjuncts, get_predicates, Filter};
+use super::join::{get_sorted_column, get_using_columns, Join, JoinClause, SemiJoin, CROSS_JOIN};
 use super::limit::Limit;
+use super::profile::{NoProfile, Profiler, Stage};
+use super::projection::get_column_names;
+use super::sort::Sort;
+use super::subquery_cache::SubqueryCache;
+use super::validate::validate_columns;
+use super::window::Window;
 use crate::data::{Row, Table};
 use crate::result::Result;
-use crate::store::Store;
+use crate::store::{Predicate, Store};
 
 #[derive(Error, Serialize, Debug, PartialEq)]
 pub enum SelectError {
-    #[error("unimplemented! select on two or more than tables are not supported")]
-    TooManyTables,
-
     #[error("unreachable!")]
     Unreachable,
-}
 
-macro_rules! err {
-    ($err: expr) => {{
-        return Err($err.into());
-    }};
+    #[error("table alias specified more than once: {0}")]
+    DuplicateTableAlias(String),
+
+    #[error("WITH clause table name conflicts with a stored table: {0}")]
+    CteNameConflictsWithTable(String),
+
+    #[error("DISTINCT ON expressions must match the leading ORDER BY expressions")]
+    DistinctOnNotMatchingOrderBy,
 }
 
-fn fetch_blended<'a, T: 'static + Debug>(
+fn fetch_blended<'a, T: 'static + Debug + PartialEq>(
     storage: &dyn Store<T>,
     table: Table<'a>,
-    columns: Rc<Vec<Ident>>,
+    table_data: &TableData<'a>,
+    predicates: &[Predicate],
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
 ) -> Result<impl Iterator<Item = Result<BlendContext<'a>>> + 'a> {
-    let rows = storage.scan_data(table.get_name())?.map(move |data| {
-        let (_, row) = data?;
-        let row = Some(row);
-        let columns = Rc::clone(&columns);
-
-        Ok(BlendContext {
-            table_alias: table.get_alias(),
-            columns,
-            row,
-            next: None,
-        })
-    });
+    let table_data = table_data.clone();
+    let rows = table_data
+        .scan_pushed(storage, predicates, projection, limit)?
+        .map(move |row| Ok(table_data.blend(table.get_alias(), Some(row?), None)));
 
     Ok(rows)
 }
 
-pub fn select<'a, T: 'static + Debug>(
+/// Context of the joined tables without rows, `join_data` can be fewer than `join_tables` to have
+/// only the tables joined so far.
+fn blend_tables<'a>(
+    empty_context: &Rc<BlendContext<'a>>,
+    join_tables: &[Table<'a>],
+    join_data: &[TableData<'a>],
+) -> Rc<BlendContext<'a>> {
+    join_tables
+        .iter()
+        .zip(join_data.iter())
+        .fold(Rc::clone(empty_context), |next, (table, data)| {
+            Rc::new(data.blend(table.get_alias(), None, Some(next)))
+        })
+}
+
+/// Rows of each table in `JOIN`, a `LATERAL` derived table gets the tables joined before it.
+pub fn fetch_join_data<'a, T: 'static + Debug + PartialEq>(
     storage: &'a dyn Store<T>,
-    query: &'a Query,
+    empty_context: &Rc<BlendContext<'a>>,
+    join_tables: &[Table<'a>],
     filter_context: Option<&'a FilterContext<'a>>,
-) -> Result<impl Iterator<Item = Result<Row>> + 'a> {
-    let (table_with_joins, where_clause, projection, group_by, having) = match &query.body {
-        SetExpr::Select(statement) => {
-            let tables = &statement.from;
-            let table_with_joins = match tables.len() {
-                1 => &tables[0],
-                0 => err!(SelectError::Unreachable),
-                _ => err!(SelectError::TooManyTables),
-            };
-
-            (
-                table_with_joins,
-                statement.selection.as_ref(),
-                statement.projection.as_ref(),
-                &statement.group_by,
-                statement.having.as_ref(),
-            )
+    columns_cache: &ColumnsCache<'a>,
+) -> Result<Vec<TableData<'a>>> {
+    join_tables.iter().try_fold(vec![], |mut join_data, table| {
+        let data = if table.is_lateral() {
+            let context = blend_tables(empty_context, join_tables, &join_data);
+
+            TableData::lateral(storage, table, filter_context, &context)?
+        } else {
+            TableData::new(storage, table, filter_context, columns_cache)?
+        };
+
+        join_data.push(data);
+
+        Ok(join_data)
+    })
+}
+
+/// Every conjunct of `WHERE` clause is evaluated, even after one of them fails, so that errors
+/// are not hidden by the order of conjuncts.
+fn check_where<T: 'static + Debug + PartialEq>(
+    filters: &[Filter<'_, T>],
+    semi_joins: &[SemiJoin<'_>],
+    blend_context: &BlendContext<'_>,
+) -> Result<bool> {
+    let passes = filters
+        .iter()
+        .map(|filter| filter.check_blended(blend_context))
+        .chain(
+            semi_joins
+                .iter()
+                .map(|semi_join| semi_join.check(blend_context)),
+        )
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(passes.into_iter().all(|pass| pass))
+}
+
+/// Columns merged by `USING` by the alias of each joined table, including the tables joined in
+/// nested joins.
+pub fn get_all_using_columns<'a>(
+    table: &Table<'a>,
+    joins: &[JoinClause<'a>],
+) -> Result<Vec<UsingColumns<'a>>> {
+    let nested = match table.get_nested() {
+        Some(nested) => {
+            let table = Table::new(&nested.relation)?;
+
+            get_all_using_columns(&table, &get_join_clauses(slice::from_ref(nested)))?
         }
-        _ => err!(SelectError::Unreachable),
+        None => vec![],
     };
 
-    let TableWithJoins { relation, joins } = &table_with_joins;
-    let table = Table::new(relation)?;
+    joins
+        .iter()
+        .try_fold(nested, |mut using_columns, (relation, join_operator)| {
+            let table = Table::new(relation)?;
+
+            using_columns.push((table.get_alias().as_str(), get_using_columns(join_operator)));
+            using_columns.extend(get_all_using_columns(&table, &[])?);
 
-    let columns = fetch_columns(storage, table.get_name())?;
-    let columns = Rc::new(columns);
-    let join_columns = joins
+            Ok(using_columns)
+        })
+}
+
+/// Every table in `FROM` and `JOIN` except the first one, with the operator it is joined by.
+pub fn get_join_clauses(tables: &[TableWithJoins]) -> Vec<JoinClause<'_>> {
+    tables
         .iter()
-        .map(|join| {
-            let table_name = Table::new(&join.relation)?.get_name();
-            let columns = fetch_columns(storage, table_name)?;
+        .enumerate()
+        .flat_map(|(i, TableWithJoins { relation, joins })| {
+            let cross_join = (i > 0).as_some((relation, &CROSS_JOIN));
+            let joins = joins.iter().map(
+                |AstJoin {
+                     relation,
+                     join_operator,
+                 }| (relation, join_operator),
+            );
 
-            Ok(Rc::new(columns))
+            cross_join.into_iter().chain(joins)
         })
-        .collect::<Result<_>>()?;
-    let join_columns = Rc::new(join_columns);
+        .collect()
+}
 
-    let join = Join::new(storage, joins, filter_context);
-    let aggregate = Aggregate::new(storage, projection, group_by, having, filter_context);
-    let blend = Blend::new(storage, projection);
-    let filter = Filter::new(storage, where_clause, filter_context, None);
-    let limit = Limit::new(query.limit.as_ref(), query.offset.as_ref())?;
+/// Aliases of the table, or of every table joined in it if it is a nested join.
+pub fn get_aliases<'a>(table: &Table<'a>) -> Result<Vec<&'a String>> {
+    let nested = match table.get_nested() {
+        Some(nested) => nested,
+        None => {
+            return Ok(vec![table.get_alias()]);
+        }
+    };
 
-    let rows = fetch_blended(storage, table, columns)?
-        .flat_map(move |blend_context| {
-            let join_columns = Rc::clone(&join_columns);
+    once(&nested.relation)
+        .chain(nested.joins.iter().map(|AstJoin { relation, .. }| relation))
+        .map(|relation| get_aliases(&Table::new(relation)?))
+        .collect::<Result<Vec<_>>>()
+        .map(|aliases| aliases.concat())
+}
 
-            join.apply(blend_context, join_columns)
+/// Columns are found by table aliases, so every table in `FROM` and `JOIN` should have its own
+/// alias, e.g. a table joined with itself. Tables in nested joins are counted with the others.
+pub fn check_table_aliases(table: &Table<'_>, joins: &[JoinClause<'_>]) -> Result<()> {
+    let aliases = joins
+        .iter()
+        .map(|(relation, _)| get_aliases(&Table::new(relation)?))
+        .collect::<Result<Vec<_>>>()?;
+
+    get_aliases(table)?
+        .into_iter()
+        .chain(aliases.into_iter().flatten())
+        .try_fold(HashSet::new(), |aliases, alias| {
+            if aliases.contains(alias) {
+                Err(SelectError::DuplicateTableAlias(alias.to_owned()).into())
+            } else {
+                Ok(aliases.update(alias))
+            }
         })
+        .map(|_| ())
+}
+
+/// Rows of a query with the labels of its output columns. A label is the alias of a
+/// `SELECT` item if it has one, otherwise the column name or the expression itself, and `*`
+/// is expanded to the labels of every column it covers.
+pub struct ResultSet<I> {
+    pub labels: Vec<String>,
+    pub rows: I,
+}
+
+impl<I: Iterator<Item = Result<Row>>> Iterator for ResultSet<I> {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next()
+    }
+}
+
+#[derive(Iterator)]
+enum Selected<I1, I2> {
+    Select(I1),
+    Compound(I2),
+}
+
+#[derive(Iterator)]
+enum Materialized<I1, I2> {
+    Body(I1),
+    With(I2),
+}
+
+/// Tables of `WITH` clause are materialized in order before the body runs, each of them can
+/// refer to the preceding ones. Rows of the body are collected too, because the tables only
+/// live while `select` runs.
+pub fn select<'a, T: 'static + Debug + PartialEq>(
+    storage: &'a dyn Store<T>,
+    query: &'a Query,
+    filter_context: Option<&'a FilterContext<'a>>,
+) -> Result<ResultSet<impl Iterator<Item = Result<Row>> + 'a>> {
+    if query.ctes.is_empty() {
+        let ResultSet { labels, rows } = select_body(storage, query, filter_context)?;
+
+        return Ok(ResultSet {
+            labels,
+            rows: Materialized::Body(rows),
+        });
+    }
+
+    let (labels, rows) = select_with(storage, query, &query.ctes, filter_context)?;
+
+    Ok(ResultSet {
+        labels,
+        rows: Materialized::With(rows.into_iter().map(Ok)),
+    })
+}
+
+fn select_body<'a, T: 'static + Debug + PartialEq>(
+    storage: &'a dyn Store<T>,
+    query: &'a Query,
+    filter_context: Option<&'a FilterContext<'a>>,
+) -> Result<ResultSet<impl Iterator<Item = Result<Row>> + 'a>> {
+    match &query.body {
+        SetExpr::Select(statement) => {
+            let limit = Limit::new(
+                query.limit.as_ref(),
+                query.offset.as_ref(),
+                query.fetch.as_ref(),
+            )?;
+            let ResultSet { labels, rows } =
+                select_statement(storage, statement, &query.order_by, limit, filter_context)?;
+
+            Ok(ResultSet {
+                labels,
+                rows: Selected::Select(rows),
+            })
+        }
+        _ => {
+            let ResultSet { labels, rows } = compound(storage, query, filter_context)?;
+
+            Ok(ResultSet {
+                labels,
+                rows: Selected::Compound(rows),
+            })
+        }
+    }
+}
+
+fn select_with<T: 'static + Debug + PartialEq>(
+    storage: &dyn Store<T>,
+    query: &Query,
+    ctes: &[Cte],
+    filter_context: Option<&FilterContext<'_>>,
+) -> Result<(Vec<String>, Vec<Row>)> {
+    let (Cte { alias, query: cte }, ctes) = match ctes.split_first() {
+        Some(first) => first,
+        None => {
+            let ResultSet { labels, rows } = select_body(storage, query, filter_context)?;
+
+            return Ok((labels, rows.collect::<Result<_>>()?));
+        }
+    };
+
+    let TableAlias { name, columns } = alias;
+    let name = name.value.as_str();
+
+    if storage.fetch_schema(name).is_ok() {
+        return Err(SelectError::CteNameConflictsWithTable(name.to_owned()).into());
+    }
+
+    let ResultSet { labels, rows } = select(storage, cte, filter_context)?;
+    let labels = labels.into_iter().map(Ident::new).collect();
+    let table_data = TableData::derived(
+        rename_columns(name, columns, labels)?,
+        rows.collect::<Result<_>>()?,
+    );
+    let context = FilterContext::with_cte(name, &table_data, filter_context);
+
+    select_with(storage, query, ctes, Some(&context))
+}
+
+pub fn select_statement<'a, T: 'static + Debug + PartialEq>(
+    storage: &'a dyn Store<T>,
+    statement: &'a Select,
+    order_by: &'a [OrderByExpr],
+    limit: Limit,
+    filter_context: Option<&'a FilterContext<'a>>,
+) -> Result<ResultSet<impl Iterator<Item = Result<Row>> + 'a>> {
+    select_profiled(
+        storage,
+        statement,
+        order_by,
+        limit,
+        filter_context,
+        &NoProfile,
+    )
+}
+
+/// `select_statement` which records the rows and time of each stage by `profiler`.
+pub fn select_profiled<'a, T: 'static + Debug + PartialEq, P: Profiler>(
+    storage: &'a dyn Store<T>,
+    statement: &'a Select,
+    order_by: &'a [OrderByExpr],
+    limit: Limit,
+    filter_context: Option<&'a FilterContext<'a>>,
+    profiler: &'a P,
+) -> Result<ResultSet<impl Iterator<Item = Result<Row>> + 'a>> {
+    let Select {
+        from: tables,
+        selection: where_clause,
+        group_by,
+        having,
+        distinct,
+        ..
+    } = statement;
+    let where_clause = where_clause.as_ref();
+    let having = having.as_ref();
+    let (distinct_on, projection) = get_distinct_on(statement, order_by)?;
+
+    let table = match tables.first() {
+        Some(TableWithJoins { relation, .. }) => Table::new(relation)?,
+        None => Table::empty(),
+    };
+    let joins = get_join_clauses(tables);
+
+    check_table_aliases(&table, &joins)?;
+
+    let join_tables = joins
+        .iter()
+        .map(|(relation, _)| Table::new(relation))
+        .collect::<Result<Vec<_>>>()?;
+    let columns_cache = fetch_columns_cache(
+        storage,
+        tables
+            .first()
+            .map(|_| &table)
+            .into_iter()
+            .chain(join_tables.iter()),
+        filter_context,
+    )?;
+
+    // without `FROM`, the fields are evaluated once with a single row which has no columns
+    let table_data = match tables.first() {
+        Some(_) => TableData::new(storage, &table, filter_context, &columns_cache)?,
+        None => TableData::derived(vec![], vec![Row(vec![])]),
+    };
+    let columns = Rc::clone(&table_data.columns);
+    let empty_context = Rc::new(table_data.blend(table.get_alias(), None, None));
+    let join_data = fetch_join_data(
+        storage,
+        &empty_context,
+        &join_tables,
+        filter_context,
+        &columns_cache,
+    )?;
+    let using_columns = get_all_using_columns(&table, &joins)?;
+
+    let tables = join_tables
+        .iter()
+        .zip(join_data.iter())
+        .flat_map(|(table, data)| data.get_tables(table.get_alias()));
+    let tables = table_data
+        .get_tables(table.get_alias())
+        .into_iter()
+        .chain(tables)
+        .collect::<Vec<_>>();
+
+    if !filter_context.is_some_and(FilterContext::columns_validated) {
+        validate_columns(statement, order_by, &tables, &joins, filter_context)?;
+    }
+
+    let aggregate = Aggregate::new(storage, projection, group_by, having, filter_context)
+        .with_order_by(order_by)
+        .with_sorted_input(is_sorted_by_group(group_by, &table, &joins));
+    let blend = Blend::new(storage, projection, using_columns);
+    let labels = blend.get_labels(&tables)?;
+    let blend = blend.with_width(labels.len());
+    let tables_context = blend_tables(&empty_context, &join_tables, &join_data);
+    let conjuncts = where_clause.map(get_conjuncts).unwrap_or_default();
+    let semi_joins = conjuncts
+        .iter()
+        .filter_map(|expr| SemiJoin::new(storage, expr, &tables_context, filter_context))
+        .collect::<Vec<_>>();
+    let filters = if semi_joins.is_empty() {
+        vec![Filter::new(storage, where_clause, filter_context, None)
+            .with_subquery_cache(SubqueryCache::default())]
+    } else {
+        conjuncts
+            .into_iter()
+            .filter(|expr| {
+                semi_joins
+                    .iter()
+                    .all(|semi_join| !ptr::eq(semi_join.expr, *expr))
+            })
+            .map(|expr| {
+                Filter::new(storage, Some(expr), filter_context, None)
+                    .with_subquery_cache(SubqueryCache::default())
+            })
+            .collect()
+    };
+    let sorted_column = get_sorted_column(&table, &table_data.columns)
+        .or_else(|| table_data.get_ordered_column(storage))
+        .map(|index| (table.get_alias().as_str(), index));
+    let join = Join::new(storage, joins, filter_context)
+        .with_where_clause(where_clause)
+        .with_sorted_column(sorted_column);
+    let sort = Sort::new(
+        storage,
+        order_by,
+        blend.get_fields(&tables)?,
+        distinct_on.unwrap_or(0),
+        filter_context,
+    );
+    let window = Window::new(storage, projection, order_by, filter_context);
+    let distinct = Distinct::new(*distinct && distinct_on.is_none());
+
+    let predicates = match where_clause {
+        Some(where_clause) => {
+            let joined_columns = join_data
+                .iter()
+                .flat_map(|TableData { columns, .. }| columns.iter().cloned())
+                .collect::<Vec<_>>();
+
+            get_predicates(where_clause, table.get_alias(), &columns, &joined_columns)
+        }
+        None => vec![],
+    };
+    let projection = get_column_names(statement, order_by).map(|names| {
+        columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, column)| names.contains(column.value.as_str()).as_some(i))
+            .collect::<Vec<_>>()
+    });
+    // every scanned row is returned as is, so the scan can stop at the limit
+    let scan_limit = (join_data.is_empty()
+        && where_clause.is_none()
+        && order_by.is_empty()
+        && !aggregate.check_aggregate()
+        && window.get_windows().is_empty()
+        && !statement.distinct)
+        .and_option_from(|| limit.get_scan_limit());
+    let rows_estimate = table_data.row_count_estimate(storage);
+    let rows = fetch_blended(
+        storage,
+        table,
+        &table_data,
+        &predicates,
+        projection.as_deref(),
+        scan_limit,
+    )?;
+    let rows = profiler.instrument(Stage::Scan(0), rows);
+    let rows = join
+        .apply(rows, empty_context, join_data, rows_estimate, profiler)?
         .filter_map(move |blend_context| {
             blend_context.map_or_else(
                 |error| Some(Err(error)),
                 |blend_context| {
-                    filter
-                        .check_blended(&blend_context)
+                    check_where(&filters, &semi_joins, &blend_context)
                         .map(|pass| pass.as_some(blend_context))
                         .transpose()
                 },
             )
-        })
-        .enumerate()
-        .filter_map(move |(i, item)| limit.check(i).as_some(item));
+        });
+    let rows = profiler.instrument(Stage::Filter, rows);
 
     let rows = {
-        let rows = aggregate.apply(rows)?;
+        let sort_stage = match distinct_on {
+            Some(_) => Stage::DistinctOn,
+            None => Stage::Sort,
+        };
+
+        let rows = profiler.time(Stage::Aggregate, || aggregate.apply(rows))?;
+        let rows = profiler.instrument(Stage::Aggregate, rows);
+        let rows = profiler.time(Stage::Window, || window.apply(rows))?;
+        let rows = profiler.instrument(Stage::Window, rows);
+        let rows = profiler.time(sort_stage, || sort.apply(rows))?;
+        let rows = profiler.instrument(sort_stage, rows);
+        let rows = rows.map(move |aggregate_context| blend.apply(aggregate_context));
+        let rows = profiler.instrument(Stage::Project, rows);
+        let rows = profiler.instrument(Stage::Distinct, distinct.apply(rows));
         let rows = Box::new(rows);
 
-        rows.map(move |blend_context| blend.apply(blend_context))
+        profiler.instrument(Stage::Limit, limit.apply(rows))
     };
 
-    Ok(rows)
+    Ok(ResultSet { labels, rows })
 }
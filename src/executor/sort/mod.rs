@@ -0,0 +1,200 @@
+mod spill;
+
+use boolinator::Boolinator;
+use im_rc::HashSet;
+use iter_enum::Iterator;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use thiserror::Error;
+
+use sqlparser::ast::{Expr, OrderByExpr, SelectItem, Value as AstValue};
+
+use super::context::{AggregateContext, FilterContext, UnionContext};
+use super::distinct::DistinctKey;
+use super::evaluate::evaluate_union;
+use crate::data::Value;
+use crate::result::Result;
+use crate::store::Store;
+
+#[derive(Error, Serialize, Debug, PartialEq)]
+pub enum SortError {
+    #[error("ORDER BY position {0} is not in select list")]
+    OrdinalOutOfRange(String),
+}
+
+/// Row with the values of its `ORDER BY` keys.
+type Keyed<'a> = (Vec<Value>, AggregateContext<'a>);
+
+#[derive(Iterator)]
+enum Sorted<I1, I2> {
+    Applied(I1),
+    Skipped(I2),
+}
+
+pub struct Sort<'a, T: 'static + Debug + PartialEq> {
+    storage: &'a dyn Store<T>,
+    order_by: &'a [OrderByExpr],
+    fields: Vec<SelectItem>,
+    distinct_on: usize,
+    filter_context: Option<&'a FilterContext<'a>>,
+}
+
+impl<'a, T: 'static + Debug + PartialEq> Sort<'a, T> {
+    pub fn new(
+        storage: &'a dyn Store<T>,
+        order_by: &'a [OrderByExpr],
+        fields: Vec<SelectItem>,
+        distinct_on: usize,
+        filter_context: Option<&'a FilterContext<'a>>,
+    ) -> Self {
+        Self {
+            storage,
+            order_by,
+            fields,
+            distinct_on,
+            filter_context,
+        }
+    }
+
+    /// Only the first row of each value of the leading `distinct_on` keys is kept once sorted,
+    /// which is `DISTINCT ON`. Rows more than `ExecuteOptions::sort_buffer` are sorted on disk.
+    pub fn apply(
+        &self,
+        rows: impl Iterator<Item = Result<AggregateContext<'a>>>,
+    ) -> Result<impl Iterator<Item = Result<AggregateContext<'a>>>> {
+        if self.order_by.is_empty() {
+            return Ok(Sorted::Skipped(rows));
+        }
+
+        let exprs = self
+            .order_by
+            .iter()
+            .map(|OrderByExpr { expr, .. }| self.resolve(expr))
+            .collect::<Result<Vec<_>>>()?;
+        let mut rows = rows.map(|row| {
+            let row = row?;
+            let keys = self.keys(&exprs, &row)?;
+
+            Ok((keys, row))
+        });
+
+        let order_by = self.order_by;
+        let distinct_on = self.distinct_on;
+        let buffer = self
+            .filter_context
+            .and_then(FilterContext::get_options)
+            .and_then(|options| options.sort_buffer)
+            .unwrap_or(usize::MAX);
+        let rows = spill::sort(&mut rows, buffer, order_by)?
+            .scan(HashSet::new(), move |distinct_keys, row| {
+                let row = row.map(|(keys, row)| {
+                    let key = keys[..distinct_on]
+                        .iter()
+                        .map(DistinctKey::from)
+                        .collect::<Vec<_>>();
+
+                    (distinct_on == 0 || distinct_keys.insert(key).is_none()).as_some(row)
+                });
+
+                Some(row.transpose())
+            })
+            .flatten();
+
+        Ok(Sorted::Applied(rows))
+    }
+
+    fn keys(&self, exprs: &[&Expr], row: &AggregateContext<'_>) -> Result<Vec<Value>> {
+        let AggregateContext { aggregated, next } = row;
+
+        exprs
+            .iter()
+            .map(|expr| {
+                let context = UnionContext::new(self.filter_context, Some(next));
+
+                evaluate_union(self.storage, context, aggregated.as_ref(), expr)
+                    .and_then(Value::try_from)
+            })
+            .collect()
+    }
+
+    /// `ORDER BY` key which is a name of an alias in `SELECT` list is replaced by the aliased
+    /// expression. An alias wins over a table column of the same name. An integer key is the
+    /// 1-based position of the `SELECT` item, e.g. `ORDER BY 2`, where `fields` have their
+    /// wildcards expanded to columns.
+    fn resolve<'b>(&'b self, expr: &'b Expr) -> Result<&'b Expr> {
+        let ident = match expr {
+            Expr::Identifier(ident) => ident,
+            Expr::Value(AstValue::Number(number)) => {
+                return self.resolve_ordinal(number);
+            }
+            _ => {
+                return Ok(expr);
+            }
+        };
+
+        let expr = self
+            .fields
+            .iter()
+            .find_map(|item| match item {
+                SelectItem::ExprWithAlias { expr, alias } if alias == ident => Some(expr),
+                _ => None,
+            })
+            .unwrap_or(expr);
+
+        Ok(expr)
+    }
+
+    fn resolve_ordinal(&self, number: &str) -> Result<&Expr> {
+        let out_of_range = || SortError::OrdinalOutOfRange(number.to_owned());
+        let position = number
+            .parse::<usize>()
+            .ok()
+            .and_then(|position| position.checked_sub(1))
+            .ok_or_else(out_of_range)?;
+        match self.fields.get(position) {
+            Some(SelectItem::UnnamedExpr(expr)) | Some(SelectItem::ExprWithAlias { expr, .. }) => {
+                Ok(expr)
+            }
+            _ => Err(out_of_range().into()),
+        }
+    }
+}
+
+/// `NULL` is larger than any other value unless `NULLS FIRST` or `NULLS LAST` is given, so it
+/// comes last in `ASC` and first in `DESC`.
+pub fn compare(order_by: &[OrderByExpr], a: &[Value], b: &[Value]) -> Ordering {
+    order_by
+        .iter()
+        .zip(a.iter().zip(b.iter()))
+        .map(
+            |(
+                OrderByExpr {
+                    asc, nulls_first, ..
+                },
+                (a, b),
+            )| {
+                let desc = asc == &Some(false);
+                let nulls_first = nulls_first.unwrap_or(desc);
+
+                match (a.is_some(), b.is_some()) {
+                    (true, true) if desc => a.partial_cmp(b).unwrap_or(Ordering::Equal).reverse(),
+                    (true, true) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+                    (false, false) => Ordering::Equal,
+                    (false, true) if nulls_first => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (true, false) if nulls_first => Ordering::Greater,
+                    (true, false) => Ordering::Less,
+                }
+            },
+        )
+        .find(|ordering| ordering != &Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+fn sort_rows<'a>(order_by: &[OrderByExpr], mut rows: Vec<Keyed<'a>>) -> Vec<Keyed<'a>> {
+    rows.sort_by(|(a, _), (b, _)| compare(order_by, a, b));
+
+    rows
+}
@@ -0,0 +1,432 @@
+use boolinator::Boolinator;
+use im_rc::HashMap;
+use iter_enum::Iterator;
+use itertools::Itertools;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::iter::{from_fn, once, successors};
+use std::path::PathBuf;
+use std::process;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use sqlparser::ast::{Function, Ident, OrderByExpr};
+
+use super::{compare, sort_rows, Keyed};
+use crate::data::{Row, Value};
+use crate::executor::context::{AggregateContext, BlendContext};
+use crate::result::{Error, Result};
+
+static RUN_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Iterator)]
+enum Merged<I1, I2> {
+    InMemory(I1),
+    Spilled(I2),
+}
+
+/// Rows are sorted in memory if there are `buffer` of them or less. Otherwise every `buffer`
+/// rows are sorted and written to a temporary file, a run, and the runs are merged. Rows of
+/// equal keys keep their order, as they do in memory.
+pub fn sort<'a>(
+    rows: &mut dyn Iterator<Item = Result<Keyed<'a>>>,
+    buffer: usize,
+    order_by: &'a [OrderByExpr],
+) -> Result<impl Iterator<Item = Result<Keyed<'a>>> + 'a> {
+    let buffer = buffer.max(1);
+    let mut rows = rows.peekable();
+    let first = rows.by_ref().take(buffer).collect::<Result<Vec<_>>>()?;
+
+    if rows.peek().is_none() {
+        return Ok(Merged::InMemory(
+            sort_rows(order_by, first).into_iter().map(Ok),
+        ));
+    }
+
+    let mut dictionary = Dictionary::default();
+    let chunks = from_fn(|| {
+        rows.peek()
+            .is_some()
+            .as_some_from(|| rows.by_ref().take(buffer).collect::<Result<Vec<_>>>())
+    });
+    let runs = once(Ok(first))
+        .chain(chunks)
+        .map(|chunk| Run::write(&mut dictionary, sort_rows(order_by, chunk?)).map_err(spill_error))
+        .collect::<Result<Vec<_>>>()?;
+
+    let dictionary = Rc::new(dictionary);
+    let rows = runs
+        .into_iter()
+        .enumerate()
+        .map(|(i, run)| {
+            let dictionary = Rc::clone(&dictionary);
+
+            run.map(move |row| (i, read_row(row, &dictionary)))
+        })
+        .kmerge_by(move |(i, a), (j, b)| match (a, b) {
+            (Ok((a, _)), Ok((b, _))) => compare(order_by, a, b).then(i.cmp(j)).is_lt(),
+            (Err(_), _) => true,
+            (Ok(_), Err(_)) => false,
+        })
+        .map(|(_, row)| row);
+
+    Ok(Merged::Spilled(rows))
+}
+
+/// Table aliases with columns and aggregate functions of the spilled rows, which are written as
+/// their positions in here.
+#[derive(Default)]
+struct Dictionary<'a> {
+    levels: Vec<(&'a str, Rc<Vec<Ident>>)>,
+    functions: Vec<&'a Function>,
+}
+
+impl<'a> Dictionary<'a> {
+    fn level(&mut self, context: &BlendContext<'a>) -> usize {
+        let position = self.levels.iter().position(|(table_alias, columns)| {
+            *table_alias == context.table_alias && Rc::ptr_eq(columns, &context.columns)
+        });
+
+        position.unwrap_or_else(|| {
+            self.levels
+                .push((context.table_alias, Rc::clone(&context.columns)));
+
+            self.levels.len() - 1
+        })
+    }
+
+    fn function(&mut self, function: &'a Function) -> usize {
+        let position = self.functions.iter().position(|f| *f == function);
+
+        position.unwrap_or_else(|| {
+            self.functions.push(function);
+
+            self.functions.len() - 1
+        })
+    }
+}
+
+/// Sorted rows in a temporary file, which is removed once the run is dropped.
+struct Run {
+    path: PathBuf,
+    file: BufReader<File>,
+    rows: usize,
+}
+
+impl Run {
+    fn write<'a>(dictionary: &mut Dictionary<'a>, rows: Vec<Keyed<'a>>) -> io::Result<Self> {
+        let path = env::temp_dir().join(format!(
+            "gluesql-sort-{}-{}",
+            process::id(),
+            RUN_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let mut run = Self {
+            path,
+            file: BufReader::new(file),
+            rows: rows.len(),
+        };
+
+        {
+            let mut writer = BufWriter::new(run.file.get_mut());
+
+            rows.into_iter()
+                .try_for_each(|row| write_row(&mut writer, dictionary, &row))?;
+            writer.flush()?;
+        }
+
+        run.file.seek(SeekFrom::Start(0))?;
+
+        Ok(run)
+    }
+}
+
+impl Iterator for Run {
+    type Item = io::Result<Vec<u8>>;
+
+    /// Each row is read as its bytes, which are decoded by `read_row`.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rows == 0 {
+            return None;
+        }
+
+        self.rows -= 1;
+
+        let read = |file: &mut BufReader<File>| {
+            let len = read_len(file)?;
+            let mut bytes = vec![0; len];
+
+            file.read_exact(&mut bytes)?;
+
+            Ok(bytes)
+        };
+
+        Some(read(&mut self.file))
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn spill_error(error: io::Error) -> Error {
+    Error::Storage(Box::new(error))
+}
+
+fn invalid_data() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "corrupted sort run")
+}
+
+/// Row is written with its length first, as keys, then the rows of its contexts from the
+/// outermost one, then its aggregated values.
+fn write_row<'a>(
+    writer: &mut dyn Write,
+    dictionary: &mut Dictionary<'a>,
+    (keys, AggregateContext { aggregated, next }): &Keyed<'a>,
+) -> io::Result<()> {
+    let mut bytes = vec![];
+    let contexts = successors(Some(next), |context| context.next.as_ref()).collect::<Vec<_>>();
+
+    write_values(&mut bytes, keys)?;
+    write_len(&mut bytes, contexts.len())?;
+    contexts.into_iter().try_for_each(|context| {
+        write_len(&mut bytes, dictionary.level(context))?;
+
+        match &context.row {
+            Some(Row(values)) => {
+                bytes.write_all(&[1])?;
+
+                write_values(&mut bytes, values)
+            }
+            None => bytes.write_all(&[0]),
+        }
+    })?;
+
+    match aggregated {
+        Some(aggregated) => {
+            bytes.write_all(&[1])?;
+            write_len(&mut bytes, aggregated.len())?;
+
+            aggregated.iter().try_for_each(|(function, value)| {
+                write_len(&mut bytes, dictionary.function(function))?;
+
+                write_value(&mut bytes, value)
+            })?;
+        }
+        None => bytes.write_all(&[0])?,
+    }
+
+    write_len(writer, bytes.len())?;
+    writer.write_all(&bytes)
+}
+
+fn read_row<'a>(bytes: io::Result<Vec<u8>>, dictionary: &Dictionary<'a>) -> Result<Keyed<'a>> {
+    let read = |bytes: Vec<u8>| {
+        let reader = &mut bytes.as_slice();
+        let keys = read_values(reader)?;
+        let contexts = (0..read_len(reader)?)
+            .map(|_| {
+                let level = dictionary
+                    .levels
+                    .get(read_len(reader)?)
+                    .ok_or_else(invalid_data)?;
+                let row = match read_u8(reader)? {
+                    0 => None,
+                    _ => Some(Row(read_values(reader)?)),
+                };
+
+                Ok((level, row))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let next = contexts
+            .into_iter()
+            .rev()
+            .fold(None, |next, ((table_alias, columns), row)| {
+                Some(Rc::new(BlendContext {
+                    table_alias,
+                    columns: Rc::clone(columns),
+                    row,
+                    next,
+                }))
+            })
+            .ok_or_else(invalid_data)?;
+        let aggregated = match read_u8(reader)? {
+            0 => None,
+            _ => {
+                let aggregated = (0..read_len(reader)?)
+                    .map(|_| {
+                        let function = dictionary
+                            .functions
+                            .get(read_len(reader)?)
+                            .ok_or_else(invalid_data)?;
+
+                        Ok((*function, read_value(reader)?))
+                    })
+                    .collect::<io::Result<HashMap<_, _>>>()?;
+
+                Some(aggregated)
+            }
+        };
+
+        Ok((keys, AggregateContext { aggregated, next }))
+    };
+
+    bytes.and_then(read).map_err(spill_error)
+}
+
+fn write_len(writer: &mut dyn Write, len: usize) -> io::Result<()> {
+    writer.write_all(&(len as u64).to_le_bytes())
+}
+
+fn write_values(writer: &mut dyn Write, values: &[Value]) -> io::Result<()> {
+    write_len(writer, values.len())?;
+
+    values
+        .iter()
+        .try_for_each(|value| write_value(writer, value))
+}
+
+/// Each value is written as its tag and, for `Opt*` values, whether it is `NULL`, then the
+/// value itself in little endian.
+fn write_value(writer: &mut dyn Write, value: &Value) -> io::Result<()> {
+    let (tag, some) = match value {
+        Value::Bool(_) => (0, None),
+        Value::I64(_) => (1, None),
+        Value::F64(_) => (2, None),
+        Value::Str(_) => (3, None),
+        Value::Date(_) => (4, None),
+        Value::Timestamp(_) => (5, None),
+        Value::OptBool(v) => (6, Some(v.is_some())),
+        Value::OptI64(v) => (7, Some(v.is_some())),
+        Value::OptF64(v) => (8, Some(v.is_some())),
+        Value::OptStr(v) => (9, Some(v.is_some())),
+        Value::OptDate(v) => (10, Some(v.is_some())),
+        Value::OptTimestamp(v) => (11, Some(v.is_some())),
+        Value::Empty => (12, None),
+        Value::Json(_) => (13, None),
+        Value::OptJson(v) => (14, Some(v.is_some())),
+        Value::Bytes(_) => (15, None),
+        Value::OptBytes(v) => (16, Some(v.is_some())),
+    };
+
+    writer.write_all(&[tag])?;
+
+    if let Some(some) = some {
+        writer.write_all(&[some as u8])?;
+    }
+
+    match value {
+        Value::Bool(v) | Value::OptBool(Some(v)) => writer.write_all(&[*v as u8]),
+        Value::I64(v) | Value::OptI64(Some(v)) => writer.write_all(&v.to_le_bytes()),
+        Value::F64(v) | Value::OptF64(Some(v)) => writer.write_all(&v.to_bits().to_le_bytes()),
+        Value::Str(v) | Value::OptStr(Some(v)) | Value::Json(v) | Value::OptJson(Some(v)) => {
+            write_len(writer, v.len())?;
+
+            writer.write_all(v.as_bytes())
+        }
+        Value::Bytes(v) | Value::OptBytes(Some(v)) => {
+            write_len(writer, v.len())?;
+
+            writer.write_all(v)
+        }
+        Value::Date(v) | Value::OptDate(Some(v)) => writer.write_all(&v.to_le_bytes()),
+        Value::Timestamp(v) | Value::OptTimestamp(Some(v)) => writer.write_all(&v.to_le_bytes()),
+        _ => Ok(()),
+    }
+}
+
+fn read_bytes<const N: usize>(reader: &mut dyn Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+
+    reader.read_exact(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+fn read_u8(reader: &mut dyn Read) -> io::Result<u8> {
+    read_bytes::<1>(reader).map(|[byte]| byte)
+}
+
+fn read_len(reader: &mut dyn Read) -> io::Result<usize> {
+    read_bytes(reader).map(|bytes| u64::from_le_bytes(bytes) as usize)
+}
+
+fn read_option<T>(
+    reader: &mut dyn Read,
+    some: bool,
+    read: fn(&mut dyn Read) -> io::Result<T>,
+) -> io::Result<Option<T>> {
+    some.as_some_from(|| read(reader)).transpose()
+}
+
+fn read_bool(reader: &mut dyn Read) -> io::Result<bool> {
+    read_u8(reader).map(|byte| byte == 1)
+}
+
+fn read_i32(reader: &mut dyn Read) -> io::Result<i32> {
+    read_bytes(reader).map(i32::from_le_bytes)
+}
+
+fn read_i64(reader: &mut dyn Read) -> io::Result<i64> {
+    read_bytes(reader).map(i64::from_le_bytes)
+}
+
+fn read_f64(reader: &mut dyn Read) -> io::Result<f64> {
+    read_bytes(reader).map(|bytes| f64::from_bits(u64::from_le_bytes(bytes)))
+}
+
+fn read_bytes_vec(reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; read_len(reader)?];
+
+    reader.read_exact(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+fn read_str(reader: &mut dyn Read) -> io::Result<String> {
+    String::from_utf8(read_bytes_vec(reader)?).map_err(|_| invalid_data())
+}
+
+fn read_values(reader: &mut dyn Read) -> io::Result<Vec<Value>> {
+    (0..read_len(reader)?).map(|_| read_value(reader)).collect()
+}
+
+fn read_value(reader: &mut dyn Read) -> io::Result<Value> {
+    let tag = read_u8(reader)?;
+    let some = match tag {
+        6..=11 | 14 | 16 => read_u8(reader)? == 1,
+        _ => true,
+    };
+
+    let value = match tag {
+        0 => Value::Bool(read_bool(reader)?),
+        1 => Value::I64(read_i64(reader)?),
+        2 => Value::F64(read_f64(reader)?),
+        3 => Value::Str(read_str(reader)?),
+        4 => Value::Date(read_i32(reader)?),
+        5 => Value::Timestamp(read_i64(reader)?),
+        6 => Value::OptBool(read_option(reader, some, read_bool)?),
+        7 => Value::OptI64(read_option(reader, some, read_i64)?),
+        8 => Value::OptF64(read_option(reader, some, read_f64)?),
+        9 => Value::OptStr(read_option(reader, some, read_str)?),
+        10 => Value::OptDate(read_option(reader, some, read_i32)?),
+        11 => Value::OptTimestamp(read_option(reader, some, read_i64)?),
+        12 => Value::Empty,
+        13 => Value::Json(read_str(reader)?),
+        14 => Value::OptJson(read_option(reader, some, read_str)?),
+        15 => Value::Bytes(read_bytes_vec(reader)?),
+        16 => Value::OptBytes(read_option(reader, some, read_bytes_vec)?),
+        _ => {
+            return Err(invalid_data());
+        }
+    };
+
+    Ok(value)
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::ptr;
+
+use sqlparser::ast::Query;
+
+use super::aggregate::GroupKey;
+use super::context::{ColumnRef, FilterContext};
+use super::evaluate::select_scalar;
+use crate::data::Value;
+use crate::result::Result;
+use crate::store::Store;
+
+/// Results of the scalar subqueries which `WHERE` clause runs for each row, e.g.
+/// `WHERE salary > (SELECT AVG(salary) FROM Emp e WHERE e.dept = Emp.dept)`. The subquery runs
+/// with the outer row as before, and the columns of the outer row which it reads are recorded.
+/// Given the same values of those columns the subquery would read them again the same way and
+/// return the same value, so a later row with the same values takes the value from the cache.
+/// A subquery which reads no outer column runs only once. Values which cannot be hashed, e.g.
+/// `FLOAT`, are not cached.
+#[derive(Default)]
+pub struct SubqueryCache {
+    entries: RefCell<Vec<Entry>>,
+}
+
+/// Values of a subquery by the values of `columns`, the outer columns which it read.
+struct Entry {
+    query: *const Query,
+    columns: Vec<ColumnRef>,
+    values: HashMap<Vec<GroupKey>, Value>,
+}
+
+impl SubqueryCache {
+    pub fn select<T: 'static + Debug + PartialEq>(
+        &self,
+        storage: &dyn Store<T>,
+        query: &Query,
+        filter_context: Option<&FilterContext<'_>>,
+    ) -> Result<Value> {
+        let cached = self
+            .entries
+            .borrow()
+            .iter()
+            .filter(|entry| ptr::eq(entry.query, query))
+            .find_map(|entry| {
+                let key = get_key(&entry.columns, filter_context)?;
+
+                entry.values.get(&key).cloned()
+            });
+
+        if let Some(value) = cached {
+            return Ok(value);
+        }
+
+        let reads = RefCell::new(vec![]);
+        let context = FilterContext::recorded(&reads, filter_context);
+        let value = select_scalar(storage, query, Some(&context))?;
+
+        let (columns, values): (Vec<_>, Vec<_>) = reads
+            .into_inner()
+            .into_iter()
+            .fold(
+                vec![],
+                |mut reads: Vec<(ColumnRef, Value)>, (column, value)| {
+                    if reads.iter().all(|(read, _)| read != &column) {
+                        reads.push((column, value));
+                    }
+
+                    reads
+                },
+            )
+            .into_iter()
+            .unzip();
+        let key = match values.iter().map(TryInto::try_into).collect::<Result<_>>() {
+            Ok(key) => key,
+            Err(_) => {
+                return Ok(value);
+            }
+        };
+
+        let mut entries = self.entries.borrow_mut();
+        let found = entries
+            .iter_mut()
+            .find(|entry| ptr::eq(entry.query, query) && entry.columns == columns);
+
+        match found {
+            Some(entry) => {
+                entry.values.insert(key, value.clone());
+            }
+            None => entries.push(Entry {
+                query,
+                columns,
+                values: vec![(key, value.clone())].into_iter().collect(),
+            }),
+        }
+
+        Ok(value)
+    }
+}
+
+/// Values of `columns` in the outer row, `None` if any of them is not found or cannot be hashed.
+fn get_key(
+    columns: &[ColumnRef],
+    filter_context: Option<&FilterContext<'_>>,
+) -> Option<Vec<GroupKey>> {
+    columns
+        .iter()
+        .map(|(table_alias, column)| {
+            let value = match table_alias {
+                Some(table_alias) => filter_context?.get_alias_value(table_alias, column),
+                None => filter_context?.get_value(column),
+            };
+
+            value.ok()?.try_into().ok()
+        })
+        .collect()
+}
@@ -22,14 +22,14 @@ pub enum UpdateError {
     Unreachable,
 }
 
-pub struct Update<'a, T: 'static + Debug> {
+pub struct Update<'a, T: 'static + Debug + PartialEq> {
     storage: &'a dyn Store<T>,
     table_name: &'a str,
     fields: &'a [Assignment],
     columns: &'a [Ident],
 }
 
-impl<'a, T: 'static + Debug> Update<'a, T> {
+impl<'a, T: 'static + Debug + PartialEq> Update<'a, T> {
     pub fn new(
         storage: &'a dyn Store<T>,
         table_name: &'a str,
@@ -66,7 +66,7 @@ impl<'a, T: 'static + Debug> Update<'a, T> {
                     .columns
                     .iter()
                     .position(|column| column.value == id.value)
-                    .ok_or_else(|| UpdateError::Unreachable)?;
+                    .ok_or(UpdateError::Unreachable)?;
 
                 let evaluated = evaluate(self.storage, context, None, value)?;
                 let Row(values) = &row;
@@ -90,10 +90,7 @@ impl<'a, T: 'static + Debug> Update<'a, T> {
             .into_iter()
             .enumerate()
             .map(|(i, value)| {
-                let column = &self
-                    .columns
-                    .get(i)
-                    .ok_or_else(|| UpdateError::ConflictOnSchema)?;
+                let column = &self.columns.get(i).ok_or(UpdateError::ConflictOnSchema)?;
 
                 self.find(&row, column).unwrap_or(Ok(value))
             })
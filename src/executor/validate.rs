@@ -0,0 +1,222 @@
+use std::iter::once;
+
+use sqlparser::ast::{
+    Expr, Function, Ident, JoinConstraint, JoinOperator, OrderByExpr, Select, SelectItem,
+    TableWithJoins, WindowSpec,
+};
+
+use super::context::FilterContext;
+use super::execute::ExecuteError;
+use super::join::JoinClause;
+use super::select::{get_aliases, get_all_using_columns};
+use crate::data::Table;
+use crate::result::Result;
+
+/// Columns of the tables of a statement, by the alias of each table in the order of `FROM`.
+pub type Tables<'a> = [(&'a str, &'a [Ident])];
+
+/// Every column which `SELECT` statement refers to should belong to one of its tables or, if the
+/// statement is a subquery, to the tables of the outer queries, so that an unknown column fails
+/// before any row is scanned. `ON` clause of a join only sees the tables joined so far, which are
+/// every table of a nested join. Subqueries and `ON` clauses in nested joins are not looked into,
+/// they are checked when they run.
+pub fn validate_columns(
+    statement: &Select,
+    order_by: &[OrderByExpr],
+    tables: &Tables<'_>,
+    joins: &[JoinClause<'_>],
+    filter_context: Option<&FilterContext<'_>>,
+) -> Result<()> {
+    let Select {
+        projection,
+        from,
+        selection,
+        group_by,
+        having,
+        ..
+    } = statement;
+
+    let using_columns = match from.first() {
+        Some(TableWithJoins { relation, .. }) => {
+            get_all_using_columns(&Table::new(relation)?, joins)?
+        }
+        None => vec![],
+    };
+    let using_columns = using_columns
+        .into_iter()
+        .flat_map(|(_, columns)| columns)
+        .map(|ident| ident.value.as_str())
+        .collect::<Vec<_>>();
+    let validator = Validator {
+        tables,
+        using_columns: &using_columns,
+        filter_context,
+    };
+
+    let joined = joins
+        .iter()
+        .map(|(relation, _)| Ok(get_aliases(&Table::new(relation)?)?.len()))
+        .collect::<Result<Vec<_>>>()?;
+    let first = tables.len() - joined.iter().sum::<usize>();
+
+    joins
+        .iter()
+        .zip(joined.iter().scan(first, |visible, joined| {
+            *visible += joined;
+
+            Some(*visible)
+        }))
+        .filter_map(|((_, join_operator), visible)| Some((visible, get_on_clause(join_operator)?)))
+        .try_for_each(|(visible, expr)| {
+            let validator = Validator {
+                tables: &tables[..visible],
+                ..validator
+            };
+
+            validator.validate(expr)
+        })?;
+
+    let aliases = projection
+        .iter()
+        .filter_map(|item| match item {
+            SelectItem::ExprWithAlias { alias, .. } => Some(alias),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let order_by = order_by
+        .iter()
+        .map(|OrderByExpr { expr, .. }| expr)
+        .filter(|expr| match expr {
+            Expr::Identifier(ident) => !aliases.contains(&ident),
+            _ => true,
+        });
+
+    projection
+        .iter()
+        .filter_map(|item| match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => Some(expr),
+            _ => None,
+        })
+        .chain(selection)
+        .chain(group_by)
+        .chain(having)
+        .chain(order_by)
+        .try_for_each(|expr| validator.validate(expr))
+}
+
+#[derive(Clone, Copy)]
+struct Validator<'a> {
+    tables: &'a Tables<'a>,
+    using_columns: &'a [&'a str],
+    filter_context: Option<&'a FilterContext<'a>>,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(&self, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::Identifier(ident) if ident.quote_style.is_none() => {
+                self.validate_column(None, &ident.value, expr)
+            }
+            Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
+                self.validate_column(Some(&idents[0].value), &idents[1].value, expr)
+            }
+            Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::UnaryOp { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Nested(expr)
+            | Expr::InSubquery { expr, .. } => self.validate(expr),
+            Expr::InList { expr, list, .. } => self.validate_all(once(expr.as_ref()).chain(list)),
+            Expr::Between {
+                expr, low, high, ..
+            } => self.validate_all([expr, low, high].iter().map(|expr| expr.as_ref())),
+            Expr::BinaryOp { left, right, .. } => {
+                self.validate_all([left, right].iter().map(|expr| expr.as_ref()))
+            }
+            Expr::Function(Function { args, over, .. }) => {
+                let window = over.iter().flat_map(
+                    |WindowSpec {
+                         partition_by,
+                         order_by,
+                         ..
+                     }| {
+                        partition_by
+                            .iter()
+                            .chain(order_by.iter().map(|OrderByExpr { expr, .. }| expr))
+                    },
+                );
+
+                self.validate_all(args.iter().chain(window))
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => self.validate_all(
+                operand
+                    .iter()
+                    .chain(else_result)
+                    .map(AsRef::as_ref)
+                    .chain(conditions)
+                    .chain(results),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_all<'b>(&self, exprs: impl IntoIterator<Item = &'b Expr>) -> Result<()> {
+        exprs.into_iter().try_for_each(|expr| self.validate(expr))
+    }
+
+    /// An unqualified column found in more than one table is ambiguous, unless the tables are
+    /// joined by `USING` the column.
+    fn validate_column(&self, table_alias: Option<&str>, column: &str, expr: &Expr) -> Result<()> {
+        let found = self
+            .tables
+            .iter()
+            .filter(|(alias, columns)| {
+                table_alias.is_none_or(|table_alias| table_alias == *alias)
+                    && columns.iter().any(|ident| ident.value == column)
+            })
+            .count();
+
+        match found {
+            0 if self
+                .filter_context
+                .is_some_and(|context| context.contains(table_alias, column)) =>
+            {
+                Ok(())
+            }
+            0 => Err(ExecuteError::ColumnNotFound {
+                column: expr.to_string(),
+                available: self.get_available(),
+            }
+            .into()),
+            1 => Ok(()),
+            _ if table_alias.is_some() || self.using_columns.contains(&column) => Ok(()),
+            _ => Err(ExecuteError::AmbiguousColumn(column.to_owned()).into()),
+        }
+    }
+
+    fn get_available(&self) -> Vec<String> {
+        self.tables
+            .iter()
+            .flat_map(|(alias, columns)| {
+                columns
+                    .iter()
+                    .map(move |column| format!("{}.{}", alias, column.value))
+            })
+            .collect()
+    }
+}
+
+fn get_on_clause(join_operator: &JoinOperator) -> Option<&Expr> {
+    match join_operator {
+        JoinOperator::Inner(JoinConstraint::On(expr))
+        | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+        | JoinOperator::RightOuter(JoinConstraint::On(expr))
+        | JoinOperator::FullOuter(JoinConstraint::On(expr)) => Some(expr),
+        _ => None,
+    }
+}
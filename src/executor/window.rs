@@ -0,0 +1,275 @@
+use im_rc::HashMap;
+use iter_enum::Iterator;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use thiserror::Error;
+
+use sqlparser::ast::{Expr, Function, OrderByExpr, SelectItem, WindowSpec};
+
+use super::context::{AggregateContext, FilterContext, UnionContext};
+use super::distinct::DistinctKey;
+use super::evaluate::{evaluate_union, is_function};
+use super::sort::compare;
+use crate::data::{get_name, Value};
+use crate::result::Result;
+use crate::store::Store;
+
+#[derive(Error, Serialize, Debug, PartialEq)]
+pub enum WindowError {
+    #[error("unsupported window function: {0}")]
+    UnsupportedWindowFunction(String),
+
+    #[error("window function takes no arguments: {0}")]
+    ArgumentsNotAllowed(String),
+
+    #[error("window frame not supported: {0}")]
+    WindowFrameNotSupported(String),
+}
+
+#[derive(Iterator)]
+enum Windowed<I1, I2> {
+    Applied(I1),
+    Skipped(I2),
+}
+
+/// Window functions, `ROW_NUMBER`, `RANK` and `DENSE_RANK`, are numbered after aggregation and
+/// before `ORDER BY`. Rows are not collapsed nor reordered, the number of each row is put in the
+/// aggregated values of the row, so it is evaluated like an aggregate function.
+pub struct Window<'a, T: 'static + Debug + PartialEq> {
+    storage: &'a dyn Store<T>,
+    fields: &'a [SelectItem],
+    order_by: &'a [OrderByExpr],
+    filter_context: Option<&'a FilterContext<'a>>,
+}
+
+impl<'a, T: 'static + Debug + PartialEq> Window<'a, T> {
+    pub fn new(
+        storage: &'a dyn Store<T>,
+        fields: &'a [SelectItem],
+        order_by: &'a [OrderByExpr],
+        filter_context: Option<&'a FilterContext<'a>>,
+    ) -> Self {
+        Self {
+            storage,
+            fields,
+            order_by,
+            filter_context,
+        }
+    }
+
+    pub fn apply(
+        &self,
+        rows: impl Iterator<Item = Result<AggregateContext<'a>>>,
+    ) -> Result<impl Iterator<Item = Result<AggregateContext<'a>>>> {
+        let windows = self.get_windows();
+
+        if windows.is_empty() {
+            return Ok(Windowed::Skipped(rows));
+        }
+
+        let rows = rows.collect::<Result<Vec<_>>>()?;
+        let rows = windows
+            .into_iter()
+            .try_fold(rows, |rows, func| self.number(rows, func))?;
+
+        Ok(Windowed::Applied(rows.into_iter().map(Ok)))
+    }
+
+    /// Window functions in `SELECT` list and `ORDER BY`, each of the same function once.
+    pub fn get_windows(&self) -> Vec<&'a Function> {
+        let exprs = self
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    Some(expr)
+                }
+                _ => None,
+            })
+            .chain(self.order_by.iter().map(|OrderByExpr { expr, .. }| expr));
+
+        exprs
+            .flat_map(find_windows)
+            .fold(vec![], |mut windows, func| {
+                if !windows.contains(&func) {
+                    windows.push(func);
+                }
+
+                windows
+            })
+    }
+
+    fn number(
+        &self,
+        rows: Vec<AggregateContext<'a>>,
+        func: &'a Function,
+    ) -> Result<Vec<AggregateContext<'a>>> {
+        let Function {
+            name, args, over, ..
+        } = func;
+        let name = get_name(name)?.to_uppercase();
+
+        let (partition_by, order_by) = match over {
+            Some(WindowSpec {
+                window_frame: Some(_),
+                ..
+            }) => {
+                return Err(WindowError::WindowFrameNotSupported(func.to_string()).into());
+            }
+            Some(WindowSpec {
+                partition_by,
+                order_by,
+                ..
+            }) => (partition_by, order_by),
+            None => {
+                return Err(WindowError::UnsupportedWindowFunction(name).into());
+            }
+        };
+
+        if !["ROW_NUMBER", "RANK", "DENSE_RANK"].contains(&name.as_str()) {
+            return Err(WindowError::UnsupportedWindowFunction(name).into());
+        } else if !args.is_empty() {
+            return Err(WindowError::ArgumentsNotAllowed(name).into());
+        }
+
+        let keys = rows
+            .iter()
+            .map(|row| {
+                let partition = partition_by
+                    .iter()
+                    .map(|expr| {
+                        self.evaluate(row, expr)
+                            .map(|value| DistinctKey::from(&value))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let order = order_by
+                    .iter()
+                    .map(|OrderByExpr { expr, .. }| self.evaluate(row, expr))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok((partition, order))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let partitions = keys.iter().enumerate().fold(
+            HashMap::<&Vec<DistinctKey>, Vec<usize>>::new(),
+            |partitions, (i, (partition, _))| {
+                partitions.update_with(partition, vec![i], |mut rows, row| {
+                    rows.extend(row);
+                    rows
+                })
+            },
+        );
+
+        let mut numbers = partitions
+            .into_iter()
+            .flat_map(|(_, mut indexes)| {
+                indexes.sort_by(|a, b| compare(order_by, &keys[*a].1, &keys[*b].1));
+
+                number_partition(&name, order_by, &keys, indexes)
+            })
+            .collect::<Vec<_>>();
+        numbers.sort_unstable();
+
+        let rows = rows
+            .into_iter()
+            .zip(numbers)
+            .map(|(AggregateContext { aggregated, next }, (_, number))| {
+                let aggregated = aggregated
+                    .unwrap_or_default()
+                    .update(func, Value::I64(number));
+
+                AggregateContext {
+                    aggregated: Some(aggregated),
+                    next,
+                }
+            })
+            .collect();
+
+        Ok(rows)
+    }
+
+    fn evaluate(&self, row: &AggregateContext<'_>, expr: &Expr) -> Result<Value> {
+        let AggregateContext { aggregated, next } = row;
+        let context = UnionContext::new(self.filter_context, Some(next));
+
+        evaluate_union(self.storage, context, aggregated.as_ref(), expr).and_then(Value::try_from)
+    }
+}
+
+/// Rows of a partition are numbered in the sorted order, tied rows by `ORDER BY` get the same
+/// `RANK` and `DENSE_RANK`, and `RANK` skips the numbers of the ties.
+fn number_partition(
+    name: &str,
+    order_by: &[OrderByExpr],
+    keys: &[(Vec<DistinctKey>, Vec<Value>)],
+    indexes: Vec<usize>,
+) -> Vec<(usize, i64)> {
+    indexes
+        .iter()
+        .enumerate()
+        .scan(
+            None,
+            |prev: &mut Option<(usize, i64, i64)>, (position, i)| {
+                let row_number = position as i64 + 1;
+                let (rank, dense_rank) = match *prev {
+                    Some((prev, rank, dense_rank))
+                        if compare(order_by, &keys[prev].1, &keys[*i].1) == Ordering::Equal =>
+                    {
+                        (rank, dense_rank)
+                    }
+                    Some((_, _, dense_rank)) => (row_number, dense_rank + 1),
+                    None => (1, 1),
+                };
+                *prev = Some((*i, rank, dense_rank));
+
+                let number = match name {
+                    "ROW_NUMBER" => row_number,
+                    "RANK" => rank,
+                    _ => dense_rank,
+                };
+
+                Some((*i, number))
+            },
+        )
+        .collect()
+}
+
+fn find_windows(expr: &Expr) -> Vec<&Function> {
+    match expr {
+        Expr::Function(func @ Function { over: Some(_), .. }) => vec![func],
+        Expr::Function(Function { name, args, .. }) => match get_name(name) {
+            Ok(name) if is_function(name) => args.iter().flat_map(find_windows).collect(),
+            _ => vec![],
+        },
+        Expr::Between {
+            expr, low, high, ..
+        } => [expr, low, high]
+            .iter()
+            .flat_map(|expr| find_windows(expr))
+            .collect(),
+        Expr::BinaryOp { left, right, .. } => [left, right]
+            .iter()
+            .flat_map(|expr| find_windows(expr))
+            .collect(),
+        Expr::UnaryOp { expr, .. } | Expr::Nested(expr) | Expr::Cast { expr, .. } => {
+            find_windows(expr)
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .iter()
+            .chain(else_result)
+            .map(AsRef::as_ref)
+            .chain(conditions)
+            .chain(results)
+            .flat_map(find_windows)
+            .collect(),
+        _ => vec![],
+    }
+}
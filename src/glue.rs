@@ -1,5 +1,8 @@
 #[cfg(feature = "sled-storage")]
-use crate::{execute, storages::SledStorage, Payload, Query, Result};
+use crate::{
+    execute, execute_batch, explain, explain_analyze, paginate, sled::IVec, storages::SledStorage,
+    MutResult, Page, Payload, Plan, Query, Result,
+};
 
 #[cfg(feature = "sled-storage")]
 pub struct Glue {
@@ -17,7 +20,37 @@ impl Glue {
     pub fn execute(&mut self, query: &Query) -> Result<Payload> {
         let storage = self.storage.take().unwrap();
 
-        match execute(storage, query) {
+        self.restore(execute(storage, query))
+    }
+
+    /// Runs every statement of `sql`, see `execute_batch`.
+    pub fn execute_batch(&mut self, sql: &str) -> Result<Vec<Payload>> {
+        let storage = self.storage.take().unwrap();
+
+        self.restore(execute_batch(storage, sql))
+    }
+
+    pub fn explain(&self, query: &Query) -> Result<Plan> {
+        explain(self.storage.as_ref().unwrap(), query)
+    }
+
+    /// Runs the query to annotate its plan, see `explain_analyze`.
+    pub fn explain_analyze(&self, query: &Query) -> Result<Plan> {
+        explain_analyze(self.storage.as_ref().unwrap(), query)
+    }
+
+    /// Page of the rows after `after_key`, see `paginate`.
+    pub fn paginate(
+        &self,
+        query: &Query,
+        after_key: Option<IVec>,
+        page_size: usize,
+    ) -> Result<Page<IVec>> {
+        paginate(self.storage.as_ref().unwrap(), query, after_key, page_size)
+    }
+
+    fn restore<U>(&mut self, result: MutResult<SledStorage, U>) -> Result<U> {
+        match result {
             Ok((storage, payload)) => {
                 self.storage = Some(storage);
 
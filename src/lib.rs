@@ -61,6 +61,7 @@
 mod executor;
 mod glue;
 mod parse;
+mod prepared;
 mod storages;
 mod utils;
 
@@ -72,6 +73,7 @@ pub mod tests;
 pub use data::*;
 pub use executor::*;
 pub use parse::*;
+pub use prepared::{Prepared, PreparedError};
 pub use result::*;
 pub use store::*;
 
@@ -79,5 +81,5 @@ pub use store::*;
 pub use glue::Glue;
 #[cfg(feature = "sled-storage")]
 pub use sled;
-#[cfg(feature = "sled-storage")]
+#[cfg(any(feature = "memory-storage", feature = "sled-storage"))]
 pub use storages::*;
@@ -1,12 +1,540 @@
 use sqlparser::ast::Statement;
+use sqlparser::dialect::keywords::Keyword;
 use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::Parser;
 pub use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace, Word};
 
-pub struct Query(pub Statement);
+/// `EXPLAIN` or `EXPLAIN ANALYZE` written before a statement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Explain {
+    Plan,
+    Analyze,
+}
+
+/// Statement with `EXPLAIN` of it, if any, then `execute` returns its plan instead of running it,
+/// see `Payload::Explain`.
+pub struct Query(pub Statement, pub Option<Explain>);
+
+/// Names of the functions which the syntax `sqlparser` cannot parse is rewritten into, see the
+/// rewrites below. They start with `%`, which no name written in SQL can start with, so they are
+/// never taken for the functions of users. Quoted names starting with `%` are rejected.
+pub(crate) const DISTINCT_ON: &str = "%ON";
+pub(crate) const AGGREGATE_FILTER: &str = "%FILTER";
+pub(crate) const JSON_FIELD: &str = "%->";
+pub(crate) const JSON_TEXT: &str = "%->>";
+pub(crate) const ILIKE_PATTERN: &str = "%ILIKE";
 
 pub fn parse(sql: &str) -> Result<Vec<Query>, ParserError> {
     let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, sql).tokenize()?;
+
+    if let Some(word) = tokens.iter().find_map(|token| match token {
+        Token::Word(word) if word.value.starts_with('%') => Some(word),
+        _ => None,
+    }) {
+        return Err(ParserError::ParserError(format!(
+            "Names starting with % are reserved, found: {}",
+            word
+        )));
+    }
+
+    let (tokens, explains) = take_explains(tokens);
+    let mut explains = explains.into_iter();
+    let mut parser = Parser::new(wrap_ilike_patterns(wrap_quantified_subqueries(
+        move_filters(split_distinct_on(wrap_json_arrows(number_placeholders(
+            tokens,
+        )))),
+    )));
+
+    let mut queries = vec![];
+    let mut expecting_delimiter = false;
+
+    loop {
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_delimiter = false;
+        }
+
+        match parser.peek_token() {
+            Token::EOF => {
+                return Ok(queries);
+            }
+            token if expecting_delimiter => {
+                return Err(ParserError::ParserError(format!(
+                    "Expected end of statement, found: {}",
+                    token
+                )));
+            }
+            _ => {}
+        }
+
+        let statement = parser.parse_statement()?;
+
+        queries.push(Query(statement, explains.next().flatten()));
+        expecting_delimiter = true;
+    }
+}
+
+/// `sqlparser` cannot parse `EXPLAIN`, so `EXPLAIN` and `EXPLAIN ANALYZE` at the start of each
+/// statement are taken out, with which of them each statement is written with. They are put
+/// back if no statement follows, to fail parsing.
+fn take_explains(tokens: Vec<Token>) -> (Vec<Token>, Vec<Option<Explain>>) {
+    let is_word = |token: &Token, value: &str| match token {
+        Token::Word(word) => word.quote_style.is_none() && word.value.eq_ignore_ascii_case(value),
+        _ => false,
+    };
+
+    let mut taken = vec![];
+    let mut explains = vec![];
+    let mut explain: Option<(Explain, Vec<Token>)> = None;
+    let mut starting = true;
+
+    for token in tokens {
+        if !starting {
+            starting = token == Token::SemiColon;
+            taken.push(token);
+
+            continue;
+        }
+
+        match (explain.as_mut(), &token) {
+            (Some((_, explained)), Token::Whitespace(_)) => explained.push(token),
+            (None, Token::Whitespace(_)) => taken.push(token),
+            (None, _) if is_word(&token, "EXPLAIN") => explain = Some((Explain::Plan, vec![token])),
+            (Some((mode @ Explain::Plan, explained)), _) if is_word(&token, "ANALYZE") => {
+                *mode = Explain::Analyze;
+                explained.push(token);
+            }
+            (_, Token::SemiColon) => {
+                taken.extend(
+                    explain
+                        .take()
+                        .into_iter()
+                        .flat_map(|(_, explained)| explained),
+                );
+                taken.push(token);
+            }
+            _ => {
+                explains.push(explain.take().map(|(mode, _)| mode));
+                starting = false;
+                taken.push(token);
+            }
+        }
+    }
+
+    taken.extend(explain.into_iter().flat_map(|(_, explained)| explained));
+
+    (taken, explains)
+}
+
+enum DistinctOn {
+    None,
+    Distinct,
+    On,
+    Nested(usize),
+}
+
+/// `sqlparser` cannot parse `DISTINCT ON (...)`, so a comma is put after its closing parenthesis
+/// and the list is parsed as a function `DISTINCT_ON` at the first item of `SELECT`.
+fn split_distinct_on(tokens: Vec<Token>) -> Vec<Token> {
+    let (tokens, _) = tokens
+        .into_iter()
+        .fold((vec![], DistinctOn::None), push_token);
+
+    tokens
+}
+
+fn push_token(
+    (mut tokens, state): (Vec<Token>, DistinctOn),
+    token: Token,
+) -> (Vec<Token>, DistinctOn) {
+    let keyword = match &token {
+        Token::Word(word) if word.quote_style.is_none() => Some(word.keyword),
+        _ => None,
+    };
+    let state = match (state, keyword, &token) {
+        (state, _, Token::Whitespace(_)) => state,
+        (_, Some(Keyword::DISTINCT), _) => DistinctOn::Distinct,
+        (DistinctOn::Distinct, Some(Keyword::ON), _) => {
+            tokens.push(Token::Word(Word {
+                value: DISTINCT_ON.to_owned(),
+                quote_style: None,
+                keyword: Keyword::NoKeyword,
+            }));
+
+            return (tokens, DistinctOn::On);
+        }
+        (DistinctOn::On, _, Token::LParen) => DistinctOn::Nested(1),
+        (DistinctOn::Nested(depth), _, Token::LParen) => DistinctOn::Nested(depth + 1),
+        (DistinctOn::Nested(1), _, Token::RParen) => {
+            tokens.push(token);
+            tokens.push(Token::Comma);
+
+            return (tokens, DistinctOn::None);
+        }
+        (DistinctOn::Nested(depth), _, Token::RParen) => DistinctOn::Nested(depth - 1),
+        (DistinctOn::Nested(depth), _, _) => DistinctOn::Nested(depth),
+        _ => DistinctOn::None,
+    };
+
+    tokens.push(token);
+
+    (tokens, state)
+}
+
+enum Filter {
+    None,
+    Keyword(usize),
+    Open(usize),
+    Nested(usize),
+}
+
+/// `sqlparser` cannot parse `FILTER (WHERE ...)` of aggregates either, so the condition is moved
+/// into the parentheses of the aggregate as its last argument, a function `AGGREGATE_FILTER`.
+fn move_filters(tokens: Vec<Token>) -> Vec<Token> {
+    let (tokens, _) = tokens
+        .into_iter()
+        .fold((vec![], Filter::None), push_filter_token);
+
+    tokens
+}
+
+fn push_filter_token(
+    (mut tokens, state): (Vec<Token>, Filter),
+    token: Token,
+) -> (Vec<Token>, Filter) {
+    let keyword = match &token {
+        Token::Word(word) if word.quote_style.is_none() => Some(word.keyword),
+        _ => None,
+    };
+    let state = match (state, keyword, &token) {
+        (state, _, Token::Whitespace(_)) => state,
+        (Filter::None, Some(Keyword::FILTER), _) => {
+            let last = tokens
+                .iter()
+                .rposition(|token| !matches!(token, Token::Whitespace(_)));
+
+            match last {
+                Some(index) if tokens[index] == Token::RParen => Filter::Keyword(index),
+                _ => Filter::None,
+            }
+        }
+        (Filter::Keyword(index), _, Token::LParen) => Filter::Open(index),
+        (Filter::Open(index), Some(Keyword::WHERE), _) => {
+            let filter = Token::Word(Word {
+                value: AGGREGATE_FILTER.to_owned(),
+                quote_style: None,
+                keyword: Keyword::NoKeyword,
+            });
+
+            tokens.truncate(index);
+            tokens.extend(vec![Token::Comma, filter, Token::LParen]);
+
+            return (tokens, Filter::Nested(1));
+        }
+        (Filter::Nested(depth), _, Token::LParen) => Filter::Nested(depth + 1),
+        (Filter::Nested(1), _, Token::RParen) => {
+            tokens.push(token);
+            tokens.push(Token::RParen);
+
+            return (tokens, Filter::None);
+        }
+        (Filter::Nested(depth), _, Token::RParen) => Filter::Nested(depth - 1),
+        (Filter::Nested(depth), _, _) => Filter::Nested(depth),
+        _ => Filter::None,
+    };
+
+    tokens.push(token);
+
+    (tokens, state)
+}
+
+enum Quantifier {
+    None,
+    Compared,
+    Keyword,
+}
+
+/// Comparisons with `ANY`, `SOME` or `ALL` subqueries cannot be parsed by `sqlparser` either, so
+/// the subquery is wrapped into one more pair of parentheses, to be parsed as the argument of a
+/// function `ANY`, `SOME` or `ALL`. The depths of the parentheses to be closed twice are kept,
+/// as the subqueries may be nested.
+fn wrap_quantified_subqueries(tokens: Vec<Token>) -> Vec<Token> {
+    let (tokens, ..) = tokens.into_iter().fold(
+        (vec![], Quantifier::None, 0, vec![]),
+        |(mut tokens, state, depth, mut closes): (Vec<Token>, _, usize, Vec<usize>), token| {
+            let keyword = match &token {
+                Token::Word(word) if word.quote_style.is_none() => Some(word.keyword),
+                _ => None,
+            };
+            let (state, depth) = match (state, keyword, &token) {
+                (state, _, Token::Whitespace(_)) => (state, depth),
+                (_, _, Token::Eq)
+                | (_, _, Token::Neq)
+                | (_, _, Token::Lt)
+                | (_, _, Token::LtEq)
+                | (_, _, Token::Gt)
+                | (_, _, Token::GtEq) => (Quantifier::Compared, depth),
+                (Quantifier::Compared, Some(Keyword::ANY), _)
+                | (Quantifier::Compared, Some(Keyword::SOME), _)
+                | (Quantifier::Compared, Some(Keyword::ALL), _) => (Quantifier::Keyword, depth),
+                (Quantifier::Keyword, _, Token::LParen) => {
+                    tokens.push(Token::LParen);
+                    closes.push(depth + 1);
+
+                    (Quantifier::None, depth + 1)
+                }
+                (_, _, Token::LParen) => (Quantifier::None, depth + 1),
+                (_, _, Token::RParen) => {
+                    if closes.last() == Some(&depth) {
+                        closes.pop();
+                        tokens.push(Token::RParen);
+                    }
+
+                    (Quantifier::None, depth.saturating_sub(1))
+                }
+                _ => (Quantifier::None, depth),
+            };
+
+            tokens.push(token);
+
+            (tokens, state, depth, closes)
+        },
+    );
+
+    tokens
+}
+
+/// `->` and `->>` are not operators of `sqlparser`, they are tokenized as `-` followed by `>`.
+/// `a->'b'` is rewritten into `%->(a, 'b')`, which is parsed as a function `JSON_FIELD`, and a
+/// chain `a->'b'->>'c'` into `%->>(%->(a, 'b'), 'c')`, `->>` being `JSON_TEXT`. The left operand is the identifier, literal or
+/// parenthesized expression right before the arrow, with the name of a function it is the
+/// arguments of, and the right operand is the literal or parenthesized expression after it, a
+/// negative index is taken as a single number.
+fn wrap_json_arrows(tokens: Vec<Token>) -> Vec<Token> {
+    let mut wrapped = vec![];
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        if token != Token::Minus || tokens.next_if_eq(&Token::Gt).is_none() {
+            wrapped.push(token);
+
+            continue;
+        }
+
+        let name = match tokens.next_if_eq(&Token::Gt) {
+            Some(_) => JSON_TEXT,
+            None => JSON_FIELD,
+        };
+        let left = wrapped.split_off(find_left_operand(&wrapped));
+
+        while tokens
+            .next_if(|token| matches!(token, Token::Whitespace(_)))
+            .is_some()
+        {}
+
+        let right = match tokens.next() {
+            Some(Token::LParen) => wrap_json_arrows(take_parenthesized(&mut tokens)),
+            Some(Token::Minus) => match tokens.next() {
+                Some(Token::Number(n)) => vec![Token::Number(format!("-{}", n))],
+                token => std::iter::once(Token::Minus).chain(token).collect(),
+            },
+            token => token.into_iter().collect(),
+        };
+
+        wrapped.push(Token::Word(Word {
+            value: name.to_owned(),
+            quote_style: None,
+            keyword: Keyword::NoKeyword,
+        }));
+        wrapped.push(Token::LParen);
+        wrapped.extend(left);
+        wrapped.push(Token::Comma);
+        wrapped.extend(right);
+        wrapped.push(Token::RParen);
+    }
+
+    wrapped
+}
+
+/// Tokens of a parenthesized expression whose `(` is just taken from `tokens`, up to its closing
+/// `)` with both parentheses.
+fn take_parenthesized(tokens: &mut impl Iterator<Item = Token>) -> Vec<Token> {
+    let mut depth = 1;
+
+    std::iter::once(Token::LParen)
+        .chain(tokens.take_while(|token| {
+            depth += match token {
+                Token::LParen => 1,
+                Token::RParen => -1,
+                _ => 0,
+            };
+
+            depth > 0
+        }))
+        .chain(std::iter::once(Token::RParen))
+        .collect()
+}
+
+/// `ILIKE` is not a keyword of `sqlparser` either, so `a ILIKE 'b'` is rewritten into
+/// `a LIKE %ILIKE('b')`, which is parsed as `LIKE` whose pattern is a function `ILIKE_PATTERN`, and
+/// `NOT ILIKE` into `NOT LIKE` likewise. The pattern is the operand which `sqlparser` would
+/// take after `LIKE`, up to an operator which binds as loosely as `LIKE`, so `a ILIKE 'b' || c`
+/// wraps `'b' || c`. It runs after the other rewrites, so that the operand can be parsed, and
+/// the tokens after `ILIKE` are rewritten first, as the pattern may have `ILIKE` in it.
+fn wrap_ilike_patterns(mut tokens: Vec<Token>) -> Vec<Token> {
+    let is_ilike = |token: &Token| match token {
+        Token::Word(word) => word.quote_style.is_none() && word.value.eq_ignore_ascii_case("ILIKE"),
+        _ => false,
+    };
+    let position = match tokens.iter().position(is_ilike) {
+        Some(position) => position,
+        None => {
+            return tokens;
+        }
+    };
+    let mut rest = wrap_ilike_patterns(tokens.split_off(position + 1));
+    let end = match find_like_pattern(&rest) {
+        Some(end) => end,
+        None => {
+            tokens.extend(rest);
+
+            return tokens;
+        }
+    };
+    let tail = rest.split_off(end);
+
+    tokens.pop();
+    tokens.push(Token::Word(Word {
+        value: "LIKE".to_owned(),
+        quote_style: None,
+        keyword: Keyword::LIKE,
+    }));
+    tokens.push(Token::Whitespace(Whitespace::Space));
+    tokens.push(Token::Word(Word {
+        value: ILIKE_PATTERN.to_owned(),
+        quote_style: None,
+        keyword: Keyword::NoKeyword,
+    }));
+    tokens.push(Token::LParen);
+    tokens.extend(rest);
+    tokens.push(Token::RParen);
+    tokens.extend(tail);
+
+    tokens
+}
+
+/// Position where the pattern of `LIKE` at the start of `tokens` ends, `None` if it cannot be
+/// parsed. The pattern is parsed by `sqlparser` at the precedence of `LIKE`, and the position is
+/// found by the tokens it leaves.
+fn find_like_pattern(tokens: &[Token]) -> Option<usize> {
+    const LIKE_PRECEDENCE: u8 = 20;
+
+    let mut parser = Parser::new(tokens.to_vec());
+    parser.parse_subexpr(LIKE_PRECEDENCE).ok()?;
+
+    let mut left = 0;
+
+    while parser.next_token() != Token::EOF {
+        left += 1;
+    }
+
+    let mut end = tokens.len();
+
+    while left > 0 {
+        end -= 1;
+
+        if !matches!(tokens[end], Token::Whitespace(_)) {
+            left -= 1;
+        }
+    }
+
+    Some(end)
+}
+
+/// Position where the left operand of an arrow at the end of `tokens` starts.
+fn find_left_operand(tokens: &[Token]) -> usize {
+    let is_whitespace = |token: &Token| matches!(token, Token::Whitespace(_));
+    let end = tokens
+        .iter()
+        .rposition(|token| !is_whitespace(token))
+        .unwrap_or(0);
+
+    match tokens.get(end) {
+        Some(Token::RParen) => {
+            let mut depth = 0;
+            let start = (0..=end)
+                .rev()
+                .find(|i| {
+                    depth += match tokens[*i] {
+                        Token::RParen => 1,
+                        Token::LParen => -1,
+                        _ => 0,
+                    };
+
+                    depth == 0
+                })
+                .unwrap_or(0);
+
+            match start.checked_sub(1).map(|i| &tokens[i]) {
+                Some(Token::Word(_)) => start - 1,
+                _ => start,
+            }
+        }
+        Some(Token::Word(_)) => {
+            let mut start = end;
+
+            while start >= 2
+                && tokens[start - 1] == Token::Period
+                && matches!(tokens[start - 2], Token::Word(_))
+            {
+                start -= 2;
+            }
+
+            start
+        }
+        _ => end,
+    }
+}
+
+/// Placeholders of prepared statements, `?` and `$1`, are not known to `sqlparser`, so each of
+/// them is replaced by an identifier `$n`, which cannot be written as a column name. `?` is
+/// numbered by its position among the other `?`s.
+fn number_placeholders(tokens: Vec<Token>) -> Vec<Token> {
+    let (tokens, _) = tokens
+        .into_iter()
+        .fold((vec![], 0), |(mut tokens, count), token| {
+            let count = match (tokens.last(), token) {
+                (_, Token::Char('?')) => {
+                    tokens.push(placeholder(count + 1));
+
+                    count + 1
+                }
+                (Some(Token::Char('$')), Token::Number(n))
+                    if n.bytes().all(|b| b.is_ascii_digit()) =>
+                {
+                    tokens.pop();
+                    tokens.push(placeholder(n));
+
+                    count
+                }
+                (_, token) => {
+                    tokens.push(token);
+
+                    count
+                }
+            };
+
+            (tokens, count)
+        });
+
+    tokens
+}
 
-    Parser::parse_sql(&dialect, sql).map(|parsed| parsed.into_iter().map(Query).collect())
+fn placeholder(index: impl ToString) -> Token {
+    Token::Word(Word {
+        value: format!("${}", index.to_string()),
+        quote_style: None,
+        keyword: Keyword::NoKeyword,
+    })
 }
@@ -0,0 +1,302 @@
+use serde::Serialize;
+use thiserror::Error;
+
+use sqlparser::ast::{
+    Assignment, Cte, DataType, Expr, Function, Ident, Join, JoinConstraint, JoinOperator,
+    OrderByExpr, Query as AstQuery, Select, SelectItem, SetExpr, Statement, TableFactor,
+    TableWithJoins, Value as AstValue, Values, WindowSpec,
+};
+
+use crate::data::{format_date, format_hex, format_timestamp, Value};
+use crate::parse::{parse, Explain, Query};
+use crate::result::Result;
+
+#[derive(Error, Serialize, Debug, PartialEq)]
+pub enum PreparedError {
+    #[error("prepared statement should be a single statement, found {0}")]
+    NotSingleStatement(usize),
+
+    #[error("number of parameters not matching, expected: {expected}, found: {found}")]
+    ParamCountMismatch { expected: usize, found: usize },
+}
+
+/// Statement parsed once, to be executed many times with different parameters. Parameters are
+/// written as `?`, numbered by their positions, or as `$1`, `$2`, ... which can be used more
+/// than once.
+pub struct Prepared {
+    statement: Statement,
+    explain: Option<Explain>,
+    params: usize,
+}
+
+impl Prepared {
+    pub fn new(sql: &str) -> Result<Self> {
+        let queries = parse(sql)?;
+
+        if queries.len() != 1 {
+            return Err(PreparedError::NotSingleStatement(queries.len()).into());
+        }
+
+        let Query(mut statement, explain) = queries.into_iter().next().unwrap();
+        let mut params = 0;
+
+        statement_exprs(&mut statement, &mut |expr| {
+            params = params.max(get_index(expr).unwrap_or(0));
+        });
+
+        Ok(Self {
+            statement,
+            explain,
+            params,
+        })
+    }
+
+    /// Number of the parameters which `bind` takes.
+    pub fn params(&self) -> usize {
+        self.params
+    }
+
+    /// Statement of which every placeholder is replaced by the literal of its parameter, ready
+    /// to be executed by `execute`.
+    pub fn bind(&self, params: &[Value]) -> Result<Query> {
+        if params.len() != self.params {
+            return Err(PreparedError::ParamCountMismatch {
+                expected: self.params,
+                found: params.len(),
+            }
+            .into());
+        }
+
+        let mut statement = self.statement.clone();
+
+        statement_exprs(&mut statement, &mut |expr| {
+            if let Some(index) = get_index(expr) {
+                *expr = to_expr(&params[index - 1]);
+            }
+        });
+
+        Ok(Query(statement, self.explain))
+    }
+}
+
+fn get_index(expr: &Expr) -> Option<usize> {
+    match expr {
+        Expr::Identifier(ident) if ident.quote_style.is_none() => ident
+            .value
+            .strip_prefix('$')?
+            .parse()
+            .ok()
+            .filter(|index| *index > 0),
+        _ => None,
+    }
+}
+
+/// Strings are double-quoted, which is taken as a string by `INSERT` too.
+fn to_expr(value: &Value) -> Expr {
+    let typed = |data_type, value| Expr::TypedString { data_type, value };
+
+    match value {
+        Value::Bool(v) | Value::OptBool(Some(v)) => Expr::Value(AstValue::Boolean(*v)),
+        Value::I64(v) | Value::OptI64(Some(v)) => Expr::Value(AstValue::Number(v.to_string())),
+        Value::F64(v) | Value::OptF64(Some(v)) => Expr::Value(AstValue::Number(format!("{:?}", v))),
+        Value::Str(v) | Value::OptStr(Some(v)) => Expr::Identifier(Ident::with_quote('"', v)),
+        Value::Date(v) | Value::OptDate(Some(v)) => typed(DataType::Date, format_date(*v)),
+        Value::Timestamp(v) | Value::OptTimestamp(Some(v)) => {
+            typed(DataType::Timestamp, format_timestamp(*v))
+        }
+        Value::Json(v) | Value::OptJson(Some(v)) => {
+            Expr::Value(AstValue::SingleQuotedString(v.clone()))
+        }
+        Value::Bytes(v) | Value::OptBytes(Some(v)) => {
+            Expr::Value(AstValue::HexStringLiteral(format_hex(v)))
+        }
+        Value::OptBool(None)
+        | Value::OptI64(None)
+        | Value::OptF64(None)
+        | Value::OptStr(None)
+        | Value::OptDate(None)
+        | Value::OptTimestamp(None)
+        | Value::OptJson(None)
+        | Value::OptBytes(None)
+        | Value::Empty => Expr::Value(AstValue::Null),
+    }
+}
+
+type Visit<'a> = dyn FnMut(&mut Expr) + 'a;
+
+/// `f` is called on every expression of the statements which can be executed, including those
+/// in subqueries, and then on the expressions inside of what `f` leaves.
+fn statement_exprs(statement: &mut Statement, f: &mut Visit<'_>) {
+    match statement {
+        Statement::Query(query) => query_exprs(query, f),
+        Statement::Insert { source, .. } => query_exprs(source, f),
+        Statement::Update {
+            assignments,
+            selection,
+            ..
+        } => {
+            assignments
+                .iter_mut()
+                .for_each(|Assignment { value, .. }| expr_exprs(value, f));
+
+            selection.iter_mut().for_each(|expr| expr_exprs(expr, f))
+        }
+        Statement::Delete { selection, .. } => {
+            selection.iter_mut().for_each(|expr| expr_exprs(expr, f))
+        }
+        _ => {}
+    }
+}
+
+fn query_exprs(query: &mut AstQuery, f: &mut Visit<'_>) {
+    let AstQuery {
+        ctes,
+        body,
+        order_by,
+        ..
+    } = query;
+
+    ctes.iter_mut()
+        .for_each(|Cte { query, .. }| query_exprs(query, f));
+    set_expr_exprs(body, f);
+    order_by
+        .iter_mut()
+        .for_each(|OrderByExpr { expr, .. }| expr_exprs(expr, f));
+}
+
+fn set_expr_exprs(set_expr: &mut SetExpr, f: &mut Visit<'_>) {
+    match set_expr {
+        SetExpr::Select(statement) => select_exprs(statement, f),
+        SetExpr::Query(query) => query_exprs(query, f),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_exprs(left, f);
+            set_expr_exprs(right, f)
+        }
+        SetExpr::Values(Values(rows)) => rows
+            .iter_mut()
+            .flatten()
+            .for_each(|expr| expr_exprs(expr, f)),
+    }
+}
+
+fn select_exprs(statement: &mut Select, f: &mut Visit<'_>) {
+    let Select {
+        projection,
+        from,
+        selection,
+        group_by,
+        having,
+        ..
+    } = statement;
+
+    projection.iter_mut().for_each(|item| match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+            expr_exprs(expr, f)
+        }
+        _ => {}
+    });
+    from.iter_mut()
+        .for_each(|table| table_with_joins_exprs(table, f));
+
+    selection
+        .iter_mut()
+        .chain(group_by.iter_mut())
+        .chain(having.iter_mut())
+        .for_each(|expr| expr_exprs(expr, f))
+}
+
+fn table_with_joins_exprs(table: &mut TableWithJoins, f: &mut Visit<'_>) {
+    let TableWithJoins { relation, joins } = table;
+
+    table_exprs(relation, f);
+
+    joins.iter_mut().for_each(
+        |Join {
+             relation,
+             join_operator,
+         }| {
+            table_exprs(relation, f);
+
+            match join_operator {
+                JoinOperator::Inner(JoinConstraint::On(expr))
+                | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+                | JoinOperator::RightOuter(JoinConstraint::On(expr))
+                | JoinOperator::FullOuter(JoinConstraint::On(expr)) => expr_exprs(expr, f),
+                _ => {}
+            }
+        },
+    )
+}
+
+fn table_exprs(relation: &mut TableFactor, f: &mut Visit<'_>) {
+    match relation {
+        TableFactor::Derived { subquery, .. } => query_exprs(subquery, f),
+        TableFactor::NestedJoin(table) => table_with_joins_exprs(table, f),
+        TableFactor::Table { .. } => {}
+    }
+}
+
+fn expr_exprs(expr: &mut Expr, f: &mut Visit<'_>) {
+    f(expr);
+
+    match expr {
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::Collate { expr, .. }
+        | Expr::Nested(expr) => expr_exprs(expr, f),
+        Expr::InList { expr, list, .. } => {
+            expr_exprs(expr, f);
+
+            list.iter_mut().for_each(|expr| expr_exprs(expr, f))
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            expr_exprs(expr, f);
+
+            query_exprs(subquery, f)
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            expr_exprs(expr, f);
+            expr_exprs(low, f);
+            expr_exprs(high, f)
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            expr_exprs(left, f);
+            expr_exprs(right, f)
+        }
+        Expr::Function(Function { args, over, .. }) => {
+            args.iter_mut().for_each(|expr| expr_exprs(expr, f));
+
+            over.iter_mut().for_each(
+                |WindowSpec {
+                     partition_by,
+                     order_by,
+                     ..
+                 }| {
+                    partition_by
+                        .iter_mut()
+                        .chain(order_by.iter_mut().map(|OrderByExpr { expr, .. }| expr))
+                        .for_each(|expr| expr_exprs(expr, f))
+                },
+            )
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => operand
+            .iter_mut()
+            .chain(else_result.iter_mut())
+            .map(AsMut::as_mut)
+            .chain(conditions.iter_mut())
+            .chain(results.iter_mut())
+            .for_each(|expr| expr_exprs(expr, f)),
+        Expr::Exists(query) | Expr::Subquery(query) => query_exprs(query, f),
+        _ => {}
+    }
+}
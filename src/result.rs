@@ -4,8 +4,11 @@ use thiserror::Error as ThisError;
 use crate::data::{RowError, TableError, ValueError};
 use crate::executor::{
     AggregateError, BlendContextError, BlendError, EvaluateError, ExecuteError, FilterContextError,
-    FilterError, JoinError, LimitError, SelectError, UnionContextError, UpdateError,
+    FilterError, JoinError, LimitError, PaginateError, SelectError, SortError, UnionContextError,
+    UpdateError, WindowError,
 };
+use crate::parse::ParserError;
+use crate::prepared::PreparedError;
 use crate::store::StoreError;
 
 #[derive(ThisError, Serialize, Debug)]
@@ -15,6 +18,9 @@ pub enum Error {
     #[error(transparent)]
     #[serde(with = "stringify")]
     Storage(#[from] Box<dyn std::error::Error>),
+    #[error(transparent)]
+    #[serde(with = "stringify")]
+    Parser(#[from] ParserError),
 
     #[error(transparent)]
     Execute(#[from] ExecuteError),
@@ -41,6 +47,14 @@ pub enum Error {
     #[error(transparent)]
     Limit(#[from] LimitError),
     #[error(transparent)]
+    Paginate(#[from] PaginateError),
+    #[error(transparent)]
+    Sort(#[from] SortError),
+    #[error(transparent)]
+    Window(#[from] WindowError),
+    #[error(transparent)]
+    Prepared(#[from] PreparedError),
+    #[error(transparent)]
     Row(#[from] RowError),
     #[error(transparent)]
     Table(#[from] TableError),
@@ -57,6 +71,7 @@ impl PartialEq for Error {
 
         match (self, other) {
             (Store(e), Store(e2)) => e == e2,
+            (Parser(e), Parser(e2)) => e == e2,
             (Execute(e), Execute(e2)) => e == e2,
             (Evaluate(e), Evaluate(e2)) => e == e2,
             (Select(e), Select(e2)) => e == e2,
@@ -69,6 +84,10 @@ impl PartialEq for Error {
             (FilterContext(e), FilterContext(e2)) => e == e2,
             (UnionContext(e), UnionContext(e2)) => e == e2,
             (Limit(e), Limit(e2)) => e == e2,
+            (Paginate(e), Paginate(e2)) => e == e2,
+            (Sort(e), Sort(e2)) => e == e2,
+            (Window(e), Window(e2)) => e == e2,
+            (Prepared(e), Prepared(e2)) => e == e2,
             (Row(e), Row(e2)) => e == e2,
             (Table(e), Table(e2)) => e == e2,
             (Value(e), Value(e2)) => e == e2,
@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Bound;
+
+use sqlparser::ast::DataType;
+
+use crate::{
+    MutResult, Result, Row, RowIter, Schema, Store, StoreError, StoreMut, TableSchema, Value,
+};
+
+/// Key of a row, ids are generated in ascending order so rows are scanned in the order they
+/// were inserted.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DataKey {
+    pub table_name: String,
+    pub id: u64,
+}
+
+#[derive(Debug)]
+struct Item {
+    schema: Schema,
+    rows: BTreeMap<u64, Row>,
+    index: Option<Index>,
+}
+
+/// Index of the primary key of a table, which is a single `INTEGER` or `TEXT` column, it has the
+/// value of the column and the id of every row. `ordered` is whether the rows, which are scanned
+/// in the order of ids, are also in the order of the column.
+#[derive(Debug)]
+struct Index {
+    column: usize,
+    keys: BTreeSet<(IndexKey, u64)>,
+    ordered: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum IndexKey {
+    Null,
+    I64(i64),
+    Str(String),
+}
+
+impl Index {
+    fn new(schema: &Schema, rows: &BTreeMap<u64, Row>) -> Option<Self> {
+        let primary_key = match TableSchema::from(schema).primary_key.as_slice() {
+            [column] => column.to_owned(),
+            _ => {
+                return None;
+            }
+        };
+        let column = schema
+            .column_defs
+            .iter()
+            .position(|column_def| column_def.name.value == primary_key)?;
+
+        if !matches!(
+            schema.column_defs[column].data_type,
+            DataType::Int | DataType::Text
+        ) {
+            return None;
+        }
+
+        let mut index = Self {
+            column,
+            keys: BTreeSet::new(),
+            ordered: true,
+        };
+
+        rows.iter().for_each(|(id, row)| index.insert(*id, row));
+
+        Some(index)
+    }
+
+    fn get_key(&self, row: &Row) -> IndexKey {
+        match row.get_value(self.column) {
+            Some(Value::I64(v)) | Some(Value::OptI64(Some(v))) => IndexKey::I64(*v),
+            Some(Value::Str(v)) | Some(Value::OptStr(Some(v))) => IndexKey::Str(v.clone()),
+            _ => IndexKey::Null,
+        }
+    }
+
+    /// Ids only grow, so the rows stay in order while each new row has the largest value.
+    fn insert(&mut self, id: u64, row: &Row) {
+        let key = self.get_key(row);
+
+        self.ordered = match self.keys.iter().next_back() {
+            Some((last, _)) => self.ordered && last <= &key,
+            None => true,
+        };
+        self.keys.insert((key, id));
+    }
+
+    /// The rows are no longer known to be in order once the value of a row changes.
+    fn update(&mut self, id: u64, replaced: &Row, row: &Row) {
+        let key = self.get_key(row);
+
+        if self.get_key(replaced) == key {
+            return;
+        }
+
+        self.remove(id, replaced);
+        self.keys.insert((key, id));
+        self.ordered = false;
+    }
+
+    fn remove(&mut self, id: u64, row: &Row) {
+        self.keys.remove(&(self.get_key(row), id));
+    }
+
+    /// Ids of the rows of which the column equals `value`, `None` if the value is not of the
+    /// type the column is compared to, e.g. a float which is not an integer.
+    fn get_ids(&self, value: &Value) -> Option<Vec<u64>> {
+        let key = match value {
+            Value::I64(v) | Value::OptI64(Some(v)) => IndexKey::I64(*v),
+            Value::F64(v) | Value::OptF64(Some(v)) if v.fract() == 0.0 => IndexKey::I64(*v as i64),
+            Value::Str(v) | Value::OptStr(Some(v)) => IndexKey::Str(v.clone()),
+            _ => {
+                return None;
+            }
+        };
+        let ids = self
+            .keys
+            .range((key.clone(), 0)..=(key, u64::MAX))
+            .map(|(_, id)| *id)
+            .collect();
+
+        Some(ids)
+    }
+}
+
+/// Storage which keeps every table in memory, nothing is persisted.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    id_counter: u64,
+    items: HashMap<String, Item>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn scan(
+        &self,
+        table_name: &str,
+        limit: Option<usize>,
+        copy: impl Fn(&Row) -> Row,
+    ) -> RowIter<DataKey> {
+        let rows = match self.items.get(table_name) {
+            Some(item) => item
+                .rows
+                .iter()
+                .take(limit.unwrap_or(usize::MAX))
+                .map(|(id, row)| {
+                    let key = DataKey {
+                        table_name: table_name.to_owned(),
+                        id: *id,
+                    };
+
+                    Ok((key, copy(row)))
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        Box::new(rows.into_iter())
+    }
+}
+
+impl StoreMut<DataKey> for MemoryStorage {
+    fn generate_id(self, table_name: &str) -> MutResult<Self, DataKey> {
+        let id = self.id_counter + 1;
+        let key = DataKey {
+            table_name: table_name.to_owned(),
+            id,
+        };
+
+        Ok((
+            Self {
+                id_counter: id,
+                ..self
+            },
+            key,
+        ))
+    }
+
+    fn insert_schema(mut self, schema: &Schema) -> MutResult<Self, ()> {
+        let table_name = schema.table_name.clone();
+        let rows = match self.items.remove(&table_name) {
+            Some(Item { rows, .. }) => rows,
+            None => BTreeMap::new(),
+        };
+        let item = Item {
+            schema: schema.clone(),
+            index: Index::new(schema, &rows),
+            rows,
+        };
+
+        self.items.insert(table_name, item);
+
+        Ok((self, ()))
+    }
+
+    fn delete_schema(mut self, table_name: &str) -> MutResult<Self, ()> {
+        self.items.remove(table_name);
+
+        Ok((self, ()))
+    }
+
+    fn insert_data(mut self, key: &DataKey, row: Row) -> MutResult<Self, Row> {
+        match self.items.get_mut(&key.table_name) {
+            Some(item) => {
+                let replaced = item.rows.insert(key.id, row.clone());
+
+                if let Some(index) = &mut item.index {
+                    match replaced {
+                        Some(replaced) => index.update(key.id, &replaced, &row),
+                        None => index.insert(key.id, &row),
+                    }
+                }
+            }
+            None => {
+                return Err((self, StoreError::SchemaNotFound.into()));
+            }
+        }
+
+        Ok((self, row))
+    }
+
+    fn delete_data(mut self, key: &DataKey) -> MutResult<Self, ()> {
+        if let Some(item) = self.items.get_mut(&key.table_name) {
+            let removed = item.rows.remove(&key.id);
+
+            if let (Some(index), Some(removed)) = (&mut item.index, removed) {
+                index.remove(key.id, &removed);
+            }
+        }
+
+        Ok((self, ()))
+    }
+}
+
+impl Store<DataKey> for MemoryStorage {
+    fn fetch_schema(&self, table_name: &str) -> Result<Schema> {
+        self.items
+            .get(table_name)
+            .map(|item| item.schema.clone())
+            .ok_or_else(|| StoreError::SchemaNotFound.into())
+    }
+
+    fn scan_data(&self, table_name: &str) -> Result<RowIter<DataKey>> {
+        Ok(self.scan(table_name, None, Row::clone))
+    }
+
+    /// Values of the other columns are not cloned.
+    fn scan_projected(&self, table_name: &str, columns: &[usize]) -> Result<RowIter<DataKey>> {
+        Ok(self.scan(table_name, None, |Row(values)| {
+            let values = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    if columns.contains(&i) {
+                        value.clone()
+                    } else {
+                        Value::Empty
+                    }
+                })
+                .collect();
+
+            Row(values)
+        }))
+    }
+
+    /// Only the first `limit` rows are cloned.
+    fn scan_limited(&self, table_name: &str, limit: usize) -> Result<RowIter<DataKey>> {
+        Ok(self.scan(table_name, Some(limit), Row::clone))
+    }
+
+    /// Rows are kept in the order of ids, so the scan starts right after `key`.
+    fn scan_after(&self, table_name: &str, key: Option<&DataKey>) -> Result<RowIter<DataKey>> {
+        let start = key.map_or(Bound::Unbounded, |key| Bound::Excluded(key.id));
+        let rows = match self.items.get(table_name) {
+            Some(item) => item
+                .rows
+                .range((start, Bound::Unbounded))
+                .map(|(id, row)| {
+                    let key = DataKey {
+                        table_name: table_name.to_owned(),
+                        id: *id,
+                    };
+
+                    Ok((key, row.clone()))
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        Ok(Box::new(rows.into_iter()))
+    }
+
+    fn row_count_estimate(&self, table_name: &str) -> Option<usize> {
+        self.items.get(table_name).map(|item| item.rows.len())
+    }
+
+    /// Tables of which the primary key is a single `INTEGER` or `TEXT` column have an index of it.
+    fn get_indexed_column(&self, table_name: &str) -> Option<usize> {
+        self.items
+            .get(table_name)
+            .and_then(|item| item.index.as_ref())
+            .map(|index| index.column)
+    }
+
+    /// Rows are in the order of the primary key while each row is inserted with the largest
+    /// value of it and it is never updated.
+    fn get_ordered_column(&self, table_name: &str) -> Option<usize> {
+        self.items
+            .get(table_name)
+            .and_then(|item| item.index.as_ref())
+            .filter(|index| index.ordered)
+            .map(|index| index.column)
+    }
+
+    fn get_keys_by_index(&self, table_name: &str, value: &Value) -> Result<Option<Vec<DataKey>>> {
+        let ids = self
+            .items
+            .get(table_name)
+            .and_then(|item| item.index.as_ref())
+            .and_then(|index| index.get_ids(value));
+        let keys = ids.map(|ids| {
+            ids.into_iter()
+                .map(|id| DataKey {
+                    table_name: table_name.to_owned(),
+                    id,
+                })
+                .collect()
+        });
+
+        Ok(keys)
+    }
+
+    fn get_data_by_key(&self, table_name: &str, key: &DataKey) -> Result<Option<(DataKey, Row)>> {
+        if key.table_name != table_name {
+            return Ok(None);
+        }
+
+        let row = self
+            .items
+            .get(table_name)
+            .and_then(|item| item.rows.get(&key.id))
+            .map(|row| (key.clone(), row.clone()));
+
+        Ok(row)
+    }
+}
@@ -1,5 +1,9 @@
+#[cfg(feature = "memory-storage")]
+mod memory_storage;
 #[cfg(feature = "sled-storage")]
 mod sled_storage;
 
+#[cfg(feature = "memory-storage")]
+pub use memory_storage::{DataKey, MemoryStorage};
 #[cfg(feature = "sled-storage")]
 pub use sled_storage::SledStorage;
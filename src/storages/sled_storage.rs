@@ -1,5 +1,6 @@
 use sled::{self, Config, Db, IVec};
 use std::convert::TryFrom;
+use std::ops::Bound;
 use thiserror::Error as ThisError;
 
 use crate::{Error, MutResult, Result, Row, RowIter, Schema, Store, StoreError, StoreMut};
@@ -15,11 +16,11 @@ enum StorageError {
     Bincode(#[from] bincode::Error),
 }
 
-impl Into<Error> for StorageError {
-    fn into(self) -> Error {
+impl From<StorageError> for Error {
+    fn from(val: StorageError) -> Self {
         use StorageError::*;
 
-        match self {
+        match val {
             Sled(e) => Error::Storage(Box::new(e)),
             Bincode(e) => Error::Storage(e),
             Store(e) => e.into(),
@@ -128,7 +129,7 @@ impl Store<IVec> for SledStorage {
     fn fetch_schema(&self, table_name: &str) -> Result<Schema> {
         let key = format!("schema/{}", table_name);
         let key = key.as_bytes();
-        let value = try_into!(self.tree.get(&key));
+        let value = try_into!(self.tree.get(key));
         let value = value.ok_or(StoreError::SchemaNotFound)?;
         let statement = try_into!(bincode::deserialize(&value));
 
@@ -147,4 +148,47 @@ impl Store<IVec> for SledStorage {
 
         Ok(Box::new(result_set))
     }
+
+    /// Keys of a table share its prefix and the tree is ordered by keys, so the scan starts
+    /// right after `key` and stops at the end of the prefix.
+    fn scan_after(&self, table_name: &str, key: Option<&IVec>) -> Result<RowIter<IVec>> {
+        let prefix = format!("data/{}/", table_name);
+        let start = match key {
+            Some(key) if key.starts_with(prefix.as_bytes()) => Bound::Excluded(key.clone()),
+            _ => Bound::Included(IVec::from(prefix.as_bytes())),
+        };
+
+        let result_set = self
+            .tree
+            .range((start, Bound::Unbounded))
+            .take_while(move |item| match item {
+                Ok((key, _)) => key.starts_with(prefix.as_bytes()),
+                Err(_) => true,
+            })
+            .map(|item| {
+                let (key, value) = try_into!(item);
+                let value = try_into!(bincode::deserialize(&value));
+
+                Ok((key, value))
+            });
+
+        Ok(Box::new(result_set))
+    }
+
+    fn get_data_by_key(&self, table_name: &str, key: &IVec) -> Result<Option<(IVec, Row)>> {
+        let prefix = format!("data/{}/", table_name);
+
+        if !key.starts_with(prefix.as_bytes()) {
+            return Ok(None);
+        }
+
+        match try_into!(self.tree.get(key)) {
+            Some(value) => {
+                let row = try_into!(bincode::deserialize(&value));
+
+                Ok(Some((key.clone(), row)))
+            }
+            None => Ok(None),
+        }
+    }
 }
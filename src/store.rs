@@ -1,9 +1,10 @@
 use serde::Serialize;
+use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::marker::Sized;
 use thiserror::Error;
 
-use super::data::{Row, Schema};
+use super::data::{Row, Schema, TableSchema, Value};
 use super::result::{MutResult, Result};
 
 #[derive(Error, Serialize, Debug, PartialEq)]
@@ -14,11 +15,166 @@ pub enum StoreError {
 
 pub type RowIter<T> = Box<dyn Iterator<Item = Result<(T, Row)>>>;
 
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+pub enum PredicateOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+/// `column op literal` found in `WHERE` clause, pushed down to the scan of a table. `index` is
+/// the position of the column in rows of the table.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Predicate {
+    pub index: usize,
+    pub op: PredicateOp,
+    pub value: Value,
+}
+
+impl Predicate {
+    /// Returns `false` only if the row surely does not match. Rows of which the column cannot
+    /// be compared to the value, e.g. `NULL`, are kept, `WHERE` clause is checked again anyway.
+    pub fn check(&self, row: &Row) -> bool {
+        let ordering = row
+            .get_value(self.index)
+            .and_then(|value| value.partial_cmp(&self.value));
+
+        match ordering {
+            Some(ordering) => match self.op {
+                PredicateOp::Eq => ordering == Ordering::Equal,
+                PredicateOp::NotEq => ordering != Ordering::Equal,
+                PredicateOp::Lt => ordering == Ordering::Less,
+                PredicateOp::LtEq => ordering != Ordering::Greater,
+                PredicateOp::Gt => ordering == Ordering::Greater,
+                PredicateOp::GtEq => ordering != Ordering::Less,
+            },
+            None => true,
+        }
+    }
+}
+
 /// By implementing `Store` trait, you can run `SELECT` queries.
 pub trait Store<T: Debug> {
     fn fetch_schema(&self, table_name: &str) -> Result<Schema>;
 
+    /// Columns of the table with their declared types and the primary key, e.g. for a client to
+    /// show column headers before running a query. `None` if the table does not exist.
+    fn get_schema(&self, table_name: &str) -> Option<TableSchema> {
+        self.fetch_schema(table_name)
+            .ok()
+            .map(|schema| TableSchema::from(&schema))
+    }
+
     fn scan_data(&self, table_name: &str) -> Result<RowIter<T>>;
+
+    /// Scan which skips rows not matching to every predicate. Returning extra rows is fine, so
+    /// the default implementation filters the full scan in memory, storages which can apply a
+    /// predicate earlier, e.g. using an index, should override it.
+    fn scan_filtered(&self, table_name: &str, predicates: &[Predicate]) -> Result<RowIter<T>>
+    where
+        T: 'static,
+    {
+        let predicates = predicates.to_vec();
+        let rows = self.scan_data(table_name)?.filter(move |item| match item {
+            Ok((_, row)) => predicates.iter().all(|predicate| predicate.check(row)),
+            Err(_) => true,
+        });
+
+        Ok(Box::new(rows))
+    }
+
+    /// Scan which needs only the values of `columns`, given as positions in rows of the table.
+    /// Rows still have every column in place, but the others can be `Value::Empty`, so a
+    /// storage can skip reading them. The default implementation returns full rows.
+    fn scan_projected(&self, table_name: &str, columns: &[usize]) -> Result<RowIter<T>> {
+        let _ = columns;
+
+        self.scan_data(table_name)
+    }
+
+    /// Scan which stops after the first `limit` rows, used by `SELECT` with `LIMIT` when every
+    /// scanned row is returned as is. The default implementation takes the rows of the full
+    /// scan, which is lazy anyway, storages which read a table at once should override it.
+    fn scan_limited(&self, table_name: &str, limit: usize) -> Result<RowIter<T>>
+    where
+        T: 'static,
+    {
+        Ok(Box::new(self.scan_data(table_name)?.take(limit)))
+    }
+
+    /// Rows of which the key is greater than `key`, or every row if `key` is `None`, in the order
+    /// of keys, used by `paginate`. The default implementation sorts the rows of the full scan,
+    /// storages which keep rows ordered by key should override it to start the scan at `key`.
+    fn scan_after(&self, table_name: &str, key: Option<&T>) -> Result<RowIter<T>>
+    where
+        T: 'static + Clone + Ord,
+    {
+        let key = key.cloned();
+        let mut rows = self
+            .scan_data(table_name)?
+            .filter(|item| match (item, &key) {
+                (Ok((k, _)), Some(key)) => k > key,
+                _ => true,
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        rows.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(Box::new(rows.into_iter().map(Ok)))
+    }
+
+    /// Number of rows in the table, used to choose the order of joins. It does not need to be
+    /// exact, and `None` if it is not known without scanning the table, which is the default.
+    fn row_count_estimate(&self, table_name: &str) -> Option<usize> {
+        let _ = table_name;
+
+        None
+    }
+
+    /// Position of the column which the storage keeps an index of, e.g. the primary key, so that
+    /// `WHERE` and joins comparing it for equality find the rows by `get_keys_by_index` and fetch
+    /// them by `get_data_by_key` instead of scanning the table. `None` if the table has no index,
+    /// which is the default.
+    fn get_indexed_column(&self, table_name: &str) -> Option<usize> {
+        let _ = table_name;
+
+        None
+    }
+
+    /// Position of the column which the rows of `scan_data` are in ascending order of, `NULL`
+    /// first, so that joins on it can be done by merging the sorted rows. `None` if the order is
+    /// not known, which is the default.
+    fn get_ordered_column(&self, table_name: &str) -> Option<usize> {
+        let _ = table_name;
+
+        None
+    }
+
+    /// Keys of the rows of which the indexed column, see `get_indexed_column`, can be equal to
+    /// `value`. Returning extra keys is fine, the rows are checked again anyway. `None` if the
+    /// index cannot be looked up by the value, e.g. of another type, then the table is scanned.
+    fn get_keys_by_index(&self, table_name: &str, value: &Value) -> Result<Option<Vec<T>>> {
+        let _ = (table_name, value);
+
+        Ok(None)
+    }
+
+    /// Point fetch of a single row. The default implementation scans the whole table, storages
+    /// which can look up a key directly should override it.
+    fn get_data_by_key(&self, table_name: &str, key: &T) -> Result<Option<(T, Row)>>
+    where
+        T: PartialEq,
+    {
+        self.scan_data(table_name)?
+            .find(|item| match item {
+                Ok((k, _)) => k == key,
+                Err(_) => true,
+            })
+            .transpose()
+    }
 }
 
 /// `StoreMut` takes role of mutation, related to `INSERT`, `CREATE`, `DELETE`, `DROP` and
@@ -10,6 +10,7 @@ pub fn aggregate(mut tester: impl tests::Tester) {
     ";
 
     tester.run_and_print(create_sql);
+    tester.run_and_print("CREATE TABLE EmptyItem (id INTEGER);");
 
     let insert_sqls = [
         "INSERT INTO Item (id, quantity, age) VALUES (1, 10, 11);",
@@ -28,31 +29,71 @@ pub fn aggregate(mut tester: impl tests::Tester) {
     let mut run = |sql| tester.run(sql).expect("select");
 
     let test_cases = vec![
-        ("SELECT COUNT(*) FROM Item", select!(I64; 5)),
-        ("SELECT count(*) FROM Item", select!(I64; 5)),
-        ("SELECT COUNT(*), COUNT(*) FROM Item", select!(I64 I64; 5 5)),
+        ("SELECT COUNT(*) FROM Item", select!("COUNT(*)"; I64; 5)),
+        ("SELECT count(*) FROM Item", select!("count(*)"; I64; 5)),
+        (
+            "SELECT COUNT(*), COUNT(*) FROM Item",
+            select!("COUNT(*)" | "COUNT(*)"; I64 I64; 5 5),
+        ),
         (
             "SELECT SUM(quantity), MAX(quantity), MIN(quantity) FROM Item",
-            select!(I64 I64 I64; 47 25 0),
+            select!("SUM(quantity)" | "MAX(quantity)" | "MIN(quantity)"; I64 I64 I64; 47 25 0),
         ),
         (
             "SELECT SUM(quantity) * 2 + MAX(quantity) - 3 / 1 FROM Item",
-            select!(I64; 116),
+            select!("SUM(quantity) * 2 + MAX(quantity) - 3 / 1"; I64; 116),
         ),
         (
             "SELECT SUM(age), MAX(age), MIN(age) FROM Item",
             select!(
-                OptI64 OptI64   OptI64;
-                None   Some(90) Some(3)
+                "SUM(age)" | "MAX(age)" | "MIN(age)";
+                OptI64    OptI64   OptI64;
+                Some(104) Some(90) Some(3)
             ),
         ),
         (
             "SELECT SUM(age) + SUM(quantity) FROM Item",
-            select!(OptI64; None),
+            select!("SUM(age) + SUM(quantity)"; OptI64; Some(151)),
         ),
         (
             "SELECT COUNT(age), COUNT(quantity) FROM Item",
-            select!(I64 I64; 3 5),
+            select!("COUNT(age)" | "COUNT(quantity)"; I64 I64; 3 5),
+        ),
+        (
+            "SELECT AVG(quantity) FROM Item",
+            select!("AVG(quantity)"; F64; 9.4),
+        ),
+        ("SELECT AVG(id) FROM Item", select!("AVG(id)"; F64; 3.0)),
+        (
+            "SELECT AVG(age) FROM Item",
+            select!("AVG(age)"; F64; 104.0 / 3.0),
+        ),
+        (
+            "SELECT COUNT(*) FROM Item WHERE id > 10",
+            select!("COUNT(*)"; I64; 0),
+        ),
+        (
+            "SELECT COUNT(*), SUM(quantity), AVG(quantity) FROM Item WHERE id > 10",
+            select_with_empty!("COUNT(*)" | "SUM(quantity)" | "AVG(quantity)"; I64(0) Empty Empty),
+        ),
+        (
+            "SELECT id, COUNT(*) FROM Item WHERE id > 10 GROUP BY id",
+            select!(id | "COUNT(*)"; I64 I64),
+        ),
+        (
+            "SELECT MAX(quantity), MIN(quantity), AVG(quantity) FROM Item",
+            select!("MAX(quantity)" | "MIN(quantity)" | "AVG(quantity)"; I64 I64 F64; 25 0 9.4),
+        ),
+        (
+            "SELECT COUNT(*), MAX(quantity), MIN(age), COUNT(age) FROM Item WHERE id > 10",
+            select_with_empty!(
+                "COUNT(*)" | "MAX(quantity)" | "MIN(age)" | "COUNT(age)";
+                I64(0) Empty Empty I64(0)
+            ),
+        ),
+        (
+            "SELECT COUNT(*), MAX(id), AVG(id) FROM EmptyItem",
+            select_with_empty!("COUNT(*)" | "MAX(id)" | "AVG(id)"; I64(0) Empty Empty),
         ),
     ];
 
@@ -74,7 +115,15 @@ pub fn aggregate(mut tester: impl tests::Tester) {
             "SELECT SUM(1 + 2) FROM Item;",
         ),
         (
-            BlendContextError::ValueNotFound.into(),
+            ExecuteError::ColumnNotFound {
+                column: "num".to_owned(),
+                available: vec![
+                    "Item.id".to_owned(),
+                    "Item.quantity".to_owned(),
+                    "Item.age".to_owned(),
+                ],
+            }
+            .into(),
             "SELECT SUM(num) FROM Item;",
         ),
     ];
@@ -116,15 +165,16 @@ pub fn group_by(mut tester: impl tests::Tester) {
     let test_cases = vec![
         (
             "SELECT id, COUNT(*) FROM Item GROUP BY id",
-            select!(I64 I64; 1 1; 2 1; 3 2; 4 1; 5 1),
+            select!(id | "COUNT(*)"; I64 I64; 1 1; 2 1; 3 2; 4 1; 5 1),
         ),
         (
             "SELECT id FROM Item GROUP BY id",
-            select!(I64; 1; 2; 3; 4; 5),
+            select!(id; I64; 1; 2; 3; 4; 5),
         ),
         (
             "SELECT SUM(quantity), COUNT(*), city FROM Item GROUP BY city",
             select!(
+                 "SUM(quantity)" | "COUNT(*)" | city;
                  OptI64   I64 Str;
                  Some(21) 2 "Seoul".to_owned();
                  Some(0)  1 "Dhaka".to_owned();
@@ -133,9 +183,22 @@ pub fn group_by(mut tester: impl tests::Tester) {
                  Some(24) 1 "Seattle".to_owned()
             ),
         ),
+        (
+            "SELECT city, AVG(quantity) FROM Item GROUP BY city",
+            select!(
+                 city | "AVG(quantity)";
+                 Str                  OptF64;
+                 "Seoul".to_owned()   Some(10.5);
+                 "Dhaka".to_owned()   Some(0.0);
+                 "Beijing".to_owned() None;
+                 "Daejeon".to_owned() Some(30.0);
+                 "Seattle".to_owned() Some(24.0)
+            ),
+        ),
         (
             "SELECT id, city FROM Item GROUP BY city",
             select!(
+                 id | city;
                  I64 Str;
                  1 "Seoul".to_owned();
                  2 "Dhaka".to_owned();
@@ -146,19 +209,40 @@ pub fn group_by(mut tester: impl tests::Tester) {
         ),
         (
             "SELECT ratio FROM Item GROUP BY id, city",
-            select!(F64; 0.2; 0.9; 1.1; 3.2; 11.1; 6.11),
+            select!(ratio; F64; 0.2; 0.9; 1.1; 3.2; 11.1; 6.11),
         ),
         (
             "SELECT ratio FROM Item GROUP BY id, city HAVING ratio > 10",
-            select!(F64; 11.1),
+            select!(ratio; F64; 11.1),
         ),
         (
             "SELECT SUM(quantity), COUNT(*), city FROM Item GROUP BY city HAVING COUNT(*) > 1",
             select!(
+                 "SUM(quantity)" | "COUNT(*)" | city;
                  OptI64   I64 Str;
                  Some(21) 2 "Seoul".to_owned()
             ),
         ),
+        (
+            "SELECT city FROM Item GROUP BY city HAVING COUNT(*) > 1",
+            select!(city; Str; "Seoul".to_owned()),
+        ),
+        (
+            "SELECT city FROM Item GROUP BY city HAVING SUM(quantity) >= 24",
+            select!(city; Str; "Daejeon".to_owned(); "Seattle".to_owned()),
+        ),
+        (
+            "SELECT city, COUNT(*) FROM Item GROUP BY city HAVING MAX(quantity) < 10",
+            select!(city | "COUNT(*)"; Str I64; "Dhaka".to_owned() 1),
+        ),
+        (
+            "SELECT COUNT(*) FROM Item HAVING MAX(id) = 5",
+            select!("COUNT(*)"; I64; 6),
+        ),
+        (
+            "SELECT COUNT(*) FROM Item HAVING MAX(id) > 5",
+            select!("COUNT(*)"; I64),
+        ),
     ];
 
     test_cases
@@ -174,3 +258,531 @@ pub fn group_by(mut tester: impl tests::Tester) {
         .into_iter()
         .for_each(|(error, sql)| tester.test_error(sql, error));
 }
+
+pub fn distinct(mut tester: impl tests::Tester) {
+    let create_sql = "
+        CREATE TABLE Visit (
+            id INTEGER,
+            team TEXT,
+            city TEXT NULL,
+            score INTEGER NULL,
+        );
+    ";
+
+    tester.run_and_print(create_sql);
+
+    let insert_sqls = [
+        "INSERT INTO Visit (id, team, city, score) VALUES (1, \"A\", \"Seoul\", 10);",
+        "INSERT INTO Visit (id, team, city, score) VALUES (2, \"A\", \"Seoul\", 10);",
+        "INSERT INTO Visit (id, team, city, score) VALUES (3, \"A\", \"Busan\", 20);",
+        "INSERT INTO Visit (id, team, city, score) VALUES (4, \"B\", NULL, NULL);",
+        "INSERT INTO Visit (id, team, city, score) VALUES (5, \"B\", NULL, NULL);",
+        "INSERT INTO Visit (id, team, city, score) VALUES (6, \"C\", \"Busan\", 30);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT COUNT(DISTINCT city) FROM Visit",
+            select!("COUNT(DISTINCT city)"; I64; 2),
+        ),
+        (
+            "SELECT COUNT(city), COUNT(DISTINCT city), COUNT(DISTINCT team) FROM Visit",
+            select!(
+                "COUNT(city)" | "COUNT(DISTINCT city)" | "COUNT(DISTINCT team)";
+                I64 I64 I64;
+                4 2 3
+            ),
+        ),
+        (
+            "SELECT team, COUNT(DISTINCT city) AS cities FROM Visit GROUP BY team",
+            select!(
+                team | cities;
+                Str I64;
+                "A".to_owned() 2;
+                "B".to_owned() 0;
+                "C".to_owned() 1
+            ),
+        ),
+        (
+            "
+            SELECT SUM(DISTINCT score), SUM(score), MAX(DISTINCT score)
+            FROM Visit
+            WHERE score IS NOT NULL
+            ",
+            select!(
+                "SUM(DISTINCT score)" | "SUM(score)" | "MAX(DISTINCT score)";
+                OptI64 OptI64 OptI64;
+                Some(60) Some(70) Some(30)
+            ),
+        ),
+        (
+            "SELECT AVG(DISTINCT score), AVG(score) FROM Visit",
+            select!(
+                "AVG(DISTINCT score)" | "AVG(score)";
+                OptF64 OptF64;
+                Some(20.0) Some(17.5)
+            ),
+        ),
+        (
+            "
+            SELECT team, COUNT(DISTINCT score), SUM(DISTINCT score)
+            FROM Visit
+            WHERE team = 'A'
+            GROUP BY team
+            ",
+            select!(
+                team | "COUNT(DISTINCT score)" | "SUM(DISTINCT score)";
+                Str I64 OptI64;
+                "A".to_owned() 2 Some(30)
+            ),
+        ),
+        (
+            "SELECT COUNT(DISTINCT city) FROM Visit WHERE id > 100",
+            select!("COUNT(DISTINCT city)"; I64; 0),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn group_concat(mut tester: impl tests::Tester) {
+    tester.run_and_print("CREATE TABLE Member (id INTEGER, team TEXT, name TEXT NULL);");
+
+    let insert_sqls = [
+        "INSERT INTO Member (id, team, name) VALUES (1, \"A\", \"Taehoon\");",
+        "INSERT INTO Member (id, team, name) VALUES (2, \"A\", NULL);",
+        "INSERT INTO Member (id, team, name) VALUES (3, \"B\", \"Mike\");",
+        "INSERT INTO Member (id, team, name) VALUES (4, \"A\", \"Jorno\");",
+        "INSERT INTO Member (id, team, name) VALUES (5, \"C\", NULL);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+    let null = |label: &str| Payload::Select {
+        labels: vec![label.to_owned()],
+        rows: vec![Row(vec![Empty])],
+    };
+
+    let test_cases = vec![
+        (
+            "SELECT GROUP_CONCAT(name) FROM Member",
+            select!("GROUP_CONCAT(name)"; Str; "Taehoon,Mike,Jorno".to_owned()),
+        ),
+        (
+            "SELECT team, GROUP_CONCAT(name, ', ') FROM Member WHERE team <> 'C' GROUP BY team",
+            select!(
+                team | "GROUP_CONCAT(name, ', ')";
+                Str Str;
+                "A".to_owned() "Taehoon, Jorno".to_owned();
+                "B".to_owned() "Mike".to_owned()
+            ),
+        ),
+        (
+            "SELECT GROUP_CONCAT(id, '') FROM Member",
+            select!("GROUP_CONCAT(id, '')"; Str; "12345".to_owned()),
+        ),
+        (
+            "SELECT GROUP_CONCAT(DISTINCT team) FROM Member",
+            select!("GROUP_CONCAT(DISTINCT team)"; Str; "A,B,C".to_owned()),
+        ),
+        (
+            "
+            SELECT GROUP_CONCAT(name, '/')
+            FROM (SELECT name FROM Member WHERE name IS NOT NULL ORDER BY name) AS Sorted
+            ",
+            select!("GROUP_CONCAT(name, '/')"; Str; "Jorno/Mike/Taehoon".to_owned()),
+        ),
+        (
+            "SELECT GROUP_CONCAT(name) FROM Member WHERE team = 'C'",
+            null("GROUP_CONCAT(name)"),
+        ),
+        (
+            "SELECT GROUP_CONCAT(name) FROM Member WHERE id > 100",
+            null("GROUP_CONCAT(name)"),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    tester.test_error(
+        "SELECT GROUP_CONCAT(name, id) FROM Member",
+        AggregateError::SeparatorNotString.into(),
+    );
+}
+
+pub fn stream(mut tester: impl tests::Tester) {
+    tester.run_and_print("CREATE TABLE Item (id INTEGER, k INTEGER, k2 INTEGER, v INTEGER NULL);");
+
+    let values = (1..=1000)
+        .map(|id| match id % 97 {
+            0 => format!("({}, {}, {}, NULL)", id, id * 7 % 37, id % 3),
+            _ => format!("({}, {}, {}, {})", id, id * 7 % 37, id % 3, id % 11),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    tester
+        .run(&format!("INSERT INTO Item VALUES {};", values))
+        .unwrap();
+
+    let test_cases = vec![
+        (
+            "
+            SELECT k, COUNT(*), COUNT(v), SUM(v), MIN(v), MAX(v)
+            FROM (SELECT k, v FROM Item ORDER BY k) AS s
+            GROUP BY k
+            ",
+            "SELECT k, COUNT(*), COUNT(v), SUM(v), MIN(v), MAX(v) FROM Item GROUP BY k ORDER BY k",
+            37,
+        ),
+        (
+            "
+            SELECT s.k, s.k2, SUM(v)
+            FROM (SELECT k2, k, v FROM Item ORDER BY k DESC, k2) AS s
+            GROUP BY s.k2, s.k
+            ",
+            "SELECT k, k2, SUM(v) FROM Item GROUP BY k2, k ORDER BY k DESC, k2",
+            111,
+        ),
+        (
+            "
+            SELECT k, AVG(v) FROM (SELECT k, v FROM Item ORDER BY k) AS s
+            GROUP BY k
+            HAVING COUNT(*) > 27
+            ",
+            "SELECT k, AVG(v) FROM Item GROUP BY k HAVING COUNT(*) > 27 ORDER BY k",
+            1,
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(streamed, hashed, count)| {
+            let streamed = tester.run(streamed).expect("select");
+
+            match &streamed {
+                Payload::Select { rows, .. } => assert_eq!(count, rows.len()),
+                _ => panic!("unexpected payload: {:?}", streamed),
+            }
+
+            assert_eq!(tester.run(hashed).expect("select"), streamed);
+        });
+}
+
+pub fn filter(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "CREATE TABLE Ledger (id INTEGER, account TEXT, type TEXT, amount INTEGER);",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO Ledger VALUES (1, \"A\", \"credit\", 100);",
+        "INSERT INTO Ledger VALUES (2, \"A\", \"debit\", 30);",
+        "INSERT INTO Ledger VALUES (3, \"B\", \"debit\", 20);",
+        "INSERT INTO Ledger VALUES (4, \"A\", \"credit\", 50);",
+        "INSERT INTO Ledger VALUES (5, \"C\", \"credit\", 10);",
+        "INSERT INTO Ledger VALUES (6, \"B\", \"debit\", 5);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "
+            SELECT
+                account,
+                SUM(amount) FILTER (WHERE type = 'credit'),
+                SUM(amount),
+                COUNT(*) FILTER (WHERE type = 'debit')
+            FROM Ledger
+            GROUP BY account
+            ",
+            select_with_empty!(
+                account
+                    | "SUM(amount) FILTER (WHERE type = 'credit')"
+                    | "SUM(amount)"
+                    | "COUNT(*) FILTER (WHERE type = 'debit')";
+                Str("A".to_owned()) I64(150) I64(180) I64(1);
+                Str("B".to_owned()) Empty    I64(25)  I64(2);
+                Str("C".to_owned()) I64(10)  I64(10)  I64(0)
+            ),
+        ),
+        (
+            "
+            SELECT account FROM Ledger
+            GROUP BY account
+            HAVING COUNT(*) FILTER (WHERE type = 'credit') > 0
+            ",
+            select!(account; Str; "A".to_owned(); "C".to_owned()),
+        ),
+        (
+            "
+            SELECT
+                MAX(amount) FILTER (WHERE type = 'debit') AS debit,
+                AVG(amount) FILTER (WHERE account = 'A' AND amount > 40) AS average,
+                COUNT(DISTINCT type) FILTER (WHERE amount > 15) AS types
+            FROM Ledger
+            ",
+            select!(debit | average | types; I64 F64 I64; 30 75.0 2),
+        ),
+        (
+            "
+            SELECT SUM(amount) FILTER (WHERE amount > 1000) AS total, COUNT(*) AS count
+            FROM Ledger
+            ",
+            select_with_empty!(total | count; Empty I64(6)),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn rollup(mut tester: impl tests::Tester) {
+    tester.run_and_print("CREATE TABLE Sales (region TEXT, product TEXT NULL, amount INTEGER);");
+
+    let insert_sqls = [
+        "INSERT INTO Sales VALUES (\"East\", \"apple\", 10);",
+        "INSERT INTO Sales VALUES (\"East\", \"pear\", 5);",
+        "INSERT INTO Sales VALUES (\"West\", \"apple\", 7);",
+        "INSERT INTO Sales VALUES (\"East\", \"apple\", 3);",
+        "INSERT INTO Sales VALUES (\"West\", NULL, 1);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+    let str = |v: &str| Str(v.to_owned());
+    let opt_str = |v: &str| OptStr(Some(v.to_owned()));
+
+    let test_cases = vec![
+        (
+            "
+            SELECT region, product, SUM(amount), GROUPING(region), GROUPING(product)
+            FROM Sales
+            GROUP BY ROLLUP(region, product)
+            ",
+            select_with_empty!(
+                region | product | "SUM(amount)" | "GROUPING(region)" | "GROUPING(product)";
+                str("East") opt_str("apple") I64(13) I64(0) I64(0);
+                str("East") opt_str("pear")  I64(5)  I64(0) I64(0);
+                str("West") opt_str("apple") I64(7)  I64(0) I64(0);
+                str("West") OptStr(None)     I64(1)  I64(0) I64(0);
+                str("East") Empty            I64(18) I64(0) I64(1);
+                str("West") Empty            I64(8)  I64(0) I64(1);
+                Empty       Empty            I64(26) I64(1) I64(1)
+            ),
+        ),
+        (
+            "
+            SELECT region, COUNT(*) FROM Sales
+            GROUP BY ROLLUP(region, product)
+            HAVING GROUPING(product) = 1
+            ",
+            select_with_empty!(
+                region | "COUNT(*)";
+                str("East") I64(3);
+                str("West") I64(2);
+                Empty       I64(5)
+            ),
+        ),
+        (
+            "SELECT region, product, COUNT(*) FROM Sales GROUP BY region, ROLLUP(product)",
+            select_with_empty!(
+                region | product | "COUNT(*)";
+                str("East") opt_str("apple") I64(2);
+                str("East") opt_str("pear")  I64(1);
+                str("West") opt_str("apple") I64(1);
+                str("West") OptStr(None)     I64(1);
+                str("East") Empty            I64(3);
+                str("West") Empty            I64(2)
+            ),
+        ),
+        (
+            "SELECT product, SUM(amount) FROM Sales GROUP BY CUBE(product)",
+            select_with_empty!(
+                product | "SUM(amount)";
+                opt_str("apple") I64(20);
+                opt_str("pear")  I64(5);
+                OptStr(None)     I64(1);
+                Empty            I64(26)
+            ),
+        ),
+        (
+            "SELECT COUNT(*) FROM Sales WHERE amount > 100 GROUP BY ROLLUP(region)",
+            select!("COUNT(*)"; I64; 0),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            AggregateError::GroupingNotGrouped("GROUPING(amount)".to_owned()).into(),
+            "SELECT GROUPING(amount) FROM Sales GROUP BY ROLLUP(region)",
+        ),
+        (
+            AggregateError::GroupingSetNotColumn("amount + 1".to_owned()).into(),
+            "SELECT COUNT(*) FROM Sales GROUP BY ROLLUP(amount + 1)",
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(error, sql)| tester.test_error(sql, error));
+}
+
+pub fn expression(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "CREATE TABLE Ledger (id INTEGER, account TEXT, credit INTEGER, debit INTEGER, total FLOAT);",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO Ledger VALUES (1, \"cash\", 10, 3, 1.5);",
+        "INSERT INTO Ledger VALUES (2, \"cash\", 5, 1, 2.5);",
+        "INSERT INTO Ledger VALUES (3, \"bank\", 7, 9, 4.0);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+    let loss = -2;
+    let average = 22.0 / 3.0;
+
+    let test_cases = vec![
+        (
+            "SELECT account, SUM(credit) - SUM(debit) AS net FROM Ledger GROUP BY account ORDER BY account",
+            select!(
+                account | net;
+                Str I64;
+                "bank".to_owned() loss;
+                "cash".to_owned() 11
+            ),
+        ),
+        (
+            "SELECT COUNT(*) * 100.0 / SUM(total) AS ratio FROM Ledger",
+            select!(ratio; F64; 37.5),
+        ),
+        (
+            "SELECT SUM(credit) / COUNT(*), CAST(SUM(credit) AS FLOAT) / COUNT(*) FROM Ledger",
+            select!(
+                "SUM(credit) / COUNT(*)" | "CAST(SUM(credit) AS FLOAT) / COUNT(*)";
+                I64 F64;
+                7 average
+            ),
+        ),
+        (
+            "
+            SELECT account, (SUM(credit) + 1) * 2 AS doubled, MAX(credit) - MIN(debit) AS spread
+            FROM Ledger GROUP BY account ORDER BY account
+            ",
+            select!(
+                account | doubled | spread;
+                Str I64 I64;
+                "bank".to_owned() 16 loss;
+                "cash".to_owned() 32 9
+            ),
+        ),
+        (
+            "
+            SELECT account, CASE WHEN SUM(credit) > SUM(debit) THEN 'up' ELSE 'down' END AS trend
+            FROM Ledger GROUP BY account ORDER BY account
+            ",
+            select!(
+                account | trend;
+                Str Str;
+                "bank".to_owned() "down".to_owned();
+                "cash".to_owned() "up".to_owned()
+            ),
+        ),
+        (
+            "SELECT account FROM Ledger GROUP BY account HAVING SUM(credit) - SUM(debit) > 0",
+            select!(account; Str; "cash".to_owned()),
+        ),
+        // aggregates only found in ORDER BY are aggregated as well
+        (
+            "SELECT account FROM Ledger GROUP BY account ORDER BY SUM(debit) - SUM(credit)",
+            select!(account; Str; "cash".to_owned(); "bank".to_owned()),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn null(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "CREATE TABLE NullAggregate (id INTEGER, grp TEXT, x INTEGER NULL, y INTEGER NULL);",
+    );
+    tester.run_and_print(
+        "
+        INSERT INTO NullAggregate VALUES
+            (1, \"a\", NULL, 1), (2, \"a\", 10, NULL), (3, \"a\", 5, 2), (4, \"b\", NULL, NULL),
+            (5, \"b\", NULL, 3);
+        ",
+    );
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    // the first row of each group is NULL
+    let test_cases = vec![
+        (
+            "
+            SELECT grp, SUM(x), MIN(x), MAX(x), AVG(x), COUNT(x) FROM NullAggregate
+            GROUP BY grp ORDER BY grp
+            ",
+            select_with_empty!(
+                grp | "SUM(x)" | "MIN(x)" | "MAX(x)" | "AVG(x)" | "COUNT(x)";
+                Str("a".to_owned()) OptI64(Some(15)) OptI64(Some(5)) OptI64(Some(10)) F64(7.5) I64(2);
+                Str("b".to_owned()) OptI64(None) OptI64(None) OptI64(None) OptF64(None) I64(0)
+            ),
+        ),
+        (
+            "SELECT SUM(DISTINCT x), SUM(x) - SUM(y) AS net FROM NullAggregate WHERE grp = 'a'",
+            select!("SUM(DISTINCT x)" | net; OptI64 OptI64; Some(15) Some(12)),
+        ),
+        (
+            "SELECT SUM(y), MIN(y), MAX(y) FROM NullAggregate",
+            select!("SUM(y)" | "MIN(y)" | "MAX(y)"; OptI64 OptI64 OptI64; Some(6) Some(1) Some(3)),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql), "{}", sql));
+}
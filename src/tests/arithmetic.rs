@@ -48,6 +48,11 @@ pub fn arithmetic(mut tester: impl tests::Tester) {
         (5, "SELECT * FROM Arith WHERE id > id / 2;"),
         (3, "SELECT * FROM Arith WHERE id > num / id;"),
         (2, "SELECT * FROM Arith WHERE 10 / id = 2;"),
+        // modulo on WHERE
+        (1, "SELECT * FROM Arith WHERE id = 7 % 5;"),
+        (3, "SELECT * FROM Arith WHERE id % 2 = 1;"),
+        (2, "SELECT * FROM Arith WHERE num % id = 0;"),
+        (1, "SELECT * FROM Arith WHERE (id + num) % 3 = 0;"),
         // etc
         (1, "SELECT * FROM Arith WHERE 1 + 1 = id;"),
         (5, "UPDATE Arith SET id = id + 1;"),
@@ -80,6 +85,26 @@ pub fn arithmetic(mut tester: impl tests::Tester) {
             ValueError::DivideOnNonNumeric.into(),
             "SELECT * FROM Arith WHERE name / id < 1",
         ),
+        (
+            ValueError::ModuloOnNonNumeric.into(),
+            "SELECT * FROM Arith WHERE name % id < 1",
+        ),
+        (
+            ValueError::DivisorShouldNotBeZero.into(),
+            "SELECT * FROM Arith WHERE id / 0 = 1",
+        ),
+        (
+            ValueError::DivisorShouldNotBeZero.into(),
+            "SELECT * FROM Arith WHERE id % (num - num) = 1",
+        ),
+        (
+            ValueError::DivisorShouldNotBeZero.into(),
+            "SELECT id / (num - num) FROM Arith",
+        ),
+        (
+            ValueError::DivisorShouldNotBeZero.into(),
+            "SELECT 1 % 0 FROM Arith",
+        ),
         (
             UpdateError::ColumnNotFound("aaa".to_owned()).into(),
             "UPDATE Arith SET aaa = 1",
@@ -120,13 +145,14 @@ pub fn blend(mut tester: impl tests::Tester) {
 
     let sql = "SELECT 1 * 2 + 1 - 3 / 1 FROM Arith LIMIT 1;";
     let found = tester.run(sql).expect("select");
-    let expected = select!(I64; 0);
+    let expected = select!("1 * 2 + 1 - 3 / 1"; I64; 0);
     assert_eq!(expected, found);
 
     let found = tester
         .run("SELECT id, id + 1, id + num, 1 + 1 FROM Arith")
         .expect("select");
     let expected = select!(
+        id | "id + 1" | "id + num" | "1 + 1";
         I64 I64 I64 I64;
         1   2   7   2;
         2   3   10  2;
@@ -142,6 +168,100 @@ pub fn blend(mut tester: impl tests::Tester) {
       JOIN Arith b ON a.id = b.id + 1
     ";
     let found = tester.run(sql).expect("select");
-    let expected = select!(I64; 3; 5; 7; 9);
+    let expected = select!("a.id + b.id"; I64; 3; 5; 7; 9);
+    assert_eq!(expected, found);
+
+    let found = tester
+        .run("SELECT id, num % id, (id + num) * 2, num * 2 - id, (num + id) / 2 FROM Arith")
+        .expect("select");
+    let expected = select!(
+        id | "num % id" | "(id + num) * 2" | "num * 2 - id" | "(num + id) / 2";
+        I64 I64 I64 I64 I64;
+        1   0   14  11  3;
+        2   0   20  14  5;
+        3   1   14  5   3;
+        4   2   12  0   3;
+        5   3   16  1   4
+    );
+    assert_eq!(expected, found);
+
+    let sql = "SELECT (id + 1) * (num - 1) % 7 AS result FROM Arith WHERE id < 3";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(result; I64; 3; 0);
     assert_eq!(expected, found);
 }
+
+pub fn division(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "CREATE TABLE ArithDiv (id INTEGER, total INTEGER, ratio FLOAT, num INTEGER NULL);",
+    );
+    tester
+        .run("INSERT INTO ArithDiv VALUES (1, 7, 2.5, NULL), (2, 7, 0.5, 3);")
+        .unwrap();
+    tester
+        .run("UPDATE ArithDiv SET total = total - 14 WHERE id = 2;")
+        .unwrap();
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        // both integers, truncated toward zero
+        (
+            "SELECT total / 2 AS q FROM ArithDiv",
+            select!(q; I64; 3; -3),
+        ),
+        (
+            "SELECT total % 3 AS r FROM ArithDiv",
+            select!(r; I64; 1; -1),
+        ),
+        (
+            "SELECT 7 / 2 AS q, 7 % 2 AS r FROM ArithDiv WHERE id = 1",
+            select!(q | r; I64 I64; 3 1),
+        ),
+        // either one is a float
+        (
+            "SELECT total / 2.0 AS q FROM ArithDiv",
+            select!(q; F64; 3.5; -3.5),
+        ),
+        (
+            "SELECT 7 / 2.0 AS q, 7.0 / 2 AS q2 FROM ArithDiv WHERE id = 1",
+            select!(q | q2; F64 F64; 3.5 3.5),
+        ),
+        (
+            "SELECT total / ratio AS q FROM ArithDiv",
+            select!(q; F64; 2.8; -14.0),
+        ),
+        (
+            "SELECT ratio % 2 AS r FROM ArithDiv",
+            select!(r; F64; 0.5; 0.5),
+        ),
+        // NULL dividend stays NULL, even divided by zero
+        (
+            "SELECT num / 2 AS q FROM ArithDiv",
+            select!(q; OptI64; None; Some(1)),
+        ),
+        (
+            "SELECT num / 0 AS q, num % 0 AS r FROM ArithDiv WHERE id = 1",
+            select!(q | r; OptI64 OptI64; None None),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql), "{}", sql));
+
+    let error_cases = vec![
+        "SELECT total / 0 FROM ArithDiv",
+        "SELECT total % 0 FROM ArithDiv",
+        "SELECT ratio / 0 FROM ArithDiv",
+        "SELECT total / 0.0 FROM ArithDiv",
+        "SELECT 1 / 0 FROM ArithDiv",
+        "SELECT num / 0 FROM ArithDiv",
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|sql| tester.test_error(sql, ValueError::DivisorShouldNotBeZero.into()));
+}
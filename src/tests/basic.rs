@@ -21,6 +21,7 @@ CREATE TABLE Test (
         .run("SELECT id, num, name FROM Test")
         .expect("select");
     let expected = select!(
+        id | num | name;
         I64 I64 Str;
         1   2   "Hello".to_owned();
         1   9   "World".to_owned();
@@ -32,10 +33,10 @@ CREATE TABLE Test (
     tester.run_and_print("UPDATE Test SET id = 2");
 
     let found = tester.run("SELECT id FROM Test").expect("select");
-    let expected = select!(I64; 2; 2; 2; 2);
+    let expected = select!(id; I64; 2; 2; 2; 2);
     assert_eq!(expected, found);
 
     let found = tester.run("SELECT id, num FROM Test").expect("select");
-    let expected = select!(I64 I64; 2 2; 2 9; 2 4; 2 7);
+    let expected = select!(id | num; I64 I64; 2 2; 2 9; 2 4; 2 7);
     assert_eq!(expected, found);
 }
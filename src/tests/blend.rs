@@ -43,10 +43,11 @@ pub fn blend(mut tester: impl tests::Tester) {
     let mut run = |sql| tester.run(sql).expect("select");
 
     let test_cases = vec![
-        ("SELECT 1 FROM BlendUser", select!(I64; 1; 1; 1)),
+        ("SELECT 1 FROM BlendUser", select!("1"; I64; 1; 1; 1)),
         (
             "SELECT id, name FROM BlendUser",
             select!(
+                id | name;
                 I64 Str;
                 1   "Taehoon".to_owned();
                 2   "Mike".to_owned();
@@ -55,11 +56,31 @@ pub fn blend(mut tester: impl tests::Tester) {
         ),
         (
             "SELECT player_id, quantity FROM BlendItem",
-            select!(I64 I64; 1 1; 2 4; 2 9; 3 2; 3 1),
+            select!(player_id | quantity; I64 I64; 1 1; 2 4; 2 9; 3 2; 3 1),
         ),
         (
             "SELECT player_id, player_id FROM BlendItem",
-            select!(I64 I64; 1 1; 2 2; 2 2; 3 3; 3 3),
+            select!(player_id | player_id; I64 I64; 1 1; 2 2; 2 2; 3 3; 3 3),
+        ),
+        (
+            "SELECT id AS user_id, name FROM BlendUser WHERE id = 1",
+            select!(user_id | name; I64 Str; 1 "Taehoon".to_owned()),
+        ),
+        (
+            "SELECT quantity * 2 AS double_quantity, quantity * 3 FROM BlendItem WHERE id = 102",
+            select!(double_quantity | "quantity * 3"; I64 I64; 8 12),
+        ),
+        (
+            "
+            SELECT u.name AS player, i.id item_id
+            FROM BlendUser u
+            JOIN BlendItem i ON u.id = 1 AND u.id = i.player_id
+            ",
+            select!(player | item_id; Str I64; "Taehoon".to_owned() 101),
+        ),
+        (
+            "SELECT COUNT(*) AS num_items, SUM(quantity) FROM BlendItem",
+            select!(num_items | "SUM(quantity)"; I64 I64; 5 17),
         ),
         (
             "
@@ -67,7 +88,7 @@ pub fn blend(mut tester: impl tests::Tester) {
             FROM BlendUser u
             JOIN BlendItem i ON u.id = 1 AND u.id = i.player_id
             ",
-            select!(I64 I64 I64; 1 101 1),
+            select!(id | id | player_id; I64 I64 I64; 1 101 1),
         ),
         (
             "
@@ -76,6 +97,7 @@ pub fn blend(mut tester: impl tests::Tester) {
             JOIN BlendItem i ON u.id = 2 AND u.id = i.player_id
             ",
             select!(
+                id | player_id | quantity | name;
                 I64 I64 I64 Str;
                 102 2   4   "Mike".to_owned();
                 103 2   9   "Mike".to_owned()
@@ -88,6 +110,7 @@ pub fn blend(mut tester: impl tests::Tester) {
             JOIN BlendItem i ON u.id = i.player_id
             ",
             select!(
+                id | name | id | player_id | quantity;
                 I64 Str                  I64 I64 I64;
                 1   "Taehoon".to_owned() 101 1   1;
                 2   "Mike".to_owned()    102 2   4;
@@ -96,6 +119,34 @@ pub fn blend(mut tester: impl tests::Tester) {
                 3   "Jorno".to_owned()   105 3   1
             ),
         ),
+        (
+            "
+            SELECT u.*, i.id
+            FROM BlendUser u
+            JOIN BlendItem i ON u.id = i.player_id
+            WHERE i.quantity > 3
+            ",
+            select!(
+                id | name | id;
+                I64 Str I64;
+                2   "Mike".to_owned() 102;
+                2   "Mike".to_owned() 103
+            ),
+        ),
+        (
+            "
+            SELECT BlendItem.*, u.id
+            FROM BlendUser u
+            JOIN BlendItem ON u.id = BlendItem.player_id
+            WHERE u.id = 3
+            ",
+            select!(
+                id | player_id | quantity | id;
+                I64 I64 I64 I64;
+                104 3   2   3;
+                105 3   1   3
+            ),
+        ),
     ];
 
     test_cases
@@ -105,16 +156,20 @@ pub fn blend(mut tester: impl tests::Tester) {
     let error_cases = vec![
         (
             BlendError::FieldDefinitionNotSupported,
-            "SELECT (1 * 2) as a FROM BlendItem;",
-        ),
-        (
-            BlendError::ColumnNotFound("a".to_owned()),
-            "SELECT a FROM BlendUser",
+            "SELECT id IS NULL FROM BlendItem;",
         ),
         (
             BlendError::TableNotFound("Whatever".to_owned()),
             "SELECT Whatever.* FROM BlendUser",
         ),
+        (
+            BlendError::TableNotFound("BlendUser".to_owned()),
+            "SELECT BlendUser.*, u.id FROM BlendUser u",
+        ),
+        (
+            BlendError::TableNotFound("i".to_owned()),
+            "SELECT u.*, i.* FROM BlendUser u JOIN BlendItem ON u.id = BlendItem.player_id",
+        ),
     ];
 
     error_cases
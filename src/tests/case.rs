@@ -0,0 +1,103 @@
+use crate::*;
+
+pub fn case(mut tester: impl tests::Tester) {
+    let create_sql = "
+        CREATE TABLE Student (
+            id INTEGER,
+            name TEXT,
+            score INTEGER NULL,
+        );
+    ";
+
+    tester.run_and_print(create_sql);
+
+    let insert_sqls = [
+        "INSERT INTO Student (id, name, score) VALUES (1, \"Taehoon\", 90);",
+        "INSERT INTO Student (id, name, score) VALUES (2, \"Mike\", 55);",
+        "INSERT INTO Student (id, name, score) VALUES (3, \"Jorno\", NULL);",
+        "INSERT INTO Student (id, name, score) VALUES (4, \"Berry\", 70);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT id, CASE WHEN score >= 60 THEN 'pass' ELSE 'fail' END FROM Student",
+            select!(
+                id | "CASE WHEN score >= 60 THEN 'pass' ELSE 'fail' END";
+                I64 Str;
+                1   "pass".to_owned();
+                2   "fail".to_owned();
+                3   "fail".to_owned();
+                4   "pass".to_owned()
+            ),
+        ),
+        (
+            "
+            SELECT
+                id,
+                CASE
+                    WHEN score >= 80 THEN 'A'
+                    WHEN score >= 60 THEN 'B'
+                    WHEN score IS NULL THEN 'none'
+                END AS grade
+            FROM Student
+            ",
+            select_with_empty!(
+                id | grade;
+                I64(1) Str("A".to_owned());
+                I64(2) Empty;
+                I64(3) Str("none".to_owned());
+                I64(4) Str("B".to_owned())
+            ),
+        ),
+        (
+            "SELECT CASE id WHEN 1 THEN 'one' WHEN 2 THEN 'two' ELSE name END AS num FROM Student",
+            select!(
+                num;
+                Str;
+                "one".to_owned();
+                "two".to_owned();
+                "Jorno".to_owned();
+                "Berry".to_owned()
+            ),
+        ),
+        (
+            "SELECT CASE score WHEN NULL THEN 'null' ELSE 'other' END AS s FROM Student WHERE id = 3",
+            select!(s; Str; "other".to_owned()),
+        ),
+        (
+            "SELECT id, 10 + CASE WHEN score > 60 THEN score ELSE 0 END * 2 FROM Student",
+            select!(
+                id | "10 + CASE WHEN score > 60 THEN score ELSE 0 END * 2";
+                I64 OptI64;
+                1   Some(190);
+                2   Some(10);
+                3   Some(10);
+                4   Some(150)
+            ),
+        ),
+        (
+            "SELECT id FROM Student WHERE CASE WHEN score IS NULL THEN 0 ELSE score END > 60",
+            select!(id; I64; 1; 4),
+        ),
+        (
+            "SELECT id FROM Student ORDER BY CASE WHEN score IS NULL THEN 1 ELSE 0 END, id DESC",
+            select!(id; I64; 4; 2; 1; 3),
+        ),
+        (
+            "SELECT CASE WHEN COUNT(*) > 3 THEN 'many' ELSE 'few' END AS cnt FROM Student",
+            select!(cnt; Str; "many".to_owned()),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
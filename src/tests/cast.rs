@@ -0,0 +1,101 @@
+use crate::*;
+
+pub fn cast(mut tester: impl tests::Tester) {
+    let create_sql = "
+        CREATE TABLE Item (
+            id INTEGER,
+            code TEXT,
+            ratio FLOAT,
+            flag BOOLEAN,
+            memo TEXT NULL,
+        );
+    ";
+
+    tester.run_and_print(create_sql);
+
+    let insert_sqls = [
+        "INSERT INTO Item (id, code, ratio, flag, memo) VALUES (1, \"10\", 1.4, true, NULL);",
+        "INSERT INTO Item (id, code, ratio, flag, memo) VALUES (2, \" 7 \", 2.5, false, \"abc\");",
+        "INSERT INTO Item (id, code, ratio, flag, memo) VALUES (3, \"-3\", 0.5, true, \"12\");",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT CAST(code AS INTEGER), CAST(id AS TEXT) FROM Item",
+            select!(
+                "CAST(code AS INT)" | "CAST(id AS TEXT)";
+                I64 Str;
+                10 "1".to_owned();
+                7  "2".to_owned();
+                -3 "3".to_owned()
+            ),
+        ),
+        (
+            "SELECT CAST(ratio AS INTEGER), CAST(id AS FLOAT), CAST(flag AS TEXT) FROM Item WHERE id = 2",
+            select!(
+                "CAST(ratio AS INT)" | "CAST(id AS FLOAT)" | "CAST(flag AS TEXT)";
+                I64 F64 Str;
+                3 2.0 "FALSE".to_owned()
+            ),
+        ),
+        (
+            "SELECT CAST(flag AS INTEGER), CAST(id - 2 AS BOOLEAN), CAST('TRUE' AS BOOLEAN) FROM Item WHERE id = 1",
+            select!(
+                "CAST(flag AS INT)" | "CAST(id - 2 AS BOOLEAN)" | "CAST('TRUE' AS BOOLEAN)";
+                I64 Bool Bool;
+                1 true true
+            ),
+        ),
+        (
+            "SELECT CAST(memo AS INTEGER) AS memo FROM Item WHERE id = 1",
+            select!(memo; OptI64; None),
+        ),
+        (
+            "SELECT id FROM Item WHERE CAST(code AS INTEGER) > 5",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM Item WHERE CAST(id AS TEXT) = '3'",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT CAST(SUM(id) AS TEXT) FROM Item",
+            select!("CAST(SUM(id) AS TEXT)"; Str; "6".to_owned()),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            ValueError::ImpossibleCast.into(),
+            "SELECT CAST(memo AS INTEGER) FROM Item",
+        ),
+        (
+            ValueError::ImpossibleCast.into(),
+            "SELECT CAST('abc' AS INTEGER) FROM Item",
+        ),
+        (
+            ValueError::ImpossibleCast.into(),
+            "SELECT id FROM Item WHERE CAST(code AS BOOLEAN) = true",
+        ),
+        (
+            ValueError::SqlTypeNotSupported.into(),
+            "SELECT CAST(id AS TIME) FROM Item",
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(error, sql)| tester.test_error(sql, error));
+}
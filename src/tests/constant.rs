@@ -0,0 +1,39 @@
+use crate::*;
+
+pub fn constant(mut tester: impl tests::Tester) {
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT 1 + 1, 'hello', UPPER('x')",
+            select!("1 + 1" | "'hello'" | "UPPER('x')"; I64 Str Str; 2 "hello".to_owned() "X".to_owned()),
+        ),
+        (
+            "SELECT (2 + 3) * 4 - 10 / 5 AS result, 7 % 4 AS rest",
+            select!(result | rest; I64 I64; 18 3),
+        ),
+        (
+            "SELECT 1.5 * 2, CAST('10' AS INTEGER), LOWER('ABC')",
+            select!("1.5 * 2" | "CAST('10' AS INT)" | "LOWER('ABC')"; F64 I64 Str; 3.0 10 "abc".to_owned()),
+        ),
+        (
+            "SELECT CASE WHEN 1 < 2 THEN 'yes' ELSE 'no' END AS answer",
+            select!(answer; Str; "yes".to_owned()),
+        ),
+    ];
+
+    for (sql, expected) in test_cases.into_iter() {
+        assert_eq!(tester.run(sql), Ok(expected), "{}", sql);
+    }
+
+    tester.test_rows("SELECT 1 WHERE 1 = 1", 1);
+    tester.test_rows("SELECT 1 WHERE 1 = 2", 0);
+    tester.test_error(
+        "SELECT id",
+        ExecuteError::ColumnNotFound {
+            column: "id".to_owned(),
+            available: vec![],
+        }
+        .into(),
+    );
+}
@@ -0,0 +1,110 @@
+use crate::*;
+
+pub fn cte(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "
+        CREATE TABLE CteUser (
+            id INTEGER,
+            name TEXT,
+        );
+        ",
+        "
+        CREATE TABLE CteOrder (
+            id INTEGER,
+            user_id INTEGER,
+            amount INTEGER,
+        );
+        ",
+    ];
+
+    for create_sql in create_sqls.iter() {
+        tester.run_and_print(create_sql);
+    }
+
+    let insert_sqls = [
+        "INSERT INTO CteUser VALUES (1, \"Alice\"), (2, \"Bob\"), (3, \"Carol\");",
+        "INSERT INTO CteOrder VALUES (1, 1, 100), (2, 1, 50), (3, 2, 30), (4, 3, 10);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "
+            WITH Total AS (
+                SELECT user_id, SUM(amount) AS total FROM CteOrder GROUP BY user_id
+            )
+            SELECT name, total
+            FROM CteUser
+            JOIN Total ON Total.user_id = CteUser.id
+            WHERE total > 20
+            ORDER BY total DESC
+            ",
+            select!(
+                name | total;
+                Str I64;
+                "Alice".to_owned() 150;
+                "Bob".to_owned() 30
+            ),
+        ),
+        (
+            "
+            WITH Big AS (SELECT * FROM CteOrder WHERE amount >= 50),
+                 Small AS (SELECT * FROM CteOrder WHERE amount < 50)
+            SELECT Big.id, Small.id FROM Big, Small WHERE Big.user_id = Small.user_id OR Small.id = 4
+            ",
+            select!(
+                id | id;
+                I64 I64;
+                1 4;
+                2 4
+            ),
+        ),
+        (
+            "
+            WITH Total AS (SELECT user_id, SUM(amount) AS total FROM CteOrder GROUP BY user_id),
+                 Top AS (SELECT user_id FROM Total WHERE total = (SELECT MAX(total) FROM Total))
+            SELECT name FROM CteUser WHERE id IN (SELECT user_id FROM Top)
+            ",
+            select!(name; Str; "Alice".to_owned()),
+        ),
+        (
+            "
+            WITH Person (person_id, person_name) AS (SELECT id, name FROM CteUser)
+            SELECT person_name FROM Person WHERE person_id = 2
+            ",
+            select!(person_name; Str; "Bob".to_owned()),
+        ),
+        (
+            "
+            WITH Rich AS (SELECT user_id FROM CteOrder WHERE amount = 100)
+            SELECT id FROM CteUser WHERE EXISTS (
+                SELECT * FROM Rich WHERE Rich.user_id = CteUser.id
+            )
+            ",
+            select!(id; I64; 1),
+        ),
+        (
+            "
+            WITH Empty AS (SELECT * FROM CteUser WHERE id > 10)
+            SELECT id FROM Empty
+            ",
+            select!(id; I64),
+        ),
+    ];
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    tester.test_error(
+        "WITH CteUser AS (SELECT * FROM CteOrder) SELECT * FROM CteUser",
+        SelectError::CteNameConflictsWithTable("CteUser".to_owned()).into(),
+    );
+}
@@ -0,0 +1,241 @@
+use crate::*;
+
+pub fn derived(mut tester: impl tests::Tester) {
+    let create_sqls: [&str; 2] = [
+        "
+        CREATE TABLE DerivedPlayer (
+            id INTEGER,
+            name TEXT,
+            score INTEGER,
+        );
+    ",
+        "
+        CREATE TABLE DerivedItem (
+            id INTEGER,
+            player_id INTEGER,
+            quantity INTEGER,
+        );
+    ",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO DerivedPlayer (id, name, score) VALUES (1, \"Taehoon\", 30);",
+        "INSERT INTO DerivedPlayer (id, name, score) VALUES (2, \"Mike\", 10);",
+        "INSERT INTO DerivedPlayer (id, name, score) VALUES (3, \"Jorno\", 20);",
+        "INSERT INTO DerivedItem (id, player_id, quantity) VALUES (101, 1, 5);",
+        "INSERT INTO DerivedItem (id, player_id, quantity) VALUES (102, 1, 2);",
+        "INSERT INTO DerivedItem (id, player_id, quantity) VALUES (103, 3, 7);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT * FROM (SELECT id, name FROM DerivedPlayer) AS Sub",
+            select!(
+                id | name;
+                I64 Str;
+                1   "Taehoon".to_owned();
+                2   "Mike".to_owned();
+                3   "Jorno".to_owned()
+            ),
+        ),
+        (
+            "
+            SELECT Sub.name, Sub.doubled
+            FROM (SELECT name, score * 2 AS doubled FROM DerivedPlayer) AS Sub
+            WHERE doubled > 30
+            ORDER BY Sub.doubled
+            ",
+            select!(
+                name | doubled;
+                Str I64;
+                "Jorno".to_owned() 40;
+                "Taehoon".to_owned() 60
+            ),
+        ),
+        (
+            "SELECT num FROM (SELECT id FROM DerivedPlayer WHERE id > 1) AS Sub (num)",
+            select!(num; I64; 2; 3),
+        ),
+        (
+            "
+            SELECT s.name, s.quantity
+            FROM (
+                SELECT p.name, i.quantity
+                FROM DerivedPlayer p
+                JOIN DerivedItem i ON p.id = i.player_id
+            ) AS s
+            WHERE s.quantity > 3
+            ",
+            select!(
+                name | quantity;
+                Str I64;
+                "Taehoon".to_owned() 5;
+                "Jorno".to_owned() 7
+            ),
+        ),
+        (
+            "
+            SELECT p.name, t.total
+            FROM DerivedPlayer p
+            JOIN (
+                SELECT player_id, SUM(quantity) AS total
+                FROM DerivedItem
+                GROUP BY player_id
+            ) AS t ON t.player_id = p.id
+            ",
+            select!(
+                name | total;
+                Str I64;
+                "Taehoon".to_owned() 7;
+                "Jorno".to_owned() 7
+            ),
+        ),
+        (
+            "
+            SELECT COUNT(*)
+            FROM (SELECT id FROM DerivedPlayer UNION ALL SELECT id FROM DerivedItem) AS ids
+            ",
+            select!("COUNT(*)"; I64; 6),
+        ),
+        (
+            "
+            SELECT *, s.score + 1 AS next, name
+            FROM (SELECT id AS player, name, score FROM DerivedPlayer WHERE id = 1) AS s
+            ",
+            select!(
+                player | name | score | next | name;
+                I64 Str I64 I64 Str;
+                1 "Taehoon".to_owned() 30 31 "Taehoon".to_owned()
+            ),
+        ),
+        (
+            "
+            SELECT p.*, s.*
+            FROM DerivedPlayer p
+            JOIN (SELECT id FROM DerivedItem WHERE quantity = 7) AS s (item) ON p.id = 3
+            ",
+            select!(
+                id | name | score | item;
+                I64 Str I64 I64;
+                3 "Jorno".to_owned() 20 103
+            ),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn lateral(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE LateralUser (id INTEGER, name TEXT);",
+        "CREATE TABLE LateralOrder (id INTEGER, user_id INTEGER, amount INTEGER);",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO LateralUser VALUES (1, \"Taehoon\");",
+        "INSERT INTO LateralUser VALUES (2, \"Mike\");",
+        "INSERT INTO LateralUser VALUES (3, \"Jorno\");",
+        "INSERT INTO LateralOrder VALUES (101, 1, 5);",
+        "INSERT INTO LateralOrder VALUES (102, 1, 20);",
+        "INSERT INTO LateralOrder VALUES (103, 3, 7);",
+        "INSERT INTO LateralOrder VALUES (104, 1, 9);",
+        "INSERT INTO LateralOrder VALUES (105, 3, 1);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "
+            SELECT u.name, x.id
+            FROM LateralUser u
+            JOIN LATERAL (
+                SELECT * FROM LateralOrder o WHERE o.user_id = u.id ORDER BY amount DESC LIMIT 1
+            ) x ON true
+            ",
+            select!(
+                name | id;
+                Str I64;
+                "Taehoon".to_owned() 102;
+                "Jorno".to_owned() 103
+            ),
+        ),
+        (
+            "
+            SELECT u.id, x.id
+            FROM LateralUser u
+            JOIN LATERAL (SELECT id FROM LateralOrder WHERE user_id = u.id LIMIT 2) x ON x.id > 101
+            ",
+            select!(id | id; I64 I64; 1 102; 3 103; 3 105),
+        ),
+        (
+            "
+            SELECT u.id, x.id
+            FROM LateralUser u
+            LEFT JOIN LATERAL (SELECT id FROM LateralOrder WHERE user_id = u.id LIMIT 1) x ON true
+            ",
+            select_with_empty!(
+                id | id;
+                I64(1) I64(101);
+                I64(2) Empty;
+                I64(3) I64(103)
+            ),
+        ),
+        (
+            "
+            SELECT u.id, c.orders, c.total
+            FROM LateralUser u,
+                LATERAL (
+                    SELECT COUNT(*) AS orders, SUM(amount) AS total
+                    FROM LateralOrder
+                    WHERE user_id = u.id
+                ) c
+            WHERE c.orders > 0
+            ",
+            select!(id | orders | total; I64 I64 I64; 1 3 34; 3 2 8),
+        ),
+        (
+            "
+            SELECT u.id, x.amount, y.id
+            FROM LateralUser u
+            JOIN LATERAL (
+                SELECT amount FROM LateralOrder WHERE user_id = u.id ORDER BY amount LIMIT 1
+            ) x ON true
+            JOIN LATERAL (SELECT id FROM LateralOrder WHERE amount = x.amount + 4) y ON true
+            ",
+            select!(id | amount | id; I64 I64 I64; 1 5 104; 3 1 101),
+        ),
+    ];
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    tester.test_error(
+        "
+        SELECT * FROM LateralUser u
+        RIGHT JOIN LATERAL (SELECT id FROM LateralOrder WHERE user_id = u.id) x ON true
+        ",
+        JoinError::LateralJoinTypeNotSupported("RIGHT OUTER JOIN".to_owned()).into(),
+    );
+}
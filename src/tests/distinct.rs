@@ -0,0 +1,280 @@
+use crate::*;
+
+pub fn distinct(mut tester: impl tests::Tester) {
+    let create_sql = "
+        CREATE TABLE DistinctUser (
+            id INTEGER,
+            city TEXT NULL,
+            age INTEGER,
+            score FLOAT,
+        );
+    ";
+
+    tester.run_and_print(create_sql);
+
+    let insert_sqls = [
+        "INSERT INTO DistinctUser (id, city, age, score) VALUES (1, \"Seoul\", 20, 1.5);",
+        "INSERT INTO DistinctUser (id, city, age, score) VALUES (2, \"Busan\", 30, 2.0);",
+        "INSERT INTO DistinctUser (id, city, age, score) VALUES (3, \"Seoul\", 30, 1.5);",
+        "INSERT INTO DistinctUser (id, city, age, score) VALUES (4, NULL, 20, 2.0);",
+        "INSERT INTO DistinctUser (id, city, age, score) VALUES (5, \"Jeju\", 20, 3.5);",
+        "INSERT INTO DistinctUser (id, city, age, score) VALUES (6, NULL, 40, 1.5);",
+        "INSERT INTO DistinctUser (id, city, age, score) VALUES (7, \"Busan\", 30, 2.0);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT DISTINCT age FROM DistinctUser",
+            select!(age; I64; 20; 30; 40),
+        ),
+        (
+            "SELECT DISTINCT city FROM DistinctUser",
+            select!(
+                city;
+                OptStr;
+                Some("Seoul".to_owned());
+                Some("Busan".to_owned());
+                None;
+                Some("Jeju".to_owned())
+            ),
+        ),
+        (
+            "SELECT DISTINCT city, age FROM DistinctUser WHERE city IS NOT NULL",
+            select!(
+                city | age;
+                OptStr I64;
+                Some("Seoul".to_owned()) 20;
+                Some("Busan".to_owned()) 30;
+                Some("Seoul".to_owned()) 30;
+                Some("Jeju".to_owned()) 20
+            ),
+        ),
+        (
+            "SELECT DISTINCT score FROM DistinctUser",
+            select!(score; F64; 1.5; 2.0; 3.5),
+        ),
+        (
+            "SELECT DISTINCT age FROM DistinctUser ORDER BY age DESC",
+            select!(age; I64; 40; 30; 20),
+        ),
+        (
+            "SELECT DISTINCT age FROM DistinctUser ORDER BY age LIMIT 2",
+            select!(age; I64; 20; 30),
+        ),
+        (
+            "SELECT DISTINCT age FROM DistinctUser ORDER BY age LIMIT 1 OFFSET 1",
+            select!(age; I64; 30),
+        ),
+        (
+            "SELECT DISTINCT * FROM DistinctUser WHERE id > 6",
+            select!(
+                id | city | age | score;
+                I64 OptStr I64 F64;
+                7 Some("Busan".to_owned()) 30 2.0
+            ),
+        ),
+        (
+            "SELECT age FROM DistinctUser LIMIT 3",
+            select!(age; I64; 20; 30; 30),
+        ),
+        (
+            "
+            SELECT DISTINCT a.age
+            FROM DistinctUser a
+            JOIN DistinctUser b ON a.city = b.city AND a.id < b.id
+            ",
+            select!(age; I64; 20; 30),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn distinct_on(mut tester: impl tests::Tester) {
+    let create_sql = "
+        CREATE TABLE DistinctOnEvent (
+            id INTEGER,
+            user_id INTEGER,
+            kind TEXT,
+            created_at INTEGER,
+        );
+    ";
+
+    tester.run_and_print(create_sql);
+
+    let insert_sqls = [
+        "INSERT INTO DistinctOnEvent VALUES (1, 1, \"login\", 10);",
+        "INSERT INTO DistinctOnEvent VALUES (2, 2, \"login\", 20);",
+        "INSERT INTO DistinctOnEvent VALUES (3, 1, \"click\", 30);",
+        "INSERT INTO DistinctOnEvent VALUES (4, 3, \"login\", 15);",
+        "INSERT INTO DistinctOnEvent VALUES (5, 2, \"logout\", 40);",
+        "INSERT INTO DistinctOnEvent VALUES (6, 1, \"logout\", 25);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "
+            SELECT DISTINCT ON (user_id) user_id, created_at FROM DistinctOnEvent
+            ORDER BY user_id, created_at DESC
+            ",
+            select!(user_id | created_at; I64 I64; 1 30; 2 40; 3 15),
+        ),
+        (
+            "
+            SELECT DISTINCT ON (user_id) id FROM DistinctOnEvent
+            ORDER BY user_id DESC, created_at
+            ",
+            select!(id; I64; 4; 2; 1),
+        ),
+        (
+            "
+            SELECT DISTINCT ON (kind) * FROM DistinctOnEvent
+            WHERE user_id < 3
+            ORDER BY kind, id
+            ",
+            select!(
+                id | user_id | kind | created_at;
+                I64 I64 Str I64;
+                3 1 "click".to_owned() 30;
+                1 1 "login".to_owned() 10;
+                5 2 "logout".to_owned() 40
+            ),
+        ),
+        (
+            "
+            SELECT DISTINCT ON (user_id, kind) user_id, kind FROM DistinctOnEvent
+            ORDER BY kind, user_id
+            ",
+            select!(
+                user_id | kind;
+                I64 Str;
+                1 "click".to_owned();
+                1 "login".to_owned();
+                2 "login".to_owned();
+                3 "login".to_owned();
+                1 "logout".to_owned();
+                2 "logout".to_owned()
+            ),
+        ),
+        (
+            "
+            SELECT DISTINCT ON (user_id) user_id, COUNT(*) FROM DistinctOnEvent
+            GROUP BY user_id
+            ORDER BY user_id
+            LIMIT 2
+            ",
+            select!(user_id | "COUNT(*)"; I64 I64; 1 3; 2 2),
+        ),
+    ];
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        "SELECT DISTINCT ON (user_id) id FROM DistinctOnEvent",
+        "SELECT DISTINCT ON (user_id) id FROM DistinctOnEvent ORDER BY id, user_id",
+        "SELECT DISTINCT ON (user_id, kind) id FROM DistinctOnEvent ORDER BY user_id",
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|sql| tester.test_error(sql, SelectError::DistinctOnNotMatchingOrderBy.into()));
+}
+
+pub fn distinct_null(mut tester: impl tests::Tester) {
+    let create_sqls: [&str; 2] = [
+        "CREATE TABLE DistinctNullA (id INTEGER, code INTEGER NULL, name TEXT NULL);",
+        "CREATE TABLE DistinctNullB (id INTEGER, code INTEGER NULL, name TEXT NULL);",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO DistinctNullA (id, code, name) VALUES (1, NULL, NULL);",
+        "INSERT INTO DistinctNullA (id, code, name) VALUES (1, NULL, NULL);",
+        "INSERT INTO DistinctNullA (id, code, name) VALUES (2, NULL, \"Mike\");",
+        "INSERT INTO DistinctNullA (id, code, name) VALUES (2, NULL, \"Mike\");",
+        "INSERT INTO DistinctNullA (id, code, name) VALUES (3, 30, NULL);",
+        "INSERT INTO DistinctNullB (id, code, name) VALUES (1, NULL, NULL);",
+        "INSERT INTO DistinctNullB (id, code, name) VALUES (3, 30, \"Jorno\");",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT DISTINCT id, code, name FROM DistinctNullA",
+            select!(
+                id | code | name;
+                I64 OptI64 OptStr;
+                1 None None;
+                2 None Some("Mike".to_owned());
+                3 Some(30) None
+            ),
+        ),
+        (
+            "SELECT DISTINCT code FROM DistinctNullA",
+            select!(code; OptI64; None; Some(30)),
+        ),
+        (
+            "SELECT id, code FROM DistinctNullA UNION SELECT id, code FROM DistinctNullB",
+            select!(
+                id | code;
+                I64 OptI64;
+                1 None;
+                2 None;
+                3 Some(30)
+            ),
+        ),
+        (
+            "SELECT id, name FROM DistinctNullA INTERSECT SELECT id, name FROM DistinctNullB",
+            select!(id | name; I64 OptStr; 1 None),
+        ),
+        (
+            "SELECT id, name FROM DistinctNullA EXCEPT SELECT id, name FROM DistinctNullB",
+            select!(
+                id | name;
+                I64 OptStr;
+                2 Some("Mike".to_owned());
+                3 None
+            ),
+        ),
+        // comparing NULLs with `=` is still unknown, only deduplication treats them as equal
+        (
+            "
+            SELECT a.id FROM DistinctNullA a
+            JOIN DistinctNullB b ON a.id = b.id AND a.code = b.code
+            ",
+            select!(id; I64; 3),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
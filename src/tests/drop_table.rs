@@ -22,6 +22,7 @@ CREATE TABLE DropTable (
         (
             "SELECT id, num, name FROM DropTable;",
             Ok(select!(
+                id | num | name;
                 I64 I64 Str;
                 1   2   "Hello".to_owned()
             )),
@@ -34,7 +35,7 @@ CREATE TABLE DropTable (
         (create_sql, Ok(Payload::Create)),
         (
             "SELECT id, num, name FROM DropTable;",
-            Ok(Payload::Select(vec![])),
+            Ok(select!(id | num | name; I64 I64 Str)),
         ),
         (
             "DROP VIEW DropTable;",
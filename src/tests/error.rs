@@ -8,27 +8,60 @@ pub fn error(mut tester: impl tests::Tester) {
         (ExecuteError::QueryNotSupported.into(), "COMMIT;"),
         (StoreError::SchemaNotFound.into(), "SELECT * FROM Nothing;"),
         (
-            SelectError::TooManyTables.into(),
-            "SELECT * FROM TableA, TableB",
+            StoreError::SchemaNotFound.into(),
+            "SELECT * FROM TableA JOIN Nothing ON 1 = 1;",
         ),
         (
-            TableError::TableFactorNotSupported.into(),
+            StoreError::SchemaNotFound.into(),
+            "SELECT * FROM TableA LEFT JOIN TableA a ON 1 = 1 RIGHT JOIN Nothing ON 1 = 1;",
+        ),
+        (
+            StoreError::SchemaNotFound.into(),
+            "SELECT * FROM TableA, Nothing;",
+        ),
+        (
+            StoreError::SchemaNotFound.into(),
             "SELECT * FROM TableA JOIN (SELECT * FROM TableB) as TableC ON 1 = 1",
         ),
         (
-            JoinError::UsingOnJoinNotSupported.into(),
-            "SELECT * FROM TableA JOIN TableA USING (id);",
+            TableError::DerivedTableAliasRequired.into(),
+            "SELECT * FROM TableA JOIN (SELECT * FROM TableA) ON 1 = 1",
+        ),
+        (
+            TableError::TooManyColumnAliases {
+                table: "TableC".to_owned(),
+                expected: 1,
+                found: 2,
+            }
+            .into(),
+            "SELECT * FROM (SELECT id FROM TableA) AS TableC (a, b)",
+        ),
+        (
+            JoinError::UsingColumnNotFound("noname".to_owned()).into(),
+            "SELECT * FROM TableA JOIN TableA a USING (noname);",
+        ),
+        (
+            JoinError::JoinTypeNotSupported("CROSS APPLY".to_owned()).into(),
+            "SELECT * FROM TableA CROSS APPLY TableA as A;",
+        ),
+        (
+            JoinError::JoinTypeNotSupported("OUTER APPLY".to_owned()).into(),
+            "SELECT * FROM TableA OUTER APPLY TableA as A;",
         ),
         (
-            JoinError::JoinTypeNotSupported.into(),
-            "SELECT * FROM TableA CROSS JOIN TableA as A;",
+            EvaluateError::NestedSelectMultipleRows.into(),
+            "SELECT * FROM TableA WHERE id = (SELECT id FROM TableA UNION ALL SELECT id FROM TableA);",
         ),
         (
-            EvaluateError::NestedSelectRowNotFound.into(),
-            "SELECT * FROM TableA WHERE id = (SELECT id FROM TableA WHERE id = 2);",
+            EvaluateError::NestedSelectColumnCountNotOne(2).into(),
+            "SELECT * FROM TableA WHERE id = (SELECT id, id FROM TableA);",
         ),
         (
-            FilterContextError::ValueNotFound.into(),
+            ExecuteError::ColumnNotFound {
+                column: "noname".to_owned(),
+                available: vec!["TableA.id".to_owned()],
+            }
+            .into(),
             "SELECT * FROM TableA WHERE noname = 1;",
         ),
         (
@@ -51,3 +84,98 @@ pub fn error(mut tester: impl tests::Tester) {
         ValueError::SqlTypeNotSupported.into(),
     );
 }
+
+pub fn column_reference(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE RefUser (id INTEGER, name TEXT);",
+        "CREATE TABLE RefItem (id INTEGER, user_id INTEGER);",
+        "CREATE TABLE RefEmpty (id INTEGER);",
+    ];
+
+    for create_sql in create_sqls.iter() {
+        tester.run_and_print(create_sql);
+    }
+
+    let insert_sqls = [
+        "INSERT INTO RefUser VALUES (1, \"Alice\"), (2, \"Bob\");",
+        "INSERT INTO RefItem VALUES (1, 1), (2, 1), (3, 2);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    let test_cases = vec![
+        (2, "SELECT id FROM RefUser JOIN RefItem USING (id)"),
+        (2, "SELECT name AS id FROM RefUser u ORDER BY id"),
+        (2, "SELECT * FROM RefUser u WHERE id IN (SELECT user_id FROM RefItem WHERE user_id = u.id)"),
+        (3, "SELECT i.id FROM RefUser u JOIN RefItem i ON u.id = i.user_id"),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(num, sql)| tester.test_rows(sql, num));
+
+    let user_columns = || vec!["RefUser.id".to_owned(), "RefUser.name".to_owned()];
+    let not_found = |column: &str, available: Vec<String>| {
+        ExecuteError::ColumnNotFound {
+            column: column.to_owned(),
+            available,
+        }
+        .into()
+    };
+
+    let error_cases = vec![
+        (
+            not_found("nothing", vec!["RefEmpty.id".to_owned()]),
+            "SELECT nothing FROM RefEmpty",
+        ),
+        (
+            not_found("nothing", user_columns()),
+            "SELECT id FROM RefUser WHERE nothing IS NULL",
+        ),
+        (
+            not_found("RefUser.nothing", user_columns()),
+            "SELECT COUNT(*) FROM RefUser GROUP BY RefUser.nothing",
+        ),
+        (
+            not_found("nothing", user_columns()),
+            "SELECT id FROM RefUser ORDER BY nothing",
+        ),
+        (
+            not_found(
+                "j.user_id",
+                vec![
+                    "u.id".to_owned(),
+                    "u.name".to_owned(),
+                    "i.id".to_owned(),
+                    "i.user_id".to_owned(),
+                ],
+            ),
+            "
+            SELECT * FROM RefUser u
+            JOIN RefItem i ON u.id = j.user_id
+            JOIN RefItem j ON i.id = j.id
+            ",
+        ),
+        (
+            not_found(
+                "nothing",
+                vec!["RefItem.id".to_owned(), "RefItem.user_id".to_owned()],
+            ),
+            "SELECT * FROM RefUser WHERE id IN (SELECT nothing FROM RefItem)",
+        ),
+        (
+            ExecuteError::AmbiguousColumn("id".to_owned()).into(),
+            "SELECT id FROM RefUser u JOIN RefItem i ON u.id = i.user_id",
+        ),
+        (
+            ExecuteError::AmbiguousColumn("id".to_owned()).into(),
+            "SELECT u.name FROM RefUser u, RefItem i WHERE id = 1",
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(error, sql)| tester.test_error(sql, error));
+}
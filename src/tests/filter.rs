@@ -32,6 +32,20 @@ pub fn filter(mut tester: impl tests::Tester) {
 
     let select_sqls = [
         (3, "SELECT id, name FROM Boss WHERE id BETWEEN 2 AND 4"),
+        (1, "SELECT id, name FROM Boss WHERE id BETWEEN 2 AND 2"),
+        (0, "SELECT id, name FROM Boss WHERE id BETWEEN 4 AND 2"),
+        (5, "SELECT id, name FROM Boss WHERE id NOT BETWEEN 4 AND 2"),
+        (
+            2,
+            "SELECT id, name FROM Boss WHERE strength BETWEEN 10.10 AND 20.20",
+        ),
+        (5, "SELECT id, name FROM Boss WHERE id BETWEEN 1 AND id"),
+        (3, "SELECT id, name FROM Boss WHERE 3 BETWEEN id AND 4"),
+        (
+            3,
+            "SELECT * FROM Boss JOIN Hunter ON Boss.name = Hunter.name
+             WHERE Boss.id BETWEEN Hunter.id + 2 AND Hunter.id + 2",
+        ),
         (
             3,
             "SELECT id, name FROM Boss WHERE name BETWEEN 'Doll' AND 'Gehrman'",
@@ -44,6 +58,34 @@ pub fn filter(mut tester: impl tests::Tester) {
             2,
             "SELECT strength, name FROM Boss WHERE name NOT BETWEEN 'Doll' AND 'Gehrman'",
         ),
+        (
+            2,
+            "SELECT id FROM Boss WHERE id = 1 OR id = 2 AND name = 'Doll'",
+        ),
+        (
+            1,
+            "SELECT id FROM Boss WHERE (id = 1 OR id = 2) AND name = 'Doll'",
+        ),
+        (
+            2,
+            "SELECT id FROM Boss WHERE id = 1 OR (id = 2 AND name = 'Doll')",
+        ),
+        (
+            2,
+            "SELECT id FROM Boss WHERE name = 'Doll' AND id = 2 OR id = 1",
+        ),
+        (
+            1,
+            "SELECT id FROM Boss WHERE name = 'Doll' AND (id = 2 OR id = 1)",
+        ),
+        (
+            3,
+            "SELECT id FROM Boss WHERE id = 1 OR id = 2 AND name = 'Doll' OR id > 4",
+        ),
+        (
+            0,
+            "SELECT id FROM Boss WHERE (id = 1 OR id = 2) AND (name = 'Maria' OR name = 'Gehrman')",
+        ),
         (
             3,
             "SELECT name 
@@ -66,3 +108,439 @@ pub fn filter(mut tester: impl tests::Tester) {
         .iter()
         .for_each(|(num, sql)| tester.test_rows(sql, *num));
 }
+
+pub fn like(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "
+        CREATE TABLE Product (
+            id INTEGER,
+            name TEXT,
+            memo TEXT NULL
+        );",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO Product (id, name, memo) VALUES (1, \"Apple\", \"fruit\");",
+        "INSERT INTO Product (id, name, memo) VALUES (2, \"Avocado\", NULL);",
+        "INSERT INTO Product (id, name, memo) VALUES (3, \"banana\", \"fruit\");",
+        "INSERT INTO Product (id, name, memo) VALUES (4, \"50% off\", \"sale\");",
+        "INSERT INTO Product (id, name, memo) VALUES (5, \"a_b\", NULL);",
+        "INSERT INTO Product (id, name, memo) VALUES (6, \"ab\", \"x\");",
+        "INSERT INTO Product (id, name, memo) VALUES (7, \"Ångström\", NULL);",
+    ];
+
+    insert_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let select_sqls = [
+        (2, "SELECT * FROM Product WHERE name LIKE 'A%'"),
+        (5, "SELECT * FROM Product WHERE name NOT LIKE 'A%'"),
+        (1, "SELECT * FROM Product WHERE name LIKE '%an%'"),
+        (1, "SELECT * FROM Product WHERE name LIKE '_pple'"),
+        (0, "SELECT * FROM Product WHERE name LIKE '_Apple'"),
+        (1, "SELECT * FROM Product WHERE name LIKE 'a_b'"),
+        (1, "SELECT * FROM Product WHERE name LIKE 'a\\_b'"),
+        (2, "SELECT * FROM Product WHERE name LIKE 'a%b'"),
+        (1, "SELECT * FROM Product WHERE name LIKE '%\\%%'"),
+        (1, "SELECT * FROM Product WHERE name LIKE '%o%o%'"),
+        (1, "SELECT * FROM Product WHERE name LIKE 'Å_gstr_m'"),
+        (7, "SELECT * FROM Product WHERE name LIKE '%'"),
+        (0, "SELECT * FROM Product WHERE name LIKE ''"),
+        (1, "SELECT * FROM Product WHERE name LIKE \"Apple\""),
+        (4, "SELECT * FROM Product WHERE memo LIKE '%'"),
+        (2, "SELECT * FROM Product WHERE memo NOT LIKE 'f%'"),
+        (0, "SELECT * FROM Product WHERE name LIKE NULL"),
+        (0, "SELECT * FROM Product WHERE name NOT LIKE NULL"),
+        (4, "SELECT * FROM Product WHERE name ILIKE 'a%'"),
+        (3, "SELECT * FROM Product WHERE name NOT ILIKE 'a%'"),
+        (1, "SELECT * FROM Product WHERE name ilike '%AN%'"),
+        (1, "SELECT * FROM Product WHERE name ILIKE 'A\\_B'"),
+        (1, "SELECT * FROM Product WHERE name ILIKE 'åNGSTRÖM'"),
+        (1, "SELECT * FROM Product WHERE name ILIKE ('APP' || '%')"),
+        (1, "SELECT * FROM Product WHERE name ILIKE 'APP' || '%'"),
+        (
+            2,
+            "SELECT * FROM Product WHERE name ILIKE 'A' || '%' || 'O' || '%' OR id = 6",
+        ),
+        (
+            3,
+            "SELECT * FROM Product WHERE memo ILIKE Product.memo AND name ILIKE '%A%'",
+        ),
+        (0, "SELECT * FROM Product WHERE name ILIKE NULL"),
+    ];
+
+    select_sqls
+        .iter()
+        .for_each(|(num, sql)| tester.test_rows(sql, *num));
+
+    use Value::*;
+
+    let test_cases = vec![(
+        "SELECT id, name ILIKE 'A%' AS a, memo NOT ILIKE 'F' || '%' FROM Product WHERE id < 4",
+        select_with_empty!(
+            id | a | "memo NOT ILIKE 'F' || '%'";
+            I64(1) Bool(true) Bool(false);
+            I64(2) Bool(true) Empty;
+            I64(3) Bool(false) Bool(false)
+        ),
+    )];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+
+    tester.test_error(
+        "SELECT * FROM Product WHERE id LIKE '1%'",
+        FilterError::LikeOnNonString.into(),
+    );
+    tester.test_error(
+        "SELECT * FROM Product WHERE id ILIKE '1%'",
+        FilterError::LikeOnNonString.into(),
+    );
+}
+
+pub fn exists(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE ExistsUser (id INTEGER, name TEXT);",
+        "CREATE TABLE ExistsOrder (id INTEGER, user_id INTEGER);",
+        "CREATE TABLE ExistsEmpty (id INTEGER);",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO ExistsUser (id, name) VALUES (1, \"Taehoon\");",
+        "INSERT INTO ExistsUser (id, name) VALUES (2, \"Mike\");",
+        "INSERT INTO ExistsUser (id, name) VALUES (3, \"Jorno\");",
+        "INSERT INTO ExistsOrder (id, user_id) VALUES (101, 1);",
+        "INSERT INTO ExistsOrder (id, user_id) VALUES (102, 3);",
+        "INSERT INTO ExistsOrder (id, user_id) VALUES (103, 3);",
+    ];
+
+    insert_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "
+            SELECT id FROM ExistsUser u
+            WHERE EXISTS (SELECT 1 FROM ExistsOrder WHERE ExistsOrder.user_id = u.id)
+            ",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "
+            SELECT id FROM ExistsUser u
+            WHERE NOT EXISTS (SELECT 1 FROM ExistsOrder WHERE ExistsOrder.user_id = u.id)
+            ",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM ExistsUser WHERE EXISTS (SELECT id FROM ExistsOrder WHERE id = 102)",
+            select!(id; I64; 1; 2; 3),
+        ),
+        (
+            "SELECT id FROM ExistsUser WHERE EXISTS (SELECT id FROM ExistsEmpty)",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM ExistsUser WHERE NOT EXISTS (SELECT id FROM ExistsEmpty)",
+            select!(id; I64; 1; 2; 3),
+        ),
+        (
+            "
+            SELECT u.id, o.id
+            FROM ExistsUser u
+            JOIN ExistsOrder o ON o.user_id = u.id
+            WHERE NOT EXISTS (
+                SELECT 1 FROM ExistsOrder x WHERE x.user_id = u.id AND x.id < o.id
+            )
+            ",
+            select!(id | id; I64 I64; 1 101; 3 102),
+        ),
+        (
+            "
+            SELECT
+                id,
+                CASE
+                    WHEN EXISTS (SELECT 1 FROM ExistsOrder WHERE user_id = u.id) THEN 'buyer'
+                    ELSE 'visitor'
+                END AS kind
+            FROM ExistsUser u
+            ",
+            select!(
+                id | kind;
+                I64 Str;
+                1   "buyer".to_owned();
+                2   "visitor".to_owned();
+                3   "buyer".to_owned()
+            ),
+        ),
+        (
+            "
+            SELECT id FROM ExistsUser u
+            ORDER BY CASE WHEN EXISTS (SELECT 1 FROM ExistsOrder WHERE user_id = u.id) THEN 1 ELSE 0 END, id DESC
+            ",
+            select!(id; I64; 2; 3; 1),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn boolean(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "CREATE TABLE BoolUser (id INTEGER, active BOOLEAN, verified BOOLEAN NULL, name TEXT);",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO BoolUser (id, active, verified, name) VALUES (1, TRUE, TRUE, \"Taehoon\");",
+        "INSERT INTO BoolUser (id, active, verified, name) VALUES (2, TRUE, FALSE, \"Mike\");",
+        "INSERT INTO BoolUser (id, active, verified, name) VALUES (3, FALSE, NULL, \"Jorno\");",
+        "INSERT INTO BoolUser (id, active, verified, name) VALUES (4, FALSE, TRUE, \"Berry\");",
+    ];
+
+    insert_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT id, active FROM BoolUser WHERE id < 3",
+            select!(id | active; I64 Bool; 1 true; 2 true),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE active",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE active = true",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE NOT active",
+            select!(id; I64; 3; 4),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE active AND verified",
+            select!(id; I64; 1),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE active OR verified",
+            select!(id; I64; 1; 2; 4),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE verified",
+            select!(id; I64; 1; 4),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE NOT verified",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM BoolUser u WHERE u.active AND NOT u.verified",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE TRUE",
+            select!(id; I64; 1; 2; 3; 4),
+        ),
+        ("SELECT id FROM BoolUser WHERE FALSE", select!(id; I64)),
+        ("SELECT id FROM BoolUser WHERE NULL", select!(id; I64)),
+        (
+            "SELECT id FROM BoolUser WHERE (SELECT active FROM BoolUser WHERE id = 4)",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM BoolUser WHERE CASE WHEN id > 2 THEN active ELSE verified END",
+            select!(id; I64; 1),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+
+    tester.test_error(
+        "SELECT id FROM BoolUser WHERE name",
+        FilterError::BooleanValueRequired("name".to_owned()).into(),
+    );
+    tester.test_error(
+        "SELECT id FROM BoolUser WHERE id AND active",
+        FilterError::BooleanValueRequired("id".to_owned()).into(),
+    );
+}
+
+pub fn column_comparison(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "
+        CREATE TABLE Stock (
+            id INTEGER,
+            qty INTEGER,
+            reorder_level INTEGER,
+            price FLOAT,
+            budget FLOAT NULL
+        );",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO Stock VALUES (1, 10, 5, 2.5, 10.0);",
+        "INSERT INTO Stock VALUES (2, 3, 5, 6.0, 10.0);",
+        "INSERT INTO Stock VALUES (3, 5, 5, 4.0, NULL);",
+        "INSERT INTO Stock VALUES (4, 8, 2, 1.5, 3.0);",
+    ];
+
+    insert_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT id FROM Stock WHERE qty > reorder_level",
+            select!(id; I64; 1; 4),
+        ),
+        (
+            "SELECT id FROM Stock WHERE reorder_level >= qty",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM Stock WHERE qty = reorder_level",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM Stock WHERE price * 2 < budget",
+            select!(id; I64; 1),
+        ),
+        (
+            "SELECT id FROM Stock WHERE price * 2 <= budget",
+            select!(id; I64; 1; 4),
+        ),
+        (
+            "SELECT id FROM Stock WHERE qty - reorder_level > price",
+            select!(id; I64; 1; 4),
+        ),
+        (
+            "SELECT id FROM Stock s WHERE s.qty * s.price > s.budget OR s.qty < s.reorder_level",
+            select!(id; I64; 1; 2; 4),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+}
+
+pub fn negation(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "CREATE TABLE Task (id INTEGER, status TEXT, active BOOLEAN, due INTEGER NULL);",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO Task VALUES (1, \"done\", TRUE, 10);",
+        "INSERT INTO Task VALUES (2, \"todo\", FALSE, NULL);",
+        "INSERT INTO Task VALUES (3, \"doing\", TRUE, 5);",
+        "INSERT INTO Task VALUES (4, \"done\", FALSE, NULL);",
+    ];
+
+    insert_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT id FROM Task WHERE NOT (status = 'done')",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT active",
+            select!(id; I64; 2; 4),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT NOT active",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (due IS NULL)",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT due IS NULL",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (due IS NOT NULL)",
+            select!(id; I64; 2; 4),
+        ),
+        // NOT of unknown is still unknown
+        (
+            "SELECT id FROM Task WHERE NOT (due > 7)",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT NOT (due > 7)",
+            select!(id; I64; 1),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (due > 7) OR due IS NULL",
+            select!(id; I64; 2; 3; 4),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (due > 7 AND active)",
+            select!(id; I64; 2; 3; 4),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (due > 7 OR active)",
+            select!(id; I64),
+        ),
+        // negated forms are the negation of their positive forms
+        (
+            "SELECT id FROM Task WHERE status NOT LIKE 'do%'",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (status LIKE 'do%')",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM Task WHERE id NOT IN (1, 3)",
+            select!(id; I64; 2; 4),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (id NOT IN (1, 3))",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE due NOT BETWEEN 6 AND 20",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (due BETWEEN 6 AND 20)",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT EXISTS (SELECT id FROM Task WHERE due > 100)",
+            select!(id; I64; 1; 2; 3; 4),
+        ),
+        // NOT IN cannot be true once the list has NULL, the target is either found or unknown
+        (
+            "SELECT id FROM Task WHERE id NOT IN (1, NULL)",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM Task WHERE id NOT IN (SELECT due FROM Task)",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM Task WHERE NOT (id NOT IN (1, NULL))",
+            select!(id; I64; 1),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+}
@@ -0,0 +1,301 @@
+use crate::*;
+
+pub fn function(mut tester: impl tests::Tester) {
+    let create_sql = "
+        CREATE TABLE Item (
+            id INTEGER,
+            name TEXT,
+            nickname TEXT NULL,
+        );
+    ";
+
+    tester.run_and_print(create_sql);
+
+    let insert_sqls = [
+        "INSERT INTO Item (id, name, nickname) VALUES (1, \"Apple\", \"  red \");",
+        "INSERT INTO Item (id, name, nickname) VALUES (2, \"banana\", NULL);",
+        "INSERT INTO Item (id, name, nickname) VALUES (3, \"Kiwi\", \"Green\");",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT UPPER(name), lower(name) FROM Item",
+            select!(
+                "UPPER(name)" | "lower(name)";
+                Str Str;
+                "APPLE".to_owned() "apple".to_owned();
+                "BANANA".to_owned() "banana".to_owned();
+                "KIWI".to_owned() "kiwi".to_owned()
+            ),
+        ),
+        (
+            "SELECT LENGTH(name), LENGTH(nickname) FROM Item",
+            select!(
+                "LENGTH(name)" | "LENGTH(nickname)";
+                I64 OptI64;
+                5 Some(6);
+                6 None;
+                4 Some(5)
+            ),
+        ),
+        (
+            "SELECT TRIM(nickname) AS a, LTRIM(nickname) AS b, RTRIM(nickname) AS c FROM Item WHERE id = 1",
+            select!(
+                a | b | c;
+                OptStr OptStr OptStr;
+                Some("red".to_owned()) Some("red ".to_owned()) Some("  red".to_owned())
+            ),
+        ),
+        (
+            "SELECT UPPER(nickname) FROM Item WHERE id = 2",
+            select!("UPPER(nickname)"; OptStr; None),
+        ),
+        (
+            "SELECT id FROM Item WHERE LENGTH(name) > 4",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM Item WHERE UPPER(name) = 'KIWI' OR LOWER(nickname) = 'green'",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM Item WHERE UPPER(TRIM(nickname)) = 'RED'",
+            select!(id; I64; 1),
+        ),
+        (
+            "SELECT LENGTH(UPPER('hello')), LENGTH(NULL) FROM Item WHERE id = 1",
+            select!(
+                "LENGTH(UPPER('hello'))" | "LENGTH(NULL)";
+                I64 OptI64;
+                5 None
+            ),
+        ),
+        (
+            "SELECT UPPER(name), COUNT(*) FROM Item GROUP BY name HAVING LENGTH(name) = 4",
+            select!("UPPER(name)" | "COUNT(*)"; Str I64; "KIWI".to_owned() 1),
+        ),
+        (
+            "SELECT id FROM Item ORDER BY LENGTH(name) DESC, id",
+            select!(id; I64; 2; 1; 3),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            EvaluateError::FunctionRequiresStringValue("UPPER".to_owned()).into(),
+            "SELECT UPPER(id) FROM Item",
+        ),
+        (
+            EvaluateError::NumberOfFunctionParamsNotMatching {
+                name: "LOWER".to_owned(),
+                expected: 1,
+                found: 2,
+            }
+            .into(),
+            "SELECT LOWER(name, nickname) FROM Item",
+        ),
+        (
+            AggregateError::UnsupportedAggregation("WHATEVER".to_owned()).into(),
+            "SELECT WHATEVER(name) FROM Item",
+        ),
+        (
+            AggregateError::UnsupportedAggregation("ON".to_owned()).into(),
+            "SELECT ON(id) FROM Item",
+        ),
+        (
+            AggregateError::UnsupportedAggregation("FILTER".to_owned()).into(),
+            "SELECT \"FILTER\"(id = 1) FROM Item",
+        ),
+        (
+            AggregateError::UnsupportedAggregation("->".to_owned()).into(),
+            "SELECT \"->\"(name, 1) FROM Item",
+        ),
+        (
+            AggregateError::UnsupportedAggregation("ILIKE".to_owned()).into(),
+            "SELECT \"ILIKE\"(name) FROM Item",
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(error, sql)| tester.test_error(sql, error));
+
+    assert_eq!(
+        parse("SELECT \"%ON\"(id) FROM Item").err(),
+        Some(ParserError::ParserError(
+            "Names starting with % are reserved, found: \"%ON\"".to_owned()
+        ))
+    );
+}
+
+pub fn coalesce(mut tester: impl tests::Tester) {
+    let create_sqls: [&str; 2] = [
+        "
+        CREATE TABLE Player (
+            id INTEGER,
+            name TEXT,
+        );
+    ",
+        "
+        CREATE TABLE Item (
+            id INTEGER,
+            player_id INTEGER,
+            nickname TEXT NULL,
+            score INTEGER NULL,
+        );
+    ",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO Player (id, name) VALUES (1, \"Taehoon\");",
+        "INSERT INTO Player (id, name) VALUES (2, \"Mike\");",
+        "INSERT INTO Player (id, name) VALUES (3, \"Jorno\");",
+        "INSERT INTO Item (id, player_id, nickname, score) VALUES (101, 1, \"sword\", 10);",
+        "INSERT INTO Item (id, player_id, nickname, score) VALUES (102, 2, NULL, NULL);",
+        "INSERT INTO Item (id, player_id, nickname, score) VALUES (103, 1, NULL, 3);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "
+            SELECT p.id, COALESCE(i.nickname, p.name)
+            FROM Player p
+            LEFT JOIN Item i ON p.id = i.player_id
+            ",
+            select!(
+                id | "COALESCE(i.nickname, p.name)";
+                I64 Str;
+                1   "sword".to_owned();
+                1   "Taehoon".to_owned();
+                2   "Mike".to_owned();
+                3   "Jorno".to_owned()
+            ),
+        ),
+        (
+            "SELECT id, COALESCE(score, 0) AS score FROM Item",
+            select!(id | score; I64 OptI64; 101 Some(10); 102 Some(0); 103 Some(3)),
+        ),
+        (
+            "SELECT COALESCE(NULL, nickname, NULL) FROM Item WHERE id = 102",
+            select_with_empty!("COALESCE(NULL, nickname, NULL)"; Empty),
+        ),
+        (
+            "SELECT COALESCE(score, 1 / 0) FROM Item WHERE id = 101",
+            select!("COALESCE(score, 1 / 0)"; OptI64; Some(10)),
+        ),
+        (
+            "SELECT id FROM Item WHERE COALESCE(score, 0) < 5",
+            select!(id; I64; 102; 103),
+        ),
+        (
+            "SELECT id, NULLIF(score, 10) FROM Item",
+            select_with_empty!(
+                id | "NULLIF(score, 10)";
+                I64(101) Empty;
+                I64(102) OptI64(None);
+                I64(103) OptI64(Some(3))
+            ),
+        ),
+        (
+            "SELECT NULLIF(id, 'abc'), NULLIF(name, 1), NULLIF(name, 'Mike') FROM Player WHERE id = 2",
+            select_with_empty!(
+                "NULLIF(id, 'abc')" | "NULLIF(name, 1)" | "NULLIF(name, 'Mike')";
+                I64(2) Str("Mike".to_owned()) Empty
+            ),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            ValueError::DivisorShouldNotBeZero.into(),
+            "SELECT COALESCE(score, 1 / 0) FROM Item",
+        ),
+        (
+            EvaluateError::NumberOfFunctionParamsNotMatching {
+                name: "NULLIF".to_owned(),
+                expected: 2,
+                found: 1,
+            }
+            .into(),
+            "SELECT NULLIF(id) FROM Item",
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(error, sql)| tester.test_error(sql, error));
+}
+
+pub fn concat(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "CREATE TABLE ConcatUser (id INTEGER, first_name TEXT, last_name TEXT NULL, age INTEGER);",
+    );
+    tester.run_and_print(
+        "
+        INSERT INTO ConcatUser VALUES
+            (1, \"Taehoon\", \"Kim\", 30), (2, \"Mike\", NULL, 20), (3, \"Jorno\", \"Giovanna\", 15);
+        ",
+    );
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT first_name || ' ' || last_name AS full_name FROM ConcatUser",
+            select_with_empty!(
+                full_name;
+                Str("Taehoon Kim".to_owned());
+                Empty;
+                Str("Jorno Giovanna".to_owned())
+            ),
+        ),
+        (
+            "SELECT id FROM ConcatUser WHERE first_name || '-' || last_name = 'Jorno-Giovanna'",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT first_name || age || 1.5 || TRUE AS mixed FROM ConcatUser WHERE id = 1",
+            select!(mixed; Str; "Taehoon301.5TRUE".to_owned()),
+        ),
+        (
+            "SELECT 'a' || NULL AS a, NULL || 'b' AS b, 'a' || 'b' AS ab FROM ConcatUser WHERE id = 1",
+            select_with_empty!(a | b | ab; Empty Empty Str("ab".to_owned())),
+        ),
+        (
+            "SELECT id FROM ConcatUser WHERE last_name || first_name IS NULL",
+            select!(id; I64; 2),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql), "{}", sql));
+}
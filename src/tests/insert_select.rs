@@ -0,0 +1,113 @@
+use crate::*;
+
+pub fn insert_select(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE Sales (id INTEGER, region TEXT, amount INTEGER, price FLOAT NULL);",
+        "CREATE TABLE Summary (region TEXT, total INTEGER);",
+        "CREATE TABLE Price (id INTEGER, price FLOAT NULL, label TEXT NULL);",
+    ];
+
+    for create_sql in create_sqls.iter() {
+        tester.run_and_print(create_sql);
+    }
+
+    let insert_sqls = [
+        "INSERT INTO Sales VALUES (1, \"east\", 10, 1.5);",
+        "INSERT INTO Sales VALUES (2, \"west\", 20, NULL);",
+        "INSERT INTO Sales VALUES (3, \"east\", 5, 2.5);",
+        "INSERT INTO Sales VALUES (4, \"north\", 7, NULL);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    assert_eq!(
+        Ok(Payload::Insert(3)),
+        tester.run(
+            "INSERT INTO Summary SELECT region, SUM(amount) FROM Sales GROUP BY region ORDER BY region"
+        )
+    );
+    assert_eq!(
+        Ok(Payload::Insert(1)),
+        tester.run("INSERT INTO Summary (total, region) SELECT 100, 'all' FROM Sales WHERE id = 1")
+    );
+    assert_eq!(
+        Ok(Payload::Insert(0)),
+        tester.run("INSERT INTO Summary SELECT region, amount FROM Sales WHERE amount > 100")
+    );
+    assert_eq!(
+        tester.run("SELECT region, total FROM Summary ORDER BY region"),
+        Ok(select!(
+            region | total;
+            Str I64;
+            "all".to_owned() 100;
+            "east".to_owned() 15;
+            "north".to_owned() 7;
+            "west".to_owned() 20
+        ))
+    );
+
+    // an integer is stored as a float, and NULL is kept in a nullable column
+    assert_eq!(
+        Ok(Payload::Insert(2)),
+        tester.run("INSERT INTO Price SELECT id, price, region FROM Sales WHERE id > 2")
+    );
+    assert_eq!(
+        Ok(Payload::Insert(1)),
+        tester.run("INSERT INTO Price SELECT id * 10, amount, NULL FROM Sales WHERE id = 1")
+    );
+    assert_eq!(
+        tester.run("SELECT id, price, label FROM Price ORDER BY id"),
+        Ok(select!(
+            id | price | label;
+            I64 OptF64 OptStr;
+            3 Some(2.5) Some("east".to_owned());
+            4 None Some("north".to_owned());
+            10 Some(10.0) None
+        ))
+    );
+
+    let error_cases = vec![
+        (
+            ExecuteError::InsertColumnCountMismatch {
+                expected: 2,
+                found: 3,
+            }
+            .into(),
+            "INSERT INTO Summary SELECT region, amount, id FROM Sales",
+        ),
+        (
+            ExecuteError::InsertColumnCountMismatch {
+                expected: 1,
+                found: 2,
+            }
+            .into(),
+            "INSERT INTO Summary (region) SELECT region, amount FROM Sales",
+        ),
+        (
+            ValueError::IncompatibleDataType {
+                data_type: "INT".to_owned(),
+                value: "Str(\"east\")".to_owned(),
+            }
+            .into(),
+            "INSERT INTO Summary SELECT region, region FROM Sales",
+        ),
+        (
+            ValueError::NullValueOnNotNullField.into(),
+            "INSERT INTO Summary SELECT region, NULL FROM Sales",
+        ),
+        (
+            RowError::LackOfRequiredColumn("region".to_owned()).into(),
+            "INSERT INTO Summary (total) SELECT amount FROM Sales",
+        ),
+    ];
+
+    for (error, sql) in error_cases.into_iter() {
+        tester.test_error(sql, error);
+    }
+
+    tester.test_rows("SELECT * FROM Summary", 4);
+}
@@ -119,6 +119,125 @@ pub fn join(mut tester: impl tests::Tester) {
         (15, "SELECT * FROM Player INNER JOIN Item ON Player.id = Item.player_id;"),
         (25, "SELECT * FROM Player p1 LEFT JOIN Player p2 ON 1 = 1"),
         (30, "SELECT * FROM Item INNER JOIN Item i2 ON i2.id IN (101, 103);"),
+        (15, "SELECT * FROM Item JOIN Player ON Item.player_id = Player.id;"),
+        (15, "SELECT * FROM Item JOIN Player ON player_id = Player.id;"),
+        (7, "SELECT * FROM Item JOIN Player ON (Player.id = Item.player_id AND Player.id = 1);"),
+        (15, "SELECT * FROM Item i1 JOIN Item i2 ON i1.id = i2.id JOIN Player ON i2.player_id = Player.id;"),
+        (23, "SELECT * FROM Item i1 JOIN Item i2 ON i1.player_id = i2.player_id AND i1.quantity = i2.quantity;"),
+        (19, "SELECT * FROM Player JOIN Item ON Player.id = Item.player_id OR Item.quantity = 9;"),
+        (8, "SELECT * FROM Player JOIN Item
+            ON (Player.id = Item.player_id AND Item.quantity > 5) OR Item.id = 101;"),
+        (3, "SELECT * FROM Player JOIN Item
+            ON Player.id = Item.player_id AND (Item.quantity = 9 OR Item.quantity = 8 OR Item.id = 111);"),
+        // one to many tests
+        (7, "SELECT * FROM Player JOIN Item ON Player.id = Item.player_id WHERE Player.id = 1;"),
+        (4, "SELECT * FROM Player JOIN Item ON Item.player_id + 0 = Player.id WHERE Player.id = 3;"),
+        (16, "SELECT * FROM Player LEFT JOIN Item ON Player.id = Item.player_id;"),
+        (16, "SELECT * FROM Player LEFT JOIN Item ON Item.player_id + 0 = Player.id;"),
+        (1, "SELECT * FROM Player LEFT JOIN Item ON Player.id = Item.player_id WHERE Player.id = 4;"),
+        // join conditions referring to the first table or to the outer query
+        (15, "SELECT * FROM Player JOIN Item ON Item.player_id = Player.id JOIN Player p2 ON p2.id = Player.id;"),
+        (16, "SELECT * FROM Player LEFT JOIN Item ON Item.player_id = Player.id LEFT JOIN Player p2 ON p2.id = Player.id;"),
+        (15, "SELECT * FROM Item i1
+            JOIN Player ON Player.id = i1.player_id
+            JOIN Item i2 ON i2.id = i1.id AND i2.player_id + 0 = i1.player_id;"),
+        (4, "SELECT * FROM Player WHERE EXISTS
+            (SELECT * FROM Item i1 JOIN Item i2 ON i2.id = i1.id AND i2.player_id = Player.id);"),
+        // right join tests
+        (15, "SELECT * FROM Player RIGHT JOIN Item ON Player.id = Item.player_id;"),
+        (16, "SELECT * FROM Item RIGHT JOIN Player ON Player.id = Item.player_id;"),
+        (16, "SELECT * FROM Item RIGHT OUTER JOIN Player ON Player.id = Item.player_id;"),
+        (5, "SELECT * FROM Item RIGHT JOIN Player ON 1 = 2;"),
+        (2, "SELECT * FROM Item i RIGHT JOIN Player p ON p.id = i.player_id WHERE p.id = 2;"),
+        (15, "SELECT * FROM Item i1
+            INNER JOIN Player ON Player.id = i1.player_id
+            RIGHT JOIN Item i2 ON i2.id = i1.id;"),
+        (1, "SELECT * FROM Item i RIGHT JOIN Player p ON p.id = i.player_id WHERE p.id = 2 AND i.id = 111;"),
+        (0, "SELECT * FROM Item i RIGHT JOIN Player p ON p.id = i.player_id WHERE i.id = 999;"),
+        (1, "SELECT * FROM Item i RIGHT JOIN Player p ON p.id = i.player_id WHERE i.id IS NULL;"),
+        (15, "SELECT * FROM Item i RIGHT JOIN Player p ON p.id = i.player_id WHERE i.id IS NOT NULL;"),
+        (0, "SELECT * FROM Item i RIGHT JOIN Player p ON p.id = i.player_id WHERE p.id IS NULL;"),
+        // full join tests
+        (16, "SELECT * FROM Item FULL JOIN Player ON Player.id = Item.player_id;"),
+        (16, "SELECT * FROM Player FULL OUTER JOIN Item ON Player.id = Item.player_id;"),
+        (20, "SELECT * FROM Player FULL JOIN Item ON 1 = 2;"),
+        (17, "SELECT * FROM Player FULL JOIN Item ON Player.id = Item.player_id AND Item.quantity = 1;"),
+        // using tests
+        (15, "SELECT * FROM Item JOIN Item i2 USING (id);"),
+        (5, "SELECT * FROM Player p1 JOIN Player p2 USING (id, name);"),
+        (15, "SELECT * FROM Item LEFT JOIN Player USING (id);"),
+        (0, "SELECT * FROM Item JOIN Player USING (id);"),
+        // cross join tests
+        (75, "SELECT * FROM Player, Item;"),
+        (75, "SELECT * FROM Player CROSS JOIN Item;"),
+        (15, "SELECT * FROM Player, Item WHERE Player.id = Item.player_id;"),
+        (15, "SELECT * FROM Player CROSS JOIN Item WHERE Player.id = Item.player_id;"),
+        (6, "SELECT * FROM Player, Item WHERE Player.id = Item.player_id AND Item.quantity > 3;"),
+        (4, "SELECT * FROM Player, Item WHERE Item.player_id = Player.id AND Player.name = 'Taehoon' AND Item.quantity < 5;"),
+        (1, "SELECT * FROM Player, Item WHERE Player.id = Item.player_id AND Player.id + Item.quantity = 9;"),
+        (7, "SELECT * FROM Player p1, Item, Player p2 WHERE p1.id = Item.player_id AND p2.id = p1.id AND p2.id = 1;"),
+        (15, "SELECT * FROM Player, Item
+            RIGHT JOIN Player p2 ON p2.id = Item.player_id
+            WHERE Player.id = Item.player_id;"),
+        (125, "SELECT * FROM Player p1, Player p2, Player p3;"),
+        (5, "SELECT * FROM Player p1, Player p2 WHERE p1.id = p2.id;"),
+        (15, "SELECT * FROM Player p1
+            JOIN Item i ON p1.id = i.player_id,
+            Player p2
+            WHERE p2.id = 1;"),
+    ];
+
+    select_sqls
+        .iter()
+        .for_each(|(num, sql)| tester.test_rows(sql, *num));
+
+    tester.run_and_print("CREATE TABLE Ratio (id INTEGER, ratio FLOAT);");
+    tester.run_and_print("INSERT INTO Ratio (id, ratio) VALUES (1, 0.5), (2, 1.5), (3, 0.5);");
+    tester.run_and_print("CREATE TABLE NullKey (id INTEGER, num INTEGER NULL);");
+    tester.run_and_print("INSERT INTO NullKey (id, num) VALUES (1, 1), (2, NULL), (3, NULL);");
+
+    let select_sqls = [
+        (
+            5,
+            "SELECT * FROM Ratio r1 JOIN Ratio r2 ON r1.ratio = r2.ratio;",
+        ),
+        (
+            1,
+            "SELECT * FROM NullKey n1 JOIN NullKey n2 ON n1.num = n2.num;",
+        ),
+        (
+            3,
+            "SELECT * FROM NullKey n1 LEFT JOIN NullKey n2 ON n1.num = n2.num;",
+        ),
+    ];
+
+    select_sqls
+        .iter()
+        .for_each(|(num, sql)| tester.test_rows(sql, *num));
+
+    tester.run_and_print("CREATE TABLE EmptyA (id INTEGER);");
+    tester.run_and_print("CREATE TABLE EmptyB (id INTEGER);");
+
+    let select_sqls = [
+        (
+            0,
+            "SELECT * FROM EmptyA FULL JOIN EmptyB ON EmptyA.id = EmptyB.id;",
+        ),
+        (
+            5,
+            "SELECT * FROM EmptyA FULL JOIN Player ON EmptyA.id = Player.id;",
+        ),
+        (
+            5,
+            "SELECT * FROM Player FULL JOIN EmptyA ON EmptyA.id = Player.id;",
+        ),
+        (
+            5,
+            "SELECT * FROM EmptyA RIGHT JOIN Player ON EmptyA.id = Player.id;",
+        ),
+        (0, "SELECT * FROM Player, EmptyA;"),
+        (0, "SELECT * FROM EmptyA, Player, Item;"),
+        (0, "SELECT * FROM Player CROSS JOIN Item CROSS JOIN EmptyA;"),
     ];
 
     select_sqls
@@ -172,6 +291,7 @@ pub fn blend(mut tester: impl tests::Tester) {
     ";
     let found = tester.run(sql).expect("select");
     let expected = select_with_empty!(
+        id | id;
         I64(1) I64(101);
         I64(2) I64(102);
         I64(3) Empty;
@@ -188,6 +308,7 @@ pub fn blend(mut tester: impl tests::Tester) {
     ";
     let found = tester.run(sql).expect("select");
     let expected = select_with_empty!(
+        id | player_id;
         I64(1) I64(1);
         I64(2) I64(2);
         I64(3) Empty;
@@ -204,6 +325,7 @@ pub fn blend(mut tester: impl tests::Tester) {
     ";
     let found = tester.run(sql).expect("select");
     let expected = select_with_empty!(
+        id | quantity | player_id;
         I64(101) I64(1) I64(1);
         I64(102) I64(4) I64(2);
         Empty    Empty  Empty;
@@ -220,6 +342,7 @@ pub fn blend(mut tester: impl tests::Tester) {
     ";
     let found = tester.run(sql).expect("select");
     let expected = select_with_empty!(
+        id | name | id | quantity | player_id;
         I64(1) Str("Taehoon".to_owned()) I64(101) I64(1) I64(1);
         I64(2) Str("Mike".to_owned())    I64(102) I64(4) I64(2);
         I64(3) Str("Jorno".to_owned())   Empty    Empty  Empty;
@@ -227,4 +350,651 @@ pub fn blend(mut tester: impl tests::Tester) {
         I64(5) Str("Hwan".to_owned())    Empty    Empty  Empty
     );
     assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id, i.id
+        FROM Item i
+        RIGHT JOIN Player p
+        ON p.id = i.player_id
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | id;
+        I64(1) I64(101);
+        I64(2) I64(102);
+        I64(3) Empty;
+        I64(4) I64(103);
+        I64(5) Empty
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT *
+        FROM Item
+        RIGHT JOIN Player p
+        ON p.id = player_id
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | quantity | player_id | id | name;
+        I64(101) I64(1) I64(1) I64(1) Str("Taehoon".to_owned());
+        I64(102) I64(4) I64(2) I64(2) Str("Mike".to_owned());
+        Empty    Empty  Empty  I64(3) Str("Jorno".to_owned());
+        I64(103) I64(9) I64(4) I64(4) Str("Berry".to_owned());
+        Empty    Empty  Empty  I64(5) Str("Hwan".to_owned())
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id, i.id, p2.id
+        FROM Player p
+        JOIN Item i ON p.id = i.player_id
+        RIGHT JOIN Player p2 ON p2.id = p.id
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | id | id;
+        I64(1) I64(101) I64(1);
+        I64(2) I64(102) I64(2);
+        Empty  Empty    I64(3);
+        I64(4) I64(103) I64(4);
+        Empty  Empty    I64(5)
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id, i.id
+        FROM Player p
+        FULL JOIN Item i
+        ON p.id = i.player_id AND i.id > 101
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | id;
+        I64(1) Empty;
+        I64(2) I64(102);
+        I64(3) Empty;
+        I64(4) I64(103);
+        I64(5) Empty;
+        Empty  I64(101)
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id, p2.name
+        FROM Player p
+        LEFT JOIN Item i ON p.id = i.player_id
+        LEFT JOIN Player p2 ON p2.id = i.player_id
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | name;
+        I64(1) Str("Taehoon".to_owned());
+        I64(2) Str("Mike".to_owned());
+        I64(3) Empty;
+        I64(4) Str("Berry".to_owned());
+        I64(5) Empty
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id, p2.name
+        FROM Player p
+        LEFT JOIN Item i ON p.id = i.player_id
+        LEFT JOIN Player p2 ON p2.id = i.player_id OR p2.id = i.quantity
+        WHERE p.id < 4
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | name;
+        I64(1) Str("Taehoon".to_owned());
+        I64(2) Str("Mike".to_owned());
+        I64(2) Str("Berry".to_owned());
+        I64(3) Empty
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id
+        FROM Player p
+        LEFT JOIN Item i ON p.id = i.player_id
+        WHERE i.id IS NULL
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(id; I64; 3; 5);
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id
+        FROM Player p
+        LEFT JOIN Item i ON p.id = i.player_id
+        WHERE i.id IS NOT NULL AND p.name IS NOT NULL
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(id; I64; 1; 2; 4);
+    assert_eq!(expected, found);
+
+    tester.run_and_print("CREATE TABLE Membership (player_id INTEGER, active BOOLEAN);");
+    tester.run_and_print("INSERT INTO Membership (player_id, active) VALUES (1, true);");
+    tester.run_and_print("INSERT INTO Membership (player_id, active) VALUES (2, false);");
+    tester.run_and_print("INSERT INTO Membership (player_id, active) VALUES (4, true);");
+
+    let sql = "
+        SELECT p.name
+        FROM Player p
+        JOIN Membership m ON p.id = m.player_id
+        WHERE m.active = true
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(name; Str; "Taehoon".to_owned(); "Berry".to_owned());
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.name, i.id
+        FROM Player p
+        LEFT JOIN Item i ON p.id = i.player_id
+        LEFT JOIN Membership m ON p.id = m.player_id
+        WHERE active = false OR i.quantity > 5
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(
+        name | id;
+        Str I64;
+        "Mike".to_owned() 102;
+        "Berry".to_owned() 103
+    );
+    assert_eq!(expected, found);
+
+    tester.run_and_print("CREATE TABLE Team (player_id INTEGER, name TEXT);");
+    tester.run_and_print("INSERT INTO Team (player_id, name) VALUES (1, \"Red\");");
+    tester.run_and_print("INSERT INTO Team (player_id, name) VALUES (2, \"Blue\");");
+    tester.run_and_print("INSERT INTO Team (player_id, name) VALUES (5, \"Green\");");
+
+    let sql = "SELECT * FROM Item JOIN Team USING (player_id)";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(
+        id | quantity | player_id | name;
+        I64 I64 I64 Str;
+        101 1   1   "Red".to_owned();
+        102 4   2   "Blue".to_owned()
+    );
+    assert_eq!(expected, found);
+
+    let sql = "SELECT * FROM Item LEFT JOIN Team USING (player_id)";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | quantity | player_id | name;
+        I64(101) I64(1) I64(1) Str("Red".to_owned());
+        I64(102) I64(4) I64(2) Str("Blue".to_owned());
+        I64(103) I64(9) I64(4) Empty
+    );
+    assert_eq!(expected, found);
+
+    let sql = "SELECT * FROM Item RIGHT JOIN Team USING (player_id)";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | quantity | player_id | name;
+        I64(101) I64(1) I64(1) Str("Red".to_owned());
+        I64(102) I64(4) I64(2) Str("Blue".to_owned());
+        Empty    Empty  I64(5) Str("Green".to_owned())
+    );
+    assert_eq!(expected, found);
+
+    let sql = "SELECT * FROM Item FULL JOIN Team USING (player_id) ORDER BY 3 DESC";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | quantity | player_id | name;
+        Empty    Empty  I64(5) Str("Green".to_owned());
+        I64(103) I64(9) I64(4) Empty;
+        I64(102) I64(4) I64(2) Str("Blue".to_owned());
+        I64(101) I64(1) I64(1) Str("Red".to_owned())
+    );
+    assert_eq!(expected, found);
+
+    let sql = "SELECT Team.*, Item.id FROM Item JOIN Team USING (player_id)";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(
+        player_id | name | id;
+        I64 Str               I64;
+        1   "Red".to_owned()  101;
+        2   "Blue".to_owned() 102
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p.id, t.name
+        FROM Player p
+        JOIN Item i ON p.id = i.player_id
+        JOIN Team t USING (player_id)
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(
+        id | name;
+        I64 Str;
+        1   "Red".to_owned();
+        2   "Blue".to_owned()
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT p1.id, p2.id, i.id
+        FROM Player p1, Player p2, Item i
+        WHERE p1.id = 1 AND p2.id < 3 AND i.id = 101
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(
+        id | id | id;
+        I64 I64 I64;
+        1   1   101;
+        1   2   101
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT *
+        FROM Player p
+        JOIN Item i ON p.id = i.player_id
+        LEFT JOIN Player p2 ON p2.id = i.quantity
+        WHERE p.id < 4
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select_with_empty!(
+        id | name | id | quantity | player_id | id | name;
+        I64(1) Str("Taehoon".to_owned()) I64(101) I64(1) I64(1) I64(1) Str("Taehoon".to_owned());
+        I64(2) Str("Mike".to_owned())    I64(102) I64(4) I64(2) I64(4) Str("Berry".to_owned())
+    );
+    assert_eq!(expected, found);
+
+    let sql = "
+        SELECT *
+        FROM Item i, Player p, Item i2
+        WHERE i.id = 103 AND p.id = i.player_id AND i2.id = 101
+    ";
+    let found = tester.run(sql).expect("select");
+    let expected = select!(
+        id | quantity | player_id | id | name | id | quantity | player_id;
+        I64 I64 I64 I64 Str I64 I64 I64;
+        103 9 4 4 "Berry".to_owned() 101 1 1
+    );
+    assert_eq!(expected, found);
+
+    tester.test_columns(
+        "SELECT * FROM Player p JOIN Item i ON p.id = i.player_id JOIN Player p2 ON p2.id = p.id",
+        7,
+    );
+}
+
+pub fn self_join(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "
+        CREATE TABLE Employee (
+            id INTEGER,
+            name TEXT,
+            manager_id INTEGER NULL,
+        );
+    ",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO Employee (id, name, manager_id) VALUES (1, \"Taehoon\", NULL);",
+        "INSERT INTO Employee (id, name, manager_id) VALUES (2, \"Mike\", 1);",
+        "INSERT INTO Employee (id, name, manager_id) VALUES (3, \"Jorno\", 1);",
+        "INSERT INTO Employee (id, name, manager_id) VALUES (4, \"Berry\", 3);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "
+            SELECT e.name, m.name
+            FROM Employee e
+            JOIN Employee m ON e.manager_id = m.id
+            ",
+            select!(
+                name | name;
+                Str Str;
+                "Mike".to_owned() "Taehoon".to_owned();
+                "Jorno".to_owned() "Taehoon".to_owned();
+                "Berry".to_owned() "Jorno".to_owned()
+            ),
+        ),
+        (
+            "
+            SELECT e.id, m.id
+            FROM Employee e
+            LEFT JOIN Employee m ON e.manager_id = m.id
+            WHERE m.name = 'Taehoon' OR e.id = 1
+            ",
+            select_with_empty!(
+                id | id;
+                I64(1) Empty;
+                I64(2) I64(1);
+                I64(3) I64(1)
+            ),
+        ),
+        (
+            "
+            SELECT e.name, b.name
+            FROM Employee e
+            JOIN Employee m ON e.manager_id = m.id
+            JOIN Employee b ON m.manager_id = b.id
+            ",
+            select!(name | name; Str Str; "Berry".to_owned() "Taehoon".to_owned()),
+        ),
+        (
+            "SELECT m.name FROM Employee e JOIN Employee m ON m.id = e.manager_id WHERE e.id = 4",
+            select!(name; Str; "Jorno".to_owned()),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            SelectError::DuplicateTableAlias("Employee".to_owned()).into(),
+            "SELECT * FROM Employee JOIN Employee ON Employee.id = Employee.manager_id",
+        ),
+        (
+            SelectError::DuplicateTableAlias("e".to_owned()).into(),
+            "SELECT * FROM Employee e JOIN Employee e ON e.id = e.manager_id",
+        ),
+        (
+            SelectError::DuplicateTableAlias("Employee".to_owned()).into(),
+            "SELECT * FROM Employee e, Employee Employee, Employee",
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(error, sql)| tester.test_error(sql, error));
+}
+
+pub fn ambiguous_column(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE AmbUser (id INTEGER, name TEXT);",
+        "CREATE TABLE AmbItem (id INTEGER, user_id INTEGER, name TEXT);",
+    ];
+
+    for create_sql in create_sqls.iter() {
+        tester.run_and_print(create_sql);
+    }
+
+    let insert_sqls = [
+        "INSERT INTO AmbUser VALUES (1, \"Alice\"), (2, \"Bob\");",
+        "INSERT INTO AmbItem VALUES (10, 1, \"Pen\"), (20, 2, \"Cup\"), (30, 1, \"Mug\");",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "
+            SELECT u.id, i.id, user_id
+            FROM AmbUser u
+            JOIN AmbItem i ON u.id = i.user_id
+            ORDER BY i.id
+            ",
+            select!(
+                id | id | user_id;
+                I64 I64 I64;
+                1 10 1;
+                2 20 2;
+                1 30 1
+            ),
+        ),
+        (
+            "
+            SELECT AmbUser.name, AmbItem.name
+            FROM AmbUser
+            JOIN AmbItem ON AmbUser.id = AmbItem.user_id
+            WHERE AmbItem.id = 20
+            ",
+            select!(name | name; Str Str; "Bob".to_owned() "Cup".to_owned()),
+        ),
+        (
+            "SELECT id FROM AmbUser WHERE EXISTS (SELECT * FROM AmbItem WHERE id = 20)",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "
+            SELECT id FROM AmbUser
+            WHERE 2 = (SELECT COUNT(*) FROM AmbItem WHERE user_id = AmbUser.id AND id > 5)
+            ",
+            select!(id; I64; 1),
+        ),
+        (
+            "
+            SELECT id, (
+                SELECT id FROM AmbItem WHERE user_id = AmbUser.id ORDER BY id DESC LIMIT 1
+            ) AS last_item
+            FROM AmbUser
+            ",
+            select!(id | last_item; I64 I64; 1 30; 2 20),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            ExecuteError::AmbiguousColumn("id".to_owned()).into(),
+            "SELECT id FROM AmbUser JOIN AmbItem ON AmbUser.id = AmbItem.user_id",
+        ),
+        (
+            ExecuteError::AmbiguousColumn("name".to_owned()).into(),
+            "SELECT AmbUser.id FROM AmbUser, AmbItem WHERE name = 'Pen'",
+        ),
+        (
+            ExecuteError::AmbiguousColumn("name".to_owned()).into(),
+            "SELECT u.id FROM AmbUser u JOIN AmbItem i ON u.id = i.user_id ORDER BY name",
+        ),
+        (
+            ExecuteError::AmbiguousColumn("id".to_owned()).into(),
+            "SELECT i.name FROM AmbUser u JOIN AmbItem i ON id = i.user_id",
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(error, sql)| tester.test_error(sql, error));
+}
+
+pub fn join_order(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE OrderTeam (id INTEGER, name TEXT, rate FLOAT);",
+        "CREATE TABLE OrderMember (id INTEGER, team_id INTEGER, rate FLOAT);",
+        "CREATE TABLE OrderTask (id INTEGER, member_id INTEGER);",
+    ];
+
+    for create_sql in create_sqls.iter() {
+        tester.run_and_print(create_sql);
+    }
+
+    let insert_sqls = [
+        "INSERT INTO OrderTeam VALUES (2, \"Blue\", 2.0), (1, \"Red\", 1.5);",
+        "
+        INSERT INTO OrderMember VALUES
+            (10, 1, 1.5), (11, 2, 2.0), (12, 1, 1.5), (13, 3, 1.0), (14, 2, 2.0), (15, 1, 1.0);
+        ",
+        "
+        INSERT INTO OrderTask VALUES
+            (100, 12), (101, 11), (102, 15), (103, 10), (104, 13), (105, 10), (106, 14),
+            (107, 11);
+        ",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT t.name, m.id FROM OrderTeam t JOIN OrderMember m ON t.id = m.team_id",
+            select!(
+                name | id;
+                Str I64;
+                "Blue".to_owned() 11;
+                "Blue".to_owned() 14;
+                "Red".to_owned() 10;
+                "Red".to_owned() 12;
+                "Red".to_owned() 15
+            ),
+        ),
+        (
+            "
+            SELECT t.id, m.id, k.id
+            FROM OrderTeam t
+            JOIN OrderMember m ON m.team_id = t.id AND m.id > 10
+            JOIN OrderTask k ON k.member_id = m.id
+            WHERE k.id <> 101
+            ",
+            select!(
+                id | id | id;
+                I64 I64 I64;
+                2 11 107;
+                2 14 106;
+                1 12 100;
+                1 15 102
+            ),
+        ),
+        (
+            "SELECT t.id, m.id FROM OrderTeam t JOIN OrderMember m ON t.rate = m.rate",
+            select!(
+                id | id;
+                I64 I64;
+                2 11;
+                2 14;
+                1 10;
+                1 12
+            ),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn nested_join(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE NestA (id INTEGER, name TEXT);",
+        "CREATE TABLE NestB (id INTEGER, a_id INTEGER);",
+        "CREATE TABLE NestC (id INTEGER, b_id INTEGER);",
+    ];
+
+    for create_sql in create_sqls.iter() {
+        tester.run_and_print(create_sql);
+    }
+
+    let insert_sqls = [
+        "INSERT INTO NestA VALUES (1, \"a1\"), (2, \"a2\"), (3, \"a3\");",
+        "INSERT INTO NestB VALUES (10, 1), (11, 1), (12, 2), (13, 4);",
+        "INSERT INTO NestC VALUES (100, 10), (101, 12), (102, 12), (103, 14);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    // the right side joined in parentheses gives the same rows as joining one by one
+    let right_nested = run("
+        SELECT a.id, b.id, c.id FROM NestA a
+        JOIN (NestB b JOIN NestC c ON c.b_id = b.id) ON b.a_id = a.id
+        ORDER BY c.id
+    ");
+    let left_nested = run("
+        SELECT a.id, b.id, c.id FROM NestA a
+        JOIN NestB b ON b.a_id = a.id
+        JOIN NestC c ON c.b_id = b.id
+        ORDER BY c.id
+    ");
+    let expected = select!(
+        id | id | id;
+        I64 I64 I64;
+        1 10 100;
+        2 12 101;
+        2 12 102
+    );
+
+    assert_eq!(expected, right_nested);
+    assert_eq!(expected, left_nested);
+
+    let test_cases = vec![
+        (
+            "
+            SELECT * FROM (NestA a JOIN NestB b ON b.a_id = a.id)
+            JOIN NestC c ON c.b_id = b.id
+            ORDER BY c.id
+            ",
+            select!(
+                id | name | id | a_id | id | b_id;
+                I64 Str I64 I64 I64 I64;
+                1 "a1".to_owned() 10 1 100 10;
+                2 "a2".to_owned() 12 2 101 12;
+                2 "a2".to_owned() 12 2 102 12
+            ),
+        ),
+        (
+            "
+            SELECT a.id, b.id, c.id FROM NestA a
+            LEFT JOIN (NestB b JOIN NestC c ON c.b_id = b.id) ON b.a_id = a.id
+            ORDER BY a.id, c.id
+            ",
+            select_with_empty!(
+                id | id | id;
+                I64(1) I64(10) I64(100);
+                I64(2) I64(12) I64(101);
+                I64(2) I64(12) I64(102);
+                I64(3) Empty Empty
+            ),
+        ),
+        (
+            "
+            SELECT a.name, c.* FROM NestA a
+            JOIN (NestB JOIN NestC c ON c.b_id = NestB.id) ON NestB.a_id = a.id
+            WHERE NestB.id = 12
+            ORDER BY c.id
+            ",
+            select!(
+                name | id | b_id;
+                Str I64 I64;
+                "a2".to_owned() 101 12;
+                "a2".to_owned() 102 12
+            ),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql), "{}", sql));
+
+    tester.test_error(
+        "SELECT * FROM NestA a JOIN (NestB a JOIN NestC c ON c.b_id = a.id) ON a.id = 1",
+        SelectError::DuplicateTableAlias("a".to_owned()).into(),
+    );
 }
@@ -5,30 +5,43 @@ macro_rules! row {
     )
 }
 
+/// Labels are separated by `|`, use a string literal for a label which is not an identifier.
+///
+/// ```ignore
+/// select!(id | "COUNT(*)"; I64 I64; 1 3; 2 5)
+/// ```
 #[macro_export]
 macro_rules! select {
-    () => (
-        Payload::Select(vec![])
+    ( @label $c: ident ) => (
+        stringify!($c).to_owned()
     );
-    ( $( $t:path )*) => (
-        select!()
+    ( @label $c: literal ) => (
+        $c.to_owned()
     );
-    ( $( $t:path )* ;) => (
-        select!()
+    ( $( $c: tt )|+ ; $( $t:path )* ) => (
+        Payload::Select {
+            labels: vec![$( select!(@label $c) ),+],
+            rows: vec![],
+        }
     );
-    ( $( $t:path )* ; $( $v: expr )* ) => (
-        Payload::Select(vec![
-            row!($( $t )* ; $( $v )* )
-        ])
+    ( $( $c: tt )|+ ; $( $t:path )* ;) => (
+        select!($( $c )|+ ; $( $t )*)
     );
-    ( $( $t:path )* ; $( $v: expr )* ; $( $( $v2: expr )* );*) => ({
+    ( $( $c: tt )|+ ; $( $t:path )* ; $( $v: expr )* ) => (
+        Payload::Select {
+            labels: vec![$( select!(@label $c) ),+],
+            rows: vec![row!($( $t )* ; $( $v )* )],
+        }
+    );
+    ( $( $c: tt )|+ ; $( $t:path )* ; $( $v: expr )* ; $( $( $v2: expr )* );*) => ({
         let mut rows = vec![
             row!($( $t )* ; $( $v )*),
         ];
 
-        Payload::Select(
-            concat_with!(rows ; $( $t )* ; $( $( $v2 )* );*)
-        )
+        Payload::Select {
+            labels: vec![$( select!(@label $c) ),+],
+            rows: concat_with!(rows ; $( $t )* ; $( $( $v2 )* );*),
+        }
     });
 }
 
@@ -48,19 +61,21 @@ macro_rules! concat_with {
 
 #[macro_export]
 macro_rules! select_with_empty {
-    ( $( $v: expr )* ) => (
-        Payload::Select(vec![
-            Row(vec![$( $v ),*])
-        ])
+    ( $( $c: tt )|+ ; $( $v: expr )* ) => (
+        Payload::Select {
+            labels: vec![$( select!(@label $c) ),+],
+            rows: vec![Row(vec![$( $v ),*])],
+        }
     );
-    ( $( $v: expr )* ; $( $( $v2: expr )* );*) => ({
+    ( $( $c: tt )|+ ; $( $v: expr )* ; $( $( $v2: expr )* );*) => ({
         let mut rows = vec![
             Row(vec![$( $v ),*])
         ];
 
-        Payload::Select(
-            concat_with_empty!(rows ; $( $( $v2 )* );*)
-        )
+        Payload::Select {
+            labels: vec![$( select!(@label $c) ),+],
+            rows: concat_with_empty!(rows ; $( $( $v2 )* );*),
+        }
     });
 }
 
@@ -35,11 +35,11 @@ CREATE TABLE Test (
         ),
         (
             JoinError::NaturalOnJoinNotSupported.into(),
-            "SELECT * FROM Test NATURAL JOIN Test",
+            "SELECT * FROM Test NATURAL JOIN Test t",
         ),
         (
-            TableError::TableFactorNotSupported.into(),
-            "SELECT * FROM (SELECT * FROM Test) as A;",
+            TableError::DerivedTableAliasRequired.into(),
+            "SELECT * FROM (SELECT * FROM Test);",
         ),
     ];
 
@@ -55,6 +55,7 @@ CREATE TABLE Test (
         .run("SELECT id, num, name FROM Test")
         .expect("select");
     let expected = select!(
+        id | num | name;
         I64 I64 Str;
         1   2   "Hello".to_owned();
         1   9   "World".to_owned();
@@ -66,6 +67,7 @@ CREATE TABLE Test (
         .run("SELECT id, num, name FROM Test WHERE id = 1")
         .expect("select");
     let expected = select!(
+        id | num | name;
         I64 I64 Str;
         1   2   "Hello".to_owned();
         1   9   "World".to_owned()
@@ -78,6 +80,7 @@ CREATE TABLE Test (
         .run("SELECT id, num, name FROM Test")
         .expect("select");
     let expected = select!(
+        id | num | name;
         I64 I64 Str;
         2   2   "Hello".to_owned();
         2   9   "World".to_owned();
@@ -86,16 +89,16 @@ CREATE TABLE Test (
     assert_eq!(expected, found);
 
     let found = tester.run("SELECT id FROM Test").expect("select");
-    let expected = select!(I64; 2; 2; 2);
+    let expected = select!(id; I64; 2; 2; 2);
     assert_eq!(expected, found);
 
     let found = tester.run("SELECT id, num FROM Test").expect("select");
-    let expected = select!(I64 I64; 2 2; 2 9; 2 4);
+    let expected = select!(id | num; I64 I64; 2 2; 2 9; 2 4);
     assert_eq!(expected, found);
 
     let found = tester
         .run("SELECT id, num FROM Test LIMIT 1 OFFSET 1")
         .expect("select");
-    let expected = select!(I64 I64; 2 9);
+    let expected = select!(id | num; I64 I64; 2 9);
     assert_eq!(expected, found);
 }
@@ -2,17 +2,29 @@ pub mod aggregate;
 pub mod arithmetic;
 pub mod basic;
 pub mod blend;
+pub mod case;
+pub mod cast;
+pub mod constant;
+pub mod cte;
+pub mod derived;
+pub mod distinct;
 pub mod drop_table;
 pub mod error;
 pub mod filter;
+pub mod function;
+pub mod insert_select;
 pub mod join;
 pub mod migrate;
 pub mod nested_select;
 pub mod nullable;
+pub mod order_by;
 pub mod ordering;
+pub mod projection;
 pub mod sql_types;
 pub mod synthesize;
 mod tester;
+pub mod union;
+pub mod window;
 
 pub mod macros;
 
@@ -36,20 +48,65 @@ macro_rules! generate_tests {
         glue!(basic, basic::basic);
         glue!(aggregate, aggregate::aggregate);
         glue!(aggregate_group_by, aggregate::group_by);
+        glue!(aggregate_distinct, aggregate::distinct);
+        glue!(aggregate_group_concat, aggregate::group_concat);
+        glue!(aggregate_stream, aggregate::stream);
+        glue!(aggregate_filter, aggregate::filter);
+        glue!(aggregate_rollup, aggregate::rollup);
+        glue!(aggregate_expression, aggregate::expression);
+        glue!(aggregate_null, aggregate::null);
         glue!(arithmetic, arithmetic::arithmetic);
         glue!(arithmetic_blend, arithmetic::blend);
+        glue!(arithmetic_division, arithmetic::division);
         glue!(blend, blend::blend);
+        glue!(case, case::case);
+        glue!(cast, cast::cast);
+        glue!(constant, constant::constant);
+        glue!(cte, cte::cte);
+        glue!(derived, derived::derived);
+        glue!(derived_lateral, derived::lateral);
+        glue!(distinct, distinct::distinct);
+        glue!(distinct_on, distinct::distinct_on);
+        glue!(distinct_null, distinct::distinct_null);
         glue!(drop_table, drop_table::drop_table);
         glue!(error, error::error);
+        glue!(error_column_reference, error::column_reference);
         glue!(join, join::join);
         glue!(join_blend, join::blend);
+        glue!(join_self_join, join::self_join);
+        glue!(join_ambiguous_column, join::ambiguous_column);
+        glue!(join_join_order, join::join_order);
+        glue!(join_nested_join, join::nested_join);
         glue!(migrate, migrate::migrate);
         glue!(nested_select, nested_select::nested_select);
+        glue!(nested_select_scalar, nested_select::scalar_subquery);
+        glue!(nested_select_correlated, nested_select::correlated_subquery);
+        glue!(nested_select_semi_join, nested_select::semi_join);
+        glue!(nested_select_quantified, nested_select::quantified);
         glue!(nullable, nullable::nullable);
         glue!(nullable_text, nullable::nullable_text);
+        glue!(order_by, order_by::order_by);
+        glue!(order_by_nulls, order_by::nulls);
         glue!(ordering, ordering::ordering);
+        glue!(projection, projection::projection);
         glue!(sql_types, sql_types::sql_types);
+        glue!(sql_types_float, sql_types::float);
+        glue!(sql_types_date, sql_types::date);
+        glue!(sql_types_json, sql_types::json);
+        glue!(sql_types_bytes, sql_types::bytes);
         glue!(synthesize, synthesize::synthesize);
+        glue!(union, union::union);
+        glue!(union_intersect_except, union::intersect_except);
+        glue!(window, window::window);
         glue!(filter, filter::filter);
+        glue!(filter_like, filter::like);
+        glue!(filter_exists, filter::exists);
+        glue!(filter_boolean, filter::boolean);
+        glue!(filter_column_comparison, filter::column_comparison);
+        glue!(filter_negation, filter::negation);
+        glue!(function, function::function);
+        glue!(function_coalesce, function::coalesce);
+        glue!(function_concat, function::concat);
+        glue!(insert_select, insert_select::insert_select);
     };
 }
@@ -1,7 +1,7 @@
 use crate::*;
 
 pub fn nested_select(mut tester: impl tests::Tester) {
-    let create_sqls: [&str; 2] = [
+    let create_sqls: [&str; 3] = [
         "
         CREATE TABLE User (
             id INTEGER,
@@ -14,6 +14,12 @@ pub fn nested_select(mut tester: impl tests::Tester) {
             quantity INTEGER,
             user_id INTEGER,
         );
+    ",
+        "
+        CREATE TABLE Review (
+            id INTEGER,
+            user_id INTEGER NULL,
+        );
     ",
     ];
 
@@ -40,6 +46,8 @@ pub fn nested_select(mut tester: impl tests::Tester) {
         "INSERT INTO Request (id, quantity, user_id) VALUES (113, 7, 1);",
         "INSERT INTO Request (id, quantity, user_id) VALUES (114, 1, 1);",
         "INSERT INTO Request (id, quantity, user_id) VALUES (115, 2, 1);",
+        "INSERT INTO Review (id, user_id) VALUES (1, 1);",
+        "INSERT INTO Review (id, user_id) VALUES (2, NULL);",
     ];
 
     for insert_sql in insert_sqls.iter() {
@@ -64,9 +72,387 @@ pub fn nested_select(mut tester: impl tests::Tester) {
         (4, "SELECT * FROM User WHERE id IN (SELECT user_id FROM Request WHERE user_id IN (User.id));"),
         (2, "SELECT * FROM User WHERE id IN (SELECT user_id FROM Request WHERE quantity IN (6, 7, 8, 9));"),
         (9, "SELECT * FROM Request WHERE user_id IN (SELECT id FROM User WHERE name IN (\"Taehoon\", \"Hwan\"));"),
+        (
+            1,
+            "SELECT * FROM User WHERE id NOT IN (SELECT user_id FROM Request);",
+        ),
+        (
+            1,
+            "SELECT * FROM User WHERE id IN (SELECT user_id FROM Review);",
+        ),
+        (
+            0,
+            "SELECT * FROM User WHERE id NOT IN (SELECT user_id FROM Review);",
+        ),
+        (
+            4,
+            "SELECT * FROM User WHERE id NOT IN (SELECT user_id FROM Review WHERE user_id IS NOT NULL);",
+        ),
+        (
+            0,
+            "SELECT * FROM User WHERE id IN (SELECT user_id FROM Review WHERE id > 2);",
+        ),
+        (
+            5,
+            "SELECT * FROM User WHERE id NOT IN (SELECT user_id FROM Review WHERE id > 2);",
+        ),
+        (
+            1,
+            "SELECT * FROM Review WHERE user_id NOT IN (SELECT id FROM User WHERE id > 3);",
+        ),
     ];
 
     select_sqls
         .iter()
         .for_each(|(num, sql)| tester.test_rows(sql, *num));
+
+    tester.test_error(
+        "SELECT * FROM User WHERE id IN (SELECT id, user_id FROM Request);",
+        FilterError::InSubqueryColumnCountNotOne(2).into(),
+    );
+}
+
+pub fn scalar_subquery(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE ScalarUser (id INTEGER, name TEXT);",
+        "CREATE TABLE ScalarOrder (id INTEGER, user_id INTEGER, amount INTEGER);",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO ScalarUser (id, name) VALUES (1, \"Taehoon\");",
+        "INSERT INTO ScalarUser (id, name) VALUES (2, \"Mike\");",
+        "INSERT INTO ScalarUser (id, name) VALUES (3, \"Jorno\");",
+        "INSERT INTO ScalarOrder (id, user_id, amount) VALUES (101, 1, 30);",
+        "INSERT INTO ScalarOrder (id, user_id, amount) VALUES (102, 3, 10);",
+        "INSERT INTO ScalarOrder (id, user_id, amount) VALUES (103, 3, 20);",
+    ];
+
+    insert_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "
+            SELECT
+                name,
+                (SELECT COUNT(*) FROM ScalarOrder WHERE ScalarOrder.user_id = ScalarUser.id) AS order_count
+            FROM ScalarUser
+            ",
+            select!(
+                name | order_count;
+                Str I64;
+                "Taehoon".to_owned() 1;
+                "Mike".to_owned() 0;
+                "Jorno".to_owned() 2
+            ),
+        ),
+        (
+            "
+            SELECT id, (SELECT amount FROM ScalarOrder WHERE user_id = u.id AND amount > 15) AS amount
+            FROM ScalarUser u
+            ",
+            select_with_empty!(
+                id | amount;
+                I64(1) I64(30);
+                I64(2) Empty;
+                I64(3) I64(20)
+            ),
+        ),
+        (
+            "SELECT id, (SELECT MAX(amount) FROM ScalarOrder) - 5 AS diff FROM ScalarUser WHERE id = 1",
+            select!(id | diff; I64 I64; 1 25),
+        ),
+        (
+            "
+            SELECT id FROM ScalarUser u
+            WHERE (SELECT SUM(amount) FROM ScalarOrder WHERE user_id = u.id) > 20
+            ",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM ScalarUser WHERE id = (SELECT user_id FROM ScalarOrder WHERE id = 104)",
+            select!(id; I64),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    tester.test_error(
+        "SELECT id, (SELECT amount FROM ScalarOrder WHERE user_id = 3) FROM ScalarUser",
+        EvaluateError::NestedSelectMultipleRows.into(),
+    );
+}
+
+pub fn correlated_subquery(mut tester: impl tests::Tester) {
+    tester.run_and_print("CREATE TABLE CorrEmp (id INTEGER, dept TEXT, salary INTEGER);");
+    tester.run_and_print(
+        "
+        INSERT INTO CorrEmp VALUES
+            (1, \"a\", 10), (2, \"a\", 20), (3, \"a\", 30), (4, \"b\", 5), (5, \"b\", 15),
+            (6, \"c\", 7);
+        ",
+    );
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "
+            SELECT id FROM CorrEmp
+            WHERE salary > (SELECT AVG(salary) FROM CorrEmp e WHERE e.dept = CorrEmp.dept)
+            ",
+            select!(id; I64; 3; 5),
+        ),
+        (
+            "
+            SELECT id FROM CorrEmp e
+            WHERE (
+                SELECT COUNT(*) FROM CorrEmp e2 WHERE e2.dept = e.dept AND e2.salary < e.salary
+            ) = 1
+            ",
+            select!(id; I64; 2; 5),
+        ),
+        (
+            // the unqualified column of the subquery is its own column
+            "
+            SELECT id FROM CorrEmp
+            WHERE salary = (SELECT MAX(salary) FROM CorrEmp e WHERE e.dept = dept)
+            ",
+            select!(id; I64; 3),
+        ),
+        (
+            "
+            SELECT id FROM CorrEmp e
+            WHERE (SELECT MIN(id) FROM CorrEmp e2 WHERE e2.dept = e.dept) = id - 1
+            ",
+            select!(id; I64; 2; 5),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql), "{}", sql));
+
+    tester.test_error(
+        "
+        SELECT id FROM CorrEmp e
+        WHERE salary = (SELECT salary FROM CorrEmp e2 WHERE e2.dept = e.dept)
+        ",
+        EvaluateError::NestedSelectMultipleRows.into(),
+    );
+}
+
+pub fn semi_join(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE SemiUser (id INTEGER, name TEXT);",
+        "CREATE TABLE SemiOrder (id INTEGER, user_id INTEGER NULL, amount FLOAT);",
+        "CREATE TABLE SemiTag (tag_id INTEGER);",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO SemiUser VALUES (1, \"Taehoon\");",
+        "INSERT INTO SemiUser VALUES (2, \"Mike\");",
+        "INSERT INTO SemiUser VALUES (3, \"Jorno\");",
+        "INSERT INTO SemiUser VALUES (4, \"Berry\");",
+        "INSERT INTO SemiOrder VALUES (101, 1, 1.5);",
+        "INSERT INTO SemiOrder VALUES (102, 3, 2.0);",
+        "INSERT INTO SemiOrder VALUES (103, NULL, 3.0);",
+        "INSERT INTO SemiOrder VALUES (104, 3, 1.0);",
+        "INSERT INTO SemiTag VALUES (101);",
+        "INSERT INTO SemiTag VALUES (2);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT id FROM SemiUser WHERE id IN (SELECT user_id FROM SemiOrder)",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM SemiUser WHERE id NOT IN (SELECT user_id FROM SemiOrder)",
+            select!(id; I64),
+        ),
+        (
+            "
+            SELECT id FROM SemiUser
+            WHERE id NOT IN (SELECT user_id FROM SemiOrder WHERE user_id IS NOT NULL)
+            ",
+            select!(id; I64; 2; 4),
+        ),
+        (
+            "SELECT id FROM SemiOrder WHERE user_id IN (SELECT id FROM SemiUser)",
+            select!(id; I64; 101; 102; 104),
+        ),
+        (
+            "SELECT id FROM SemiOrder WHERE user_id NOT IN (SELECT id FROM SemiUser WHERE id > 1)",
+            select!(id; I64; 101),
+        ),
+        (
+            "SELECT id FROM SemiOrder WHERE user_id NOT IN (SELECT id FROM SemiUser WHERE id > 9)",
+            select!(id; I64; 101; 102; 103; 104),
+        ),
+        (
+            "
+            SELECT u.id, o.id FROM SemiUser u
+            JOIN SemiOrder o ON u.id = o.user_id
+            WHERE o.id IN (SELECT id FROM SemiOrder WHERE amount > 1.2) AND u.id > 1
+            ",
+            select!(id | id; I64 I64; 3 102),
+        ),
+        (
+            "
+            SELECT id FROM SemiUser u
+            WHERE id IN (SELECT user_id FROM SemiOrder WHERE amount > u.id)
+            ",
+            select!(id; I64; 1),
+        ),
+        (
+            "
+            SELECT id FROM SemiOrder
+            WHERE amount IN (SELECT amount FROM SemiOrder WHERE id > 102)
+            ",
+            select!(id; I64; 103; 104),
+        ),
+        (
+            "
+            SELECT id FROM SemiUser
+            WHERE id IN (
+                SELECT user_id FROM SemiOrder
+                WHERE id IN (SELECT tag_id FROM SemiTag WHERE tag_id = id)
+            )
+            ",
+            select!(id; I64; 1),
+        ),
+    ];
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
+
+pub fn quantified(mut tester: impl tests::Tester) {
+    let create_sqls = [
+        "CREATE TABLE Product (id INTEGER, price INTEGER NULL);",
+        "CREATE TABLE Competitor (id INTEGER, product_id INTEGER, price INTEGER NULL);",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO Product VALUES (1, 10);",
+        "INSERT INTO Product VALUES (2, 20);",
+        "INSERT INTO Product VALUES (3, 30);",
+        "INSERT INTO Product VALUES (4, NULL);",
+        "INSERT INTO Competitor VALUES (1, 1, 15);",
+        "INSERT INTO Competitor VALUES (2, 1, 20);",
+        "INSERT INTO Competitor VALUES (3, 2, 20);",
+        "INSERT INTO Competitor VALUES (4, 3, NULL);",
+        "INSERT INTO Competitor VALUES (5, 3, 25);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT id FROM Product WHERE price > ALL (SELECT price FROM Competitor WHERE id < 4)",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM Product WHERE price >= ALL (SELECT price FROM Competitor WHERE id < 4)",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM Product WHERE price = ANY (SELECT price FROM Competitor)",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM Product WHERE price < SOME (SELECT price FROM Competitor)",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM Product WHERE price <> ALL (SELECT price FROM Competitor WHERE id < 4)",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM Product WHERE price > ALL (SELECT price FROM Competitor WHERE id > 9)",
+            select!(id; I64; 1; 2; 3; 4),
+        ),
+        (
+            "SELECT id FROM Product WHERE price = ANY (SELECT price FROM Competitor WHERE id > 9)",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM Product WHERE price > ALL (SELECT price FROM Competitor)",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM Product WHERE NOT price > ALL (SELECT price FROM Competitor)",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM Product WHERE price > ANY (SELECT price FROM Competitor)",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM Product WHERE NOT price = ANY (SELECT price FROM Competitor)",
+            select!(id; I64),
+        ),
+        (
+            "
+            SELECT id FROM Product p
+            WHERE price < ALL (SELECT price FROM Competitor WHERE product_id = p.id)
+            ",
+            select!(id; I64; 1; 4),
+        ),
+        (
+            "
+            SELECT id FROM Product p
+            WHERE price + 0 <= ANY (SELECT price FROM Competitor WHERE product_id = p.id)
+            ",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "
+            SELECT id FROM Product
+            WHERE id = ANY (
+                SELECT product_id FROM Competitor
+                WHERE price > ALL (SELECT price FROM Product WHERE id < 3)
+            )
+            ",
+            select!(id; I64; 3),
+        ),
+    ];
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    tester.test_error(
+        "SELECT id FROM Product WHERE price > ALL (SELECT id, price FROM Competitor)",
+        FilterError::QuantifiedSubqueryColumnCountNotOne(2).into(),
+    );
 }
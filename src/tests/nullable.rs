@@ -21,6 +21,7 @@ CREATE TABLE Test (
         (
             "SELECT id, num, name FROM Test",
             select!(
+                id | num | name;
                 OptI64  I64 Str;
                 None    2   "Hello".to_owned();
                 Some(1) 9   "World".to_owned();
@@ -29,14 +30,12 @@ CREATE TABLE Test (
         ),
         (
             "SELECT id, num FROM Test WHERE id = NULL AND name = \'Hello\'",
-            select!(
-                OptI64 I64;
-                None   2
-            ),
+            select!(id | num; OptI64 I64),
         ),
         (
             "SELECT id, num FROM Test WHERE id IS NULL",
             select!(
+                id | num;
                 OptI64 I64;
                 None   2
             ),
@@ -44,6 +43,7 @@ CREATE TABLE Test (
         (
             "SELECT id, num FROM Test WHERE id IS NOT NULL",
             select!(
+                id | num;
                 OptI64  I64;
                 Some(1) 9;
                 Some(3) 4
@@ -52,6 +52,7 @@ CREATE TABLE Test (
         (
             "SELECT id, num FROM Test WHERE id + 1 IS NULL",
             select!(
+                id | num;
                 OptI64  I64;
                 None    2
             ),
@@ -59,6 +60,7 @@ CREATE TABLE Test (
         (
             "SELECT id, num FROM Test WHERE id + 1 IS NOT NULL",
             select!(
+                id | num;
                 OptI64  I64;
                 Some(1) 9;
                 Some(3) 4
@@ -66,11 +68,12 @@ CREATE TABLE Test (
         ),
         (
             "SELECT id, num FROM Test WHERE 100 IS NULL",
-            select!(OptI64 I64),
+            select!(id | num; OptI64 I64),
         ),
         (
             "SELECT id, num FROM Test WHERE 100 IS NOT NULL",
             select!(
+                id | num;
                 OptI64  I64;
                 None    2;
                 Some(1) 9;
@@ -79,11 +82,12 @@ CREATE TABLE Test (
         ),
         (
             "SELECT id, num FROM Test WHERE 8 + 3 IS NULL",
-            select!(OptI64 I64),
+            select!(id | num; OptI64 I64),
         ),
         (
             "SELECT id, num FROM Test WHERE 8 + 3 IS NOT NULL",
             select!(
+                id | num;
                 OptI64  I64;
                 None    2;
                 Some(1) 9;
@@ -93,6 +97,7 @@ CREATE TABLE Test (
         (
             "SELECT id, num FROM Test WHERE NULL IS NULL",
             select!(
+                id | num;
                 OptI64  I64;
                 None    2;
                 Some(1) 9;
@@ -101,15 +106,16 @@ CREATE TABLE Test (
         ),
         (
             "SELECT id, num FROM Test WHERE NULL IS NOT NULL",
-            select!(OptI64 I64),
+            select!(id | num; OptI64 I64),
         ),
         (
             "SELECT id, num FROM Test WHERE \"NULL\" IS NULL",
-            select!(OptI64 I64),
+            select!(id | num; OptI64 I64),
         ),
         (
             "SELECT id, num FROM Test WHERE \"NULL\" IS NOT NULL",
             select!(
+                id | num;
                 OptI64  I64;
                 None    2;
                 Some(1) 9;
@@ -118,63 +124,151 @@ CREATE TABLE Test (
         ),
         (
             "SELECT id, num FROM Test WHERE id = NULL + 1;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id = 1 + NULL;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id = NULL - 1;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id = 1 - NULL;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id = NULL * 1;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id = 1 * NULL;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id = NULL / 1;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id = 1 / NULL;",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE NULL = NULL",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id != 1",
             select!(
+                id | num;
                 OptI64  I64;
-                None    2
+                Some(3) 4
             ),
         ),
         (
-            "SELECT id, num FROM Test WHERE id = 1 + NULL;",
+            "SELECT id, num FROM Test WHERE id > 0 OR num = 2",
             select!(
+                id | num;
                 OptI64  I64;
-                None    2
+                None    2;
+                Some(1) 9;
+                Some(3) 4
             ),
         ),
         (
-            "SELECT id, num FROM Test WHERE id = NULL - 1;",
+            "SELECT id, num FROM Test WHERE id > 0 AND num = 2",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE NOT (id = 1)",
             select!(
+                id | num;
                 OptI64  I64;
-                None    2
+                Some(3) 4
             ),
         ),
         (
-            "SELECT id, num FROM Test WHERE id = 1 - NULL;",
+            "SELECT id, num FROM Test WHERE NOT (id = 1 AND num = 3)",
             select!(
+                id | num;
                 OptI64  I64;
-                None    2
+                None    2;
+                Some(1) 9;
+                Some(3) 4
             ),
         ),
         (
-            "SELECT id, num FROM Test WHERE id = NULL * 1;",
+            "SELECT id, num FROM Test WHERE NOT (id = 3 OR num = 2)",
             select!(
+                id | num;
                 OptI64  I64;
-                None    2
+                Some(1) 9
             ),
         ),
         (
-            "SELECT id, num FROM Test WHERE id = 1 * NULL;",
+            "SELECT id, num FROM Test WHERE id BETWEEN 0 AND 5",
             select!(
+                id | num;
                 OptI64  I64;
-                None    2
+                Some(1) 9;
+                Some(3) 4
             ),
         ),
         (
-            "SELECT id, num FROM Test WHERE id = NULL / 1;",
+            "SELECT id, num FROM Test WHERE id IN (1, 3)",
             select!(
+                id | num;
                 OptI64  I64;
-                None    2
+                Some(1) 9;
+                Some(3) 4
             ),
         ),
         (
-            "SELECT id, num FROM Test WHERE id = 1 / NULL;",
+            "SELECT id, num FROM Test WHERE id NOT IN (1)",
             select!(
+                id | num;
+                OptI64  I64;
+                Some(3) 4
+            ),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE id IN (NULL)",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE num IN (2, NULL)",
+            select!(
+                id | num;
                 OptI64  I64;
                 None    2
             ),
         ),
         (
-            "SELECT id + 1, 1 + id, id - 1, 1 - id, id * 1, 1 * id, id / 1, 1 / id FROM Test WHERE id = NULL;",
+            "SELECT id, num FROM Test WHERE num NOT IN (2, NULL)",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE NULL IN (SELECT id FROM Test)",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE NULL NOT IN (SELECT num FROM Test)",
+            select!(id | num; OptI64 I64),
+        ),
+        (
+            "SELECT id, num FROM Test WHERE name IN ('Hello', 'Great')",
+            select!(
+                id | num;
+                OptI64  I64;
+                None    2;
+                Some(3) 4
+            ),
+        ),
+        (
+            "SELECT id + 1, 1 + id, id - 1, 1 - id, id * 1, 1 * id, id / 1, 1 / id FROM Test WHERE id IS NULL;",
             select!(
+                "id + 1" | "1 + id" | "id - 1" | "1 - id" | "id * 1" | "1 * id" | "id / 1" | "1 / id";
                 OptI64 OptI64 OptI64 OptI64 OptI64 OptI64 OptI64 OptI64;
                 None   None   None   None   None   None   None   None
             ),
@@ -192,6 +286,7 @@ CREATE TABLE Test (
         (
             "SELECT id FROM Test",
             Ok(select!(
+                id;
                 OptI64;
                 Some(2);
                 Some(2);
@@ -201,6 +296,7 @@ CREATE TABLE Test (
         (
             "SELECT id, num FROM Test",
             Ok(select!(
+                id | num;
                 OptI64  I64;
                 Some(2) 2;
                 Some(2) 9;
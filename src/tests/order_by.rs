@@ -0,0 +1,291 @@
+use crate::*;
+
+pub fn order_by(mut tester: impl tests::Tester) {
+    let create_sqls: [&str; 2] = [
+        "
+        CREATE TABLE OrderPlayer (
+            id INTEGER,
+            name TEXT,
+            score INTEGER,
+        );
+    ",
+        "
+        CREATE TABLE OrderItem (
+            id INTEGER,
+            player_id INTEGER,
+            quantity INTEGER,
+        );
+    ",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO OrderPlayer (id, name, score) VALUES (1, \"Taehoon\", 30);",
+        "INSERT INTO OrderPlayer (id, name, score) VALUES (2, \"Mike\", 10);",
+        "INSERT INTO OrderPlayer (id, name, score) VALUES (3, \"Jorno\", 30);",
+        "INSERT INTO OrderPlayer (id, name, score) VALUES (4, \"Berry\", 20);",
+        "INSERT INTO OrderItem (id, player_id, quantity) VALUES (101, 3, 1);",
+        "INSERT INTO OrderItem (id, player_id, quantity) VALUES (102, 1, 5);",
+        "INSERT INTO OrderItem (id, player_id, quantity) VALUES (103, 4, 2);",
+        "INSERT INTO OrderItem (id, player_id, quantity) VALUES (104, 2, 9);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT id FROM OrderPlayer ORDER BY score",
+            select!(id; I64; 2; 4; 1; 3),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY score ASC",
+            select!(id; I64; 2; 4; 1; 3),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY score DESC",
+            select!(id; I64; 1; 3; 4; 2),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY score DESC, id DESC",
+            select!(id; I64; 3; 1; 4; 2),
+        ),
+        (
+            "SELECT id, name FROM OrderPlayer ORDER BY name",
+            select!(
+                id | name;
+                I64 Str;
+                4   "Berry".to_owned();
+                3   "Jorno".to_owned();
+                2   "Mike".to_owned();
+                1   "Taehoon".to_owned()
+            ),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY score + id DESC",
+            select!(id; I64; 3; 1; 4; 2),
+        ),
+        (
+            "SELECT id FROM OrderPlayer WHERE score > 10 ORDER BY score DESC, id LIMIT 2",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY score DESC LIMIT 2 OFFSET 1",
+            select!(id; I64; 3; 4),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY id OFFSET 2",
+            select!(id; I64; 3; 4),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY score DESC OFFSET 1 ROW FETCH FIRST 2 ROWS ONLY",
+            select!(id; I64; 3; 4),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY id FETCH NEXT 3 ROWS ONLY",
+            select!(id; I64; 1; 2; 3),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY id OFFSET 2 ROWS FETCH FIRST 1 ROW ONLY",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM OrderPlayer ORDER BY id DESC FETCH FIRST ROW ONLY",
+            select!(id; I64; 4),
+        ),
+        (
+            "
+            SELECT p.id, i.id
+            FROM OrderPlayer p
+            JOIN OrderItem i ON p.id = i.player_id
+            ORDER BY i.quantity DESC
+            ",
+            select!(id | id; I64 I64; 2 104; 1 102; 4 103; 3 101),
+        ),
+        (
+            "
+            SELECT p.name
+            FROM OrderItem i
+            JOIN OrderPlayer p ON p.id = i.player_id
+            ORDER BY p.score, i.id DESC
+            ",
+            select!(
+                name;
+                Str;
+                "Mike".to_owned();
+                "Berry".to_owned();
+                "Taehoon".to_owned();
+                "Jorno".to_owned()
+            ),
+        ),
+        (
+            "SELECT id, score * 2 AS double_score FROM OrderPlayer ORDER BY double_score DESC, id",
+            select!(id | double_score; I64 I64; 1 60; 3 60; 4 40; 2 20),
+        ),
+        (
+            "SELECT id, 100 - score AS score FROM OrderPlayer ORDER BY score, id DESC",
+            select!(id | score; I64 I64; 3 70; 1 70; 4 80; 2 90),
+        ),
+        (
+            "SELECT id AS num FROM OrderPlayer ORDER BY score, num DESC",
+            select!(num; I64; 2; 4; 3; 1),
+        ),
+        (
+            "SELECT score, COUNT(*) AS num FROM OrderPlayer GROUP BY score ORDER BY num DESC, score",
+            select!(score | num; I64 I64; 30 2; 10 1; 20 1),
+        ),
+        (
+            "SELECT score, COUNT(*) FROM OrderPlayer GROUP BY score ORDER BY score DESC",
+            select!(score | "COUNT(*)"; I64 I64; 30 2; 20 1; 10 1),
+        ),
+        (
+            "SELECT id, score FROM OrderPlayer ORDER BY 2 DESC, 1",
+            select!(id | score; I64 I64; 1 30; 3 30; 4 20; 2 10),
+        ),
+        (
+            "SELECT name, score * 2 AS double_score FROM OrderPlayer ORDER BY 2, name DESC",
+            select!(
+                name | double_score;
+                Str I64;
+                "Mike".to_owned() 20;
+                "Berry".to_owned() 40;
+                "Taehoon".to_owned() 60;
+                "Jorno".to_owned() 60
+            ),
+        ),
+        (
+            "SELECT score, COUNT(*) FROM OrderPlayer GROUP BY score ORDER BY 2 DESC, 1 DESC",
+            select!(score | "COUNT(*)"; I64 I64; 30 2; 20 1; 10 1),
+        ),
+        (
+            "SELECT * FROM OrderPlayer ORDER BY 3 DESC, 1",
+            select!(
+                id | name | score;
+                I64 Str I64;
+                1 "Taehoon".to_owned() 30;
+                3 "Jorno".to_owned() 30;
+                4 "Berry".to_owned() 20;
+                2 "Mike".to_owned() 10
+            ),
+        ),
+        (
+            "SELECT i.quantity, p.* FROM OrderItem i JOIN OrderPlayer p ON p.id = i.player_id ORDER BY 3",
+            select!(
+                quantity | id | name | score;
+                I64 I64 Str I64;
+                2 4 "Berry".to_owned() 20;
+                1 3 "Jorno".to_owned() 30;
+                9 2 "Mike".to_owned() 10;
+                5 1 "Taehoon".to_owned() 30
+            ),
+        ),
+        (
+            "SELECT id FROM OrderPlayer UNION SELECT player_id FROM OrderItem ORDER BY 1 DESC",
+            select!(id; I64; 4; 3; 2; 1),
+        ),
+        (
+            "SELECT id FROM OrderPlayer WHERE id IN (SELECT player_id FROM OrderItem ORDER BY quantity DESC LIMIT 2)",
+            select!(id; I64; 1; 2),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            LimitError::LimitWithFetch.into(),
+            "SELECT id FROM OrderPlayer LIMIT 1 FETCH FIRST 2 ROWS ONLY",
+        ),
+        (
+            LimitError::FetchNotSupported("WITH TIES".to_owned()).into(),
+            "SELECT id FROM OrderPlayer ORDER BY score FETCH FIRST 1 ROW WITH TIES",
+        ),
+        (
+            LimitError::FetchNotSupported("PERCENT".to_owned()).into(),
+            "SELECT id FROM OrderPlayer FETCH FIRST 50 PERCENT ROWS ONLY",
+        ),
+        (
+            SortError::OrdinalOutOfRange("3".to_owned()).into(),
+            "SELECT id, score FROM OrderPlayer ORDER BY 3",
+        ),
+        (
+            SortError::OrdinalOutOfRange("0".to_owned()).into(),
+            "SELECT id FROM OrderPlayer ORDER BY 0 DESC",
+        ),
+        (
+            SortError::OrdinalOutOfRange("2".to_owned()).into(),
+            "SELECT id FROM OrderPlayer WHERE id > 10 ORDER BY 2",
+        ),
+        (
+            SortError::OrdinalOutOfRange("2".to_owned()).into(),
+            "SELECT id FROM OrderPlayer UNION SELECT id FROM OrderItem ORDER BY 2",
+        ),
+    ];
+
+    for (error, sql) in error_cases.into_iter() {
+        tester.test_error(sql, error);
+    }
+}
+
+pub fn nulls(mut tester: impl tests::Tester) {
+    tester.run_and_print("CREATE TABLE OrderNull (id INTEGER, grp INTEGER, score INTEGER NULL);");
+    tester
+        .run(
+            "
+            INSERT INTO OrderNull VALUES
+                (1, 1, 20), (2, 2, NULL), (3, 1, 10), (4, 2, NULL), (5, 1, 30), (6, 2, 40);
+            ",
+        )
+        .unwrap();
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT id FROM OrderNull ORDER BY score, id",
+            select!(id; I64; 3; 1; 5; 6; 2; 4),
+        ),
+        (
+            "SELECT id FROM OrderNull ORDER BY score DESC, id",
+            select!(id; I64; 2; 4; 6; 5; 1; 3),
+        ),
+        (
+            "SELECT id FROM OrderNull ORDER BY score ASC NULLS FIRST, id",
+            select!(id; I64; 2; 4; 3; 1; 5; 6),
+        ),
+        (
+            "SELECT id FROM OrderNull ORDER BY score ASC NULLS LAST, id",
+            select!(id; I64; 3; 1; 5; 6; 2; 4),
+        ),
+        (
+            "SELECT id FROM OrderNull ORDER BY score DESC NULLS FIRST, id",
+            select!(id; I64; 2; 4; 6; 5; 1; 3),
+        ),
+        (
+            "SELECT id FROM OrderNull ORDER BY score DESC NULLS LAST, id",
+            select!(id; I64; 6; 5; 1; 3; 2; 4),
+        ),
+        (
+            "SELECT id FROM OrderNull ORDER BY score NULLS FIRST, id DESC",
+            select!(id; I64; 4; 2; 3; 1; 5; 6),
+        ),
+        (
+            "SELECT id FROM OrderNull ORDER BY grp DESC, score DESC NULLS LAST, id",
+            select!(id; I64; 6; 2; 4; 5; 1; 3),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql), "{}", sql));
+}
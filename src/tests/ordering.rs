@@ -39,7 +39,7 @@ pub fn ordering(mut tester: impl tests::Tester) {
         (0, "SELECT * FROM Operator WHERE 3 > 3;"),
         (
             5,
-            "SELECT * FROM Operator o1 WHERE 3 > (SELECT id FROM Operator WHERE o1.id < 100);",
+            "SELECT * FROM Operator o1 WHERE 3 > (SELECT id FROM Operator WHERE o1.id < 100 LIMIT 1);",
         ),
         (2, "SELECT * FROM Operator WHERE name < \"Azzzzzzzzzz\";"),
         (1, "SELECT * FROM Operator WHERE name < \"Az\";"),
@@ -0,0 +1,109 @@
+use crate::*;
+
+pub fn projection(mut tester: impl tests::Tester) {
+    let create_sqls: [&str; 2] = [
+        "
+        CREATE TABLE ProjectPlayer (
+            id INTEGER,
+            name TEXT,
+            score INTEGER,
+            team TEXT,
+        );
+    ",
+        "
+        CREATE TABLE ProjectItem (
+            id INTEGER,
+            player_id INTEGER,
+            quantity INTEGER,
+        );
+    ",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO ProjectPlayer VALUES (1, \"Taehoon\", 30, \"Red\");",
+        "INSERT INTO ProjectPlayer VALUES (2, \"Mike\", 10, \"Blue\");",
+        "INSERT INTO ProjectPlayer VALUES (3, \"Jorno\", 20, \"Red\");",
+        "INSERT INTO ProjectItem VALUES (101, 1, 5);",
+        "INSERT INTO ProjectItem VALUES (102, 1, 2);",
+        "INSERT INTO ProjectItem VALUES (103, 3, 7);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT name FROM ProjectPlayer WHERE score > 15",
+            select!(name; Str; "Taehoon".to_owned(); "Jorno".to_owned()),
+        ),
+        (
+            "SELECT name FROM ProjectPlayer WHERE team = \"Red\" ORDER BY score",
+            select!(name; Str; "Jorno".to_owned(); "Taehoon".to_owned()),
+        ),
+        (
+            "SELECT id FROM ProjectPlayer WHERE CASE WHEN team = \"Blue\" THEN TRUE ELSE FALSE END",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT team, COUNT(*) FROM ProjectPlayer GROUP BY team HAVING SUM(score) > 20",
+            select!(team | "COUNT(*)"; Str I64; "Red".to_owned() 2),
+        ),
+        (
+            "SELECT COUNT(*) FROM ProjectPlayer",
+            select!("COUNT(*)"; I64; 3),
+        ),
+        (
+            "
+            SELECT ProjectPlayer.name, ProjectItem.quantity
+            FROM ProjectPlayer
+            JOIN ProjectItem ON ProjectItem.player_id = ProjectPlayer.id
+            WHERE ProjectItem.quantity > 2
+            ",
+            select!(
+                name | quantity;
+                Str I64;
+                "Taehoon".to_owned() 5;
+                "Jorno".to_owned()   7
+            ),
+        ),
+        (
+            "
+            SELECT name FROM ProjectPlayer
+            WHERE EXISTS (
+                SELECT * FROM ProjectItem WHERE player_id = ProjectPlayer.id AND quantity > score / 5
+            )
+            ",
+            select!(name; Str; "Jorno".to_owned()),
+        ),
+        (
+            "SELECT name FROM ProjectPlayer WHERE id IN (SELECT player_id FROM ProjectItem)",
+            select!(name; Str; "Taehoon".to_owned(); "Jorno".to_owned()),
+        ),
+        (
+            "
+            SELECT Sub.name FROM (SELECT name, score FROM ProjectPlayer) AS Sub
+            WHERE Sub.score < 25
+            ",
+            select!(name; Str; "Mike".to_owned(); "Jorno".to_owned()),
+        ),
+        (
+            "SELECT ProjectPlayer.* FROM ProjectPlayer WHERE id = 2",
+            select!(
+                id | name | score | team;
+                I64 Str I64 Str;
+                2 "Mike".to_owned() 10 "Blue".to_owned()
+            ),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+}
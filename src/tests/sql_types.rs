@@ -46,3 +46,496 @@ pub fn sql_types(mut tester: impl tests::Tester) {
 
     tester.run_and_print(delete_sql);
 }
+
+pub fn float(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "
+        CREATE TABLE FloatItem (
+            id INTEGER,
+            score INTEGER,
+            price FLOAT
+        );
+    ",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO FloatItem (id, score, price) VALUES (1, 10, 9.99);",
+        "INSERT INTO FloatItem (id, score, price) VALUES (2, 20, 20.0);",
+        "INSERT INTO FloatItem (id, score, price) VALUES (3, 5, 12.5);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT id, price FROM FloatItem",
+            select!(id | price; I64 F64; 1 9.99; 2 20.0; 3 12.5),
+        ),
+        (
+            "SELECT id FROM FloatItem WHERE price > 9.99",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM FloatItem WHERE price >= 10",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM FloatItem WHERE score > 9.5",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM FloatItem WHERE score = 20.0",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM FloatItem WHERE score = price",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM FloatItem WHERE score < price",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM FloatItem WHERE price IN (10, 20)",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id, score * 1.5 AS scaled FROM FloatItem",
+            select!(id | scaled; I64 F64; 1 15.0; 2 30.0; 3 7.5),
+        ),
+        (
+            "SELECT id, price - score AS diff FROM FloatItem WHERE id = 3",
+            select!(id | diff; I64 F64; 3 7.5),
+        ),
+        (
+            "SELECT id, price * 2 AS doubled FROM FloatItem WHERE id = 3",
+            select!(id | doubled; I64 F64; 3 25.0),
+        ),
+        (
+            "SELECT 1.5 + 2 AS a, 5 / 2.0 AS b FROM FloatItem WHERE id = 1",
+            select!(a | b; F64 F64; 3.5 2.5),
+        ),
+        (
+            "SELECT id FROM FloatItem ORDER BY price DESC",
+            select!(id; I64; 2; 3; 1),
+        ),
+        (
+            "SELECT AVG(score) AS avg_score FROM FloatItem",
+            select!(avg_score; F64; 35.0 / 3.0),
+        ),
+        (
+            "SELECT SUM(price) AS total FROM FloatItem WHERE id > 1",
+            select!(total; F64; 32.5),
+        ),
+        (
+            "
+            SELECT a.id, b.id
+            FROM FloatItem a
+            JOIN FloatItem b ON a.price = b.score
+            ",
+            select!(id | id; I64 I64; 2 2),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+
+    tester.test_error(
+        "SELECT price / 0 FROM FloatItem",
+        ValueError::DivisorShouldNotBeZero.into(),
+    );
+    tester.test_error(
+        "SELECT 1.5 / 0 FROM FloatItem",
+        ValueError::DivisorShouldNotBeZero.into(),
+    );
+}
+
+pub fn date(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "
+        CREATE TABLE DateItem (
+            id INTEGER,
+            created DATE,
+            updated TIMESTAMP,
+            removed DATE NULL
+        );
+    ",
+    );
+
+    let insert_sqls = [
+        "INSERT INTO DateItem VALUES (1, '2023-01-01', '2023-01-01 00:00:00', NULL);",
+        "INSERT INTO DateItem VALUES (2, '2022-12-31', '2023-01-02 09:30:00', '2023-02-01');",
+        "INSERT INTO DateItem VALUES (3, DATE '2023-03-15', '2023-03-15T12:00:00.5', NULL);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let date = |s| parse_date(s).unwrap();
+    let timestamp = |s| parse_timestamp(s).unwrap();
+
+    let test_cases = vec![
+        (
+            "SELECT id, created, updated FROM DateItem WHERE id = 3",
+            select!(
+                id | created | updated;
+                I64 Date Timestamp;
+                3 date("2023-03-15") timestamp("2023-03-15 12:00:00.5")
+            ),
+        ),
+        (
+            "SELECT id, removed FROM DateItem WHERE id = 2",
+            select!(id | removed; I64 OptDate; 2 Some(date("2023-02-01"))),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE created > '2023-01-01'",
+            select!(id; I64; 3),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE created >= '2023-01-01'",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE created = '2022-12-31'",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE created < DATE '2023-01-01'",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE updated > '2023-01-02'",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE updated BETWEEN '2023-01-01' AND '2023-01-02 10:00:00'",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE created > '2023-01-01 12:00:00'",
+            select!(id; I64; 3),
+        ),
+        // DATE is compared to TIMESTAMP as the timestamp of its midnight
+        (
+            "SELECT id FROM DateItem WHERE created = updated",
+            select!(id; I64; 1),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE created < updated",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE removed < updated",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM DateItem WHERE removed > updated",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM DateItem ORDER BY created",
+            select!(id; I64; 2; 1; 3),
+        ),
+        (
+            "SELECT id FROM DateItem ORDER BY updated DESC",
+            select!(id; I64; 3; 2; 1),
+        ),
+        (
+            "SELECT MAX(created) AS latest FROM DateItem",
+            select!(latest; Date; date("2023-03-15")),
+        ),
+        (
+            "SELECT CAST(created AS TEXT) AS a, CAST(updated AS TEXT) AS b FROM DateItem WHERE id = 3",
+            select!(
+                a | b;
+                Str Str;
+                "2023-03-15".to_owned() "2023-03-15 12:00:00.5".to_owned()
+            ),
+        ),
+        (
+            "SELECT CAST(updated AS DATE) AS a, CAST(created AS TIMESTAMP) AS b FROM DateItem WHERE id = 2",
+            select!(
+                a | b;
+                Date Timestamp;
+                date("2023-01-02") timestamp("2022-12-31")
+            ),
+        ),
+        (
+            "
+            SELECT a.id, b.id
+            FROM DateItem a
+            JOIN DateItem b ON a.created = b.updated
+            ",
+            select!(id | id; I64 I64; 1 1),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+
+    tester.run_and_print("UPDATE DateItem SET created = '2024-02-29' WHERE id = 1;");
+    tester.test_rows("SELECT id FROM DateItem WHERE created = '2024-02-29'", 1);
+
+    let error_cases = vec![
+        (
+            ValueError::FailedToParseDate("2023-02-30".to_owned()).into(),
+            "INSERT INTO DateItem VALUES (4, '2023-02-30', '2023-01-01', NULL);",
+        ),
+        (
+            ValueError::FailedToParseTimestamp("2023-01-01 25:00:00".to_owned()).into(),
+            "INSERT INTO DateItem VALUES (4, '2023-01-01', '2023-01-01 25:00:00', NULL);",
+        ),
+        (
+            ValueError::ImpossibleCast.into(),
+            "SELECT CAST('hello' AS DATE) FROM DateItem",
+        ),
+    ];
+
+    for (error, sql) in error_cases.into_iter() {
+        tester.test_error(sql, error);
+    }
+}
+
+pub fn json(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "
+        CREATE TABLE Event (
+            id INTEGER,
+            data JSON,
+            extra JSON NULL
+        );
+    ",
+    );
+
+    let insert_sqls = [
+        r#"INSERT INTO Event VALUES (1, '{"type": "click", "name": "a", "meta": {"x": 1, "tags": ["p", "q"]}}', NULL);"#,
+        r#"INSERT INTO Event VALUES (2, '{"type":"view","name":"b","meta":{"x":2}}', '{"k": null}');"#,
+        r#"INSERT INTO Event VALUES (3, '{"type": "click", "name": "c"}', '[1, "two"]');"#,
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let json = |s: &str| Some(s.to_owned());
+    let text = |s: &str| Some(s.to_owned());
+
+    let test_cases = vec![
+        (
+            "SELECT data->>'name' FROM Event WHERE data->>'type' = 'click'",
+            select!("data->>'name'"; OptStr; text("a"); text("c")),
+        ),
+        (
+            "SELECT data FROM Event WHERE id = 2",
+            select!(
+                data;
+                Json;
+                r#"{"type": "view", "name": "b", "meta": {"x": 2}}"#.to_owned()
+            ),
+        ),
+        (
+            "SELECT id, data->'meta' AS meta FROM Event",
+            select!(
+                id | meta;
+                I64 OptJson;
+                1 json(r#"{"x": 1, "tags": ["p", "q"]}"#);
+                2 json(r#"{"x": 2}"#);
+                3 None
+            ),
+        ),
+        (
+            "SELECT id, data->'meta'->>'x' AS x, data->'meta'->'tags'->>0 AS tag FROM Event",
+            select!(
+                id | x | tag;
+                I64 OptStr OptStr;
+                1 text("1") text("p");
+                2 text("2") None;
+                3 None None
+            ),
+        ),
+        (
+            "SELECT data->'meta'->'tags'->>-1 FROM Event WHERE id = 1",
+            select!("data->'meta'->'tags'->>-1"; OptStr; text("q")),
+        ),
+        (
+            "SELECT id, extra->>'k' AS k, extra->>1 AS second FROM Event",
+            select!(
+                id | k | second;
+                I64 OptStr OptStr;
+                1 None None;
+                2 None None;
+                3 None text("two")
+            ),
+        ),
+        (
+            "SELECT id FROM Event WHERE data->>'missing' IS NULL AND (data->'meta')->>'x' = '1'",
+            select!(id; I64; 1),
+        ),
+        (
+            r#"SELECT id FROM Event WHERE data->'meta' = '{ "x" : 2 }'"#,
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT data->>'type' AS type, COUNT(*) AS n FROM Event GROUP BY data->>'type'",
+            select!(
+                "type" | n;
+                OptStr I64;
+                text("click") 2;
+                text("view") 1
+            ),
+        ),
+        (
+            r#"SELECT CAST(data->'meta' AS TEXT) AS a, CAST('[1,2]' AS JSON) AS b FROM Event WHERE id = 2"#,
+            select!(
+                a | b;
+                Str Json;
+                r#"{"x": 2}"#.to_owned() "[1, 2]".to_owned()
+            ),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+
+    let error_cases = vec![
+        (
+            ValueError::FailedToParseJson("{\"a\": }".to_owned()).into(),
+            r#"INSERT INTO Event VALUES (4, '{"a": }', NULL);"#,
+        ),
+        (
+            ValueError::NullValueOnNotNullField.into(),
+            "INSERT INTO Event VALUES (4, NULL, NULL);",
+        ),
+        (
+            EvaluateError::OperatorRequiresJsonValue("->>".to_owned()).into(),
+            "SELECT id->>'a' FROM Event",
+        ),
+        (
+            EvaluateError::InvalidJsonKey("true".to_owned()).into(),
+            "SELECT data->TRUE FROM Event",
+        ),
+    ];
+
+    for (error, sql) in error_cases.into_iter() {
+        tester.test_error(sql, error);
+    }
+}
+
+pub fn bytes(mut tester: impl tests::Tester) {
+    tester.run_and_print(
+        "
+        CREATE TABLE Blob (
+            id INTEGER,
+            content BYTEA,
+            extra BYTEA NULL
+        );
+    ",
+    );
+    tester.run_and_print("CREATE TABLE BlobRef (id INTEGER, content BYTEA);");
+
+    let insert_sqls = [
+        "INSERT INTO Blob VALUES (1, X'00FF10', NULL);",
+        "INSERT INTO Blob VALUES (2, X'00ff', X'');",
+        "INSERT INTO Blob VALUES (3, X'7F000000', X'68656C6C6F');",
+        "INSERT INTO Blob VALUES (4, X'', NULL);",
+        "INSERT INTO BlobRef VALUES (10, X'00FF');",
+        "INSERT INTO BlobRef VALUES (20, X'7F000000');",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "SELECT id, content FROM Blob",
+            select!(
+                id | content;
+                I64 Bytes;
+                1 vec![0x00, 0xFF, 0x10];
+                2 vec![0x00, 0xFF];
+                3 vec![0x7F, 0x00, 0x00, 0x00];
+                4 vec![]
+            ),
+        ),
+        (
+            "SELECT id, extra FROM Blob WHERE id < 3",
+            select!(id | extra; I64 OptBytes; 1 None; 2 Some(vec![])),
+        ),
+        (
+            "SELECT id FROM Blob WHERE content = X'00FF'",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM Blob WHERE content > X'00FF'",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM Blob ORDER BY content DESC",
+            select!(id; I64; 3; 1; 2; 4),
+        ),
+        (
+            "SELECT id, LENGTH(content) AS a, LENGTH(extra) AS b FROM Blob",
+            select!(
+                id | a | b;
+                I64 I64 OptI64;
+                1 3 None;
+                2 2 Some(0);
+                3 4 Some(5);
+                4 0 None
+            ),
+        ),
+        (
+            "SELECT b.id, r.id FROM Blob b JOIN BlobRef r ON b.content = r.content",
+            select!(id | id; I64 I64; 2 10; 3 20),
+        ),
+        (
+            "SELECT id FROM Blob WHERE content IN (SELECT content FROM BlobRef)",
+            select!(id; I64; 2; 3),
+        ),
+        (
+            "SELECT CAST(extra AS TEXT) AS a FROM Blob WHERE id = 3",
+            select!(a; Str; "hello".to_owned()),
+        ),
+        (
+            "SELECT COUNT(DISTINCT content) AS n FROM Blob",
+            select!(n; I64; 4),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, tester.run(sql).expect("select")));
+
+    tester.run_and_print("UPDATE Blob SET extra = X'0102' WHERE id = 1;");
+    tester.test_rows("SELECT id FROM Blob WHERE extra = X'0102'", 1);
+
+    let error_cases = vec![
+        (
+            ValueError::FailedToParseHex("ABC".to_owned()).into(),
+            "INSERT INTO Blob VALUES (5, X'ABC', NULL);",
+        ),
+        (
+            ValueError::ImpossibleCast.into(),
+            "SELECT CAST(content AS TEXT) FROM Blob WHERE id = 1",
+        ),
+    ];
+
+    for (error, sql) in error_cases.into_iter() {
+        tester.test_error(sql, error);
+    }
+}
@@ -45,6 +45,12 @@ pub fn synthesize(mut tester: impl tests::Tester) {
         (1, "SELECT * FROM TableA WHERE id != 3 AND test != 100;"),
         (2, "SELECT * FROM TableA WHERE id = 3 LIMIT 2;"),
         (4, "SELECT * FROM TableA LIMIT 10 OFFSET 2;"),
+        (2, "SELECT * FROM TableA LIMIT 2 OFFSET 3;"),
+        (0, "SELECT * FROM TableA LIMIT 10 OFFSET 20;"),
+        (1, "SELECT id FROM TableA LIMIT 2 OFFSET 5;"),
+        (0, "SELECT * FROM TableA LIMIT 0;"),
+        (2, "SELECT * FROM TableA OFFSET 4;"),
+        (0, "SELECT * FROM TableA OFFSET 6;"),
         (
             1,
             "SELECT * FROM TableA WHERE (id = 3 OR test = 100) AND test = 300;",
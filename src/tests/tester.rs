@@ -31,12 +31,13 @@ pub trait Tester {
         let result = self.run(sql);
 
         match result.unwrap() {
-            Payload::Select(rows) => println!("[Ok ]\n{:#?}\n", rows),
+            Payload::Select { labels, rows } => println!("[Ok ]\n{:?}\n{:#?}\n", labels, rows),
             Payload::Insert(num) => println!("[Ok ] {} rows inserted.\n", num),
             Payload::Delete(num) => println!("[Ok ] {} rows deleted.\n", num),
             Payload::Update(num) => println!("[Ok ] {} rows updated.\n", num),
             Payload::DropTable => println!("[Ok ] :)\n"),
             Payload::Create => println!("[Ok ] :)\n"),
+            Payload::Explain(plan) => println!("[Ok ]\n{}\n", plan),
         };
     }
 
@@ -44,7 +45,7 @@ pub trait Tester {
         let result = self.run(sql);
 
         match result.unwrap() {
-            Payload::Select(rows) => assert_eq!(count, rows.len()),
+            Payload::Select { rows, .. } => assert_eq!(count, rows.len()),
             Payload::Delete(num) => assert_eq!(count, num),
             Payload::Update(num) => assert_eq!(count, num),
             _ => panic!("compare is only for Select, Delete and Update"),
@@ -55,7 +56,7 @@ pub trait Tester {
         let result = self.run(sql);
 
         match result.unwrap() {
-            Payload::Select(rows) => {
+            Payload::Select { rows, .. } => {
                 let Row(items) = rows.into_iter().next().unwrap();
 
                 assert_eq!(count, items.len())
@@ -0,0 +1,206 @@
+use crate::*;
+
+pub fn union(mut tester: impl tests::Tester) {
+    let create_sqls: [&str; 2] = [
+        "
+        CREATE TABLE UnionPlayer (
+            id INTEGER,
+            name TEXT,
+        );
+    ",
+        "
+        CREATE TABLE UnionCoach (
+            id INTEGER,
+            name TEXT,
+            team TEXT,
+        );
+    ",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO UnionPlayer (id, name) VALUES (1, \"Taehoon\");",
+        "INSERT INTO UnionPlayer (id, name) VALUES (2, \"Mike\");",
+        "INSERT INTO UnionPlayer (id, name) VALUES (3, \"Jorno\");",
+        "INSERT INTO UnionCoach (id, name, team) VALUES (2, \"Mike\", \"Blue\");",
+        "INSERT INTO UnionCoach (id, name, team) VALUES (4, \"Berry\", \"Red\");",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT id, name FROM UnionPlayer UNION ALL SELECT id, name FROM UnionCoach",
+            select!(
+                id | name;
+                I64 Str;
+                1   "Taehoon".to_owned();
+                2   "Mike".to_owned();
+                3   "Jorno".to_owned();
+                2   "Mike".to_owned();
+                4   "Berry".to_owned()
+            ),
+        ),
+        (
+            "SELECT id, name FROM UnionPlayer UNION SELECT id, name FROM UnionCoach",
+            select!(
+                id | name;
+                I64 Str;
+                1   "Taehoon".to_owned();
+                2   "Mike".to_owned();
+                3   "Jorno".to_owned();
+                4   "Berry".to_owned()
+            ),
+        ),
+        (
+            "SELECT id AS num FROM UnionPlayer UNION SELECT id FROM UnionCoach ORDER BY num DESC",
+            select!(num; I64; 4; 3; 2; 1),
+        ),
+        (
+            "
+            SELECT id FROM UnionPlayer WHERE id = 1
+            UNION ALL SELECT id FROM UnionCoach
+            UNION ALL SELECT id FROM UnionPlayer WHERE id > 1
+            ORDER BY id
+            LIMIT 3 OFFSET 1
+            ",
+            select!(id; I64; 2; 2; 3),
+        ),
+        (
+            "SELECT name FROM UnionPlayer WHERE id = 2 UNION SELECT name FROM UnionCoach WHERE id = 2",
+            select!(name; Str; "Mike".to_owned()),
+        ),
+        (
+            "SELECT p.name FROM UnionPlayer p UNION ALL SELECT team FROM UnionCoach ORDER BY name",
+            select!(
+                name;
+                Str;
+                "Blue".to_owned();
+                "Jorno".to_owned();
+                "Mike".to_owned();
+                "Red".to_owned();
+                "Taehoon".to_owned()
+            ),
+        ),
+        (
+            "SELECT id FROM UnionPlayer WHERE id IN (SELECT id FROM UnionPlayer UNION SELECT id FROM UnionCoach WHERE id = 4) ORDER BY id DESC",
+            select!(id; I64; 3; 2; 1),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    tester.test_error(
+        "SELECT id, name FROM UnionPlayer UNION SELECT id FROM UnionCoach",
+        ExecuteError::ColumnCountMismatch.into(),
+    );
+}
+
+pub fn intersect_except(mut tester: impl tests::Tester) {
+    let create_sqls: [&str; 3] = [
+        "CREATE TABLE SetA (id INTEGER, name TEXT);",
+        "CREATE TABLE SetB (id INTEGER, name TEXT);",
+        "CREATE TABLE SetEmpty (id INTEGER, name TEXT);",
+    ];
+
+    create_sqls.iter().for_each(|sql| tester.run_and_print(sql));
+
+    let insert_sqls = [
+        "INSERT INTO SetA (id, name) VALUES (1, \"Taehoon\");",
+        "INSERT INTO SetA (id, name) VALUES (1, \"Taehoon\");",
+        "INSERT INTO SetA (id, name) VALUES (2, \"Mike\");",
+        "INSERT INTO SetA (id, name) VALUES (3, \"Jorno\");",
+        "INSERT INTO SetB (id, name) VALUES (1, \"Taehoon\");",
+        "INSERT INTO SetB (id, name) VALUES (3, \"Jorno\");",
+        "INSERT INTO SetB (id, name) VALUES (3, \"Jorno\");",
+        "INSERT INTO SetB (id, name) VALUES (5, \"Berry\");",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    let test_cases = vec![
+        (
+            "SELECT id FROM SetA INTERSECT SELECT id FROM SetB",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM SetA INTERSECT ALL SELECT id FROM SetB",
+            select!(id; I64; 1; 3),
+        ),
+        (
+            "SELECT id FROM SetB INTERSECT ALL SELECT id FROM SetB WHERE id > 1",
+            select!(id; I64; 3; 3; 5),
+        ),
+        (
+            "SELECT id FROM SetA EXCEPT SELECT id FROM SetB",
+            select!(id; I64; 2),
+        ),
+        (
+            "SELECT id FROM SetA EXCEPT ALL SELECT id FROM SetB",
+            select!(id; I64; 1; 2),
+        ),
+        (
+            "SELECT id FROM SetB EXCEPT ALL SELECT id FROM SetA",
+            select!(id; I64; 3; 5),
+        ),
+        (
+            "SELECT id, name FROM SetA EXCEPT SELECT id, name FROM SetEmpty",
+            select!(
+                id | name;
+                I64 Str;
+                1   "Taehoon".to_owned();
+                2   "Mike".to_owned();
+                3   "Jorno".to_owned()
+            ),
+        ),
+        (
+            "SELECT id FROM SetA EXCEPT ALL SELECT id FROM SetEmpty",
+            select!(id; I64; 1; 1; 2; 3),
+        ),
+        (
+            "SELECT id FROM SetA WHERE id = 2 INTERSECT SELECT id FROM SetB",
+            select!(id; I64),
+        ),
+        (
+            "SELECT id FROM SetA INTERSECT SELECT id FROM SetEmpty",
+            select!(id; I64),
+        ),
+        (
+            "
+            SELECT id FROM SetA
+            UNION SELECT id FROM SetB
+            EXCEPT SELECT id FROM SetB WHERE id = 3
+            ORDER BY id DESC
+            ",
+            select!(id; I64; 5; 2; 1),
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    tester.test_error(
+        "SELECT id, name FROM SetA INTERSECT SELECT id FROM SetB",
+        ExecuteError::ColumnCountMismatch.into(),
+    );
+    tester.test_error(
+        "SELECT id FROM SetA EXCEPT ALL SELECT id, name FROM SetB",
+        ExecuteError::ColumnCountMismatch.into(),
+    );
+}
@@ -0,0 +1,111 @@
+use crate::*;
+
+pub fn window(mut tester: impl tests::Tester) {
+    let create_sql = "
+        CREATE TABLE WindowEmployee (
+            id INTEGER,
+            dept TEXT,
+            salary INTEGER,
+        );
+    ";
+
+    tester.run_and_print(create_sql);
+
+    let insert_sqls = [
+        "INSERT INTO WindowEmployee VALUES (1, \"Dev\", 300);",
+        "INSERT INTO WindowEmployee VALUES (2, \"Dev\", 200);",
+        "INSERT INTO WindowEmployee VALUES (3, \"Dev\", 300);",
+        "INSERT INTO WindowEmployee VALUES (4, \"Ops\", 100);",
+        "INSERT INTO WindowEmployee VALUES (5, \"Ops\", 150);",
+        "INSERT INTO WindowEmployee VALUES (6, \"Dev\", 100);",
+        "INSERT INTO WindowEmployee VALUES (7, \"Ops\", 150);",
+    ];
+
+    for insert_sql in insert_sqls.iter() {
+        tester.run(insert_sql).unwrap();
+    }
+
+    use Value::*;
+
+    let test_cases = vec![
+        (
+            "
+            SELECT
+                id,
+                ROW_NUMBER() OVER (PARTITION BY dept ORDER BY salary DESC) AS row_number,
+                RANK() OVER (PARTITION BY dept ORDER BY salary DESC) AS rank,
+                DENSE_RANK() OVER (PARTITION BY dept ORDER BY salary DESC) AS dense_rank
+            FROM WindowEmployee
+            ORDER BY id
+            ",
+            select!(
+                id | row_number | rank | dense_rank;
+                I64 I64 I64 I64;
+                1 1 1 1;
+                2 3 3 2;
+                3 2 1 1;
+                4 3 3 2;
+                5 1 1 1;
+                6 4 4 3;
+                7 2 1 1
+            ),
+        ),
+        (
+            "SELECT id, RANK() OVER (ORDER BY salary) AS rank FROM WindowEmployee ORDER BY id",
+            select!(id | rank; I64 I64; 1 6; 2 5; 3 6; 4 1; 5 3; 6 1; 7 3),
+        ),
+        (
+            "
+            SELECT id, ROW_NUMBER() OVER (ORDER BY salary DESC, id) AS row_number
+            FROM WindowEmployee
+            ORDER BY row_number
+            LIMIT 3
+            ",
+            select!(id | row_number; I64 I64; 1 1; 3 2; 2 3),
+        ),
+        (
+            "
+            SELECT id, ROW_NUMBER() OVER (ORDER BY id) * 10 AS n
+            FROM WindowEmployee
+            WHERE dept = 'Ops'
+            ORDER BY id
+            ",
+            select!(id | n; I64 I64; 4 10; 5 20; 7 30),
+        ),
+        (
+            "
+            SELECT dept, SUM(salary) AS total, RANK() OVER (ORDER BY SUM(salary) DESC) AS rank
+            FROM WindowEmployee
+            GROUP BY dept
+            ORDER BY rank
+            ",
+            select!(
+                dept | total | rank;
+                Str I64 I64;
+                "Dev".to_owned() 900 1;
+                "Ops".to_owned() 400 2
+            ),
+        ),
+    ];
+
+    let mut run = |sql| tester.run(sql).expect("select");
+
+    test_cases
+        .into_iter()
+        .for_each(|(sql, expected)| assert_eq!(expected, run(sql)));
+
+    let error_cases = vec![
+        (
+            "SELECT SUM(salary) OVER (ORDER BY id) FROM WindowEmployee",
+            WindowError::UnsupportedWindowFunction("SUM".to_owned()).into(),
+        ),
+        (
+            "SELECT RANK(id) OVER (ORDER BY id) FROM WindowEmployee",
+            WindowError::ArgumentsNotAllowed("RANK".to_owned()).into(),
+        ),
+    ];
+
+    error_cases
+        .into_iter()
+        .for_each(|(sql, error)| tester.test_error(sql, error));
+}
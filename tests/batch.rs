@@ -0,0 +1,79 @@
+#[cfg(feature = "memory-storage")]
+use gluesql::{execute_batch, Error, ExecuteError, MemoryStorage, Payload, Row, StoreError, Value};
+
+#[cfg(feature = "memory-storage")]
+fn ids(rows: &[i64]) -> Payload {
+    Payload::Select {
+        labels: vec!["id".to_owned()],
+        rows: rows.iter().map(|id| Row(vec![Value::I64(*id)])).collect(),
+    }
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn batch() {
+    let sql = "
+        CREATE TABLE Batch (id INTEGER);
+        INSERT INTO Batch VALUES (1), (2);
+        UPDATE Batch SET id = 3 WHERE id = 2;
+        SELECT id FROM Batch;
+    ";
+    let (storage, payloads) = execute_batch(MemoryStorage::new(), sql)
+        .map_err(|(_, error)| error)
+        .unwrap();
+
+    assert_eq!(
+        payloads,
+        vec![
+            Payload::Create,
+            Payload::Insert(2),
+            Payload::Update(1),
+            ids(&[1, 3]),
+        ]
+    );
+
+    let (_, payloads) = execute_batch(storage, "")
+        .map_err(|(_, error)| error)
+        .unwrap();
+    assert_eq!(payloads, vec![]);
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn batch_aborted() {
+    let sql = "
+        CREATE TABLE Batch (id INTEGER);
+        INSERT INTO Batch VALUES (1);
+        SELECT * FROM Nothing;
+        INSERT INTO Batch VALUES (2);
+    ";
+    let (storage, error) = execute_batch(MemoryStorage::new(), sql).unwrap_err();
+
+    assert_eq!(
+        error,
+        ExecuteError::BatchStatementFailed {
+            index: 2,
+            error: Box::new(StoreError::SchemaNotFound.into()),
+        }
+        .into()
+    );
+
+    // statements before the failing one stay applied, the rest never run
+    let (storage, payloads) = execute_batch(storage, "SELECT id FROM Batch")
+        .map_err(|(_, error)| error)
+        .unwrap();
+    assert_eq!(payloads, vec![ids(&[1])]);
+
+    // nothing runs if the script cannot be parsed
+    let sql = "
+        INSERT INTO Batch VALUES (3);
+        SELEC id FROM Batch;
+    ";
+    let (storage, error) = execute_batch(storage, sql).unwrap_err();
+    assert!(matches!(error, Error::Parser(_)), "{:?}", error);
+
+    let (_, payloads) = execute_batch(storage, "SELECT id FROM Batch")
+        .map_err(|(_, error)| error)
+        .unwrap();
+    assert_eq!(payloads, vec![ids(&[1])]);
+}
@@ -0,0 +1,176 @@
+#[cfg(feature = "memory-storage")]
+use gluesql::{
+    execute_batch, execute_with_options, parse, ExecuteOptions, JoinAlgorithm, JoinError,
+    MemoryStorage, Payload, Result,
+};
+
+#[cfg(feature = "memory-storage")]
+fn run(
+    storage: MemoryStorage,
+    sql: &str,
+    join_algorithm: Option<JoinAlgorithm>,
+) -> Result<Payload> {
+    let query = parse(sql).unwrap().into_iter().next().unwrap();
+    let options = ExecuteOptions {
+        join_algorithm,
+        ..ExecuteOptions::default()
+    };
+
+    execute_with_options(storage, &query, options)
+        .map(|(_, payload)| payload)
+        .map_err(|(_, error)| error)
+}
+
+#[cfg(feature = "memory-storage")]
+fn storage() -> MemoryStorage {
+    let sql = "
+        CREATE TABLE Player (id INTEGER, name TEXT);
+        CREATE TABLE Item (id INTEGER, player_id INTEGER NULL, ratio FLOAT);
+        INSERT INTO Player VALUES (1, \"Taehoon\"), (2, \"Mike\"), (3, \"Jorno\");
+        INSERT INTO Item VALUES (101, 1, 0.5), (102, 3, 1.5), (103, 3, 2.5), (104, NULL, 3.5);
+    ";
+
+    execute_batch(MemoryStorage::new(), sql)
+        .map_err(|(_, error)| error)
+        .unwrap()
+        .0
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn join_algorithm() {
+    let algorithms = [
+        None,
+        Some(JoinAlgorithm::NestedLoop),
+        Some(JoinAlgorithm::Hash),
+        Some(JoinAlgorithm::ReversedHash),
+    ];
+    let sql = "
+        SELECT p.id, i.id FROM Player p
+        JOIN Item i ON p.id = i.player_id
+        ORDER BY i.id
+    ";
+
+    assert!(matches!(
+        run(storage(), sql, None),
+        Ok(Payload::Select { rows, .. }) if rows.len() == 3
+    ));
+
+    for algorithm in algorithms.iter() {
+        assert_eq!(run(storage(), sql, *algorithm), run(storage(), sql, None));
+    }
+
+    let sql = "
+        SELECT p.id, i.id FROM Player p
+        LEFT JOIN Item i ON p.id = i.player_id
+        ORDER BY p.id, i.id
+    ";
+
+    assert!(matches!(
+        run(storage(), sql, None),
+        Ok(Payload::Select { rows, .. }) if rows.len() == 4
+    ));
+
+    for algorithm in algorithms[..3].iter() {
+        assert_eq!(run(storage(), sql, *algorithm), run(storage(), sql, None));
+    }
+
+    let not_applicable = |algorithm, join_type: &str| {
+        Err(JoinError::JoinAlgorithmNotApplicable {
+            algorithm,
+            join_type: join_type.to_owned(),
+        }
+        .into())
+    };
+    let test_cases = vec![
+        (
+            "SELECT * FROM Player p LEFT JOIN Item i ON p.id = i.player_id",
+            JoinAlgorithm::ReversedHash,
+            "LEFT OUTER JOIN",
+        ),
+        (
+            "SELECT * FROM Player p JOIN Item i ON p.id < i.player_id",
+            JoinAlgorithm::Hash,
+            "INNER JOIN",
+        ),
+        (
+            "SELECT * FROM Player, Item",
+            JoinAlgorithm::Hash,
+            "CROSS JOIN",
+        ),
+        (
+            "SELECT * FROM Player p RIGHT JOIN Item i ON p.id = i.player_id",
+            JoinAlgorithm::Hash,
+            "RIGHT OUTER JOIN",
+        ),
+        (
+            "SELECT * FROM Player p JOIN Item i ON p.id = i.player_id",
+            JoinAlgorithm::Merge,
+            "INNER JOIN",
+        ),
+        (
+            "
+            SELECT * FROM (SELECT * FROM Player ORDER BY id) p
+            JOIN (SELECT * FROM Item ORDER BY id) i ON p.id = i.player_id
+            ",
+            JoinAlgorithm::Merge,
+            "INNER JOIN",
+        ),
+        (
+            "
+            SELECT * FROM (SELECT * FROM Player ORDER BY id) p
+            RIGHT JOIN (SELECT * FROM Item ORDER BY player_id) i ON p.id = i.player_id
+            ",
+            JoinAlgorithm::Merge,
+            "RIGHT OUTER JOIN",
+        ),
+        (
+            "
+            SELECT * FROM Player
+            WHERE id IN (SELECT p.id FROM Player p JOIN Item i ON p.id < i.player_id)
+            ",
+            JoinAlgorithm::Hash,
+            "INNER JOIN",
+        ),
+        (
+            "
+            SELECT * FROM Player p
+            JOIN LATERAL (SELECT id FROM Item WHERE player_id = p.id) AS i ON true
+            ",
+            JoinAlgorithm::Hash,
+            "INNER JOIN",
+        ),
+    ];
+
+    for (sql, algorithm, join_type) in test_cases.into_iter() {
+        assert_eq!(
+            run(storage(), sql, Some(algorithm)),
+            not_applicable(algorithm, join_type),
+            "{}",
+            sql
+        );
+    }
+
+    // merge join needs both sides sorted by the join columns
+    let sql = "
+        SELECT p.id, i.id FROM (SELECT * FROM Player ORDER BY id) p
+        LEFT JOIN (SELECT * FROM Item ORDER BY player_id) i ON p.id = i.player_id
+    ";
+
+    assert_eq!(
+        run(storage(), sql, Some(JoinAlgorithm::Merge)),
+        run(storage(), sql, Some(JoinAlgorithm::Hash))
+    );
+    assert!(matches!(
+        run(storage(), sql, Some(JoinAlgorithm::Merge)),
+        Ok(Payload::Select { rows, .. }) if rows.len() == 4
+    ));
+
+    // nested loop joins anything
+    assert!(run(
+        storage(),
+        "SELECT * FROM Player p JOIN Item i ON p.id < i.player_id",
+        Some(JoinAlgorithm::NestedLoop),
+    )
+    .is_ok());
+}
@@ -0,0 +1,613 @@
+#[cfg(feature = "sled-storage")]
+use std::convert::TryFrom;
+
+#[cfg(feature = "sled-storage")]
+use gluesql::{parse, sled, Glue, JoinAlgorithm, PlanNode, SledStorage};
+
+#[cfg(feature = "sled-storage")]
+fn glue(namespace: &str) -> Glue {
+    let config = sled::Config::default()
+        .path(format!("data/{}", namespace))
+        .temporary(true);
+    let storage = SledStorage::try_from(config).expect("SledStorage::new");
+    let mut glue = Glue::new(storage);
+
+    let sqls = "
+        CREATE TABLE Player (id INTEGER, name TEXT, score INTEGER);
+        CREATE TABLE Item (id INTEGER, player_id INTEGER, ratio FLOAT);
+        INSERT INTO Player VALUES (1, \"Taehoon\", 30);
+        INSERT INTO Player VALUES (2, \"Mike\", 10);
+        INSERT INTO Player VALUES (3, \"Jorno\", 20);
+        INSERT INTO Item VALUES (101, 1, 0.5);
+        INSERT INTO Item VALUES (102, 3, 1.5);
+    ";
+
+    for query in parse(sqls).unwrap() {
+        glue.execute(&query).unwrap();
+    }
+
+    glue
+}
+
+#[cfg(feature = "sled-storage")]
+fn explain(glue: &Glue, sql: &str) -> String {
+    let query = parse(sql).unwrap().into_iter().next().unwrap();
+
+    glue.explain(&query).unwrap().to_string()
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn explain_select() {
+    let glue = glue("explain_select");
+
+    assert_eq!(
+        explain(
+            &glue,
+            "SELECT id FROM Player WHERE score > 10 ORDER BY score DESC LIMIT 1 OFFSET 1"
+        ),
+        "\
+Limit 1 OFFSET 1 (estimated rows: 1)
+  Project: id (estimated rows: 3)
+    Sort: score DESC (estimated rows: 3)
+      Filter: score > 10 (estimated rows: 3)
+        Scan Player (estimated rows: 3)
+"
+    );
+
+    assert_eq!(
+        explain(
+            &glue,
+            "
+            SELECT DISTINCT p.name, COUNT(*)
+            FROM Player p
+            JOIN Item i ON p.id = i.player_id
+            GROUP BY p.name
+            "
+        ),
+        "\
+Distinct (estimated rows: 3)
+  Project: name, COUNT(*) (estimated rows: 3)
+    Aggregate GROUP BY p.name (estimated rows: 3)
+      Hash Join (INNER JOIN) ON p.id = i.player_id (estimated rows: 3)
+        Scan Player AS p (estimated rows: 3)
+        Scan Item AS i (estimated rows: 2)
+"
+    );
+
+    assert_eq!(
+        explain(
+            &glue,
+            "SELECT DISTINCT ON (score) name FROM Player ORDER BY score, name"
+        ),
+        "\
+Project: name (estimated rows: 3)
+  Distinct On: score (estimated rows: 3)
+    Sort: score, name (estimated rows: 3)
+      Scan Player (estimated rows: 3)
+"
+    );
+
+    assert_eq!(
+        explain(
+            &glue,
+            "SELECT name, RANK() OVER (ORDER BY score DESC) FROM Player ORDER BY name"
+        ),
+        "\
+Project: name, RANK() OVER (ORDER BY score DESC) (estimated rows: 3)
+  Sort: name (estimated rows: 3)
+    Window: RANK() OVER (ORDER BY score DESC) (estimated rows: 3)
+      Scan Player (estimated rows: 3)
+"
+    );
+
+    assert_eq!(
+        explain(
+            &glue,
+            "SELECT * FROM Player p LEFT JOIN Item i ON i.id > p.id, (SELECT id FROM Item) AS s"
+        ),
+        "\
+Project: id, name, score, id, player_id, ratio, id (estimated rows: 6)
+  Nested Loop Join (CROSS JOIN) (estimated rows: 6)
+    Nested Loop Join (LEFT OUTER JOIN) ON i.id > p.id (estimated rows: 3)
+      Scan Player AS p (estimated rows: 3)
+      Scan Item AS i (estimated rows: 2)
+    Derived AS s (estimated rows: 2)
+      Project: id (estimated rows: 2)
+        Scan Item (estimated rows: 2)
+"
+    );
+
+    assert_eq!(
+        explain(
+            &glue,
+            "
+            SELECT s.score, COUNT(*)
+            FROM (SELECT score, name FROM Player ORDER BY score DESC) AS s
+            GROUP BY s.score
+            "
+        ),
+        "\
+Project: score, COUNT(*) (estimated rows: 3)
+  Stream Aggregate GROUP BY s.score (estimated rows: 3)
+    Derived AS s (estimated rows: 3)
+      Project: score, name (estimated rows: 3)
+        Sort: score DESC (estimated rows: 3)
+          Scan Player (estimated rows: 3)
+"
+    );
+
+    assert_eq!(
+        explain(
+            &glue,
+            "SELECT id FROM Player UNION SELECT id FROM Item ORDER BY id LIMIT 2"
+        ),
+        "\
+Limit 2 (estimated rows: 2)
+  Sort: id (estimated rows: 5)
+    UNION (estimated rows: 5)
+      Project: id (estimated rows: 3)
+        Scan Player (estimated rows: 3)
+      Project: id (estimated rows: 2)
+        Scan Item (estimated rows: 2)
+"
+    );
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn explain_plan() {
+    let glue = glue("explain_plan");
+    let query = parse("SELECT COUNT(*) FROM Player JOIN Item ON Item.ratio = Player.score")
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+    let plan = glue.explain(&query).unwrap();
+
+    assert_eq!(plan.node, PlanNode::Project(vec!["COUNT(*)".to_owned()]));
+    assert_eq!(plan.estimated_rows, 1);
+    assert_eq!(plan.actual_rows, None);
+
+    let join = &plan.children[0].children[0];
+
+    assert_eq!(
+        join.node,
+        PlanNode::Join {
+            join_type: "INNER JOIN".to_owned(),
+            algorithm: JoinAlgorithm::Hash,
+            constraint: Some("ON Item.ratio = Player.score".to_owned()),
+        }
+    );
+    assert_eq!(join.children.len(), 2);
+
+    let query = parse("DELETE FROM Player")
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+
+    assert!(glue.explain(&query).is_err());
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn explain_analyze() {
+    use gluesql::Plan;
+
+    fn collect_lines(plan: &Plan, depth: usize, lines: &mut Vec<String>) {
+        assert_eq!(plan.actual_rows.is_some(), plan.elapsed.is_some());
+
+        let actual_rows = plan
+            .actual_rows
+            .map_or_else(|| "-".to_owned(), |rows| rows.to_string());
+        lines.push(format!(
+            "{}{}: {}",
+            "  ".repeat(depth),
+            plan.node,
+            actual_rows
+        ));
+
+        plan.children
+            .iter()
+            .for_each(|child| collect_lines(child, depth + 1, lines));
+    }
+
+    let glue = glue("explain_analyze");
+    let analyze = |sql| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+        let plan = glue.explain_analyze(&query).unwrap();
+
+        assert!(plan.to_string().contains(", actual rows: "));
+        assert!(plan.to_string().contains(", time: "));
+
+        let mut actual_rows = vec![];
+        collect_lines(&plan, 0, &mut actual_rows);
+
+        actual_rows
+    };
+
+    assert_eq!(
+        analyze(
+            "
+            SELECT p.name FROM Player p
+            JOIN Item i ON p.id = i.player_id
+            WHERE i.ratio > 1
+            ORDER BY p.name
+            LIMIT 1
+            "
+        ),
+        vec![
+            "Limit 1: 1",
+            "  Project: name: 1",
+            "    Sort: p.name: 1",
+            "      Filter: i.ratio > 1: 1",
+            "        Hash Join (INNER JOIN) ON p.id = i.player_id: 2",
+            "          Scan Player AS p: 3",
+            "          Scan Item AS i: 2",
+        ]
+    );
+
+    assert_eq!(
+        analyze(
+            "
+            SELECT score, COUNT(*) FROM Player p
+            LEFT JOIN Item i ON i.id > p.id, (SELECT id FROM Item) AS s
+            GROUP BY score
+            "
+        ),
+        vec![
+            "Project: score, COUNT(*): 3",
+            "  Aggregate GROUP BY score: 3",
+            "    Nested Loop Join (CROSS JOIN): 12",
+            "      Nested Loop Join (LEFT OUTER JOIN) ON i.id > p.id: 6",
+            "        Scan Player AS p: 3",
+            "        Scan Item AS i: 6",
+            "      Derived AS s: 12",
+            "        Project: id: -",
+            "          Scan Item: -",
+        ]
+    );
+
+    assert_eq!(
+        analyze("SELECT DISTINCT ON (score) name FROM Player ORDER BY score, name"),
+        vec![
+            "Project: name: 3",
+            "  Distinct On: score: 3",
+            "    Sort: score, name: -",
+            "      Scan Player: 3",
+        ]
+    );
+
+    [
+        "SELECT id FROM Player UNION SELECT id FROM Item",
+        "DELETE FROM Player",
+    ]
+    .iter()
+    .for_each(|sql| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+
+        assert!(glue.explain_analyze(&query).is_err());
+    });
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn explain_statement() {
+    use gluesql::{Explain, Payload};
+
+    let mut glue = glue("explain_statement");
+    let mut run = |sql| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+
+        match glue.execute(&query) {
+            Ok(Payload::Explain(plan)) => (query.1, plan),
+            payload => panic!("{:?}", payload),
+        }
+    };
+
+    let (explain, plan) = run("EXPLAIN SELECT id FROM Player WHERE score > 10");
+    assert_eq!(explain, Some(Explain::Plan));
+    assert_eq!(
+        plan.to_string(),
+        "\
+Project: id (estimated rows: 3)
+  Filter: score > 10 (estimated rows: 3)
+    Scan Player (estimated rows: 3)
+"
+    );
+
+    let (explain, plan) = run("explain analyze SELECT id FROM Player WHERE score > 10");
+    assert_eq!(explain, Some(Explain::Analyze));
+    assert_eq!(plan.actual_rows, Some(2));
+
+    let queries = parse(
+        "
+        EXPLAIN DELETE FROM Player;
+        DELETE FROM Player WHERE id = 3;
+        SELECT \"explain\" FROM (SELECT id AS \"explain\" FROM Player) AS p;
+        ",
+    )
+    .unwrap();
+    let explains = queries.iter().map(|query| query.1).collect::<Vec<_>>();
+    assert_eq!(explains, vec![Some(Explain::Plan), None, None]);
+
+    assert!(glue.execute(&queries[0]).is_err());
+    assert_eq!(glue.execute(&queries[1]), Ok(Payload::Delete(1)));
+
+    assert!(parse("EXPLAIN").is_err());
+    assert!(parse("EXPLAIN ANALYZE;").is_err());
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn join_order() {
+    use gluesql::{execute, explain, parse, JoinAlgorithm, MemoryStorage, PlanNode, Store};
+
+    let sqls = "
+        CREATE TABLE Team (id INTEGER, name TEXT);
+        CREATE TABLE Member (id INTEGER, team_id INTEGER);
+        INSERT INTO Team VALUES (1, \"Red\"), (2, \"Blue\");
+        INSERT INTO Member VALUES (10, 1), (11, 2), (12, 1);
+    ";
+    let storage = parse(sqls)
+        .unwrap()
+        .iter()
+        .fold(MemoryStorage::new(), |storage, query| {
+            execute(storage, query).map(|(storage, _)| storage).unwrap()
+        });
+
+    assert_eq!(storage.row_count_estimate("Team"), Some(2));
+    assert_eq!(storage.row_count_estimate("Member"), Some(3));
+    assert_eq!(storage.row_count_estimate("Nothing"), None);
+
+    let algorithm = |sql| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+        let plan = explain(&storage, &query).unwrap();
+
+        match &plan.children[0].node {
+            PlanNode::Join { algorithm, .. } => *algorithm,
+            node => panic!("not a join: {:?}", node),
+        }
+    };
+
+    let test_cases = vec![
+        (
+            JoinAlgorithm::ReversedHash,
+            "SELECT * FROM Team t JOIN Member m ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::Hash,
+            "SELECT * FROM Member m JOIN Team t ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::Hash,
+            "SELECT * FROM Team t LEFT JOIN Member m ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::Hash,
+            "SELECT * FROM (SELECT * FROM Member) m JOIN Team t ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::NestedLoop,
+            "SELECT * FROM Team t JOIN Member m ON t.id > m.team_id",
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(expected, sql)| assert_eq!(expected, algorithm(sql), "{}", sql));
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn merge_join() {
+    use gluesql::{
+        execute, execute_with_options, explain, parse, ExecuteOptions, JoinAlgorithm,
+        MemoryStorage, Payload, PlanNode, Row, Value,
+    };
+
+    let sqls = "
+        CREATE TABLE Team (id INTEGER PRIMARY KEY, name TEXT);
+        CREATE TABLE Member (id INTEGER PRIMARY KEY, team_id INTEGER);
+        INSERT INTO Team VALUES (1, \"Red\"), (2, \"Blue\"), (3, \"Green\");
+        INSERT INTO Member VALUES (10, 1), (11, 2), (12, 1);
+    ";
+    let storage = || {
+        parse(sqls)
+            .unwrap()
+            .iter()
+            .fold(MemoryStorage::new(), |storage, query| {
+                execute(storage, query).map(|(storage, _)| storage).unwrap()
+            })
+    };
+
+    let algorithm = |sql| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+        let plan = explain(&storage(), &query).unwrap();
+
+        match &plan.children[0].node {
+            PlanNode::Join { algorithm, .. } => *algorithm,
+            node => panic!("not a join: {:?}", node),
+        }
+    };
+    let run = |sql, join_algorithm| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+        let options = ExecuteOptions {
+            join_algorithm,
+            ..ExecuteOptions::default()
+        };
+
+        execute_with_options(storage(), &query, options)
+            .map(|(_, payload)| payload)
+            .unwrap()
+    };
+
+    let test_cases = vec![
+        (
+            JoinAlgorithm::Merge,
+            "SELECT * FROM Team t JOIN (SELECT * FROM Member ORDER BY team_id) m ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::Merge,
+            "SELECT * FROM Team t LEFT JOIN (SELECT * FROM Member ORDER BY team_id) m ON m.team_id = t.id",
+        ),
+        (
+            JoinAlgorithm::Merge,
+            "SELECT * FROM (SELECT * FROM Member ORDER BY team_id) m JOIN Team t ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::Hash,
+            "SELECT * FROM Team t JOIN (SELECT * FROM Member ORDER BY team_id DESC) m ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::Lookup,
+            "SELECT * FROM Member m JOIN Team t ON t.id = m.team_id",
+        ),
+        (
+            JoinAlgorithm::NestedLoop,
+            "SELECT * FROM Team t RIGHT JOIN (SELECT * FROM Member ORDER BY team_id) m ON t.id = m.team_id",
+        ),
+    ];
+
+    for (expected, sql) in test_cases.into_iter() {
+        assert_eq!(expected, algorithm(sql), "{}", sql);
+        assert_eq!(
+            run(sql, None),
+            run(sql, Some(JoinAlgorithm::NestedLoop)),
+            "{}",
+            sql
+        );
+    }
+
+    let sql = "
+        SELECT t.id, m.id FROM Team t
+        LEFT JOIN (SELECT * FROM Member ORDER BY team_id) m ON t.id = m.team_id
+    ";
+    let rows = vec![(1, Some(10)), (1, Some(12)), (2, Some(11)), (3, None)]
+        .into_iter()
+        .map(|(team_id, member_id)| {
+            let member_id = member_id.map_or(Value::Empty, Value::I64);
+
+            Row(vec![Value::I64(team_id), member_id])
+        })
+        .collect();
+
+    assert_eq!(
+        run(sql, None),
+        Payload::Select {
+            labels: vec!["id".to_owned(), "id".to_owned()],
+            rows,
+        }
+    );
+
+    // rows are no longer known to be in order once the primary key is updated
+    let sql =
+        "SELECT * FROM Team t JOIN (SELECT * FROM Member ORDER BY team_id) m ON t.id = m.team_id";
+    let query = parse(sql).unwrap().into_iter().next().unwrap();
+    let update = parse("UPDATE Team SET id = 4 WHERE id = 1").unwrap();
+    let (storage, _) = execute(storage(), &update[0]).unwrap();
+    let plan = explain(&storage, &query).unwrap();
+
+    assert!(matches!(
+        plan.children[0].node,
+        PlanNode::Join {
+            algorithm: JoinAlgorithm::Hash,
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn cross_join_filtered() {
+    use gluesql::{execute, explain, parse, JoinAlgorithm, MemoryStorage, Plan, PlanNode};
+
+    let sqls = "
+        CREATE TABLE Team (id INTEGER, name TEXT);
+        CREATE TABLE Member (id INTEGER, team_id INTEGER);
+        INSERT INTO Team VALUES (1, \"Red\"), (2, \"Blue\"), (3, \"Green\");
+        INSERT INTO Member VALUES (10, 1), (11, 2);
+    ";
+    let storage = parse(sqls)
+        .unwrap()
+        .iter()
+        .fold(MemoryStorage::new(), |storage, query| {
+            execute(storage, query).map(|(storage, _)| storage).unwrap()
+        });
+
+    fn find_joins(plan: &Plan) -> Vec<(JoinAlgorithm, Option<String>)> {
+        let joins = plan.children.iter().flat_map(find_joins);
+
+        match &plan.node {
+            PlanNode::Join {
+                algorithm,
+                constraint,
+                ..
+            } => vec![(*algorithm, constraint.clone())]
+                .into_iter()
+                .chain(joins)
+                .collect(),
+            _ => joins.collect(),
+        }
+    }
+
+    let joins = |sql| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+
+        find_joins(&explain(&storage, &query).unwrap())
+    };
+    let on = |constraint: &str| Some(format!("ON {}", constraint));
+
+    let test_cases = vec![
+        (
+            vec![(JoinAlgorithm::Hash, on("t.id = m.team_id"))],
+            "SELECT * FROM Team t, Member m WHERE t.id = m.team_id",
+        ),
+        (
+            vec![(JoinAlgorithm::ReversedHash, on("t.id = m.team_id"))],
+            "SELECT * FROM Member m, Team t WHERE m.id > 10 AND t.id = m.team_id",
+        ),
+        (
+            vec![(JoinAlgorithm::ReversedHash, on("m.team_id = t.id"))],
+            "SELECT * FROM Member m CROSS JOIN Team t WHERE m.team_id = t.id AND t.name = 'Blue'",
+        ),
+        (
+            vec![(JoinAlgorithm::NestedLoop, None)],
+            "SELECT * FROM Member m, Team t WHERE m.team_id > t.id",
+        ),
+        (
+            vec![(JoinAlgorithm::NestedLoop, None)],
+            "SELECT * FROM Member m, Team t WHERE m.team_id = 1 OR t.id = m.team_id",
+        ),
+    ];
+
+    test_cases
+        .into_iter()
+        .for_each(|(expected, sql)| assert_eq!(expected, joins(sql), "{}", sql));
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn explain_nested_join() {
+    let glue = glue("explain_nested_join");
+
+    assert_eq!(
+        explain(
+            &glue,
+            "
+            SELECT p.name, i.id FROM Player p
+            LEFT JOIN (Item i JOIN Player q ON q.id = i.player_id) ON p.id = i.player_id
+            "
+        ),
+        "\
+Project: name, id (estimated rows: 3)
+  Nested Loop Join (LEFT OUTER JOIN) ON p.id = i.player_id (estimated rows: 3)
+    Scan Player AS p (estimated rows: 3)
+    Nested (estimated rows: 3)
+      Hash Join (INNER JOIN) ON q.id = i.player_id (estimated rows: 3)
+        Scan Item AS i (estimated rows: 2)
+        Scan Player AS q (estimated rows: 3)
+"
+    );
+}
@@ -0,0 +1,36 @@
+#[cfg(feature = "memory-storage")]
+use gluesql::{execute, generate_tests, tests::*, MemoryStorage, Payload, Query, Result};
+
+#[cfg(feature = "memory-storage")]
+struct MemoryTester {
+    storage: Option<MemoryStorage>,
+}
+
+#[cfg(feature = "memory-storage")]
+impl Tester for MemoryTester {
+    fn new(_: &str) -> Self {
+        let storage = Some(MemoryStorage::new());
+
+        MemoryTester { storage }
+    }
+
+    fn execute(&mut self, query: &Query) -> Result<Payload> {
+        let storage = self.storage.take().unwrap();
+
+        match execute(storage, query) {
+            Ok((storage, payload)) => {
+                self.storage = Some(storage);
+
+                Ok(payload)
+            }
+            Err((storage, error)) => {
+                self.storage = Some(storage);
+
+                Err(error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "memory-storage")]
+generate_tests!(test, MemoryTester);
@@ -0,0 +1,651 @@
+#[cfg(feature = "memory-storage")]
+use std::cell::Cell;
+#[cfg(feature = "memory-storage")]
+use std::io;
+#[cfg(feature = "memory-storage")]
+use std::rc::Rc;
+#[cfg(feature = "memory-storage")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "memory-storage")]
+use std::sync::Arc;
+
+#[cfg(feature = "memory-storage")]
+use gluesql::{
+    execute, execute_cancellable, parse, DataKey, Error, ExecuteError, MemoryStorage, MutResult,
+    Payload, Predicate, Result, Row, RowIter, Schema, Store, StoreMut, Value,
+    CANCEL_CHECK_INTERVAL,
+};
+
+/// Wraps `MemoryStorage`, every scan of `Faulty` table fails at its third row, `Endless` table
+/// never runs out of rows and sets `cancelled` at its 100th row, filtered scans of it return
+/// every row, rows of `Narrow` table lose their last value, and schema fetches, scans, filtered
+/// scans and point fetches by key are counted.
+#[cfg(feature = "memory-storage")]
+struct MockStorage {
+    storage: Box<MemoryStorage>,
+    schema_fetches: Cell<usize>,
+    scans: Cell<usize>,
+    filtered_scans: Cell<usize>,
+    key_fetches: Cell<usize>,
+    cancelled: Arc<AtomicBool>,
+    rows_scanned: Rc<Cell<usize>>,
+}
+
+#[cfg(feature = "memory-storage")]
+impl MockStorage {
+    fn new(sqls: &str) -> Self {
+        let storage = Self {
+            storage: Box::new(MemoryStorage::new()),
+            schema_fetches: Cell::new(0),
+            scans: Cell::new(0),
+            filtered_scans: Cell::new(0),
+            key_fetches: Cell::new(0),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            rows_scanned: Rc::new(Cell::new(0)),
+        };
+
+        parse(sqls)
+            .unwrap()
+            .iter()
+            .try_fold(storage, |storage, query| {
+                execute(storage, query).map(|(storage, _)| storage)
+            })
+            .map_err(|(_, error)| error)
+            .unwrap()
+    }
+
+    fn lift<U>(
+        self,
+        f: impl FnOnce(MemoryStorage) -> MutResult<MemoryStorage, U>,
+    ) -> MutResult<Self, U> {
+        let Self {
+            storage,
+            schema_fetches,
+            scans,
+            filtered_scans,
+            key_fetches,
+            cancelled,
+            rows_scanned,
+        } = self;
+        let wrap = |storage| Self {
+            storage: Box::new(storage),
+            schema_fetches,
+            scans,
+            filtered_scans,
+            key_fetches,
+            cancelled,
+            rows_scanned,
+        };
+
+        match f(*storage) {
+            Ok((storage, value)) => Ok((wrap(storage), value)),
+            Err((storage, error)) => Err((wrap(storage), error)),
+        }
+    }
+}
+
+#[cfg(feature = "memory-storage")]
+impl StoreMut<DataKey> for MockStorage {
+    fn generate_id(self, table_name: &str) -> MutResult<Self, DataKey> {
+        self.lift(|storage| storage.generate_id(table_name))
+    }
+
+    fn insert_schema(self, schema: &Schema) -> MutResult<Self, ()> {
+        self.lift(|storage| storage.insert_schema(schema))
+    }
+
+    fn delete_schema(self, table_name: &str) -> MutResult<Self, ()> {
+        self.lift(|storage| storage.delete_schema(table_name))
+    }
+
+    fn insert_data(self, key: &DataKey, row: Row) -> MutResult<Self, Row> {
+        self.lift(|storage| storage.insert_data(key, row))
+    }
+
+    fn delete_data(self, key: &DataKey) -> MutResult<Self, ()> {
+        self.lift(|storage| storage.delete_data(key))
+    }
+}
+
+#[cfg(feature = "memory-storage")]
+impl Store<DataKey> for MockStorage {
+    fn fetch_schema(&self, table_name: &str) -> Result<Schema> {
+        self.schema_fetches.set(self.schema_fetches.get() + 1);
+
+        self.storage.fetch_schema(table_name)
+    }
+
+    fn scan_data(&self, table_name: &str) -> Result<RowIter<DataKey>> {
+        self.scans.set(self.scans.get() + 1);
+
+        if table_name == "Endless" {
+            let cancelled = Arc::clone(&self.cancelled);
+            let rows_scanned = Rc::clone(&self.rows_scanned);
+            let rows = (0..).map(move |id| {
+                if id == 100 {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
+
+                rows_scanned.set(rows_scanned.get() + 1);
+
+                let key = DataKey {
+                    table_name: "Endless".to_owned(),
+                    id,
+                };
+
+                Ok((key, Row(vec![Value::I64(id as i64)])))
+            });
+
+            return Ok(Box::new(rows));
+        }
+
+        let rows = self.storage.scan_data(table_name)?;
+
+        if table_name == "Narrow" {
+            let rows = rows.map(|item| {
+                item.map(|(key, Row(mut values))| {
+                    values.pop();
+
+                    (key, Row(values))
+                })
+            });
+
+            return Ok(Box::new(rows));
+        }
+
+        if table_name != "Faulty" {
+            return Ok(rows);
+        }
+
+        let rows = rows.enumerate().map(|(i, item)| match i {
+            2 => Err(Error::Storage(Box::new(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "corrupt row",
+            )))),
+            _ => item,
+        });
+
+        Ok(Box::new(rows))
+    }
+
+    fn scan_filtered(
+        &self,
+        table_name: &str,
+        predicates: &[Predicate],
+    ) -> Result<RowIter<DataKey>> {
+        self.filtered_scans.set(self.filtered_scans.get() + 1);
+
+        let rows = self.scan_data(table_name)?;
+
+        if table_name == "Endless" {
+            return Ok(rows);
+        }
+
+        let predicates = predicates.to_vec();
+        let rows = rows.filter(move |item| match item {
+            Ok((_, row)) => predicates.iter().all(|predicate| predicate.check(row)),
+            Err(_) => true,
+        });
+
+        Ok(Box::new(rows))
+    }
+
+    fn get_indexed_column(&self, table_name: &str) -> Option<usize> {
+        self.storage.get_indexed_column(table_name)
+    }
+
+    fn get_keys_by_index(&self, table_name: &str, value: &Value) -> Result<Option<Vec<DataKey>>> {
+        self.storage.get_keys_by_index(table_name, value)
+    }
+
+    fn get_data_by_key(&self, table_name: &str, key: &DataKey) -> Result<Option<(DataKey, Row)>> {
+        self.key_fetches.set(self.key_fetches.get() + 1);
+
+        self.storage.get_data_by_key(table_name, key)
+    }
+}
+
+#[cfg(feature = "memory-storage")]
+fn run(storage: MockStorage, sql: &str) -> MutResult<MockStorage, Payload> {
+    let query = parse(sql).unwrap().into_iter().next().unwrap();
+
+    execute(storage, &query)
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn storage_error() {
+    let storage = MockStorage::new(
+        "
+        CREATE TABLE Faulty (id INTEGER);
+        CREATE TABLE Healthy (id INTEGER);
+        INSERT INTO Faulty VALUES (1), (2), (3), (4);
+        INSERT INTO Healthy VALUES (1), (2), (3), (4);
+    ",
+    );
+
+    let storage = [
+        "SELECT * FROM Faulty",
+        "SELECT * FROM Faulty WHERE id > 1",
+        "SELECT * FROM Healthy WHERE id IN (SELECT id FROM Faulty)",
+        "SELECT * FROM Healthy JOIN Faulty ON Healthy.id = Faulty.id",
+        "SELECT * FROM Healthy LEFT JOIN Faulty ON Healthy.id + 1 = Faulty.id",
+        "SELECT id FROM Faulty ORDER BY id DESC",
+        "SELECT COUNT(*) FROM Faulty",
+        "SELECT DISTINCT id FROM Faulty",
+        "SELECT id FROM Healthy UNION SELECT id FROM Faulty",
+        "SELECT * FROM (SELECT id FROM Faulty) AS Derived",
+        "UPDATE Faulty SET id = 5",
+        "DELETE FROM Faulty",
+    ]
+    .iter()
+    .fold(storage, |storage, sql| match run(storage, sql) {
+        Err((storage, Error::Storage(error))) => {
+            assert_eq!(error.to_string(), "corrupt row", "{}", sql);
+
+            storage
+        }
+        Err((_, error)) => panic!("{}: unexpected error {:?}", sql, error),
+        Ok((_, payload)) => panic!("{}: unexpected payload {:?}", sql, payload),
+    });
+
+    // rows are scanned lazily, so the broken row is never read
+    let (_, payload) = run(storage, "SELECT * FROM Faulty LIMIT 2")
+        .map_err(|(_, error)| error)
+        .unwrap();
+
+    assert_eq!(
+        payload,
+        Payload::Select {
+            labels: vec!["id".to_owned()],
+            rows: vec![Row(vec![Value::I64(1)]), Row(vec![Value::I64(2)])],
+        }
+    );
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn row_width_mismatch() {
+    let storage = MockStorage::new(
+        "
+        CREATE TABLE Narrow (id INTEGER, name TEXT);
+        CREATE TABLE Healthy (id INTEGER);
+        INSERT INTO Narrow VALUES (1, \"a\"), (2, \"b\");
+        INSERT INTO Healthy VALUES (1), (3);
+    ",
+    );
+
+    let storage = [
+        "SELECT * FROM Narrow",
+        "SELECT name FROM Narrow",
+        "SELECT Narrow.* FROM Narrow",
+        "SELECT * FROM Healthy JOIN Narrow ON Healthy.id = Narrow.id",
+        "SELECT * FROM Healthy LEFT JOIN Narrow ON Healthy.id = Narrow.id",
+    ]
+    .iter()
+    .fold(storage, |storage, sql| match run(storage, sql) {
+        Err((storage, error)) => {
+            let expected = ExecuteError::RowWidthMismatch {
+                expected: 2,
+                found: 1,
+            };
+            assert_eq!(error, expected.into(), "{}", sql);
+
+            storage
+        }
+        Ok((_, payload)) => panic!("{}: unexpected payload {:?}", sql, payload),
+    });
+
+    // padded rows of the outer join fit whatever columns the table has
+    let (_, payload) = run(
+        storage,
+        "SELECT Healthy.id FROM Healthy LEFT JOIN Narrow ON Healthy.id = Narrow.id + 10",
+    )
+    .map_err(|(_, error)| error)
+    .unwrap();
+
+    assert_eq!(
+        payload,
+        Payload::Select {
+            labels: vec!["id".to_owned()],
+            rows: vec![Row(vec![Value::I64(1)]), Row(vec![Value::I64(3)])],
+        }
+    );
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn schema_fetched_once() {
+    let storage = MockStorage::new(
+        "
+        CREATE TABLE Player (id INTEGER);
+        CREATE TABLE Item (id INTEGER, player_id INTEGER);
+        INSERT INTO Player VALUES (1), (2);
+        INSERT INTO Item VALUES (101, 1), (102, 1);
+    ",
+    );
+
+    let count = |storage: MockStorage, sql: &str| {
+        storage.schema_fetches.set(0);
+
+        let (storage, _) = run(storage, sql).map_err(|(_, error)| error).unwrap();
+        let count = storage.schema_fetches.get();
+
+        (storage, count)
+    };
+
+    let (storage, found) = count(
+        storage,
+        "SELECT * FROM Player p1 JOIN Player p2 ON p1.id = p2.id JOIN Player p3 ON p3.id = p2.id",
+    );
+    assert_eq!(found, 1);
+
+    let (storage, found) = count(
+        storage,
+        "SELECT * FROM Player JOIN Item ON Player.id = Item.player_id JOIN Item i2 ON i2.id = Item.id",
+    );
+    assert_eq!(found, 2);
+
+    let (_, found) = count(
+        storage,
+        "SELECT * FROM Player p1, (SELECT * FROM Player) AS p2",
+    );
+    assert_eq!(found, 2);
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn semi_join_scanned_once() {
+    let storage = MockStorage::new(
+        "
+        CREATE TABLE Player (id INTEGER);
+        CREATE TABLE Item (id INTEGER, player_id INTEGER);
+        INSERT INTO Player VALUES (1), (2), (3);
+        INSERT INTO Item VALUES (101, 1), (102, 3);
+    ",
+    );
+
+    let count = |storage: MockStorage, sql: &str| {
+        storage.scans.set(0);
+
+        let (storage, _) = run(storage, sql).map_err(|(_, error)| error).unwrap();
+        let count = storage.scans.get();
+
+        (storage, count)
+    };
+
+    let (storage, found) = count(
+        storage,
+        "SELECT * FROM Player WHERE id NOT IN (SELECT player_id FROM Item)",
+    );
+    assert_eq!(found, 2);
+
+    let (_, found) = count(
+        storage,
+        "SELECT * FROM Player p WHERE id IN (SELECT player_id FROM Item WHERE id > p.id)",
+    );
+    assert_eq!(found, 5);
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn correlated_subquery_cached() {
+    let storage = MockStorage::new(
+        "
+        CREATE TABLE Emp (id INTEGER, dept TEXT, salary INTEGER, ratio FLOAT);
+        INSERT INTO Emp VALUES
+            (1, \"a\", 10, 0.5), (2, \"a\", 20, 0.5), (3, \"a\", 30, 1.5), (4, \"b\", 5, 1.5),
+            (5, \"b\", 15, 2.5), (6, \"a\", 30, 2.5);
+    ",
+    );
+
+    let count = |storage: MockStorage, sql: &str| {
+        storage.scans.set(0);
+
+        let (storage, payload) = run(storage, sql).map_err(|(_, error)| error).unwrap();
+        let count = storage.scans.get();
+
+        (storage, payload, count)
+    };
+    let ids = |ids: Vec<i64>| Payload::Select {
+        labels: vec!["id".to_owned()],
+        rows: ids
+            .into_iter()
+            .map(|id| Row(vec![Value::I64(id)]))
+            .collect(),
+    };
+
+    // the subquery runs once for each department
+    let (storage, payload, found) = count(
+        storage,
+        "SELECT id FROM Emp WHERE salary > (SELECT AVG(salary) FROM Emp e WHERE e.dept = Emp.dept)",
+    );
+    assert_eq!(payload, ids(vec![3, 5, 6]));
+    assert_eq!(found, 1 + 2);
+
+    // and once for each department and salary
+    let (storage, payload, found) = count(
+        storage,
+        "
+        SELECT id FROM Emp
+        WHERE (SELECT COUNT(*) FROM Emp e WHERE e.dept = Emp.dept AND e.salary < Emp.salary) = 2
+        ",
+    );
+    assert_eq!(payload, ids(vec![3, 6]));
+    assert_eq!(found, 1 + 5);
+
+    // only once if it does not refer to the outer row
+    let (storage, payload, found) = count(
+        storage,
+        "SELECT id FROM Emp WHERE salary = (SELECT MAX(salary) FROM Emp)",
+    );
+    assert_eq!(payload, ids(vec![3, 6]));
+    assert_eq!(found, 1 + 1);
+
+    // FLOAT values are not cached
+    let (_, payload, found) = count(
+        storage,
+        "SELECT id FROM Emp WHERE (SELECT COUNT(*) FROM Emp e WHERE e.ratio = Emp.ratio) > 1",
+    );
+    assert_eq!(payload, ids(vec![1, 2, 3, 4, 5, 6]));
+    assert_eq!(found, 1 + 6);
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn limit_stops_scan() {
+    let storage = MockStorage::new("CREATE TABLE Endless (id INTEGER);");
+
+    let ids = |rows: Vec<i64>| Payload::Select {
+        labels: vec!["id".to_owned()],
+        rows: rows
+            .into_iter()
+            .map(|id| Row(vec![Value::I64(id)]))
+            .collect(),
+    };
+
+    [
+        ("SELECT * FROM Endless LIMIT 3", ids(vec![0, 1, 2]), 3),
+        (
+            "SELECT id FROM Endless LIMIT 2 OFFSET 5",
+            ids(vec![5, 6]),
+            7,
+        ),
+        ("SELECT id FROM Endless LIMIT 0 OFFSET 5", ids(vec![]), 0),
+        (
+            "SELECT * FROM Endless WHERE id > 10 LIMIT 2",
+            ids(vec![11, 12]),
+            13,
+        ),
+        (
+            "SELECT id + 1 AS id FROM Endless WHERE id % 2 = 0 LIMIT 2 OFFSET 1",
+            ids(vec![3, 5]),
+            5,
+        ),
+    ]
+    .iter()
+    .fold(storage, |storage, (sql, expected, scanned)| {
+        storage.rows_scanned.set(0);
+
+        let (storage, payload) = run(storage, sql).map_err(|(_, error)| error).unwrap();
+        assert_eq!(&payload, expected, "{}", sql);
+        assert_eq!(storage.rows_scanned.get(), *scanned, "{}", sql);
+
+        storage
+    });
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn primary_key_lookup() {
+    let storage = MockStorage::new(
+        "
+        CREATE TABLE Player (id INTEGER PRIMARY KEY, name TEXT);
+        CREATE TABLE Item (id INTEGER, player_id INTEGER);
+        INSERT INTO Player VALUES (1, \"Taehoon\"), (2, \"Mike\"), (3, \"Jorno\");
+        INSERT INTO Item VALUES (10, 1), (11, 3), (12, 3), (13, 4);
+    ",
+    );
+
+    let name = |name: &str| Value::Str(name.to_owned());
+    let select = |labels: &[&str], rows: Vec<Vec<Value>>| Payload::Select {
+        labels: labels.iter().map(|label| label.to_string()).collect(),
+        rows: rows.into_iter().map(Row).collect(),
+    };
+
+    [
+        (
+            "SELECT name FROM Player WHERE id = 2",
+            select(&["name"], vec![vec![name("Mike")]]),
+            0,
+            1,
+        ),
+        (
+            "SELECT name FROM Player WHERE 2 = id AND name = 'Taehoon'",
+            select(&["name"], vec![]),
+            0,
+            1,
+        ),
+        (
+            "SELECT name FROM Player WHERE id = 5",
+            select(&["name"], vec![]),
+            0,
+            0,
+        ),
+        (
+            "SELECT name FROM Player WHERE id > 2",
+            select(&["name"], vec![vec![name("Jorno")]]),
+            1,
+            0,
+        ),
+        (
+            "SELECT Item.id, name FROM Item JOIN Player ON Player.id = Item.player_id",
+            select(
+                &["id", "name"],
+                vec![
+                    vec![Value::I64(10), name("Taehoon")],
+                    vec![Value::I64(11), name("Jorno")],
+                    vec![Value::I64(12), name("Jorno")],
+                ],
+            ),
+            1,
+            3,
+        ),
+        (
+            "SELECT Item.id, name FROM Item LEFT JOIN Player ON Item.player_id = Player.id",
+            select(
+                &["id", "name"],
+                vec![
+                    vec![Value::I64(10), name("Taehoon")],
+                    vec![Value::I64(11), name("Jorno")],
+                    vec![Value::I64(12), name("Jorno")],
+                    vec![Value::I64(13), Value::Empty],
+                ],
+            ),
+            1,
+            3,
+        ),
+    ]
+    .iter()
+    .fold(storage, |storage, (sql, expected, scans, key_fetches)| {
+        storage.scans.set(0);
+        storage.key_fetches.set(0);
+
+        let (storage, payload) = run(storage, sql).map_err(|(_, error)| error).unwrap();
+        assert_eq!(&payload, expected, "{}", sql);
+        assert_eq!(storage.scans.get(), *scans, "{}", sql);
+        assert_eq!(storage.key_fetches.get(), *key_fetches, "{}", sql);
+
+        storage
+    });
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn cancel() {
+    let storage = MockStorage::new(
+        "
+        CREATE TABLE Endless (id INTEGER);
+        CREATE TABLE Healthy (id INTEGER);
+        INSERT INTO Healthy VALUES (1), (2);
+    ",
+    );
+    let run = |storage: MockStorage, sql: &str| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+        let cancelled = Arc::clone(&storage.cancelled);
+
+        execute_cancellable(storage, &query, cancelled)
+    };
+
+    let (storage, payload) = run(storage, "SELECT id FROM Healthy")
+        .map_err(|(_, error)| error)
+        .unwrap();
+    assert_eq!(
+        payload,
+        Payload::Select {
+            labels: vec!["id".to_owned()],
+            rows: vec![Row(vec![Value::I64(1)]), Row(vec![Value::I64(2)])],
+        }
+    );
+
+    // predicates are still given to the storage
+    let (storage, payload) = run(storage, "SELECT id FROM Healthy WHERE id = 2")
+        .map_err(|(_, error)| error)
+        .unwrap();
+    assert_eq!(
+        payload,
+        Payload::Select {
+            labels: vec!["id".to_owned()],
+            rows: vec![Row(vec![Value::I64(2)])],
+        }
+    );
+    assert_eq!(storage.filtered_scans.get(), 1);
+
+    let storage = [
+        "SELECT * FROM Endless WHERE id < 0",
+        "SELECT * FROM Healthy WHERE id IN (SELECT id FROM Endless WHERE id < 0)",
+        "DELETE FROM Endless",
+    ]
+    .iter()
+    .fold(storage, |storage, sql| {
+        storage.cancelled.store(false, Ordering::Relaxed);
+        storage.rows_scanned.set(0);
+
+        match run(storage, sql) {
+            Err((storage, error)) => {
+                assert_eq!(error, ExecuteError::Cancelled.into(), "{}", sql);
+                assert!(storage.rows_scanned.get() <= 101 + CANCEL_CHECK_INTERVAL);
+
+                storage
+            }
+            Ok((_, payload)) => panic!("{}: unexpected payload {:?}", sql, payload),
+        }
+    });
+
+    // the flag is still set, so the query fails before scanning any row
+    let error = run(storage, "SELECT * FROM Healthy")
+        .map(|(_, payload)| payload)
+        .map_err(|(_, error)| error)
+        .unwrap_err();
+    assert_eq!(error, ExecuteError::Cancelled.into());
+}
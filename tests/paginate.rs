@@ -0,0 +1,141 @@
+#[cfg(feature = "memory-storage")]
+use gluesql::{
+    execute_batch, paginate, parse, DataKey, MemoryStorage, Page, PaginateError, Query, Result,
+    Row, Value,
+};
+
+#[cfg(feature = "memory-storage")]
+fn run(storage: MemoryStorage, sql: &str) -> MemoryStorage {
+    execute_batch(storage, sql)
+        .map_err(|(_, error)| error)
+        .unwrap()
+        .0
+}
+
+#[cfg(feature = "memory-storage")]
+fn query(sql: &str) -> Query {
+    parse(sql).unwrap().into_iter().next().unwrap()
+}
+
+#[cfg(feature = "memory-storage")]
+fn ids(page: &Page<DataKey>) -> Vec<i64> {
+    page.rows
+        .iter()
+        .map(|row| match row {
+            Row(values) => match values.as_slice() {
+                [Value::I64(id), ..] => *id,
+                values => panic!("unexpected values {:?}", values),
+            },
+        })
+        .collect()
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn paginate_by_key() {
+    let storage = run(
+        MemoryStorage::new(),
+        "
+        CREATE TABLE Player (id INTEGER, name TEXT, score INTEGER);
+        INSERT INTO Player VALUES (1, \"Alice\", 30), (2, \"Bob\", 10), (3, \"Carol\", 20);
+        INSERT INTO Player VALUES (4, \"Dave\", 50), (5, \"Eve\", 40);
+        ",
+    );
+    let select = query("SELECT id, name FROM Player");
+
+    let page = paginate(&storage, &select, None, 2).unwrap();
+    assert_eq!(page.labels, vec!["id".to_owned(), "name".to_owned()]);
+    assert_eq!(ids(&page), vec![1, 2]);
+    assert_eq!(
+        page.rows[0],
+        Row(vec![Value::I64(1), Value::Str("Alice".to_owned())])
+    );
+
+    // a row inserted between the pages comes after the rows which were already there
+    let storage = run(storage, "INSERT INTO Player VALUES (6, \"Frank\", 60);");
+
+    let page = paginate(&storage, &select, page.next, 2).unwrap();
+    assert_eq!(ids(&page), vec![3, 4]);
+
+    // deleting a row of a fetched page does not shift the next pages either
+    let storage = run(storage, "DELETE FROM Player WHERE id = 2;");
+
+    let page = paginate(&storage, &select, page.next, 2).unwrap();
+    assert_eq!(ids(&page), vec![5, 6]);
+    assert_eq!(page.next, None);
+
+    let filtered = query("SELECT id, score * 2 AS double FROM Player WHERE score >= 30");
+    let page = paginate(&storage, &filtered, None, 2).unwrap();
+    assert_eq!(page.labels, vec!["id".to_owned(), "double".to_owned()]);
+    assert_eq!(ids(&page), vec![1, 4]);
+
+    let page = paginate(&storage, &filtered, page.next, 2).unwrap();
+    assert_eq!(ids(&page), vec![5, 6]);
+    assert_eq!(page.next, None);
+
+    // the last page is known only when a row after it is read
+    let page = paginate(&storage, &select, None, 5).unwrap();
+    assert_eq!(ids(&page), vec![1, 3, 4, 5, 6]);
+    assert_eq!(page.next, None);
+
+    let page = paginate(&storage, &select, None, 4).unwrap();
+    assert_eq!(ids(&page), vec![1, 3, 4, 5]);
+    assert!(page.next.is_some());
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn paginate_error() {
+    let storage = run(
+        MemoryStorage::new(),
+        "
+        CREATE TABLE Player (id INTEGER, name TEXT);
+        CREATE TABLE Item (id INTEGER, player_id INTEGER);
+        INSERT INTO Player VALUES (1, \"Alice\");
+        ",
+    );
+    let page = |sql: &str, page_size| paginate(&storage, &query(sql), None, page_size);
+    let clause = |clause: &str| Err(PaginateError::ClauseNotSupported(clause.to_owned()).into());
+
+    let test_cases: Vec<(&str, usize, Result<Page<DataKey>>)> = vec![
+        (
+            "SELECT * FROM Player",
+            0,
+            Err(PaginateError::ZeroPageSize.into()),
+        ),
+        ("SELECT * FROM Player ORDER BY name", 1, clause("ORDER BY")),
+        ("SELECT * FROM Player LIMIT 1", 1, clause("LIMIT")),
+        (
+            "SELECT * FROM Player FETCH FIRST 1 ROW ONLY",
+            1,
+            clause("LIMIT"),
+        ),
+        ("SELECT * FROM Player OFFSET 1", 1, clause("OFFSET")),
+        ("SELECT DISTINCT name FROM Player", 1, clause("DISTINCT")),
+        ("SELECT COUNT(*) FROM Player", 1, clause("aggregate")),
+        (
+            "SELECT name FROM Player GROUP BY name",
+            1,
+            clause("aggregate"),
+        ),
+        (
+            "SELECT * FROM Player JOIN Item ON Player.id = Item.player_id",
+            1,
+            Err(PaginateError::TableNotSupported.into()),
+        ),
+        (
+            "SELECT * FROM (SELECT * FROM Player) AS p",
+            1,
+            Err(PaginateError::TableNotSupported.into()),
+        ),
+        (
+            "SELECT * FROM (Player JOIN Item ON Player.id = Item.player_id)",
+            1,
+            Err(PaginateError::TableNotSupported.into()),
+        ),
+    ];
+
+    for (sql, page_size, expected) in test_cases.into_iter() {
+        assert_eq!(page(sql, page_size), expected, "{}", sql);
+    }
+}
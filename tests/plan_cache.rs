@@ -0,0 +1,99 @@
+#[cfg(feature = "memory-storage")]
+use gluesql::{execute_batch, ExecuteError, MemoryStorage, Payload, PlanCache, Result, Row, Value};
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn plan_cache() {
+    use Value::*;
+
+    let (storage, _) = execute_batch(
+        MemoryStorage::new(),
+        "
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"a\"), (2, \"b\");
+        ",
+    )
+    .map_err(|(_, error)| error)
+    .unwrap();
+    let mut cache = PlanCache::new(2);
+
+    let run = |cache: &mut PlanCache, storage, sql| -> (MemoryStorage, Result<Payload>) {
+        match cache.execute(storage, sql) {
+            Ok((storage, payload)) => (storage, Ok(payload)),
+            Err((storage, error)) => (storage, Err(error)),
+        }
+    };
+    let select = |ids: &[i64]| {
+        Ok(Payload::Select {
+            labels: vec!["id".to_owned()],
+            rows: ids.iter().map(|id| Row(vec![I64(*id)])).collect(),
+        })
+    };
+
+    let (storage, found) = run(&mut cache, storage, "SELECT id FROM Item");
+    assert_eq!(found, select(&[1, 2]));
+    let (storage, found) = run(&mut cache, storage, "SELECT  id\n-- cached\nFROM Item;");
+    assert_eq!(found, select(&[1, 2]));
+    let (storage, found) = run(&mut cache, storage, "select id from Item");
+    assert_eq!(found, select(&[1, 2]));
+    assert_eq!(cache.len(), 1);
+
+    let (storage, found) = run(&mut cache, storage, "SELECT id FROM Item WHERE id > 1");
+    assert_eq!(found, select(&[2]));
+    let (storage, found) = run(&mut cache, storage, "SELECT id FROM Item");
+    assert_eq!(found, select(&[1, 2]));
+    let (storage, found) = run(&mut cache, storage, "SELECT id FROM Item WHERE id < 2");
+    assert_eq!(found, select(&[1]));
+    assert_eq!(cache.len(), 2);
+
+    let (storage, found) = run(&mut cache, storage, "SELECT id FROM Item; SELECT 1;");
+    assert_eq!(found, Err(ExecuteError::NotSingleStatement(2).into()));
+    let (storage, found) = run(&mut cache, storage, "SELECT nothing FROM Item");
+    assert!(found.is_err());
+    assert_eq!(cache.len(), 2);
+
+    // the schema changed outside of the cache, so the cached statement is validated again
+    let (storage, _) = execute_batch(
+        storage,
+        "
+        DROP TABLE Item;
+        CREATE TABLE Item (key INTEGER);
+        ",
+    )
+    .map_err(|(_, error)| error)
+    .unwrap();
+    let (storage, found) = run(&mut cache, storage, "SELECT id FROM Item");
+    assert_eq!(
+        found,
+        Err(ExecuteError::ColumnNotFound {
+            column: "id".to_owned(),
+            available: vec!["Item.key".to_owned()],
+        }
+        .into())
+    );
+    assert_eq!(cache.len(), 1);
+
+    let (storage, found) = run(&mut cache, storage, "DROP TABLE Item");
+    assert_eq!(found, Ok(Payload::DropTable));
+    assert!(cache.is_empty());
+    let (storage, found) = run(&mut cache, storage, "CREATE TABLE Item (key INTEGER)");
+    assert_eq!(found, Ok(Payload::Create));
+    let (storage, found) = run(&mut cache, storage, "SELECT id FROM Item");
+    assert!(found.is_err());
+    assert!(cache.is_empty());
+
+    let (storage, _) = run(&mut cache, storage, "SELECT key FROM Item");
+    // `key` is a keyword as well, but the identifier is not the same
+    let (storage, found) = run(&mut cache, storage, "SELECT KEY FROM Item");
+    assert!(found.is_err());
+    assert_eq!(cache.len(), 1);
+    let (storage, _) = run(&mut cache, storage, "SELECT key FROM Item WHERE key > 1");
+    assert_eq!(cache.len(), 2);
+    cache.resize(1);
+    assert_eq!(cache.len(), 1);
+    cache.clear();
+    assert!(cache.is_empty());
+
+    let (_, found) = run(&mut cache, storage, "SELECT id FROM Item");
+    assert!(found.is_err());
+}
@@ -0,0 +1,137 @@
+#[cfg(feature = "memory-storage")]
+use gluesql::{
+    execute, execute_batch, MemoryStorage, Payload, Prepared, PreparedError, Result, Row, Value,
+};
+
+#[cfg(feature = "memory-storage")]
+fn run(storage: MemoryStorage, prepared: &Prepared, params: &[Value]) -> (MemoryStorage, Payload) {
+    let query = prepared.bind(params).unwrap();
+
+    execute(storage, &query)
+        .map_err(|(_, error)| error)
+        .unwrap()
+}
+
+#[cfg(feature = "memory-storage")]
+fn rows(payload: Payload) -> Vec<Row> {
+    match payload {
+        Payload::Select { rows, .. } => rows,
+        payload => panic!("unexpected payload {:?}", payload),
+    }
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn prepared() {
+    use Value::*;
+
+    let sql = "
+        CREATE TABLE Player (id INTEGER, name TEXT, score FLOAT, joined DATE NULL);
+        INSERT INTO Player VALUES (1, \"Alice\", 1.5, '2020-01-01'), (2, \"Bob\", 2.0, NULL);
+    ";
+    let (storage, _) = execute_batch(MemoryStorage::new(), sql)
+        .map_err(|(_, error)| error)
+        .unwrap();
+
+    let insert = Prepared::new("INSERT INTO Player VALUES (?, ?, ?, ?)").unwrap();
+    assert_eq!(insert.params(), 4);
+
+    let (storage, _) = run(
+        storage,
+        &insert,
+        &[I64(3), Str("Carol".to_owned()), F64(3.0), Date(18_321)],
+    );
+    let (storage, _) = run(
+        storage,
+        &insert,
+        &[I64(4), Str("Dave's".to_owned()), F64(-1.5), OptDate(None)],
+    );
+
+    let select = Prepared::new("SELECT name FROM Player WHERE id = ? OR score = ?").unwrap();
+    let (storage, found) = run(storage, &select, &[I64(1), F64(3.0)]);
+    assert_eq!(
+        rows(found),
+        vec![
+            Row(vec![Str("Alice".to_owned())]),
+            Row(vec![Str("Carol".to_owned())])
+        ]
+    );
+    let (storage, found) = run(storage, &select, &[I64(4), F64(2.0)]);
+    assert_eq!(
+        rows(found),
+        vec![
+            Row(vec![Str("Bob".to_owned())]),
+            Row(vec![Str("Dave's".to_owned())])
+        ]
+    );
+
+    let select = Prepared::new(
+        "
+        SELECT id FROM Player
+        WHERE id > $1 AND (joined >= $2 OR id IN (SELECT id FROM Player WHERE id = $1 + 1))
+        ORDER BY id DESC
+        ",
+    )
+    .unwrap();
+    assert_eq!(select.params(), 2);
+
+    let (storage, found) = run(storage, &select, &[I64(0), Date(18_000)]);
+    assert_eq!(rows(found), vec![Row(vec![I64(3)]), Row(vec![I64(1)])]);
+    let (storage, found) = run(storage, &select, &[I64(1), Date(18_500)]);
+    assert_eq!(rows(found), vec![Row(vec![I64(2)])]);
+
+    let select = Prepared::new("SELECT id FROM Player WHERE name ILIKE ?").unwrap();
+    let (storage, found) = run(storage, &select, &[Str("%A%".to_owned())]);
+    assert_eq!(
+        rows(found),
+        vec![Row(vec![I64(1)]), Row(vec![I64(3)]), Row(vec![I64(4)])]
+    );
+
+    let select = Prepared::new(
+        "
+        SELECT a.id FROM Player a
+        JOIN (Player b JOIN Player c ON b.id = c.id AND c.id = ?) ON a.id = b.id
+        ",
+    )
+    .unwrap();
+    assert_eq!(select.params(), 1);
+
+    let (storage, found) = run(storage, &select, &[I64(2)]);
+    assert_eq!(rows(found), vec![Row(vec![I64(2)])]);
+
+    let update = Prepared::new("UPDATE Player SET score = ? WHERE name = ?").unwrap();
+    let (storage, payload) = run(storage, &update, &[F64(0.5), Str("Bob".to_owned())]);
+    assert_eq!(payload, Payload::Update(1));
+
+    let delete = Prepared::new("DELETE FROM Player WHERE score < ?").unwrap();
+    let (_, payload) = run(storage, &delete, &[F64(1.0)]);
+    assert_eq!(payload, Payload::Delete(2));
+}
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn prepared_error() {
+    let prepared = Prepared::new("SELECT * FROM Player WHERE id = $2").unwrap();
+    let bind = |params: &[Value]| prepared.bind(params).map(|_| ());
+
+    assert_eq!(
+        bind(&[Value::I64(1)]),
+        Err(PreparedError::ParamCountMismatch {
+            expected: 2,
+            found: 1
+        }
+        .into())
+    );
+    assert_eq!(
+        bind(&[Value::I64(1), Value::I64(2), Value::I64(3)]),
+        Err(PreparedError::ParamCountMismatch {
+            expected: 2,
+            found: 3
+        }
+        .into())
+    );
+    assert_eq!(bind(&[Value::I64(1), Value::I64(2)]), Ok(()));
+
+    let prepared: Result<_> = Prepared::new("SELECT 1; SELECT 2;").map(|_| ());
+    assert_eq!(prepared, Err(PreparedError::NotSingleStatement(2).into()));
+}
@@ -0,0 +1,89 @@
+#[cfg(feature = "memory-storage")]
+use std::{env, fs, process};
+
+#[cfg(feature = "memory-storage")]
+use gluesql::{execute, execute_batch, execute_with_options, parse, ExecuteOptions, MemoryStorage};
+
+#[cfg(feature = "memory-storage")]
+#[test]
+fn sort_buffer() {
+    let items = (1..=500)
+        .map(|id| match id % 17 {
+            0 => format!("({}, {}, NULL, {:?})", id, id % 7, id as f64 / 4.0),
+            _ => format!(
+                "({}, {}, \"n{}\", {:?})",
+                id,
+                id % 7,
+                id * 31 % 89,
+                id as f64 / 4.0
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "
+        CREATE TABLE Item (id INTEGER, category INTEGER, name TEXT NULL, price FLOAT);
+        CREATE TABLE Category (id INTEGER, label TEXT);
+        INSERT INTO Item VALUES {};
+        INSERT INTO Category VALUES (0, \"zero\"), (1, \"one\"), (2, \"two\"), (3, \"three\");
+        ",
+        items
+    );
+    let (storage, _) = execute_batch(MemoryStorage::new(), &sql)
+        .map_err(|(_, error)| error)
+        .unwrap();
+
+    let sqls = [
+        "SELECT id, name FROM Item ORDER BY name, id DESC",
+        "SELECT id, name FROM Item ORDER BY name DESC NULLS LAST",
+        "SELECT id FROM Item ORDER BY category, price DESC LIMIT 20 OFFSET 5",
+        "SELECT i.id, c.label FROM Item i JOIN Category c ON i.category = c.id ORDER BY c.label",
+        "SELECT i.id, c.label FROM Item i LEFT JOIN Category c ON i.category = c.id ORDER BY c.label",
+        "SELECT category, COUNT(*), SUM(price) FROM Item GROUP BY category ORDER BY SUM(price) DESC",
+        "SELECT DISTINCT ON (category) category, id FROM Item ORDER BY category, name",
+        "SELECT id, ROW_NUMBER() OVER (ORDER BY price DESC) FROM Item ORDER BY name, id",
+    ];
+
+    let run = |storage, sql: &str, sort_buffer| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+        let result = match sort_buffer {
+            Some(sort_buffer) => {
+                let options = ExecuteOptions {
+                    sort_buffer: Some(sort_buffer),
+                    ..ExecuteOptions::default()
+                };
+
+                execute_with_options(storage, &query, options)
+            }
+            None => execute(storage, &query),
+        };
+
+        result.map_err(|(_, error)| error).unwrap()
+    };
+
+    let storage = sqls.iter().fold(storage, |storage, sql| {
+        let (storage, expected) = run(storage, sql, None);
+
+        [1, 7, 64, 10_000]
+            .iter()
+            .fold(storage, |storage, sort_buffer| {
+                let (storage, found) = run(storage, sql, Some(*sort_buffer));
+                assert_eq!(
+                    expected, found,
+                    "sort_buffer: {}, sql: {}",
+                    sort_buffer, sql
+                );
+
+                storage
+            })
+    });
+    drop(storage);
+
+    let prefix = format!("gluesql-sort-{}-", process::id());
+    let runs = fs::read_dir(env::temp_dir())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .count();
+    assert_eq!(runs, 0);
+}
@@ -0,0 +1,242 @@
+#[cfg(feature = "sled-storage")]
+use std::convert::TryFrom;
+
+#[cfg(feature = "sled-storage")]
+use gluesql::{
+    execute, parse, sled, ExecuteError, Payload, Predicate, PredicateOp, Row, SledStorage, Store,
+    Value,
+};
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn get_data_by_key() {
+    let config = sled::Config::default()
+        .path("data/get_data_by_key")
+        .temporary(true);
+    let mut storage = SledStorage::try_from(config).expect("SledStorage::new");
+
+    let sqls = "
+        CREATE TABLE Player (id INTEGER);
+        CREATE TABLE Item (id INTEGER);
+        INSERT INTO Player VALUES (1);
+        INSERT INTO Player VALUES (2);
+        INSERT INTO Item VALUES (3);
+    ";
+
+    for query in parse(sqls).unwrap() {
+        storage = execute(storage, &query).map_err(|(_, e)| e).unwrap().0;
+    }
+
+    let rows = storage
+        .scan_data("Player")
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let (key, row) = &rows[1];
+
+    assert_eq!(*row, Row(vec![Value::I64(2)]));
+    assert_eq!(
+        storage.get_data_by_key("Player", key).unwrap(),
+        Some((key.clone(), row.clone()))
+    );
+    assert_eq!(storage.get_data_by_key("Item", key).unwrap(), None);
+
+    storage = execute(
+        storage,
+        &parse("DELETE FROM Player WHERE id = 2").unwrap()[0],
+    )
+    .map_err(|(_, e)| e)
+    .unwrap()
+    .0;
+    assert_eq!(storage.get_data_by_key("Player", key).unwrap(), None);
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn scan_filtered() {
+    let config = sled::Config::default()
+        .path("data/scan_filtered")
+        .temporary(true);
+    let mut storage = SledStorage::try_from(config).expect("SledStorage::new");
+
+    let sqls = "
+        CREATE TABLE Player (id INTEGER, score INTEGER NULL);
+        INSERT INTO Player VALUES (1, 30);
+        INSERT INTO Player VALUES (2, 10);
+        INSERT INTO Player VALUES (3, NULL);
+        INSERT INTO Player VALUES (4, 20);
+    ";
+
+    for query in parse(sqls).unwrap() {
+        storage = execute(storage, &query).map_err(|(_, e)| e).unwrap().0;
+    }
+
+    let scan = |predicates: &[Predicate]| {
+        storage
+            .scan_filtered("Player", predicates)
+            .unwrap()
+            .map(|item| item.map(|(_, Row(values))| values[0].clone()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    let predicate = |index, op, value| Predicate { index, op, value };
+
+    assert_eq!(
+        scan(&[predicate(1, PredicateOp::GtEq, Value::I64(20))]),
+        vec![Value::I64(1), Value::I64(3), Value::I64(4)]
+    );
+    assert_eq!(
+        scan(&[
+            predicate(1, PredicateOp::Lt, Value::F64(25.0)),
+            predicate(0, PredicateOp::NotEq, Value::I64(3)),
+        ]),
+        vec![Value::I64(2), Value::I64(4)]
+    );
+    assert_eq!(
+        scan(&[predicate(0, PredicateOp::Eq, Value::Str("1".to_owned()))]),
+        vec![Value::I64(1), Value::I64(2), Value::I64(3), Value::I64(4)]
+    );
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn scan_after() {
+    let config = sled::Config::default()
+        .path("data/scan_after")
+        .temporary(true);
+    let mut storage = SledStorage::try_from(config).expect("SledStorage::new");
+
+    let sqls = "
+        CREATE TABLE Player (id INTEGER);
+        CREATE TABLE PlayerItem (id INTEGER);
+        INSERT INTO Player VALUES (1);
+        INSERT INTO PlayerItem VALUES (10);
+        INSERT INTO Player VALUES (2);
+        INSERT INTO Player VALUES (3);
+    ";
+
+    for query in parse(sqls).unwrap() {
+        storage = execute(storage, &query).map_err(|(_, e)| e).unwrap().0;
+    }
+
+    let scan = |key| {
+        storage
+            .scan_after("Player", key)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    let rows = scan(None);
+    let values = rows
+        .iter()
+        .map(|(_, Row(values))| values[0].clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(values, vec![Value::I64(1), Value::I64(2), Value::I64(3)]);
+    assert_eq!(scan(Some(&rows[0].0)), rows[1..].to_vec());
+    assert_eq!(scan(Some(&rows[2].0)), vec![]);
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn get_schema() {
+    use gluesql::{ColumnSchema, TableSchema};
+    use sqlparser::ast::DataType;
+
+    let config = sled::Config::default()
+        .path("data/get_schema")
+        .temporary(true);
+    let mut storage = SledStorage::try_from(config).expect("SledStorage::new");
+
+    let sqls = "
+        CREATE TABLE Player (id INTEGER PRIMARY KEY, name TEXT, score FLOAT NULL);
+        CREATE TABLE Item (id INTEGER, player_id INTEGER, PRIMARY KEY (player_id, id));
+        INSERT INTO Item VALUES (1, 1);
+    ";
+
+    for query in parse(sqls).unwrap() {
+        storage = execute(storage, &query).map_err(|(_, e)| e).unwrap().0;
+    }
+
+    let column = |name: &str, data_type, nullable| ColumnSchema {
+        name: name.to_owned(),
+        data_type,
+        nullable,
+    };
+
+    assert_eq!(
+        storage.get_schema("Player"),
+        Some(TableSchema {
+            table_name: "Player".to_owned(),
+            columns: vec![
+                column("id", DataType::Int, false),
+                column("name", DataType::Text, false),
+                column("score", DataType::Float(None), true),
+            ],
+            primary_key: vec!["id".to_owned()],
+        })
+    );
+    assert_eq!(
+        storage.get_schema("Item"),
+        Some(TableSchema {
+            table_name: "Item".to_owned(),
+            columns: vec![
+                column("id", DataType::Int, false),
+                column("player_id", DataType::Int, false),
+            ],
+            primary_key: vec!["id".to_owned(), "player_id".to_owned()],
+        })
+    );
+    assert_eq!(storage.get_schema("Nothing"), None);
+
+    let query = parse("CREATE TABLE Tag (id INTEGER, PRIMARY KEY (name));").unwrap();
+    let error = execute(storage, &query[0]).map(|_| ()).map_err(|(_, e)| e);
+
+    assert_eq!(
+        error,
+        Err(ExecuteError::ColumnNotFound {
+            column: "name".to_owned(),
+            available: vec!["id".to_owned()],
+        }
+        .into())
+    );
+}
+
+#[cfg(feature = "sled-storage")]
+#[test]
+fn reopen() {
+    let path = "data/reopen";
+    let _ = std::fs::remove_dir_all(path);
+
+    let run = |storage: SledStorage, sql: &str| {
+        let query = parse(sql).unwrap().into_iter().next().unwrap();
+
+        execute(storage, &query).map_err(|(_, e)| e).unwrap()
+    };
+
+    {
+        let storage = SledStorage::new(path).expect("SledStorage::new");
+        let (storage, _) = run(storage, "CREATE TABLE Player (id INTEGER, name TEXT);");
+        let (storage, _) = run(storage, "INSERT INTO Player VALUES (1, \"Taehoon\");");
+        let (storage, _) = run(storage, "INSERT INTO Player VALUES (2, \"Mike\");");
+        let (storage, _) = run(storage, "DELETE FROM Player WHERE id = 1;");
+
+        run(storage, "INSERT INTO Player VALUES (3, \"Jorno\");");
+    }
+
+    let storage = SledStorage::new(path).expect("SledStorage::new");
+    let (_, payload) = run(storage, "SELECT id, name FROM Player;");
+
+    assert_eq!(
+        payload,
+        Payload::Select {
+            labels: vec!["id".to_owned(), "name".to_owned()],
+            rows: vec![
+                Row(vec![Value::I64(2), Value::Str("Mike".to_owned())]),
+                Row(vec![Value::I64(3), Value::Str("Jorno".to_owned())]),
+            ],
+        }
+    );
+
+    std::fs::remove_dir_all(path).unwrap();
+}